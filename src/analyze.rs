@@ -0,0 +1,117 @@
+//! One-call analysis of a configuration file for library users
+//!
+//! [`analyze`] detects the shell, parses the file and runs the checkers,
+//! returning everything in a single [`Analysis`].
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::checker::{check_all, CheckIssue};
+use crate::model::{Entry, EntryType, ParseWarning, ShellType};
+use crate::parser::get_parser;
+use crate::utils::path::read_file;
+use crate::utils::shell_detect::{resolve_shell_type, ShellSource};
+
+/// Result of analyzing a configuration file
+#[derive(Debug)]
+pub struct Analysis {
+    /// The analyzed file
+    pub path: PathBuf,
+    /// Shell the file was parsed as
+    pub shell_type: ShellType,
+    /// How `shell_type` was decided
+    pub shell_source: ShellSource,
+    /// Entries in file order
+    pub entries: Vec<Entry>,
+    /// Lines the parser could not make sense of
+    pub parse_warnings: Vec<ParseWarning>,
+    /// Checker findings (duplicates, undefined variables, PATH segments)
+    pub issues: Vec<CheckIssue>,
+}
+
+impl Analysis {
+    /// Entries of one type, in file order
+    pub fn entries_of(&self, entry_type: EntryType) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.entry_type == entry_type)
+    }
+}
+
+/// Analyze the configuration file at `path`, detecting its shell from the
+/// file name (or the environment when the name gives no hint).
+///
+/// ```
+/// use wenv::{analyze, EntryType, ShellType};
+///
+/// let dir = tempfile::tempdir()?;
+/// let path = dir.path().join(".bashrc");
+/// std::fs::write(&path, "# Listing\nalias ll='ls -la'\nexport EDITOR=vim\n")?;
+///
+/// let analysis = analyze(&path)?;
+/// assert_eq!(analysis.shell_type, ShellType::Bash);
+/// let alias = analysis.entries_of(EntryType::Alias).next().unwrap();
+/// assert_eq!((alias.name.as_str(), alias.canonical_value()), ("ll", "ls -la".to_string()));
+/// assert!(analysis.issues.is_empty());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn analyze(path: impl AsRef<Path>) -> Result<Analysis> {
+    analyze_file(path.as_ref(), None)
+}
+
+/// Analyze the configuration file at `path` as `shell_type` syntax
+pub fn analyze_as(path: impl AsRef<Path>, shell_type: ShellType) -> Result<Analysis> {
+    analyze_file(path.as_ref(), Some(shell_type))
+}
+
+fn analyze_file(path: &Path, shell_type: Option<ShellType>) -> Result<Analysis> {
+    let (shell_type, shell_source) = resolve_shell_type(shell_type, Some(path));
+    let content = read_file(path)?;
+    let parsed = get_parser(shell_type).parse(&content);
+    let issues = check_all(&parsed.entries, shell_type).issues;
+
+    Ok(Analysis {
+        path: path.to_path_buf(),
+        shell_type,
+        shell_source,
+        entries: parsed.entries,
+        parse_warnings: parsed.warnings,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_analyze_detects_shell_and_checks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".zshrc");
+        std::fs::write(
+            &path,
+            "alias ll='ls -l'\nalias ll='ls -la'\nexport EDITOR=vim\n",
+        )
+        .unwrap();
+
+        let analysis = analyze(&path).unwrap();
+        assert_eq!(analysis.shell_type, ShellType::Zsh);
+        assert_eq!(analysis.shell_source, ShellSource::FileName);
+        assert_eq!(analysis.entries_of(EntryType::Alias).count(), 2);
+        assert!(analysis
+            .issues
+            .iter()
+            .any(|i| i.entry_name.as_deref() == Some("ll")));
+
+        let analysis = analyze_as(&path, ShellType::PowerShell).unwrap();
+        assert_eq!(analysis.shell_source, ShellSource::Specified);
+        assert_eq!(analysis.entries_of(EntryType::Alias).count(), 0);
+    }
+
+    #[test]
+    fn test_analyze_missing_file() {
+        let dir = tempdir().unwrap();
+        assert!(analyze(dir.path().join(".bashrc")).is_err());
+    }
+}
@@ -1,10 +1,20 @@
 //! Backup management module
+//!
+//! Backups live in one subdirectory ("bucket") of the backup directory per
+//! syntax, not per shell: Bash, Zsh and POSIX sh files all go to `sh/`, so a
+//! file keeps its backups when it is detected as another of these shells.
+//! PowerShell uses `pwsh/` and `.env` files `dotenv/`. Older versions used a
+//! subdirectory per shell; [`migrate`] moves those backups into their
+//! bucket.
+
+pub mod watch;
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 use time::OffsetDateTime;
 
 use crate::model::{Config, ShellType};
+use crate::utils::path::expand_tilde;
 
 /// Backup entry information
 #[derive(Debug, Clone)]
@@ -16,6 +26,107 @@ pub struct BackupEntry {
     pub size: u64,
 }
 
+impl BackupEntry {
+    /// Creation time parsed from the `YYYY-MM-DD_HHMMSS` filename prefix (UTC)
+    pub fn created_at(&self) -> Option<OffsetDateTime> {
+        let (date, time) = self.timestamp.split_once('_')?;
+        let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<u32>().ok());
+        let (year, month, day) = (
+            date_parts.next()??,
+            date_parts.next()??,
+            date_parts.next()??,
+        );
+        if time.len() != 6 {
+            return None;
+        }
+        let hms = |i: usize| time.get(i..i + 2)?.parse::<u8>().ok();
+
+        let date = time::Date::from_calendar_date(
+            year as i32,
+            time::Month::try_from(month as u8).ok()?,
+            day as u8,
+        )
+        .ok()?;
+        let time = time::Time::from_hms(hms(0)?, hms(2)?, hms(4)?).ok()?;
+        Some(time::PrimitiveDateTime::new(date, time).assume_utc())
+    }
+
+    /// Name of the file this is a backup of (`.bashrc` for
+    /// `2024-03-05_140709_.bashrc.bak`)
+    pub fn source_filename(&self) -> &str {
+        let name = self.filename.trim_end_matches(".bak");
+        name.get(self.timestamp.len() + 1..).unwrap_or(name)
+    }
+}
+
+/// Per-shell subdirectories of older versions, with the bucket their
+/// backups belong to now
+const LEGACY_BUCKETS: [(&str, &str); 2] = [("bash", "sh"), ("zsh", "sh")];
+
+/// Subdirectory of the backup directory for files of `shell_type`
+pub fn bucket_name(shell_type: ShellType) -> &'static str {
+    match shell_type {
+        ShellType::Bash | ShellType::Zsh | ShellType::PosixSh => "sh",
+        ShellType::PowerShell => "pwsh",
+        ShellType::DotEnv => "dotenv",
+    }
+}
+
+/// The backup directory holding every bucket: `backup.dir` when set,
+/// otherwise [`Config::backups_dir`]
+pub fn base_dir(config: &Config) -> PathBuf {
+    match &config.backup.dir {
+        Some(dir) => expand_tilde(&dir.to_string_lossy()),
+        None => Config::backups_dir(),
+    }
+}
+
+/// Backups moved, or found to be movable, by [`migrate`]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Migration {
+    /// `(from, to)` for each backup
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    /// Backups left in place because their bucket has one of the same name
+    pub conflicts: Vec<PathBuf>,
+}
+
+/// Move backups from the per-shell subdirectories of `base` into their
+/// bucket, removing subdirectories left empty. With `dry_run`, only reports
+/// what would move.
+pub fn migrate(base: &Path, dry_run: bool) -> Result<Migration> {
+    let mut migration = Migration::default();
+    for (legacy, bucket) in LEGACY_BUCKETS {
+        let from_dir = base.join(legacy);
+        let Ok(read_dir) = std::fs::read_dir(&from_dir) else {
+            continue;
+        };
+        let to_dir = base.join(bucket);
+        let mut backups: Vec<PathBuf> = read_dir
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "bak"))
+            .collect();
+        backups.sort();
+
+        for from in backups {
+            let to = to_dir.join(from.file_name().unwrap_or_default());
+            if to.exists() {
+                migration.conflicts.push(from);
+                continue;
+            }
+            if !dry_run {
+                std::fs::create_dir_all(&to_dir)?;
+                std::fs::rename(&from, &to)?;
+            }
+            migration.moved.push((from, to));
+        }
+        if !dry_run {
+            // Only succeeds once empty
+            let _ = std::fs::remove_dir(&from_dir);
+        }
+    }
+    Ok(migration)
+}
+
 /// Backup manager
 pub struct BackupManager {
     backup_dir: PathBuf,
@@ -26,7 +137,7 @@ pub struct BackupManager {
 
 impl BackupManager {
     pub fn new(shell_type: ShellType, config: &Config) -> Self {
-        let backup_dir = Config::backups_dir().join(shell_type.name());
+        let backup_dir = Self::dir_for(shell_type, config);
         Self {
             backup_dir,
             max_count: config.backup.max_count,
@@ -35,6 +146,12 @@ impl BackupManager {
         }
     }
 
+    /// Directory backups of `shell_type` files go to: their bucket in
+    /// [`base_dir`]
+    pub fn dir_for(shell_type: ShellType, config: &Config) -> PathBuf {
+        base_dir(config).join(bucket_name(shell_type))
+    }
+
     /// Ensure backup directory exists
     fn ensure_dir(&self) -> Result<()> {
         std::fs::create_dir_all(&self.backup_dir)?;
@@ -171,6 +288,70 @@ impl BackupManager {
         Ok(removed)
     }
 
+    /// Remove backups older than the given number of days
+    pub fn cleanup_older_than(&self, days: u64) -> Result<usize> {
+        self.prune(None, Some(days))
+    }
+
+    /// Select backups for pruning without removing them.
+    ///
+    /// When both criteria are given a backup must match both: it is outside
+    /// the newest `keep` *and* older than `older_than_days`. Backups whose
+    /// timestamp can't be parsed are never selected by age.
+    pub fn select_for_prune(
+        &self,
+        keep: Option<usize>,
+        older_than_days: Option<u64>,
+    ) -> Result<Vec<BackupEntry>> {
+        let cutoff = older_than_days
+            .map(|days| OffsetDateTime::now_utc() - time::Duration::days(days as i64));
+
+        Ok(self
+            .list_backups()?
+            .into_iter()
+            .enumerate()
+            .filter(|(index, backup)| {
+                let beyond_keep = keep.is_none_or(|keep| *index >= keep);
+                let too_old =
+                    cutoff.is_none_or(|cutoff| backup.created_at().is_some_and(|t| t < cutoff));
+                beyond_keep && too_old
+            })
+            .map(|(_, backup)| backup)
+            .collect())
+    }
+
+    /// Remove backups selected by [`select_for_prune`](Self::select_for_prune)
+    pub fn prune(&self, keep: Option<usize>, older_than_days: Option<u64>) -> Result<usize> {
+        let selected = self.select_for_prune(keep, older_than_days)?;
+        for backup in &selected {
+            std::fs::remove_file(&backup.path)?;
+        }
+        Ok(selected.len())
+    }
+
+    /// The backup `id` of `source_file` followed by all newer backups of the
+    /// same file, oldest first
+    pub fn backups_since(&self, id: &str, source_file: &Path) -> Result<Vec<BackupEntry>> {
+        let since = self
+            .get_backup(id)?
+            .ok_or_else(|| anyhow::anyhow!("Backup not found: {}", id))?;
+        let filename = source_file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if since.source_filename() != filename {
+            anyhow::bail!("Backup {} is not a backup of {}", since.id, filename);
+        }
+
+        let mut backups: Vec<BackupEntry> = self
+            .list_backups()?
+            .into_iter()
+            .filter(|b| b.source_filename() == filename && b.timestamp >= since.timestamp)
+            .collect();
+        backups.reverse();
+        Ok(backups)
+    }
+
     /// Get a specific backup by ID
     pub fn get_backup(&self, id: &str) -> Result<Option<BackupEntry>> {
         let backups = self.list_backups()?;
@@ -185,6 +366,95 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    fn manager_in(dir: &Path) -> BackupManager {
+        BackupManager {
+            backup_dir: dir.to_path_buf(),
+            max_count: Config::default().backup.max_count,
+            cleanup_counter: std::cell::Cell::new(0),
+            last_cleanup_time: std::cell::Cell::new(None),
+        }
+    }
+
+    /// Write a synthetic backup file dated `days_ago` days before now
+    fn write_backup(dir: &Path, days_ago: i64) -> String {
+        let t = OffsetDateTime::now_utc() - time::Duration::days(days_ago);
+        let name = format!(
+            "{:04}-{:02}-{:02}_{:02}{:02}{:02}_.bashrc.bak",
+            t.year(),
+            t.month() as u8,
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second()
+        );
+        std::fs::write(dir.join(&name), "").unwrap();
+        name
+    }
+
+    #[test]
+    fn test_created_at_parses_filename() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("2024-03-05_140709_.bashrc.bak"), "").unwrap();
+
+        let backups = manager_in(temp_dir.path()).list_backups().unwrap();
+        let created = backups[0].created_at().unwrap();
+        assert_eq!(
+            (created.year(), created.month() as u8, created.day()),
+            (2024, 3, 5)
+        );
+        assert_eq!(
+            (created.hour(), created.minute(), created.second()),
+            (14, 7, 9)
+        );
+    }
+
+    #[test]
+    fn test_prune_keep() {
+        let temp_dir = tempdir().unwrap();
+        for days_ago in [1, 2, 3, 4] {
+            write_backup(temp_dir.path(), days_ago);
+        }
+        let manager = manager_in(temp_dir.path());
+
+        assert_eq!(manager.prune(Some(2), None).unwrap(), 2);
+        assert_eq!(manager.list_backups().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_prune_older_than() {
+        let temp_dir = tempdir().unwrap();
+        let recent = write_backup(temp_dir.path(), 1);
+        write_backup(temp_dir.path(), 40);
+        write_backup(temp_dir.path(), 50);
+        std::fs::write(temp_dir.path().join("unknown.bak"), "").unwrap();
+        let manager = manager_in(temp_dir.path());
+
+        assert_eq!(manager.cleanup_older_than(30).unwrap(), 2);
+        let remaining: Vec<_> = manager
+            .list_backups()
+            .unwrap()
+            .into_iter()
+            .map(|b| b.filename)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&recent));
+    }
+
+    #[test]
+    fn test_prune_keep_and_older_than_must_both_match() {
+        let temp_dir = tempdir().unwrap();
+        for days_ago in [40, 50, 60] {
+            write_backup(temp_dir.path(), days_ago);
+        }
+        let manager = manager_in(temp_dir.path());
+
+        // All are old, but the newest two are kept
+        let selected = manager.select_for_prune(Some(2), Some(30)).unwrap();
+        assert_eq!(selected.len(), 1);
+        // Selection alone removes nothing
+        assert_eq!(manager.list_backups().unwrap().len(), 3);
+    }
+
     #[test]
     fn test_backup_creation() {
         let temp_dir = tempdir().unwrap();
@@ -202,4 +472,120 @@ mod tests {
         let backup_path = manager.create_backup(&source_file).unwrap();
         assert!(backup_path.exists());
     }
+
+    #[test]
+    fn test_custom_backup_dir() {
+        let temp_dir = tempdir().unwrap();
+        let source_file = temp_dir.path().join(".bashrc");
+        std::fs::write(&source_file, "alias ll='ls -la'").unwrap();
+
+        let mut config = Config::default();
+        config.backup.dir = Some(temp_dir.path().join("synced"));
+        let manager = BackupManager::new(ShellType::Bash, &config);
+
+        let backup_path = manager.create_backup(&source_file).unwrap();
+        assert_eq!(
+            backup_path.parent().unwrap(),
+            temp_dir.path().join("synced").join("sh")
+        );
+        assert_eq!(manager.list_backups().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_backup_dir_expands_tilde() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let mut config = Config::default();
+        config.backup.dir = Some(PathBuf::from("~/wenv-backups"));
+        assert_eq!(
+            BackupManager::dir_for(ShellType::PowerShell, &config),
+            home.join("wenv-backups").join("pwsh")
+        );
+        assert_eq!(
+            BackupManager::dir_for(ShellType::Bash, &Config::default()),
+            Config::backups_dir().join("sh")
+        );
+    }
+
+    #[test]
+    fn test_posix_shells_share_a_bucket() {
+        let config = Config::default();
+        let dir = |shell| BackupManager::dir_for(shell, &config);
+        assert_eq!(dir(ShellType::Bash), dir(ShellType::Zsh));
+        assert_eq!(dir(ShellType::Bash), dir(ShellType::PosixSh));
+        assert_ne!(dir(ShellType::Bash), dir(ShellType::PowerShell));
+        assert_ne!(dir(ShellType::PowerShell), dir(ShellType::DotEnv));
+    }
+
+    #[test]
+    fn test_migrate_moves_legacy_backups() {
+        let base = tempdir().unwrap();
+        let base = base.path();
+        for (dir, name) in [
+            ("bash", "2024-01-01_120000_.bashrc.bak"),
+            ("bash", "2024-01-02_120000_.bashrc.bak"),
+            ("zsh", "2024-01-03_120000_.zshrc.bak"),
+            ("sh", "2024-01-02_120000_.bashrc.bak"),
+            ("pwsh", "2024-01-04_120000_profile.ps1.bak"),
+        ] {
+            std::fs::create_dir_all(base.join(dir)).unwrap();
+            std::fs::write(base.join(dir).join(name), dir).unwrap();
+        }
+
+        let planned = migrate(base, true).unwrap();
+        assert_eq!(planned.moved.len(), 2);
+        assert!(base.join("zsh").exists());
+
+        let migration = migrate(base, false).unwrap();
+        assert_eq!(migration, planned);
+        assert_eq!(
+            migration.conflicts,
+            [base.join("bash").join("2024-01-02_120000_.bashrc.bak")]
+        );
+        let bucket = manager_in(&base.join("sh")).list_backups().unwrap();
+        assert_eq!(bucket.len(), 3);
+        // The bucket's own copy wins a name clash
+        assert_eq!(
+            std::fs::read_to_string(base.join("sh").join("2024-01-02_120000_.bashrc.bak")).unwrap(),
+            "sh"
+        );
+        assert!(!base.join("zsh").exists());
+        assert!(base.join("bash").exists());
+        assert!(base
+            .join("pwsh")
+            .join("2024-01-04_120000_profile.ps1.bak")
+            .exists());
+    }
+
+    #[test]
+    fn test_backups_since() {
+        let temp_dir = tempdir().unwrap();
+        for name in [
+            "2024-01-01_120000_.bashrc.bak",
+            "2024-01-02_120000_.bashrc.bak",
+            "2024-01-02_130000_.bash_aliases.bak",
+            "2024-01-03_120000_.bashrc.bak",
+        ] {
+            std::fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+        let manager = manager_in(temp_dir.path());
+
+        let since = manager
+            .backups_since("2024-01-02_120000", Path::new("/home/u/.bashrc"))
+            .unwrap();
+        let ids: Vec<&str> = since.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["2024-01-02_120000_.bashrc", "2024-01-03_120000_.bashrc"]
+        );
+        assert_eq!(since[0].source_filename(), ".bashrc");
+
+        assert!(manager
+            .backups_since("2024-01-02_130000", Path::new(".bashrc"))
+            .is_err());
+        assert!(manager
+            .backups_since("1999-01-01", Path::new(".bashrc"))
+            .is_err());
+    }
 }
@@ -0,0 +1,97 @@
+//! Change detection for `wenv backup auto --watch`
+//!
+//! The file is polled rather than watched through OS events: polling the
+//! path sees through editors that save by writing a temporary file and
+//! renaming it over the original (the watched inode is gone, the path is
+//! not), and a file briefly missing mid-save is simply not a change yet.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Modification time and size of a file, `None` while it is missing
+pub type FileStamp = Option<(SystemTime, u64)>;
+
+/// Current [`FileStamp`] of `path`
+pub fn stamp(path: &Path) -> FileStamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Collapses a burst of changes into one event, fired once no change has
+/// been seen for the quiet period.
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet: Duration,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            last_change: None,
+        }
+    }
+
+    /// Record a change seen at `now`
+    pub fn changed(&mut self, now: Instant) {
+        self.last_change = Some(now);
+    }
+
+    /// Whether the changes recorded so far have settled by `now`. True
+    /// once per burst.
+    pub fn settled(&mut self, now: Instant) -> bool {
+        match self.last_change {
+            Some(last) if now.duration_since(last) >= self.quiet => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUIET: Duration = Duration::from_millis(500);
+
+    #[test]
+    fn test_fires_once_after_the_quiet_period() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(QUIET);
+        assert!(!debouncer.settled(start + QUIET * 2));
+
+        debouncer.changed(start);
+        assert!(!debouncer.settled(start + QUIET / 2));
+        assert!(debouncer.settled(start + QUIET));
+        assert!(!debouncer.settled(start + QUIET * 2));
+    }
+
+    #[test]
+    fn test_a_burst_of_changes_is_one_event() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(QUIET);
+
+        // An editor truncating, writing and renaming in quick succession
+        for step in 0..5 {
+            let now = start + Duration::from_millis(100) * step;
+            debouncer.changed(now);
+            assert!(!debouncer.settled(now));
+        }
+        let last = start + Duration::from_millis(400);
+        assert!(!debouncer.settled(last + QUIET / 2));
+        assert!(debouncer.settled(last + QUIET));
+    }
+
+    #[test]
+    fn test_stamp_of_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".bashrc");
+        assert_eq!(stamp(&path), None);
+
+        std::fs::write(&path, "alias ll='ls -la'\n").unwrap();
+        assert_eq!(stamp(&path).map(|(_, size)| size), Some(18));
+    }
+}
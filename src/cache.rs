@@ -1,8 +1,11 @@
-//! Path caching system for shell profile paths
+//! Caches: shell profile paths and parse results
 
-use crate::model::Config;
+use crate::model::{Config, ParseResult, ShellType};
 use anyhow::Result;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 pub struct PathCache;
 
@@ -67,3 +70,174 @@ impl CacheData {
         self.powershell_profile = Some(path.to_string_lossy().to_string());
     }
 }
+
+/// On-disk cache of parse results, stored under `parse-cache/` in the config
+/// directory.
+///
+/// An entry is only used while the file's modification time and size match
+/// the ones it was parsed at, and only by the wenv version, shell and parser
+/// options that produced it.
+pub struct ParseCache {
+    dir: PathBuf,
+}
+
+/// What a cached parse result is valid for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseCacheKey {
+    pub path: PathBuf,
+    pub shell: String,
+    pub merge_comments: bool,
+    pub attach_comments: bool,
+    pub modified_secs: u64,
+    pub modified_nanos: u32,
+    pub size: u64,
+    pub version: String,
+}
+
+impl ParseCacheKey {
+    /// Key for the current state of `path`, or `None` when its metadata
+    /// can't be read
+    pub fn for_file(
+        path: &Path,
+        shell_type: ShellType,
+        merge_comments: bool,
+        attach_comments: bool,
+    ) -> Option<Self> {
+        let path = std::fs::canonicalize(path).ok()?;
+        let metadata = std::fs::metadata(&path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            path,
+            shell: shell_type.name().to_string(),
+            merge_comments,
+            attach_comments,
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    /// Cache file name; one slot per file, shell and parser options
+    fn file_name(&self) -> String {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (
+            &self.path,
+            &self.shell,
+            self.merge_comments,
+            self.attach_comments,
+        )
+            .hash(&mut hasher);
+        format!("{:016x}.toml", hasher.finish())
+    }
+}
+
+/// Layout of a cache file
+#[derive(Serialize, Deserialize)]
+struct CachedParse<K, R> {
+    key: K,
+    result: R,
+}
+
+impl ParseCache {
+    pub fn new() -> Self {
+        Self::in_dir(Config::config_dir().join("parse-cache"))
+    }
+
+    /// Cache stored in `dir`
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Cached result for `key`, if one was stored for exactly this key.
+    /// Unreadable or outdated cache files count as a miss.
+    pub fn get(&self, key: &ParseCacheKey) -> Option<ParseResult> {
+        let content = std::fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        let cached: CachedParse<ParseCacheKey, ParseResult> = toml::from_str(&content).ok()?;
+        (cached.key == *key).then_some(cached.result)
+    }
+
+    /// Store `result` for `key`, replacing the file's previous entry
+    pub fn put(&self, key: &ParseCacheKey, result: &ParseResult) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let cached = CachedParse { key, result };
+        std::fs::write(self.dir.join(key.file_name()), toml::to_string(&cached)?)?;
+        Ok(())
+    }
+
+    /// Remove all cached parse results
+    pub fn clear(&self) -> Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for ParseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::get_parser;
+    use tempfile::tempdir;
+
+    fn parse(path: &Path) -> ParseResult {
+        get_parser(ShellType::Bash).parse(&std::fs::read_to_string(path).unwrap())
+    }
+
+    #[test]
+    fn test_parse_cache_hit_and_miss() {
+        let dir = tempdir().unwrap();
+        let cache = ParseCache::in_dir(dir.path().join("cache"));
+        let rc_file = dir.path().join(".bashrc");
+        std::fs::write(&rc_file, "# tools\nalias ll='ls -la'\nexport EDITOR=vim\n").unwrap();
+
+        let key = ParseCacheKey::for_file(&rc_file, ShellType::Bash, true, false).unwrap();
+        assert!(cache.get(&key).is_none());
+
+        let result = parse(&rc_file);
+        cache.put(&key, &result).unwrap();
+        let cached = cache.get(&key).unwrap();
+        assert_eq!(cached.entries.len(), result.entries.len());
+        assert_eq!(cached.entries[0].value, "# tools\nalias ll='ls -la'");
+        assert_eq!(cached.entries[1].line_number, Some(3));
+
+        // Other shells and parser options don't share results
+        let zsh = ParseCacheKey::for_file(&rc_file, ShellType::Zsh, true, false).unwrap();
+        assert!(cache.get(&zsh).is_none());
+        let unmerged = ParseCacheKey::for_file(&rc_file, ShellType::Bash, false, false).unwrap();
+        assert!(cache.get(&unmerged).is_none());
+        let attached = ParseCacheKey::for_file(&rc_file, ShellType::Bash, true, true).unwrap();
+        assert!(cache.get(&attached).is_none());
+        assert_ne!(attached.file_name(), key.file_name());
+    }
+
+    #[test]
+    fn test_parse_cache_invalidated_by_changes() {
+        let dir = tempdir().unwrap();
+        let cache = ParseCache::in_dir(dir.path().join("cache"));
+        let rc_file = dir.path().join(".bashrc");
+        std::fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+
+        let key = ParseCacheKey::for_file(&rc_file, ShellType::Bash, true, false).unwrap();
+        cache.put(&key, &parse(&rc_file)).unwrap();
+
+        std::fs::write(&rc_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+        let changed = ParseCacheKey::for_file(&rc_file, ShellType::Bash, true, false).unwrap();
+        assert_ne!(changed, key);
+        assert!(cache.get(&changed).is_none());
+
+        // Corrupt cache files are a miss, not an error
+        std::fs::write(dir.path().join("cache").join(changed.file_name()), "{{").unwrap();
+        assert!(cache.get(&changed).is_none());
+
+        cache.clear().unwrap();
+        assert!(!dir.path().join("cache").exists());
+        cache.clear().unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+//! Dead definition checker
+
+use super::{CheckIssue, CheckResult, Checker, DEAD_DEFINITION};
+use crate::model::{Entry, EntryType};
+
+/// Checks for definitions that a later `unalias`/`unset`/`Remove-Item`
+/// removes again, so they never take effect past that line.
+///
+/// Only removals at the top level are Unset entries; one inside an `if`
+/// block is conditional and part of a Code entry, and is not reported.
+pub struct DeadDefinitionChecker;
+
+/// How the removal reads in the message: "unaliased on line 40"
+fn removal_verb(unset: &Entry) -> &'static str {
+    let body = unset.canonical_value();
+    if body.starts_with("unalias") {
+        "unaliased"
+    } else if body.starts_with("unset") {
+        "unset"
+    } else {
+        "removed"
+    }
+}
+
+impl Checker for DeadDefinitionChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+
+        for (index, definition) in entries.iter().enumerate() {
+            if matches!(
+                definition.entry_type,
+                EntryType::Unset | EntryType::Code | EntryType::Comment
+            ) {
+                continue;
+            }
+            let Some(unset) = entries[index + 1..].iter().find(|e| {
+                e.unset_types().contains(&definition.entry_type) && e.name == definition.name
+            }) else {
+                continue;
+            };
+
+            let place = |e: &Entry| match e.line_number {
+                Some(line) => format!(" on line {}", line),
+                None => String::new(),
+            };
+            let mut issue = CheckIssue::warning(format!(
+                "{} '{}' is defined{} but {}{}",
+                definition.entry_type,
+                definition.name,
+                place(definition),
+                removal_verb(unset),
+                place(unset)
+            ))
+            .with_code(DEAD_DEFINITION)
+            .with_entry(&definition.name);
+            if let Some(line) = definition.line_number {
+                issue = issue.with_line(line);
+            }
+            result.add_issue(issue);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn check(shell_type: ShellType, content: &str) -> Vec<String> {
+        let entries = get_parser(shell_type).parse(content).entries;
+        DeadDefinitionChecker
+            .check(&entries)
+            .issues
+            .into_iter()
+            .map(|i| i.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_define_then_unalias() {
+        let content = "alias x='ls'\nexport EDITOR=vim\n\nunalias x\n";
+        assert_eq!(
+            check(ShellType::Bash, content),
+            ["alias 'x' is defined on line 1 but unaliased on line 4"]
+        );
+    }
+
+    #[test]
+    fn test_define_then_unset() {
+        let content = "export TMP_DIR=/tmp/x\narr=(a b)\ngreet() { echo hi; }\nunset TMP_DIR\nunset arr\nunset -f greet\n";
+        assert_eq!(
+            check(ShellType::Bash, content),
+            [
+                "env 'TMP_DIR' is defined on line 1 but unset on line 4",
+                "array 'arr' is defined on line 2 but unset on line 5",
+                "func 'greet' is defined on line 3 but unset on line 6",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redefinition_after_unset_is_live() {
+        let content = "alias x='ls'\nunalias x\nalias x='ls -la'\n";
+        assert_eq!(
+            check(ShellType::Bash, content),
+            ["alias 'x' is defined on line 1 but unaliased on line 2"]
+        );
+        // `unset -f` removes the function, not a variable of the same name
+        assert!(check(ShellType::Bash, "export x=1\nunset -f x\n").is_empty());
+    }
+
+    #[test]
+    fn test_conditional_unalias_is_not_reported() {
+        let content = "alias x='ls'\nif [ -n \"$SSH\" ]; then\n  unalias x\nfi\n";
+        assert!(check(ShellType::Bash, content).is_empty());
+    }
+
+    #[test]
+    fn test_powershell_removals() {
+        let content = "$env:TEMP_TOKEN = 'abc'\nSet-Alias ll Get-ChildItem\nRemove-Item Env:TEMP_TOKEN\nRemove-Alias -Name ll -Force\n";
+        assert_eq!(
+            check(ShellType::PowerShell, content),
+            [
+                "env 'TEMP_TOKEN' is defined on line 1 but removed on line 3",
+                "alias 'll' is defined on line 2 but removed on line 4",
+            ]
+        );
+    }
+}
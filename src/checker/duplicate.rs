@@ -1,9 +1,51 @@
 //! Duplicate definition checker
 
-use super::{CheckIssue, CheckResult, Checker, Severity};
+use super::{
+    CheckIssue, CheckResult, Checker, Severity, DUPLICATE_ACROSS_FILES, DUPLICATE_DEFINITION,
+};
 use crate::model::{Entry, EntryType};
 use std::collections::HashMap;
 
+/// What makes two entries the same one (see [`Entry::same_identity`]):
+/// the name, or the lines for code and comments
+#[derive(PartialEq, Eq, Hash)]
+enum Identity<'a> {
+    Named(EntryType, &'a str),
+    Lines(EntryType, Option<usize>, Option<usize>),
+}
+
+impl<'a> Identity<'a> {
+    fn of(entry: &'a Entry) -> Self {
+        match entry.entry_type {
+            EntryType::Comment | EntryType::Code => Identity::Lines(
+                entry.entry_type,
+                entry.line_number,
+                entry.end_line.or(entry.line_number),
+            ),
+            _ => Identity::Named(entry.entry_type, &entry.name),
+        }
+    }
+}
+
+/// Group `items` by the identity of their entry in a single pass, groups
+/// in order of first appearance
+fn group_by_identity<'a, T>(
+    items: impl IntoIterator<Item = (T, &'a Entry)>,
+) -> Vec<Vec<(T, &'a Entry)>> {
+    let mut index: HashMap<Identity<'a>, usize> = HashMap::new();
+    let mut groups: Vec<Vec<(T, &Entry)>> = Vec::new();
+    for (item, entry) in items {
+        match index.get(&Identity::of(entry)) {
+            Some(&i) => groups[i].push((item, entry)),
+            None => {
+                index.insert(Identity::of(entry), groups.len());
+                groups.push(vec![(item, entry)]);
+            }
+        }
+    }
+    groups
+}
+
 /// Checks for duplicate definitions
 pub struct DuplicateChecker;
 
@@ -11,30 +53,31 @@ impl Checker for DuplicateChecker {
     fn check(&self, entries: &[Entry]) -> CheckResult {
         let mut result = CheckResult::new();
 
-        // Group by type and name
-        let mut seen: HashMap<(EntryType, &str), Vec<&Entry>> = HashMap::new();
-
-        for entry in entries {
-            seen.entry((entry.entry_type, &entry.name))
-                .or_default()
-                .push(entry);
-        }
-
         // Report duplicates
-        for ((entry_type, name), occurrences) in seen {
+        for group in group_by_identity(entries.iter().map(|e| ((), e))) {
+            let occurrences: Vec<&Entry> = group.into_iter().map(|(_, e)| e).collect();
+            let (entry_type, name) = (occurrences[0].entry_type, &occurrences[0].name);
             if occurrences.len() > 1 {
                 let lines: Vec<String> = occurrences
                     .iter()
                     .filter_map(|e| e.line_number.map(|l| l.to_string()))
                     .collect();
 
+                // Definitions that only differ in quoting are redundant, not conflicting
+                let first_value = occurrences[0].canonical_value();
+                let identical = occurrences
+                    .iter()
+                    .all(|e| e.canonical_value() == first_value);
+
                 let issue = CheckIssue {
+                    code: Some(DUPLICATE_DEFINITION),
                     severity: Severity::Warning,
                     message: format!(
-                        "Duplicate {} '{}' defined on lines: {}",
+                        "Duplicate {} '{}' defined on lines: {}{}",
                         entry_type,
                         name,
-                        lines.join(", ")
+                        lines.join(", "),
+                        if identical { " (identical values)" } else { "" }
                     ),
                     line_number: occurrences.first().and_then(|e| e.line_number),
                     entry_name: Some(name.to_string()),
@@ -48,9 +91,53 @@ impl Checker for DuplicateChecker {
     }
 }
 
+/// Checks several files (each a label and its entries) for the same
+/// entry being defined in more than one of them.
+///
+/// Duplicates within one file are left to [`DuplicateChecker`]. The issue's
+/// line number refers to the first file defining the entry.
+pub fn check_across_files(files: &[(String, Vec<Entry>)]) -> CheckResult {
+    let mut result = CheckResult::new();
+
+    // Definitions of the same entry with their file
+    let definitions = files.iter().flat_map(|(label, entries)| {
+        entries
+            .iter()
+            .filter(|e| !matches!(e.entry_type, EntryType::Code | EntryType::Comment))
+            .map(move |e| (label.as_str(), e))
+    });
+
+    for occurrences in group_by_identity(definitions) {
+        let (first_file, first) = occurrences[0];
+        if occurrences.iter().all(|(file, _)| *file == first_file) {
+            continue;
+        }
+        let places: Vec<String> = occurrences
+            .iter()
+            .map(|(file, e)| match e.line_number {
+                Some(line) => format!("{}:{}", file, line),
+                None => file.to_string(),
+            })
+            .collect();
+        let mut issue = CheckIssue::warning(format!(
+            "{} '{}' is defined in several files: {}",
+            first.entry_type,
+            first.name,
+            places.join(", ")
+        ))
+        .with_code(DUPLICATE_ACROSS_FILES)
+        .with_entry(&first.name);
+        issue.line_number = first.line_number;
+        result.add_issue(issue);
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::EntryType;
 
     #[test]
     fn test_no_duplicates() {
@@ -93,4 +180,93 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_duplicate_alias_identical_canonical_value() {
+        let entries = vec![
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into())
+                .with_line_number(1),
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll=\"ls -la\"".into())
+                .with_line_number(5),
+        ];
+
+        let result = DuplicateChecker.check(&entries);
+
+        assert_eq!(result.issues.len(), 1);
+        assert!(result.issues[0].message.contains("(identical values)"));
+    }
+
+    #[test]
+    fn test_check_across_files() {
+        let files = vec![
+            (
+                ".bashrc".to_string(),
+                vec![
+                    Entry::new(EntryType::Alias, "ll".into(), "ls -la".into()).with_line_number(1),
+                    Entry::new(EntryType::Alias, "gs".into(), "git status".into())
+                        .with_line_number(2),
+                    Entry::new(EntryType::Alias, "gs".into(), "git status".into())
+                        .with_line_number(3),
+                    Entry::new(EntryType::Code, "L4".into(), "echo hi".into()).with_line_number(4),
+                ],
+            ),
+            (
+                ".bash_aliases".to_string(),
+                vec![
+                    Entry::new(EntryType::Code, "L4".into(), "echo hi".into()).with_line_number(4),
+                    Entry::new(EntryType::Alias, "ll".into(), "ls -l".into()).with_line_number(7),
+                ],
+            ),
+        ];
+
+        let result = check_across_files(&files);
+
+        // gs is only duplicated within .bashrc; code lines are not definitions
+        assert_eq!(result.issues.len(), 1);
+        assert_eq!(
+            result.issues[0].message,
+            "alias 'll' is defined in several files: .bashrc:1, .bash_aliases:7"
+        );
+        assert_eq!(result.issues[0].line_number, Some(1));
+    }
+
+    #[test]
+    fn test_many_entries() {
+        // Every 100th alias is defined again at the end of the file
+        let mut entries: Vec<Entry> = (0..5000)
+            .map(|i| {
+                Entry::new(
+                    EntryType::Alias,
+                    format!("a{}", i),
+                    format!("alias a{}='echo {}'", i, i),
+                )
+                .with_line_number(i + 1)
+            })
+            .collect();
+        for i in (0..5000).step_by(100) {
+            entries.push(
+                Entry::new(
+                    EntryType::Alias,
+                    format!("a{}", i),
+                    format!("alias a{}='echo again'", i),
+                )
+                .with_line_number(entries.len() + 1),
+            );
+        }
+
+        let start = std::time::Instant::now();
+        let result = DuplicateChecker.check(&entries);
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+
+        assert_eq!(result.issues.len(), 50);
+        assert_eq!(
+            result.issues[0].message,
+            "Duplicate alias 'a0' defined on lines: 1, 5001"
+        );
+        assert_eq!(
+            result.issues[49].message,
+            "Duplicate alias 'a4900' defined on lines: 4901, 5050"
+        );
+        assert!(result.issues.iter().all(|i| i.line_number.unwrap() <= 4901));
+    }
 }
@@ -0,0 +1,191 @@
+//! Rule registry for `wenv lint`
+//!
+//! Each rule wraps one checker under a name that the `[lint]` config table
+//! uses to set its level. Adding a rule means adding an entry to
+//! [`LINT_RULES`].
+
+use std::path::Path;
+
+use super::{
+    CheckIssue, Checker, DuplicateChecker, MissingSourceChecker, ShadowedBuiltinChecker,
+    StyleChecker, UnusedVarChecker,
+};
+use crate::model::{Entry, LintConfig, LintLevel, ShellType};
+
+/// What a rule checks: one parsed file
+pub struct LintInput<'a> {
+    pub entries: &'a [Entry],
+    pub shell_type: ShellType,
+    pub path: &'a Path,
+    /// The raw file, for rules looking at whitespace
+    pub content: &'a str,
+}
+
+/// A named, individually configurable check
+pub struct LintRule {
+    /// Key in the `[lint]` table and the tag of its findings
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Level when the config does not set one
+    pub default_level: LintLevel,
+    check: fn(&LintInput) -> Vec<CheckIssue>,
+}
+
+impl LintRule {
+    /// The level `config` sets for this rule, or its default
+    pub fn level(&self, config: &LintConfig) -> LintLevel {
+        config
+            .rules
+            .get(self.name)
+            .copied()
+            .unwrap_or(self.default_level)
+    }
+}
+
+/// Every lint rule, in the order their findings are reported
+pub const LINT_RULES: &[LintRule] = &[
+    LintRule {
+        name: "duplicate",
+        description: "The same entry defined more than once",
+        default_level: LintLevel::Warning,
+        check: |input| DuplicateChecker.check(input.entries).issues,
+    },
+    LintRule {
+        name: "shadowed-builtin",
+        description: "An alias or function named like a shell builtin",
+        default_level: LintLevel::Warning,
+        check: |input| {
+            ShadowedBuiltinChecker::new(input.shell_type)
+                .check(input.entries)
+                .issues
+        },
+    },
+    LintRule {
+        name: "missing-source",
+        description: "A sourced file that does not exist",
+        default_level: LintLevel::Error,
+        check: |input| {
+            MissingSourceChecker::new(input.path)
+                .check(input.entries)
+                .issues
+        },
+    },
+    LintRule {
+        name: "unused-var",
+        description: "A shell variable set but never used or exported",
+        default_level: LintLevel::Warning,
+        check: |input| {
+            UnusedVarChecker::new(input.shell_type)
+                .check(input.entries)
+                .issues
+        },
+    },
+    LintRule {
+        name: "style",
+        description: "Trailing whitespace and mixed indentation",
+        default_level: LintLevel::Off,
+        check: |input| StyleChecker::new(input.content).check(input.entries).issues,
+    },
+];
+
+/// Names of the rules in [`LINT_RULES`]
+pub fn rule_names() -> Vec<&'static str> {
+    LINT_RULES.iter().map(|rule| rule.name).collect()
+}
+
+/// A finding and the rule that made it
+#[derive(Debug)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    /// Severity set from the rule's level
+    pub issue: CheckIssue,
+}
+
+/// Run the rules `config` leaves on over `input`, findings in rule order
+/// and then line order
+pub fn lint(input: &LintInput, config: &LintConfig) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for rule in LINT_RULES {
+        let Some(severity) = rule.level(config).severity() else {
+            continue;
+        };
+        let mut issues = (rule.check)(input);
+        issues.sort_by_key(|issue| issue.line_number.unwrap_or(usize::MAX));
+        findings.extend(issues.into_iter().map(|mut issue| {
+            issue.severity = severity;
+            LintFinding {
+                rule: rule.name,
+                issue,
+            }
+        }));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Severity;
+    use crate::parser::get_parser;
+
+    const CONTENT: &str = "alias ll='ls'\nalias ll='ls -la'\nalias cd='z'\nstray=1 \n";
+
+    fn rules_found(config: &LintConfig) -> Vec<&'static str> {
+        let entries = get_parser(ShellType::Bash).parse(CONTENT).entries;
+        let input = LintInput {
+            entries: &entries,
+            shell_type: ShellType::Bash,
+            path: Path::new("/nonexistent/.bashrc"),
+            content: CONTENT,
+        };
+        lint(&input, config).iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_default_levels() {
+        assert_eq!(
+            rules_found(&LintConfig::default()),
+            ["duplicate", "shadowed-builtin", "unused-var"]
+        );
+    }
+
+    #[test]
+    fn test_rules_toggle_individually() {
+        let mut config = LintConfig::default();
+        config.rules.insert("duplicate".into(), LintLevel::Off);
+        config.rules.insert("style".into(), LintLevel::Warning);
+        assert_eq!(
+            rules_found(&config),
+            ["shadowed-builtin", "unused-var", "style"]
+        );
+
+        for rule in rule_names() {
+            config.rules.insert(rule.to_string(), LintLevel::Off);
+        }
+        assert!(rules_found(&config).is_empty());
+    }
+
+    #[test]
+    fn test_level_sets_severity() {
+        let mut config = LintConfig::default();
+        config
+            .rules
+            .insert("shadowed-builtin".into(), LintLevel::Error);
+        let entries = get_parser(ShellType::Bash).parse(CONTENT).entries;
+        let input = LintInput {
+            entries: &entries,
+            shell_type: ShellType::Bash,
+            path: Path::new("/nonexistent/.bashrc"),
+            content: CONTENT,
+        };
+        let findings = lint(&input, &config);
+        let severity = |rule| {
+            findings
+                .iter()
+                .find(|f| f.rule == rule)
+                .map(|f| f.issue.severity)
+        };
+        assert_eq!(severity("shadowed-builtin"), Some(Severity::Error));
+        assert_eq!(severity("duplicate"), Some(Severity::Warning));
+    }
+}
@@ -0,0 +1,74 @@
+//! Missing source file checker
+
+use std::path::PathBuf;
+
+use super::{CheckIssue, CheckResult, Checker, MISSING_SOURCE};
+use crate::model::{Entry, EntryType};
+use crate::utils::source_tree::resolve_source;
+
+/// Reports `source` lines whose file does not exist, resolved from the
+/// directory of the checked file (see [`resolve_source`]). Arguments that
+/// depend on other variables and PowerShell module imports are skipped.
+pub struct MissingSourceChecker {
+    file: PathBuf,
+}
+
+impl MissingSourceChecker {
+    /// Checker for the entries of `file`
+    pub fn new(file: impl Into<PathBuf>) -> Self {
+        Self { file: file.into() }
+    }
+}
+
+impl Checker for MissingSourceChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+
+        for entry in entries {
+            if entry.entry_type != EntryType::Source || entry.is_module_import() {
+                continue;
+            }
+            let target = entry.canonical_value();
+            let Some(path) = resolve_source(&target, &self.file) else {
+                continue;
+            };
+            if path.is_file() {
+                continue;
+            }
+            let mut issue = CheckIssue::error(format!("Sourced file '{}' does not exist", target))
+                .with_code(MISSING_SOURCE)
+                .with_entry(&entry.name);
+            if let Some(line) = entry.line_number {
+                issue = issue.with_line(line);
+            }
+            result.add_issue(issue);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    #[test]
+    fn test_missing_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("aliases.sh"), "").unwrap();
+        let content = "source aliases.sh\n. missing.sh\nsource \"$ZSH/oh-my-zsh.sh\"\n";
+        let entries = get_parser(ShellType::Bash).parse(content).entries;
+
+        let issues = MissingSourceChecker::new(dir.path().join(".bashrc"))
+            .check(&entries)
+            .issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Sourced file 'missing.sh' does not exist"
+        );
+        assert_eq!(issues[0].line_number, Some(2));
+    }
+}
@@ -1,10 +1,54 @@
 //! Checker module for validating configuration files
 
+mod dead_definition;
 mod duplicate;
-
-pub use duplicate::DuplicateChecker;
-
-use crate::model::Entry;
+pub mod lint;
+mod missing_source;
+mod path_segments;
+mod shadowed_builtin;
+mod style;
+mod undefined_vars;
+mod unused_vars;
+
+pub use dead_definition::DeadDefinitionChecker;
+pub use duplicate::{check_across_files, DuplicateChecker};
+pub use missing_source::MissingSourceChecker;
+pub use path_segments::PathSegmentChecker;
+pub use shadowed_builtin::ShadowedBuiltinChecker;
+pub use style::StyleChecker;
+pub use undefined_vars::UndefinedVarChecker;
+pub use unused_vars::UnusedVarChecker;
+
+pub use crate::model::Severity;
+
+use crate::model::{Entry, ParseWarning, ShellType};
+
+/// Codes of the issues the checkers report, for `checker.ignore_codes`
+pub const ISSUE_CODES: &[&str] = &[
+    DUPLICATE_DEFINITION,
+    DUPLICATE_ACROSS_FILES,
+    DEAD_DEFINITION,
+    DUPLICATE_PATH_SEGMENT,
+    MISSING_PATH_DIRECTORY,
+    UNDEFINED_VARIABLE,
+    TRAILING_WHITESPACE,
+    MIXED_INDENTATION,
+    SHADOWED_BUILTIN,
+    MISSING_SOURCE,
+    UNUSED_VARIABLE,
+];
+
+pub const DUPLICATE_DEFINITION: &str = "duplicate-definition";
+pub const DUPLICATE_ACROSS_FILES: &str = "duplicate-across-files";
+pub const DEAD_DEFINITION: &str = "dead-definition";
+pub const DUPLICATE_PATH_SEGMENT: &str = "duplicate-path-segment";
+pub const MISSING_PATH_DIRECTORY: &str = "missing-path-directory";
+pub const UNDEFINED_VARIABLE: &str = "undefined-variable";
+pub const TRAILING_WHITESPACE: &str = "trailing-whitespace";
+pub const MIXED_INDENTATION: &str = "mixed-indentation";
+pub const SHADOWED_BUILTIN: &str = "shadowed-builtin";
+pub const MISSING_SOURCE: &str = "missing-source";
+pub const UNUSED_VARIABLE: &str = "unused-variable";
 
 /// Check result
 #[derive(Debug)]
@@ -36,6 +80,16 @@ impl CheckResult {
     pub fn is_ok(&self) -> bool {
         self.issues.is_empty()
     }
+
+    /// Drop issues whose code is in `codes`
+    pub fn ignoring(mut self, codes: &[String]) -> Self {
+        self.issues.retain(|issue| {
+            issue
+                .code
+                .is_none_or(|code| !codes.iter().any(|c| c == code))
+        });
+        self
+    }
 }
 
 impl Default for CheckResult {
@@ -44,16 +98,11 @@ impl Default for CheckResult {
     }
 }
 
-/// Issue severity
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Severity {
-    Warning,
-    Error,
-}
-
 /// A single check issue
 #[derive(Debug)]
 pub struct CheckIssue {
+    /// Stable code of the kind of issue (see [`ISSUE_CODES`])
+    pub code: Option<&'static str>,
     pub severity: Severity,
     pub message: String,
     pub line_number: Option<usize>,
@@ -63,6 +112,7 @@ pub struct CheckIssue {
 impl CheckIssue {
     pub fn warning(message: impl Into<String>) -> Self {
         Self {
+            code: None,
             severity: Severity::Warning,
             message: message.into(),
             line_number: None,
@@ -72,6 +122,7 @@ impl CheckIssue {
 
     pub fn error(message: impl Into<String>) -> Self {
         Self {
+            code: None,
             severity: Severity::Error,
             message: message.into(),
             line_number: None,
@@ -79,6 +130,11 @@ impl CheckIssue {
         }
     }
 
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
     pub fn with_line(mut self, line: usize) -> Self {
         self.line_number = Some(line);
         self
@@ -90,13 +146,27 @@ impl CheckIssue {
     }
 }
 
+impl From<&ParseWarning> for CheckIssue {
+    /// A parse warning reported alongside the checker issues, keeping its
+    /// code and severity
+    fn from(warning: &ParseWarning) -> Self {
+        Self {
+            code: Some(warning.code.as_str()),
+            severity: warning.severity,
+            message: warning.message.clone(),
+            line_number: Some(warning.line_number),
+            entry_name: None,
+        }
+    }
+}
+
 /// Trait for checkers
 pub trait Checker {
     fn check(&self, entries: &[Entry]) -> CheckResult;
 }
 
 /// Run all checks on entries
-pub fn check_all(entries: &[Entry]) -> CheckResult {
+pub fn check_all(entries: &[Entry], shell_type: ShellType) -> CheckResult {
     let mut result = CheckResult::new();
 
     // Run duplicate check
@@ -104,5 +174,19 @@ pub fn check_all(entries: &[Entry]) -> CheckResult {
     let dup_result = dup_checker.check(entries);
     result.issues.extend(dup_result.issues);
 
+    // Run dead definition check
+    let dead_result = DeadDefinitionChecker.check(entries);
+    result.issues.extend(dead_result.issues);
+
+    // Run PATH segment check
+    let path_checker = PathSegmentChecker::new(shell_type);
+    let path_result = path_checker.check(entries);
+    result.issues.extend(path_result.issues);
+
+    // Run undefined variable check
+    let var_checker = UndefinedVarChecker::new(shell_type);
+    let var_result = var_checker.check(entries);
+    result.issues.extend(var_result.issues);
+
     result
 }
@@ -0,0 +1,168 @@
+//! Duplicate PATH segment checker
+
+use super::{CheckIssue, CheckResult, Checker, DUPLICATE_PATH_SEGMENT, MISSING_PATH_DIRECTORY};
+use crate::model::{Entry, EntryType, ShellType};
+use crate::utils::path::expand_tilde;
+use crate::utils::path_merge::{has_variable_ref, path_separator, split_path_segments};
+use std::collections::HashSet;
+
+/// Checks PATH-like variables (`PATH`, `MANPATH`, `PYTHONPATH`, ...) for
+/// segments repeated within a single assignment.
+///
+/// Optionally also warns about segments pointing to directories that do not
+/// exist. Segments containing variable references (`$PATH`, `$env:PATH`,
+/// `%APPDATA%`) are never checked for existence.
+pub struct PathSegmentChecker {
+    separator: char,
+    check_existence: bool,
+}
+
+impl PathSegmentChecker {
+    pub fn new(shell_type: ShellType) -> Self {
+        Self {
+            separator: path_separator(shell_type),
+            check_existence: false,
+        }
+    }
+
+    /// Also warn about segments whose directory does not exist
+    pub fn with_existence_check(mut self, enabled: bool) -> Self {
+        self.check_existence = enabled;
+        self
+    }
+
+    fn is_path_like(name: &str) -> bool {
+        name.to_uppercase().ends_with("PATH")
+    }
+}
+
+impl Checker for PathSegmentChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+
+        for entry in entries {
+            if entry.entry_type != EntryType::EnvVar || !Self::is_path_like(&entry.name) {
+                continue;
+            }
+
+            let value = entry.canonical_value();
+            let mut seen: HashSet<&str> = HashSet::new();
+
+            for segment in split_path_segments(&value, self.separator) {
+                if !seen.insert(segment) {
+                    let mut issue = CheckIssue::warning(format!(
+                        "Duplicate segment '{}' in {}",
+                        segment, entry.name
+                    ))
+                    .with_code(DUPLICATE_PATH_SEGMENT)
+                    .with_entry(&entry.name);
+                    if let Some(line) = entry.line_number {
+                        issue = issue.with_line(line);
+                    }
+                    result.add_issue(issue);
+                    continue;
+                }
+
+                if self.check_existence
+                    && !has_variable_ref(segment)
+                    && !expand_tilde(segment).is_dir()
+                {
+                    let mut issue = CheckIssue::warning(format!(
+                        "Directory '{}' in {} does not exist",
+                        segment, entry.name
+                    ))
+                    .with_code(MISSING_PATH_DIRECTORY)
+                    .with_entry(&entry.name);
+                    if let Some(line) = entry.line_number {
+                        issue = issue.with_line(line);
+                    }
+                    result.add_issue(issue);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_duplicate_segment() {
+        let entries = vec![Entry::new(
+            EntryType::EnvVar,
+            "PATH".into(),
+            "export PATH=$PATH:/a:/a:/b".into(),
+        )
+        .with_line_number(3)];
+
+        let result = PathSegmentChecker::new(ShellType::Bash).check(&entries);
+
+        assert_eq!(result.issues.len(), 1);
+        assert!(result.issues[0].message.contains("'/a'"));
+        assert_eq!(result.issues[0].line_number, Some(3));
+    }
+
+    #[test]
+    fn test_pwsh_duplicate_segment() {
+        let entries = vec![Entry::new(
+            EntryType::EnvVar,
+            "Path".into(),
+            r#"$env:Path = "C:\bin;$env:Path;C:\bin;C:\bin""#.into(),
+        )];
+
+        let result = PathSegmentChecker::new(ShellType::PowerShell).check(&entries);
+
+        // One issue per repeated occurrence
+        assert_eq!(result.issues.len(), 2);
+        assert!(result.issues[0].message.contains(r"'C:\bin'"));
+    }
+
+    #[test]
+    fn test_path_expansion_left_intact() {
+        let entries = vec![Entry::new(
+            EntryType::EnvVar,
+            "PATH".into(),
+            "export PATH=\"$HOME/bin:${PATH}\"".into(),
+        )];
+
+        let result = PathSegmentChecker::new(ShellType::Bash)
+            .with_existence_check(true)
+            .check(&entries);
+
+        // Variable references are neither duplicates nor checked on disk
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_nonexistent_directory_behind_flag() {
+        let entries = vec![Entry::new(
+            EntryType::EnvVar,
+            "PATH".into(),
+            "export PATH=/definitely/not/a/real/dir:$PATH".into(),
+        )];
+
+        let without_flag = PathSegmentChecker::new(ShellType::Bash).check(&entries);
+        assert!(without_flag.is_ok());
+
+        let with_flag = PathSegmentChecker::new(ShellType::Bash)
+            .with_existence_check(true)
+            .check(&entries);
+        assert_eq!(with_flag.issues.len(), 1);
+        assert!(with_flag.issues[0].message.contains("does not exist"));
+    }
+
+    #[test]
+    fn test_ignores_non_path_vars() {
+        let entries = vec![Entry::new(
+            EntryType::EnvVar,
+            "EDITOR".into(),
+            "export EDITOR=vim:vim".into(),
+        )];
+
+        let result = PathSegmentChecker::new(ShellType::Bash).check(&entries);
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+//! Shadowed builtin checker
+
+use super::{CheckIssue, CheckResult, Checker, SHADOWED_BUILTIN};
+use crate::model::{Entry, EntryType, ShellType};
+
+/// Bash/Zsh builtins
+const BASH_BUILTINS: &[&str] = &[
+    "alias", "bg", "bind", "break", "builtin", "cd", "command", "continue", "declare", "echo",
+    "eval", "exec", "exit", "export", "false", "fg", "getopts", "hash", "history", "jobs", "kill",
+    "let", "local", "printf", "pwd", "read", "readonly", "return", "set", "shift", "source",
+    "test", "times", "trap", "true", "type", "typeset", "ulimit", "umask", "unalias", "unset",
+    "wait",
+];
+
+/// PowerShell cmdlets a profile is unlikely to mean to replace, compared
+/// case-insensitively
+const PWSH_BUILTINS: &[&str] = &[
+    "Copy-Item",
+    "ForEach-Object",
+    "Get-ChildItem",
+    "Get-Command",
+    "Get-Content",
+    "Get-Help",
+    "Get-Item",
+    "Get-Location",
+    "Import-Module",
+    "Invoke-Expression",
+    "Move-Item",
+    "New-Item",
+    "Remove-Item",
+    "Select-Object",
+    "Set-Content",
+    "Set-Location",
+    "Where-Object",
+    "Write-Host",
+    "Write-Output",
+];
+
+/// Warns about aliases and functions named like a shell builtin, which
+/// replace the builtin for the rest of the session (`alias cd=...`,
+/// `function Get-ChildItem`).
+pub struct ShadowedBuiltinChecker {
+    shell_type: ShellType,
+}
+
+impl ShadowedBuiltinChecker {
+    pub fn new(shell_type: ShellType) -> Self {
+        Self { shell_type }
+    }
+
+    fn is_builtin(&self, name: &str) -> bool {
+        match self.shell_type {
+            ShellType::PowerShell => PWSH_BUILTINS.iter().any(|b| b.eq_ignore_ascii_case(name)),
+            ShellType::DotEnv => false,
+            _ => BASH_BUILTINS.contains(&name),
+        }
+    }
+}
+
+impl Checker for ShadowedBuiltinChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+
+        for entry in entries {
+            if !matches!(entry.entry_type, EntryType::Alias | EntryType::Function)
+                || !self.is_builtin(&entry.name)
+            {
+                continue;
+            }
+            let mut issue = CheckIssue::warning(format!(
+                "{} '{}' shadows the builtin of the same name",
+                entry.entry_type, entry.name
+            ))
+            .with_code(SHADOWED_BUILTIN)
+            .with_entry(&entry.name);
+            if let Some(line) = entry.line_number {
+                issue = issue.with_line(line);
+            }
+            result.add_issue(issue);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::get_parser;
+
+    fn shadowed(shell_type: ShellType, content: &str) -> Vec<String> {
+        let entries = get_parser(shell_type).parse(content).entries;
+        ShadowedBuiltinChecker::new(shell_type)
+            .check(&entries)
+            .issues
+            .into_iter()
+            .filter_map(|i| i.entry_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_bash_builtins() {
+        let content = "alias cd='z'\nalias ll='ls -la'\necho() { printf '%s\\n' \"$*\"; }\n";
+        assert_eq!(shadowed(ShellType::Bash, content), ["cd", "echo"]);
+    }
+
+    #[test]
+    fn test_powershell_cmdlets() {
+        let content = "function get-childitem { }\nSet-Alias ls Get-ChildItem\n";
+        assert_eq!(shadowed(ShellType::PowerShell, content), ["get-childitem"]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Whitespace style checker
+
+use super::{CheckIssue, CheckResult, Checker, MIXED_INDENTATION, TRAILING_WHITESPACE};
+use crate::model::{Entry, EntryType};
+
+/// Checks the raw file for trailing whitespace and for function or code
+/// bodies indenting with both tabs and spaces.
+///
+/// Not part of [`check_all`](super::check_all): style findings are noisy on
+/// hand-maintained files, so callers opt in (`wenv check --style`). Regions
+/// kept verbatim (see [`Entry::is_guarded`]) are skipped.
+pub struct StyleChecker<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> StyleChecker<'a> {
+    /// Checker for the file `content` the entries were parsed from
+    pub fn new(content: &'a str) -> Self {
+        Self {
+            lines: content.lines().collect(),
+        }
+    }
+
+    /// Lines (1-based, inclusive) covered by `entry`
+    fn span(entry: &Entry) -> Option<(usize, usize)> {
+        let start = entry.line_number?;
+        Some((start, entry.end_line.unwrap_or(start).max(start)))
+    }
+
+    /// The raw line `number` (1-based)
+    fn line(&self, number: usize) -> Option<&'a str> {
+        self.lines.get(number.checked_sub(1)?).copied()
+    }
+
+    /// First line of `entry` indented differently from the body's first
+    /// indented line, or indented with tabs and spaces at once
+    fn mixed_indent_line(&self, entry: &Entry) -> Option<usize> {
+        let (start, end) = Self::span(entry)?;
+        let mut style = None;
+        for number in start..=end {
+            let line = self.line(number)?;
+            let indent = &line[..line.len() - line.trim_start().len()];
+            if indent.is_empty() || line.trim().is_empty() {
+                continue;
+            }
+            let (tabs, spaces) = (indent.contains('\t'), indent.contains(' '));
+            if tabs && spaces {
+                return Some(number);
+            }
+            match style {
+                None => style = Some(tabs),
+                Some(uses_tabs) if uses_tabs != tabs => return Some(number),
+                Some(_) => {}
+            }
+        }
+        None
+    }
+}
+
+impl Checker for StyleChecker<'_> {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+        let guarded: Vec<(usize, usize)> = entries
+            .iter()
+            .filter(|e| e.is_guarded())
+            .filter_map(Self::span)
+            .collect();
+        let is_guarded = |number: usize| {
+            guarded
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&number))
+        };
+
+        for (index, line) in self.lines.iter().enumerate() {
+            let number = index + 1;
+            if line.trim_end().len() < line.len() && !is_guarded(number) {
+                result.add_issue(
+                    CheckIssue::warning("Trailing whitespace")
+                        .with_code(TRAILING_WHITESPACE)
+                        .with_line(number),
+                );
+            }
+        }
+
+        for entry in entries {
+            if !matches!(entry.entry_type, EntryType::Function | EntryType::Code)
+                || entry.is_guarded()
+            {
+                continue;
+            }
+            if let Some(number) = self.mixed_indent_line(entry) {
+                result.add_issue(
+                    CheckIssue::warning(format!(
+                        "Mixed tabs and spaces in indentation of {}",
+                        entry.name
+                    ))
+                    .with_code(MIXED_INDENTATION)
+                    .with_entry(&entry.name)
+                    .with_line(number),
+                );
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn check(content: &str) -> Vec<CheckIssue> {
+        let entries = get_parser(ShellType::Bash).parse(content).entries;
+        StyleChecker::new(content).check(&entries).issues
+    }
+
+    #[test]
+    fn test_trailing_whitespace() {
+        let issues = check("alias ll='ls -la' \nexport EDITOR=vim\nalias g=git\t\n");
+
+        let lines: Vec<_> = issues.iter().map(|i| i.line_number).collect();
+        assert_eq!(lines, [Some(1), Some(3)]);
+        assert!(issues.iter().all(|i| i.message == "Trailing whitespace"));
+    }
+
+    #[test]
+    fn test_mixed_indentation_in_function() {
+        let issues = check("greet() {\n    echo hi\n\techo there\n}\n");
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].entry_name.as_deref(), Some("greet"));
+        assert_eq!(issues[0].line_number, Some(3));
+
+        // A single line indented with both counts too
+        let issues = check("greet() {\n \techo hi\n}\n");
+        assert_eq!(issues[0].line_number, Some(2));
+
+        // Consistent tabs are fine
+        assert!(check("greet() {\n\techo hi\n\t\techo there\n}\n").is_empty());
+    }
+
+    #[test]
+    fn test_guarded_regions_are_skipped() {
+        let content =
+            "# >>> wenv:ignore >>>\nfoo() {\n  echo a \n\techo b\n}\n# <<< wenv:ignore <<<\n";
+        assert!(check(content).is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+//! Undefined variable reference checker
+
+use super::{CheckIssue, CheckResult, Checker, UNDEFINED_VARIABLE};
+use crate::model::{Entry, EntryType, ShellType};
+use crate::utils::strings::edit_distance;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeSet;
+
+lazy_static! {
+    /// `$NAME`, `${NAME}`, `${NAME:-default}`
+    pub(super) static ref BASH_REF_RE: Regex = Regex::new(r"\$(?:\{([A-Za-z_]\w*)|([A-Za-z_]\w*))").unwrap();
+
+    /// `$env:NAME`, `${env:NAME}`
+    static ref PWSH_REF_RE: Regex =
+        Regex::new(r"(?i)\$(?:\{env:([A-Za-z_]\w*)\}|env:([A-Za-z_]\w*))").unwrap();
+
+    /// Assignments in raw code: `NAME=`, `export NAME=`, `local -r NAME=`,
+    /// and loop variables (`for NAME in`)
+    static ref BASH_DEF_RE: Regex = Regex::new(
+        r"(?m)(?:^\s*(?:(?:export|readonly|local|declare|typeset)\s+(?:-\w+\s+)*)?([A-Za-z_]\w*)=|\bfor\s+([A-Za-z_]\w*)\s+in\b)"
+    )
+    .unwrap();
+
+    /// `$env:NAME = ...` in raw code
+    static ref PWSH_DEF_RE: Regex = Regex::new(r"(?i)\$env:([A-Za-z_]\w*)\s*=[^=]").unwrap();
+}
+
+/// Variables a Unix shell or login session normally provides
+pub(super) const BASH_SYSTEM_VARS: &[&str] = &[
+    "BASH",
+    "BASH_SOURCE",
+    "BASH_VERSION",
+    "COLUMNS",
+    "DISPLAY",
+    "EUID",
+    "HISTFILE",
+    "HISTFILESIZE",
+    "HISTSIZE",
+    "HOME",
+    "HOSTNAME",
+    "HOSTTYPE",
+    "IFS",
+    "LANG",
+    "LANGUAGE",
+    "LC_ALL",
+    "LC_CTYPE",
+    "LINENO",
+    "LINES",
+    "LOGNAME",
+    "MANPATH",
+    "OLDPWD",
+    "OSTYPE",
+    "PATH",
+    "PPID",
+    "PROMPT_COMMAND",
+    "PS1",
+    "PS2",
+    "PWD",
+    "RANDOM",
+    "SECONDS",
+    "SHELL",
+    "SHLVL",
+    "SSH_AUTH_SOCK",
+    "SSH_CONNECTION",
+    "TERM",
+    "TMPDIR",
+    "UID",
+    "USER",
+    "XDG_CACHE_HOME",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_RUNTIME_DIR",
+    "XDG_STATE_HOME",
+    "ZDOTDIR",
+    "ZSH_VERSION",
+];
+
+/// Environment variables Windows and PowerShell normally provide
+const PWSH_SYSTEM_VARS: &[&str] = &[
+    "ALLUSERSPROFILE",
+    "APPDATA",
+    "COMPUTERNAME",
+    "COMSPEC",
+    "HOME",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "LOCALAPPDATA",
+    "NUMBER_OF_PROCESSORS",
+    "ONEDRIVE",
+    "OS",
+    "PATH",
+    "PATHEXT",
+    "PROCESSOR_ARCHITECTURE",
+    "PROGRAMDATA",
+    "PROGRAMFILES",
+    "PSMODULEPATH",
+    "PUBLIC",
+    "SYSTEMDRIVE",
+    "SYSTEMROOT",
+    "TEMP",
+    "TMP",
+    "USER",
+    "USERDOMAIN",
+    "USERNAME",
+    "USERPROFILE",
+    "WINDIR",
+];
+
+/// Shortest name considered for "did you mean" suggestions; below this
+/// almost every pair of names is within edit distance 2
+const MIN_SUGGEST_LEN: usize = 4;
+
+/// Warns about variable references in env vars, aliases and sources that
+/// are neither defined in the file nor common system variables, which are
+/// most often typos (`$EDTIOR`).
+///
+/// Bash/Zsh check `$NAME` and `${NAME}`; PowerShell checks `$env:NAME`
+/// (case-insensitively), since its other `$variables` are not environment
+/// variables. Function bodies and raw code are only scanned for definitions.
+pub struct UndefinedVarChecker {
+    shell_type: ShellType,
+    extra_defined: BTreeSet<String>,
+}
+
+impl UndefinedVarChecker {
+    pub fn new(shell_type: ShellType) -> Self {
+        Self {
+            shell_type,
+            extra_defined: BTreeSet::new(),
+        }
+    }
+
+    /// Treat these names as defined, e.g. variables set in sourced files
+    pub fn with_defined<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let names: Vec<String> = names
+            .into_iter()
+            .map(|n| self.normalize(n.as_ref()))
+            .collect();
+        self.extra_defined.extend(names);
+        self
+    }
+
+    fn is_powershell(&self) -> bool {
+        self.shell_type == ShellType::PowerShell
+    }
+
+    /// PowerShell env var names are case-insensitive
+    fn normalize(&self, name: &str) -> String {
+        if self.is_powershell() {
+            name.to_uppercase()
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn system_vars(&self) -> &'static [&'static str] {
+        if self.is_powershell() {
+            PWSH_SYSTEM_VARS
+        } else {
+            BASH_SYSTEM_VARS
+        }
+    }
+
+    /// Every name defined in the file, plus extra and system names
+    fn known_names(&self, entries: &[Entry]) -> BTreeSet<String> {
+        let mut known = self.extra_defined.clone();
+        known.extend(self.system_vars().iter().map(|s| s.to_string()));
+
+        let def_re: &Regex = if self.is_powershell() {
+            &PWSH_DEF_RE
+        } else {
+            &BASH_DEF_RE
+        };
+        for entry in entries {
+            if entry.entry_type == EntryType::EnvVar {
+                known.insert(self.normalize(&entry.name));
+            }
+            for caps in def_re.captures_iter(&entry.value) {
+                if let Some(name) = caps.get(1).or(caps.get(2)) {
+                    known.insert(self.normalize(name.as_str()));
+                }
+            }
+        }
+        known
+    }
+
+    /// Variable names referenced in `text`, in order of first appearance
+    fn references(&self, text: &str) -> Vec<String> {
+        let ref_re: &Regex = if self.is_powershell() {
+            &PWSH_REF_RE
+        } else {
+            &BASH_REF_RE
+        };
+        let mut refs: Vec<String> = Vec::new();
+        for caps in ref_re.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            // `\$NAME` is a literal dollar sign
+            if text[..whole.start()].ends_with('\\') {
+                continue;
+            }
+            if let Some(name) = caps.get(1).or(caps.get(2)) {
+                let name = name.as_str().to_string();
+                if !refs.contains(&name) {
+                    refs.push(name);
+                }
+            }
+        }
+        refs
+    }
+
+    fn display_ref(&self, name: &str) -> String {
+        if self.is_powershell() {
+            format!("$env:{}", name)
+        } else {
+            format!("${}", name)
+        }
+    }
+}
+
+/// Closest known name within edit distance 2, if any
+fn suggest<'a>(name: &str, known: &'a BTreeSet<String>) -> Option<&'a str> {
+    if name.chars().count() < MIN_SUGGEST_LEN {
+        return None;
+    }
+    known
+        .iter()
+        .map(|k| (edit_distance(name, k), k))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, k)| k.as_str())
+}
+
+impl Checker for UndefinedVarChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+        let known = self.known_names(entries);
+
+        for entry in entries {
+            if !matches!(
+                entry.entry_type,
+                EntryType::EnvVar | EntryType::Alias | EntryType::Source
+            ) {
+                continue;
+            }
+
+            for name in self.references(&entry.canonical_value()) {
+                let normalized = self.normalize(&name);
+                if known.contains(&normalized) {
+                    continue;
+                }
+
+                let hint = suggest(&normalized, &known)
+                    .map(|s| format!(" (did you mean '{}'?)", s))
+                    .unwrap_or_default();
+                let mut issue = CheckIssue::warning(format!(
+                    "Undefined variable '{}' in {}{}",
+                    self.display_ref(&name),
+                    entry.name,
+                    hint
+                ))
+                .with_code(UNDEFINED_VARIABLE)
+                .with_entry(&entry.name);
+                if let Some(line) = entry.line_number {
+                    issue = issue.with_line(line);
+                }
+                result.add_issue(issue);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(shell_type: ShellType, content: &str) -> Vec<Entry> {
+        crate::parser::get_parser(shell_type).parse(content).entries
+    }
+
+    #[test]
+    fn test_bash_typo_flagged_with_suggestion() {
+        let entries = parse(
+            ShellType::Bash,
+            "export EDITOR=nvim\nexport VISUAL=$EDTIOR\nalias e='$EDITOR'\n",
+        );
+
+        let result = UndefinedVarChecker::new(ShellType::Bash).check(&entries);
+
+        assert_eq!(result.issues.len(), 1);
+        let issue = &result.issues[0];
+        assert_eq!(
+            issue.message,
+            "Undefined variable '$EDTIOR' in VISUAL (did you mean 'EDITOR'?)"
+        );
+        assert_eq!(issue.line_number, Some(2));
+    }
+
+    #[test]
+    fn test_bash_known_vars_not_flagged() {
+        let entries = parse(
+            ShellType::Bash,
+            "GOROOT=/opt/go\n\
+             export PATH=\"$HOME/bin:${PATH}:$GOROOT/bin\"\n\
+             export CARGO=${CARGO_HOME:-$HOME/.cargo}\n\
+             export CARGO_HOME=~/.cargo\n\
+             alias each='for f in *; do echo $f; done'\n\
+             alias price='echo \\$5'\n",
+        );
+
+        let result = UndefinedVarChecker::new(ShellType::Bash).check(&entries);
+        assert!(result.is_ok(), "{:?}", result.issues);
+    }
+
+    #[test]
+    fn test_defined_elsewhere() {
+        let entries = parse(ShellType::Bash, "export JAVA_BIN=$JAVA_HOME/bin\n");
+
+        let flagged = UndefinedVarChecker::new(ShellType::Bash).check(&entries);
+        assert_eq!(flagged.issues.len(), 1);
+        assert!(flagged.issues[0].message.ends_with("in JAVA_BIN"));
+
+        let result = UndefinedVarChecker::new(ShellType::Bash)
+            .with_defined(["JAVA_HOME"])
+            .check(&entries);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_pwsh_env_refs() {
+        let entries = parse(
+            ShellType::PowerShell,
+            "$env:EDITOR = \"code\"\n\
+             $env:VISUAL = $env:editor\n\
+             $env:TOOLS = \"$env:USERPROFILE\\tools;$env:EDTIOR\"\n\
+             $localVar = 'not an env var'\n\
+             Set-Alias ll Get-ChildItem\n",
+        );
+
+        let result = UndefinedVarChecker::new(ShellType::PowerShell).check(&entries);
+
+        assert_eq!(result.issues.len(), 1);
+        assert_eq!(
+            result.issues[0].message,
+            "Undefined variable '$env:EDTIOR' in TOOLS (did you mean 'EDITOR'?)"
+        );
+    }
+
+    #[test]
+    fn test_no_suggestion_for_unrelated_name() {
+        let entries = parse(ShellType::Bash, "export A=$SOMETHING_ELSE\n");
+        let result = UndefinedVarChecker::new(ShellType::Bash).check(&entries);
+        assert_eq!(
+            result.issues[0].message,
+            "Undefined variable '$SOMETHING_ELSE' in A"
+        );
+    }
+}
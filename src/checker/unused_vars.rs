@@ -0,0 +1,114 @@
+//! Unused shell variable checker
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeSet;
+
+use super::undefined_vars::{BASH_REF_RE, BASH_SYSTEM_VARS};
+use super::{CheckIssue, CheckResult, Checker, UNUSED_VARIABLE};
+use crate::model::{Entry, EntryType, ShellType};
+
+lazy_static! {
+    /// Assignments without `export` in raw code: `NAME=value`,
+    /// `readonly NAME=value`. `NAME=value command` sets the variable for the
+    /// command only and does not match.
+    static ref ASSIGNMENT_RE: Regex = Regex::new(
+        r#"(?m)^\s*(?:(?:readonly|declare|typeset)\s+(?:-\w+\s+)*)?([A-Za-z_]\w*)=(?:'[^']*'|"[^"]*"|[^\s;'"]*)\s*(?:[;#]|$)"#
+    )
+    .unwrap();
+}
+
+/// Warns about Bash/Zsh variables assigned without `export` and never
+/// referenced in the file. Such a variable is invisible to programs, so
+/// it has no effect unless a file sourced later reads it; assignments
+/// followed by a `source` line, and variables the shell itself reads
+/// (`PS1`, `HISTSIZE`, ...), are not reported. PowerShell and `.env` files
+/// are not checked.
+pub struct UnusedVarChecker {
+    shell_type: ShellType,
+}
+
+impl UnusedVarChecker {
+    pub fn new(shell_type: ShellType) -> Self {
+        Self { shell_type }
+    }
+}
+
+impl Checker for UnusedVarChecker {
+    fn check(&self, entries: &[Entry]) -> CheckResult {
+        let mut result = CheckResult::new();
+        if matches!(self.shell_type, ShellType::PowerShell | ShellType::DotEnv) {
+            return result;
+        }
+
+        let referenced: BTreeSet<&str> = entries
+            .iter()
+            .flat_map(|e| BASH_REF_RE.captures_iter(&e.value))
+            .filter_map(|caps| caps.get(1).or(caps.get(2)))
+            .map(|name| name.as_str())
+            .collect();
+        let last_source = entries
+            .iter()
+            .rposition(|e| e.entry_type == EntryType::Source);
+
+        for (index, entry) in entries.iter().enumerate() {
+            if last_source.is_some_and(|source| source > index) {
+                continue;
+            }
+            let names: Vec<&str> = match entry.entry_type {
+                EntryType::Code => ASSIGNMENT_RE
+                    .captures_iter(&entry.value)
+                    .map(|caps| caps.get(1).unwrap().as_str())
+                    .collect(),
+                EntryType::Array => vec![entry.name.as_str()],
+                _ => continue,
+            };
+            for name in names {
+                if referenced.contains(name) || BASH_SYSTEM_VARS.contains(&name) {
+                    continue;
+                }
+                let mut issue = CheckIssue::warning(format!(
+                    "Variable '{}' is set but never used or exported",
+                    name
+                ))
+                .with_code(UNUSED_VARIABLE)
+                .with_entry(name);
+                if let Some(line) = entry.line_number {
+                    issue = issue.with_line(line);
+                }
+                result.add_issue(issue);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::get_parser;
+
+    fn unused(content: &str) -> Vec<String> {
+        let entries = get_parser(ShellType::Bash).parse(content).entries;
+        UnusedVarChecker::new(ShellType::Bash)
+            .check(&entries)
+            .issues
+            .into_iter()
+            .filter_map(|i| i.entry_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_unused_variables() {
+        let content =
+            "editor=vim\nleftover=1\nHISTSIZE=5000\nLANG=C make\nexport EDITOR=\"$editor\"\n";
+        assert_eq!(unused(content), ["leftover"]);
+    }
+
+    #[test]
+    fn test_variables_before_a_source_may_be_read_by_it() {
+        let content = "ZSH_THEME=robbyrussell\nplugins=(git)\nsource $ZSH/oh-my-zsh.sh\nstray=1\n";
+        assert_eq!(unused(content), ["stray"]);
+    }
+}
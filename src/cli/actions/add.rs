@@ -0,0 +1,180 @@
+//! Add command implementation
+
+use anyhow::Result;
+use dialoguer::Confirm;
+use std::io::IsTerminal;
+
+use crate::cli::args::EntryTypeArg;
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType};
+use crate::utils::reorder::render_entries;
+use crate::utils::validate::{check_alias_name, AliasIssue};
+
+/// Ask `prompt`, or take `yes` for an answer. Without a terminal to ask on
+/// the answer is no.
+fn confirm(prompt: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    Ok(Confirm::new()
+        .with_prompt(prompt)
+        .default(true)
+        .interact()?)
+}
+
+/// Whether `existing` already means `syntax`, quoting aside:
+/// `alias ll="ls -la"` and `alias ll='ls -la'` are the same alias
+fn is_equivalent(existing: &Entry, syntax: &str) -> bool {
+    let new = Entry::new(
+        existing.entry_type,
+        existing.name.clone(),
+        syntax.to_string(),
+    );
+    existing.canonical_value() == new.canonical_value()
+}
+
+/// Execute the add action
+///
+/// `definition` is `NAME=VALUE`; further words mean the value was not
+/// quoted, and the user is offered to add the quotes. An entry of the same
+/// type and name is overwritten after confirmation, unless its value is
+/// equivalent and it is left alone; otherwise the new definition goes at
+/// the end of the file. `yes` answers every prompt.
+pub fn execute(
+    ctx: &Context,
+    entry_type: EntryTypeArg,
+    definition: &[String],
+    yes: bool,
+) -> Result<()> {
+    let entry_type: EntryType = entry_type.into();
+    let format_error = match entry_type {
+        EntryType::Alias => ctx.messages.invalid_alias_format,
+        EntryType::EnvVar => ctx.messages.invalid_env_format,
+        _ => anyhow::bail!("Only aliases and environment variables can be added"),
+    };
+    let joined = definition.join(" ");
+    let Some((name, value)) = joined.split_once('=') else {
+        anyhow::bail!(format_error);
+    };
+
+    if entry_type == EntryType::Alias {
+        if let Some(issue) = check_alias_name(name) {
+            anyhow::bail!(issue.message(ctx.messages));
+        }
+    } else if name.is_empty() || name.contains(char::is_whitespace) {
+        anyhow::bail!(format_error);
+    }
+
+    // `wenv add alias ll=ls -la` reaches us as two arguments
+    if definition.len() > 1 {
+        let issue = AliasIssue::UnquotedValue {
+            suggestion: format!("wenv add {} '{}'", entry_type, joined),
+        };
+        let message = issue.message(ctx.messages);
+        if !yes && !std::io::stdin().is_terminal() {
+            anyhow::bail!(message);
+        }
+        ctx.print_warning(&message);
+        if !confirm(ctx.messages.add_quotes_prompt, yes)? {
+            println!("{}", ctx.messages.cancelled);
+            return Ok(());
+        }
+    }
+    if entry_type == EntryType::Alias && value.is_empty() {
+        ctx.print_warning(&AliasIssue::EmptyValue(name.to_string()).message(ctx.messages));
+    }
+
+    let formatter = get_formatter(ctx.shell_type);
+    let Some(syntax) = formatter.definition(entry_type, name, value) else {
+        anyhow::bail!("Cannot write a {} for {}", entry_type, ctx.shell_type);
+    };
+
+    let mut entries = ctx.parse_config_file()?.entries;
+    // An empty file parses as one blank line, which would precede the entry
+    if entries.iter().all(Entry::is_blank) {
+        entries.clear();
+    }
+    match entries
+        .iter()
+        .position(|e| e.entry_type == entry_type && e.name == name)
+    {
+        Some(index) if is_equivalent(&entries[index], &syntax) => {
+            ctx.print_success(
+                &ctx.messages
+                    .already_exists_equivalent
+                    .replacen("{}", &entry_type.to_string(), 1)
+                    .replacen("{}", name, 1),
+            );
+            return Ok(());
+        }
+        Some(index) => {
+            println!(
+                "{}",
+                ctx.messages
+                    .already_exists_value
+                    .replacen("{}", &entry_type.to_string(), 1)
+                    .replacen("{}", name, 1)
+                    .replacen("{}", &entries[index].canonical_value(), 1)
+            );
+            if !confirm(ctx.messages.overwrite_prompt, yes)? {
+                println!(
+                    "{}",
+                    ctx.messages
+                        .already_exists_skip
+                        .replacen("{}", &entry_type.to_string(), 1)
+                        .replacen("{}", name, 1)
+                );
+                return Ok(());
+            }
+            entries[index] = entries[index].redefined(&syntax);
+        }
+        None => entries.push(Entry::new(entry_type, name.to_string(), syntax)),
+    }
+    let content = ctx.timed("Format", || render_entries(&entries, formatter.as_ref()));
+
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&content)?;
+    ctx.record_history(&format!("add {} '{}'", entry_type, name), backup.as_deref());
+
+    ctx.print_success(
+        &ctx.messages
+            .entry_added
+            .replacen("{}", &entry_type.to_string(), 1)
+            .replacen("{}", name, 1)
+            .replacen("{}", value, 1),
+    );
+    ctx.print_reload_hint();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn first_entry(content: &str) -> Entry {
+        get_parser(ShellType::Bash).parse(content).entries.remove(0)
+    }
+
+    #[test]
+    fn test_is_equivalent_ignores_quoting() {
+        let existing = first_entry("alias ll=\"ls -la\"\n");
+        assert!(is_equivalent(&existing, "alias ll='ls -la'"));
+
+        let existing = first_entry("export EDITOR=vim\n");
+        assert!(is_equivalent(&existing, "export EDITOR='vim'"));
+    }
+
+    #[test]
+    fn test_is_equivalent_rejects_different_values() {
+        let existing = first_entry("alias ll='ls -la'\n");
+        assert!(!is_equivalent(&existing, "alias ll='ls -l'"));
+        assert!(!is_equivalent(&existing, "alias ll='ls  -la'"));
+    }
+}
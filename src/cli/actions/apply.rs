@@ -0,0 +1,230 @@
+//! Apply command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::path::Path;
+
+use crate::cli::actions::import::{incoming_entries, plan_import, prompt_conflict, ImportAction};
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType};
+use crate::utils::diff::{diff_entries, DiffStats};
+use crate::utils::path::read_file;
+use crate::utils::reorder::render_entries;
+
+/// Execute the apply action
+///
+/// Merges the entries of the snippet `file` into the config file. Entries
+/// of the same type and name are handled per `--on-conflict`; overwriting
+/// redefines the existing entry in place, and everything else goes at the
+/// end of the file. The changes are previewed before anything is written;
+/// with `dry_run` that is all, and `yes` applies them without asking.
+pub fn execute(ctx: &Context, file: &Path, yes: bool, dry_run: bool) -> Result<()> {
+    let content = read_file(file)?;
+    let incoming: Vec<Entry> = incoming_entries(ctx, &file.to_string_lossy(), &content)?
+        .into_iter()
+        .filter(|e| e.entry_type != EntryType::Comment && !e.is_blank())
+        .collect();
+    if incoming.is_empty() {
+        println!("No entries found in {}.", ctx.display_path(file));
+        return Ok(());
+    }
+
+    let current = ctx.parse_config_file()?.entries;
+    let plan = plan_import(
+        &incoming,
+        &current,
+        ctx.on_conflict,
+        yes,
+        &ctx.rename_suffix,
+    );
+
+    let mut resolved = Vec::new();
+    let mut undecided = 0;
+    for planned in &plan {
+        let action = match (planned.action, planned.existing) {
+            (ImportAction::Ask, _) if dry_run => {
+                undecided += 1;
+                continue;
+            }
+            (ImportAction::Ask, Some(existing)) => {
+                prompt_conflict(planned.entry, existing, planned.renamed.as_ref())?
+            }
+            (action, _) => action,
+        };
+        match (action, &planned.renamed) {
+            (ImportAction::Rename, Some(renamed)) => resolved.push((action, renamed)),
+            _ => resolved.push((action, planned.entry)),
+        }
+    }
+
+    let merged = merge(&current, &resolved);
+    let changes = diff_entries(&current, &merged);
+    if changes.is_empty() {
+        println!("Nothing to apply: the config already has every entry.");
+        return Ok(());
+    }
+    println!(
+        "{} {} → {}",
+        "Changes".bold(),
+        ctx.display_path(file).cyan(),
+        ctx.display_path(&ctx.config_file).cyan()
+    );
+    for change in &changes {
+        println!("  {}", change.summary());
+    }
+    let stats = DiffStats::of(&changes);
+    println!(
+        "  {}",
+        format!("{} added, {} modified", stats.added, stats.modified).dimmed()
+    );
+
+    if dry_run {
+        if undecided > 0 {
+            println!(
+                "{} conflict(s) would be asked about (see --on-conflict).",
+                undecided
+            );
+        }
+        println!("No changes written.");
+        return Ok(());
+    }
+    if !yes
+        && !Confirm::new()
+            .with_prompt("Apply these changes?")
+            .default(false)
+            .interact()?
+    {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let formatter = get_formatter(ctx.shell_type);
+    let content = render_entries(&merged, formatter.as_ref());
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&content)?;
+    ctx.record_history(
+        &format!("apply {}", ctx.display_path(file)),
+        backup.as_deref(),
+    );
+
+    ctx.print_success(&format!(
+        "Applied {} ({} added, {} modified)",
+        ctx.display_path(file),
+        stats.added,
+        stats.modified
+    ));
+    ctx.print_reload_hint();
+    Ok(())
+}
+
+/// `current` with each `(action, entry)` applied: overwriting redefines the
+/// entry of the same type and name where it stands, adding and renaming
+/// append the entry with the comments it had in the snippet
+fn merge(current: &[Entry], resolved: &[(ImportAction, &Entry)]) -> Vec<Entry> {
+    let mut merged = current.to_vec();
+    // An empty file parses as one blank line, which would precede the entries
+    if merged.iter().all(Entry::is_blank) {
+        merged.clear();
+    }
+    for &(action, entry) in resolved {
+        match action {
+            ImportAction::Overwrite => {
+                if let Some(existing) = merged
+                    .iter_mut()
+                    .find(|e| e.entry_type == entry.entry_type && e.name == entry.name)
+                {
+                    *existing = existing.redefined(entry.definition());
+                }
+            }
+            ImportAction::Add | ImportAction::Rename => {
+                let mut appended = entry.clone();
+                appended.value = entry.value.trim_end().to_string();
+                appended.line_number = None;
+                appended.end_line = None;
+                merged.push(appended);
+            }
+            ImportAction::Skip | ImportAction::Ask => {}
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::get_formatter;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn parse(content: &str) -> Vec<Entry> {
+        get_parser(ShellType::Bash).parse(content).entries
+    }
+
+    fn render(entries: &[Entry]) -> String {
+        render_entries(entries, get_formatter(ShellType::Bash).as_ref())
+    }
+
+    #[test]
+    fn test_merge_overwrites_in_place_and_appends() {
+        let current = parse("# listing\nalias ll='ls -l'\nexport EDITOR=vim\n");
+        let snippet = parse("# git\nalias gs='git status'\nalias ll='ls -la'\n");
+        let resolved = [
+            (ImportAction::Add, &snippet[0]),
+            (ImportAction::Overwrite, &snippet[1]),
+        ];
+
+        let merged = merge(&current, &resolved);
+        assert_eq!(
+            render(&merged),
+            "# listing\nalias ll='ls -la'\nexport EDITOR=vim\n# git\nalias gs='git status'\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_skips_and_renames() {
+        let current = parse("alias ll='ls -l'\n");
+        let snippet = parse("alias ll='ls -la'\n");
+        let renamed = snippet[0].renamed("ll_2").unwrap();
+
+        assert_eq!(
+            render(&merge(&current, &[(ImportAction::Skip, &snippet[0])])),
+            "alias ll='ls -l'\n"
+        );
+        assert_eq!(
+            render(&merge(&current, &[(ImportAction::Rename, &renamed)])),
+            "alias ll='ls -l'\nalias ll_2='ls -la'\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_into_empty_file() {
+        let snippet = parse("export EDITOR=vim\n");
+        let merged = merge(&parse(""), &[(ImportAction::Add, &snippet[0])]);
+        assert_eq!(render(&merged), "export EDITOR=vim\n");
+    }
+
+    #[test]
+    fn test_preview_lists_added_and_modified_entries() {
+        let current = parse("alias ll='ls -l'\n");
+        let snippet = parse("alias ll='ls -la'\nalias gs='git status'\n");
+        let merged = merge(
+            &current,
+            &[
+                (ImportAction::Overwrite, &snippet[0]),
+                (ImportAction::Add, &snippet[1]),
+            ],
+        );
+
+        let changes = diff_entries(&current, &merged);
+        assert_eq!(
+            DiffStats::of(&changes),
+            DiffStats {
+                added: 1,
+                removed: 0,
+                modified: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Backup command implementation
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::backup::watch::{stamp, Debouncer};
+use crate::backup::BackupManager;
+use crate::cli::context::Context;
+use crate::model::Entry;
+use crate::utils::diff::{diff_entries, DiffStats};
+
+/// Execute `backup prune`
+pub fn prune(
+    ctx: &Context,
+    keep: Option<usize>,
+    older_than: Option<u64>,
+    dry_run: bool,
+) -> Result<()> {
+    let manager = BackupManager::new(ctx.shell_type, &ctx.config);
+
+    if dry_run {
+        let selected = manager.select_for_prune(keep, older_than)?;
+        if selected.is_empty() {
+            println!("No backups to remove.");
+            return Ok(());
+        }
+        println!("{}", "Would remove:".bold());
+        for backup in &selected {
+            println!("  {}", backup.filename.dimmed());
+        }
+        println!();
+        println!("{} backup(s) would be removed.", selected.len());
+        return Ok(());
+    }
+
+    let removed = manager.prune(keep, older_than)?;
+    ctx.print_success(&format!("Removed {} backup(s)", removed));
+
+    Ok(())
+}
+
+/// Execute `backup diff`
+pub fn diff(ctx: &Context, since: &str, chain: bool) -> Result<()> {
+    let manager = BackupManager::new(ctx.shell_type, &ctx.config);
+    let backups = manager.backups_since(since, &ctx.config_file)?;
+    let parser = ctx.parser();
+
+    // Versions oldest first, ending with the current file
+    let mut versions: Vec<(String, Vec<Entry>)> = Vec::new();
+    let steps = if chain { &backups[..] } else { &backups[..1] };
+    for backup in steps {
+        let content = std::fs::read_to_string(&backup.path)?;
+        versions.push((backup.id.clone(), parser.parse(&content).entries));
+    }
+    versions.push((
+        ctx.display_path(&ctx.config_file),
+        ctx.parse_config_file()?.entries,
+    ));
+
+    for (index, pair) in versions.windows(2).enumerate() {
+        let ((from, old), (to, new)) = (&pair[0], &pair[1]);
+        if index > 0 {
+            println!();
+        }
+        println!("{} {} → {}", "Changes".bold(), from.cyan(), to.cyan());
+
+        let changes = diff_entries(old, new);
+        if changes.is_empty() {
+            println!("  {}", "No changes.".dimmed());
+            continue;
+        }
+        for change in &changes {
+            println!("  {}", change.summary());
+        }
+        let stats = DiffStats::of(&changes);
+        println!(
+            "  {}",
+            format!(
+                "{} added, {} removed, {} modified",
+                stats.added, stats.removed, stats.modified
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// How often `backup auto --watch` looks at the file
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the file must stay unchanged before it is backed up
+const QUIET_PERIOD: Duration = Duration::from_secs(1);
+
+/// Execute `backup auto`
+///
+/// Backs up the config file once; with `watch`, keeps polling it and backs
+/// it up again after every burst of changes until interrupted. Content
+/// identical to the last backup (e.g. a save without edits) is skipped, and
+/// old backups are pruned down to `backup.max_count` each time.
+pub fn auto(ctx: &Context, watch: bool) -> Result<()> {
+    let path = &ctx.config_file;
+    let manager = BackupManager::new(ctx.shell_type, &ctx.config);
+
+    let back_up = || -> Result<Vec<u8>> {
+        let content = std::fs::read(path)?;
+        let backup = manager.create_backup(path)?;
+        manager.cleanup_old_backups()?;
+        ctx.print_success(&format!("Backed up to {}", ctx.display_path(&backup)));
+        Ok(content)
+    };
+
+    let mut backed_up = back_up()?;
+    if !watch {
+        return Ok(());
+    }
+
+    if !ctx.quiet {
+        println!(
+            "{}",
+            format!("Watching {} (Ctrl-C to stop)", ctx.display_path(path)).dimmed()
+        );
+    }
+    let mut last_stamp = stamp(path);
+    let mut debouncer = Debouncer::new(QUIET_PERIOD);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        // A file missing mid-save (rename then write) is not a change yet
+        let current = stamp(path);
+        if current.is_some() && current != last_stamp {
+            debouncer.changed(Instant::now());
+            last_stamp = current;
+        }
+        if !debouncer.settled(Instant::now()) {
+            continue;
+        }
+        match std::fs::read(path) {
+            Ok(content) if content == backed_up => {}
+            Ok(_) => backed_up = back_up()?,
+            // Gone again: wait for the next change
+            Err(_) => {}
+        }
+    }
+}
+
+/// Execute `backup migrate`
+///
+/// Moves backups out of the per-shell directories of older versions into
+/// the directory they are looked up in now. Backups whose name is already
+/// taken there are left in place and reported.
+pub fn migrate(ctx: &Context, dry_run: bool) -> Result<()> {
+    let migration = crate::backup::migrate(&crate::backup::base_dir(&ctx.config), dry_run)?;
+    if migration.moved.is_empty() && migration.conflicts.is_empty() {
+        println!("No backups to migrate.");
+        return Ok(());
+    }
+
+    for (from, to) in &migration.moved {
+        println!(
+            "{} {} -> {}",
+            "~".cyan(),
+            ctx.display_path(from),
+            ctx.display_path(to)
+        );
+    }
+    for path in &migration.conflicts {
+        ctx.print_warning(&format!(
+            "Left {} in place: a backup of the same name exists",
+            ctx.display_path(path)
+        ));
+    }
+
+    if dry_run {
+        println!();
+        println!("{} backup(s) would move.", migration.moved.len());
+    } else {
+        ctx.print_success(&format!("Moved {} backup(s)", migration.moved.len()));
+    }
+    Ok(())
+}
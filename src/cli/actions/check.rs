@@ -0,0 +1,124 @@
+//! Check command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::checker::{
+    check_across_files, check_all, CheckIssue, Checker, PathSegmentChecker, Severity, StyleChecker,
+    MISSING_PATH_DIRECTORY,
+};
+use crate::cli::context::Context;
+use crate::utils::path::file_label;
+
+/// Execute the check action
+///
+/// Reports the parse warnings and runs the checkers of [`check_all`], plus
+/// [`StyleChecker`] with `style` and the PATH directory existence check
+/// with `missing_dirs`, and prints the issues in line order,
+/// leaving out codes in `checker.ignore_codes`. Fails when any issue is an
+/// error.
+///
+/// With several `--file` paths, each file is checked in turn, issues are
+/// prefixed with their file, and entries defined in more than one file are
+/// reported too.
+pub fn execute(ctx: &Context, style: bool, missing_dirs: bool) -> Result<()> {
+    let files = ctx.parse_config_files()?;
+    let several = files.len() > 1;
+    let ignored = &ctx.config.checker.ignore_codes;
+
+    // Issues with the label of their file ("" for a single file)
+    let mut issues: Vec<(String, CheckIssue)> = Vec::new();
+    for (path, result) in &files {
+        let entries = &result.entries;
+        let mut checked = ctx.timed("Check", || check_all(entries, ctx.shell_type));
+        checked
+            .issues
+            .extend(result.warnings.iter().map(CheckIssue::from));
+        if style {
+            let content = ctx.timed("Read", || crate::utils::path::read_file(path))?;
+            checked
+                .issues
+                .extend(StyleChecker::new(&content).check(entries).issues);
+        }
+        if missing_dirs {
+            // Duplicate segments are reported by check_all already
+            let path_checker = PathSegmentChecker::new(ctx.shell_type).with_existence_check(true);
+            checked.issues.extend(
+                path_checker
+                    .check(entries)
+                    .issues
+                    .into_iter()
+                    .filter(|issue| issue.code == Some(MISSING_PATH_DIRECTORY)),
+            );
+        }
+        let mut file_issues = checked.ignoring(ignored).issues;
+        file_issues.sort_by_key(|issue| issue.line_number.unwrap_or(usize::MAX));
+
+        let label = if several {
+            file_label(path)
+        } else {
+            String::new()
+        };
+        issues.extend(file_issues.into_iter().map(|issue| (label.clone(), issue)));
+    }
+    if several {
+        let labelled: Vec<_> = files
+            .iter()
+            .map(|(path, result)| (file_label(path), result.entries.clone()))
+            .collect();
+        // The message lists every location already
+        issues.extend(
+            check_across_files(&labelled)
+                .issues
+                .into_iter()
+                .map(|mut issue| {
+                    issue.line_number = None;
+                    (String::new(), issue)
+                }),
+        );
+    }
+
+    if issues.is_empty() {
+        ctx.print_success("No issues found");
+        return Ok(());
+    }
+
+    for (file, issue) in &issues {
+        println!("{}", issue_line(file, issue));
+    }
+
+    let errors = issues
+        .iter()
+        .filter(|(_, i)| i.severity == Severity::Error)
+        .count();
+    println!();
+    if errors == 0 {
+        println!("{}", format!("{} warning(s)", issues.len()).yellow());
+        Ok(())
+    } else {
+        anyhow::bail!("{} error(s), {} warning(s)", errors, issues.len() - errors)
+    }
+}
+
+/// `⚠ line 3: message [code]` for one issue, or `⚠ .bashrc:3: message
+/// [code]` when `file` is given
+pub(super) fn issue_line(file: &str, issue: &CheckIssue) -> String {
+    let mark = match issue.severity {
+        Severity::Warning => "⚠".yellow(),
+        Severity::Error => "✗".red(),
+    };
+    let location = match (file, issue.line_number) {
+        ("", Some(line)) => Some(format!("line {}", line)),
+        ("", None) => None,
+        (file, Some(line)) => Some(format!("{}:{}", file, line)),
+        (file, None) => Some(file.to_string()),
+    };
+    let code = issue
+        .code
+        .map(|code| format!(" {}", format!("[{}]", code).dimmed()))
+        .unwrap_or_default();
+    match location {
+        Some(location) => format!("{} {}: {}{}", mark, location.dimmed(), issue.message, code),
+        None => format!("{} {}{}", mark, issue.message, code),
+    }
+}
@@ -0,0 +1,46 @@
+//! Config command implementation
+
+use anyhow::Result;
+
+use crate::cli::context::Context;
+use crate::config::{get_value, load_or_create_config, save_config, set_value};
+use crate::model::Config;
+
+/// Execute `config get`
+pub fn get(key: &str) -> Result<()> {
+    let config = load_or_create_config()?;
+    println!("{}", get_value(&config, key)?);
+    Ok(())
+}
+
+/// Execute `config set`
+pub fn set(ctx: &Context, key: &str, value: &str) -> Result<()> {
+    let mut config = load_or_create_config()?;
+    set_value(&mut config, key, value)?;
+    save_config(&config)?;
+    ctx.print_success(&format!("Set {} = {}", key, get_value(&config, key)?));
+    Ok(())
+}
+
+/// Execute `config path`
+pub fn path() -> Result<()> {
+    println!("{}", Config::config_path().display());
+    Ok(())
+}
+
+/// Execute `config init`
+///
+/// Writes the commented default configuration. An existing config file is
+/// only replaced with `force`.
+pub fn init(ctx: &Context, force: bool) -> Result<()> {
+    let path = Config::config_path();
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to replace it)",
+            ctx.display_path(&path)
+        );
+    }
+    Config::write_commented_template(&path)?;
+    ctx.print_success(&format!("Wrote {}", ctx.display_path(&path)));
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! Count command implementation
+
+use anyhow::Result;
+
+use crate::cli::args::EntryTypeArg;
+use crate::cli::context::Context;
+use crate::model::{Entry, EntryType};
+
+/// Execute the count action: print the number of entries of `entry_type`
+/// (all entries without one), and nothing else
+pub fn execute(ctx: &Context, entry_type: Option<EntryTypeArg>) -> Result<()> {
+    let entries = ctx.parse_config_file()?.entries;
+    println!("{}", count(&entries, entry_type.map(Into::into)));
+    Ok(())
+}
+
+/// Entries of `entry_type`, blank lines excluded as in `list`
+fn count(entries: &[Entry], entry_type: Option<EntryType>) -> usize {
+    entries
+        .iter()
+        .filter(|e| !e.is_blank())
+        .filter(|e| entry_type.is_none_or(|t| e.entry_type == t))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    #[test]
+    fn test_count_by_type() {
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse("alias ll='ls -la'\n\nalias gs='git status'\nexport EDITOR=vim\n")
+            .entries;
+
+        assert_eq!(count(&entries, Some(EntryType::Alias)), 2);
+        assert_eq!(count(&entries, Some(EntryType::EnvVar)), 1);
+        assert_eq!(count(&entries, Some(EntryType::Function)), 0);
+        assert_eq!(count(&entries, None), 3);
+    }
+}
@@ -0,0 +1,336 @@
+//! Doctor command implementation
+//!
+//! Read-only environment checks to help diagnose support issues.
+
+use anyhow::Result;
+use colored::Colorize;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::backup::BackupManager;
+use crate::cli::context::Context;
+use crate::model::{ColorsConfig, ShellType};
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Not a problem for the current shell, but worth knowing
+    Warn,
+    Fail,
+}
+
+/// One line of the doctor checklist
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(label: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Execute the doctor action
+pub fn execute(ctx: &Context) -> Result<()> {
+    let mut checks = vec![Check::new(
+        "Shell type",
+        CheckStatus::Pass,
+        format!("{} ({})", ctx.shell_type, ctx.shell_source.describe()),
+    )];
+    checks.extend(check_config_file(
+        &ctx.config_file,
+        &ctx.display_path(&ctx.config_file),
+    ));
+    let backup_dir = BackupManager::dir_for(ctx.shell_type, &ctx.config);
+    checks.push(check_backup_dir(
+        &backup_dir,
+        &ctx.display_path(&backup_dir),
+        ctx.backup_enabled,
+    ));
+    checks.extend(check_legacy_backups(&crate::backup::base_dir(&ctx.config)));
+    checks.push(check_language(&ctx.config.ui.language));
+    checks.extend(check_colors(&ctx.config.colors));
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    for program in validators(ctx.shell_type) {
+        let required = Some(program) == validator_for(ctx.shell_type);
+        checks.push(check_on_path(program, &path_var, required));
+    }
+
+    for check in &checks {
+        let mark = match check.status {
+            CheckStatus::Pass => "✓".green(),
+            CheckStatus::Warn => "⚠".yellow(),
+            CheckStatus::Fail => "✗".red(),
+        };
+        println!("{} {}: {}", mark, check.label.bold(), check.detail);
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    println!();
+    if failed == 0 {
+        println!("{}", "All checks passed.".green());
+        Ok(())
+    } else {
+        anyhow::bail!("{} check(s) failed", failed)
+    }
+}
+
+/// Whether the config file exists and can be written; `shown` is the path
+/// as displayed
+pub fn check_config_file(path: &Path, shown: &str) -> Vec<Check> {
+    let shown = shown.to_string();
+    if !path.exists() {
+        return vec![Check::new(
+            "Config file",
+            CheckStatus::Fail,
+            format!("{} (not found)", shown),
+        )];
+    }
+
+    let writable = if is_writable(path) {
+        Check::new("Config file writable", CheckStatus::Pass, "yes")
+    } else {
+        Check::new("Config file writable", CheckStatus::Fail, "read-only")
+    };
+    vec![
+        Check::new("Config file", CheckStatus::Pass, shown),
+        writable,
+    ]
+}
+
+/// Whether backups can be written to `dir`, displayed as `shown`
+pub fn check_backup_dir(dir: &Path, shown: &str, enabled: bool) -> Check {
+    let shown = shown.to_string();
+    if !enabled {
+        return Check::new(
+            "Backup directory",
+            CheckStatus::Warn,
+            format!("{} (backups disabled)", shown),
+        );
+    }
+    if is_writable(dir) {
+        Check::new("Backup directory", CheckStatus::Pass, shown)
+    } else {
+        Check::new(
+            "Backup directory",
+            CheckStatus::Fail,
+            format!("{} (not writable)", shown),
+        )
+    }
+}
+
+/// A warning when backups are still in the per-shell directories of older
+/// versions, where they are no longer found
+pub fn check_legacy_backups(base: &Path) -> Option<Check> {
+    let pending = crate::backup::migrate(base, true).ok()?.moved.len();
+    (pending > 0).then(|| {
+        Check::new(
+            "Old backups",
+            CheckStatus::Warn,
+            format!("{} not migrated (run `wenv backup migrate`)", pending),
+        )
+    })
+}
+
+/// Whether the configured language's messages load
+pub fn check_language(lang: &str) -> Check {
+    let Some(file) = crate::i18n::language_file(lang) else {
+        return Check::new("Language", CheckStatus::Pass, "en (embedded)");
+    };
+
+    let problem = match std::fs::read_to_string(&file) {
+        Ok(content) => crate::i18n::validate_language_file(&content)
+            .err()
+            .map(|e| format!("invalid: {}", e.to_string().lines().next().unwrap_or(""))),
+        Err(_) => Some("not found".to_string()),
+    };
+
+    match problem {
+        None => Check::new(
+            "Language",
+            CheckStatus::Pass,
+            format!("{} ({})", lang, file.display()),
+        ),
+        Some(problem) => Check::new(
+            "Language",
+            CheckStatus::Fail,
+            format!("{} ({} {}, using English)", lang, file.display(), problem),
+        ),
+    }
+}
+
+/// A warning when `[colors]` has settings that fall back to the defaults
+pub fn check_colors(colors: &ColorsConfig) -> Option<Check> {
+    let invalid = crate::utils::colors::invalid_settings(colors);
+    (!invalid.is_empty()).then(|| {
+        Check::new(
+            "Colors",
+            CheckStatus::Warn,
+            format!("{} (using the default)", invalid.join(", ")),
+        )
+    })
+}
+
+/// Whether `program` is on `path_var`. A missing program only fails the
+/// check when the current shell needs it for syntax validation.
+pub fn check_on_path(program: &str, path_var: &OsStr, required: bool) -> Check {
+    let label = format!("{} on PATH", program);
+    match find_on_path(program, path_var) {
+        Some(found) => Check::new(label, CheckStatus::Pass, found.display().to_string()),
+        None if required => Check::new(
+            label,
+            CheckStatus::Fail,
+            "not found (needed to validate edits)",
+        ),
+        None => Check::new(label, CheckStatus::Warn, "not found"),
+    }
+}
+
+/// Locate an executable in a `PATH`-style list of directories
+pub fn find_on_path(program: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var).find_map(|dir| {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if cfg!(windows) {
+            let exe = dir.join(format!("{}.exe", program));
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
+
+/// The program used to syntax-check edits for `shell`; `.env` files have
+/// none
+fn validator_for(shell: ShellType) -> Option<&'static str> {
+    match shell {
+        ShellType::Bash | ShellType::Zsh => Some("bash"),
+        ShellType::PosixSh => Some("sh"),
+        ShellType::PowerShell => Some("pwsh"),
+        ShellType::DotEnv => None,
+    }
+}
+
+/// Validation programs worth reporting: bash and pwsh, plus the current
+/// shell's validator if it is neither
+fn validators(shell: ShellType) -> Vec<&'static str> {
+    let mut programs = vec!["bash", "pwsh"];
+    if let Some(own) = validator_for(shell).filter(|own| !programs.contains(own)) {
+        programs.push(own);
+    }
+    programs
+}
+
+/// Whether `path` (or, if it does not exist yet, its nearest existing
+/// ancestor) is writable, judged from permissions without writing anything
+fn is_writable(path: &Path) -> bool {
+    path.ancestors()
+        .find_map(|p| std::fs::metadata(p).ok())
+        .is_some_and(|meta| !meta.permissions().readonly())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config_file() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join(".bashrc");
+        let checks = check_config_file(&missing, "~/.bashrc");
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert_eq!(checks[0].detail, "~/.bashrc (not found)");
+
+        fs::write(&missing, "").unwrap();
+        let checks = check_config_file(&missing, "~/.bashrc");
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Pass));
+
+        let mut perms = fs::metadata(&missing).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&missing, perms).unwrap();
+        let checks = check_config_file(&missing, "~/.bashrc");
+        assert_eq!(checks[1].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_check_backup_dir() {
+        let dir = TempDir::new().unwrap();
+        // Not created yet: judged by the nearest existing parent
+        let backups = dir.path().join("backups").join("bash");
+        assert_eq!(
+            check_backup_dir(&backups, "backups", true).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_backup_dir(&backups, "backups", false).status,
+            CheckStatus::Warn
+        );
+        assert!(!backups.exists());
+    }
+
+    #[test]
+    fn test_check_language_embedded() {
+        let check = check_language("en");
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.detail.contains("embedded"));
+    }
+
+    #[test]
+    fn test_check_colors_warns_about_unusable_settings() {
+        assert!(check_colors(&ColorsConfig::default()).is_none());
+
+        let colors: ColorsConfig = toml::from_str("alias = \"plaid\"\n").unwrap();
+        let check = check_colors(&colors).unwrap();
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("plaid"), "{}", check.detail);
+    }
+
+    #[test]
+    fn test_find_on_path() {
+        let dir = TempDir::new().unwrap();
+        let bin = dir.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        fs::write(bin.join("fakesh"), "").unwrap();
+
+        let path_var = std::env::join_paths([dir.path(), &bin]).unwrap();
+        assert_eq!(find_on_path("fakesh", &path_var), Some(bin.join("fakesh")));
+        assert_eq!(find_on_path("missing", &path_var), None);
+        // Directories with the program's name do not count
+        assert_eq!(find_on_path("bin", &path_var), None);
+    }
+
+    #[test]
+    fn test_check_on_path_required() {
+        let empty = OsStr::new("");
+        assert_eq!(check_on_path("pwsh", empty, true).status, CheckStatus::Fail);
+        assert_eq!(
+            check_on_path("pwsh", empty, false).status,
+            CheckStatus::Warn
+        );
+    }
+
+    #[test]
+    fn test_validators() {
+        assert_eq!(validators(ShellType::Bash), vec!["bash", "pwsh"]);
+        assert_eq!(validators(ShellType::PosixSh), vec!["bash", "pwsh", "sh"]);
+        assert_eq!(validators(ShellType::DotEnv), vec!["bash", "pwsh"]);
+    }
+}
@@ -2,15 +2,27 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
 
-use crate::cli::args::EntryTypeArg;
+use crate::cli::args::{EntryTypeArg, ExportFormat};
 use crate::cli::context::Context;
 use crate::formatter::get_formatter;
 use crate::model::EntryType;
+use crate::utils::bundle::Bundle;
+use crate::utils::path::save_file_with;
+
+/// Output path that means "write to stdout"
+const STDOUT_SENTINEL: &str = "-";
 
 /// Execute the export action
-pub fn execute(ctx: &Context, entry_type: Option<EntryTypeArg>, output: &PathBuf) -> Result<()> {
+pub fn execute(
+    ctx: &Context,
+    entry_type: Option<EntryTypeArg>,
+    format: ExportFormat,
+    output: &Path,
+) -> Result<()> {
     let parse_result = ctx.parse_config_file()?;
 
     // Filter entries if type specified
@@ -26,20 +38,36 @@ pub fn execute(ctx: &Context, entry_type: Option<EntryTypeArg>, output: &PathBuf
         parse_result.entries
     };
 
+    let to_stdout = is_stdout(output);
+
     if entries.is_empty() {
-        ctx.print_warning("No entries to export.");
+        if to_stdout {
+            eprintln!("{} No entries to export.", "⚠".yellow());
+        } else {
+            ctx.print_warning("No entries to export.");
+        }
         return Ok(());
     }
 
-    // Format entries
-    let formatter = get_formatter(ctx.shell_type);
-    let content = formatter.format(&entries, &ctx.config);
+    // Shell syntax is streamed to the output; bundles are built up front
+    let bundle = match format {
+        ExportFormat::Shell => None,
+        ExportFormat::Bundle => Some(bundle_content(ctx, &entries)?),
+    };
+    let write = |w: &mut dyn Write| match &bundle {
+        Some(content) => w.write_all(content.as_bytes()),
+        None => get_formatter(ctx.shell_type).format_to_writer(&entries, &ctx.config, w),
+    };
 
-    // Write to output file
-    if let Some(parent) = output.parent() {
-        std::fs::create_dir_all(parent)?;
+    // Keep stdout clean for piping: content only, no status message
+    if to_stdout {
+        let mut stdout = std::io::stdout().lock();
+        write(&mut stdout)?;
+        stdout.flush()?;
+        return Ok(());
     }
-    std::fs::write(output, &content)?;
+
+    ctx.timed("Format and write", || save_file_with(output, write))?;
 
     ctx.print_success(&format!(
         "Exported {} entries to {}",
@@ -49,3 +77,32 @@ pub fn execute(ctx: &Context, entry_type: Option<EntryTypeArg>, output: &PathBuf
 
     Ok(())
 }
+
+/// Bundle `entries` with the export time and the config file's name
+fn bundle_content(ctx: &Context, entries: &[crate::model::Entry]) -> Result<String> {
+    let mut bundle = Bundle::from_entries(ctx.shell_type, entries);
+    if let Ok(now) = time::OffsetDateTime::now_utc().format(&Rfc3339) {
+        bundle = bundle.with_created(now);
+    }
+    if let Some(name) = ctx.config_file.file_name() {
+        bundle = bundle.with_source(name.to_string_lossy());
+    }
+    bundle.to_toml()
+}
+
+/// Whether `output` is the `-` stdout sentinel
+fn is_stdout(output: &Path) -> bool {
+    output.as_os_str() == STDOUT_SENTINEL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stdout() {
+        assert!(is_stdout(Path::new("-")));
+        assert!(!is_stdout(Path::new("./-")));
+        assert!(!is_stdout(Path::new("out.sh")));
+    }
+}
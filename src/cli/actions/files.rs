@@ -0,0 +1,28 @@
+//! Files command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::context::Context;
+
+/// Execute the files action
+///
+/// Lists the files the shell conventionally reads its configuration from,
+/// marking those that exist and the one wenv works on. Read-only.
+pub fn execute(ctx: &Context) -> Result<()> {
+    println!("{}", format!("{} config files", ctx.shell_type).bold());
+    for path in ctx.shell_type.candidate_config_paths() {
+        let mark = if path.exists() {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        let current = if path == ctx.config_file {
+            format!(" {}", "(current)".cyan())
+        } else {
+            String::new()
+        };
+        println!("{} {}{}", mark, ctx.display_path(&path), current);
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Format command implementation
+
+use anyhow::Result;
+use std::ops::RangeInclusive;
+
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::formatter::range::format_range;
+use crate::utils::strings::with_single_trailing_newline;
+
+/// Execute the format action
+///
+/// Formats the entries lying entirely within `range` (1-based, inclusive),
+/// or the whole file without one. Entries only partly within the range are
+/// left alone with a warning; see [`format_range`].
+pub fn execute(ctx: &Context, range: Option<RangeInclusive<usize>>) -> Result<()> {
+    let content = ctx.timed("Read", || crate::utils::path::read_file(&ctx.config_file))?;
+    let entries = ctx.timed("Parse", || ctx.parser().parse(&content).entries);
+    let lines = range.clone().unwrap_or(1..=usize::MAX);
+
+    let formatter = get_formatter(ctx.shell_type);
+    let result = ctx.timed("Format", || {
+        format_range(&content, &entries, lines, formatter.as_ref(), &ctx.config)
+    });
+
+    for entry in &result.skipped {
+        ctx.print_warning(&format!(
+            "Skipped {} '{}' (line {}): only partly within the range",
+            entry.entry_type,
+            entry.name,
+            entry.line_number.unwrap_or(0)
+        ));
+    }
+    let Some(formatted) = result.formatted else {
+        ctx.print_warning("No entries lie entirely within the range.");
+        return Ok(());
+    };
+    // Compare what would be written, trailing newline normalized
+    let output = if ctx.config.format.preserve_trailing_blanks {
+        result.content
+    } else {
+        with_single_trailing_newline(&result.content)
+    };
+    if output == content {
+        ctx.print_success("Already formatted.");
+        return Ok(());
+    }
+
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&output)?;
+
+    let what = match range {
+        Some(_) => format!("lines {}-{}", formatted.start(), formatted.end()),
+        None => "file".to_string(),
+    };
+    ctx.record_history(&format!("format {}", what), backup.as_deref());
+    ctx.print_success(&format!("Formatted {}", what));
+    ctx.print_reload_hint();
+
+    Ok(())
+}
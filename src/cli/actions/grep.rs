@@ -0,0 +1,161 @@
+//! Grep command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+use regex::Regex;
+
+use crate::cli::args::EntryTypeArg;
+use crate::cli::context::Context;
+use crate::model::{Entry, EntryType};
+
+/// An entry matching the pattern
+struct Match<'a> {
+    entry: &'a Entry,
+    /// Canonical value of the entry
+    value: &'a str,
+    /// Lines of the canonical value containing a match, without their
+    /// indentation; empty when only the name matches
+    lines: Vec<&'a str>,
+}
+
+/// Execute the grep action
+///
+/// Prints every entry (of `entry_type`, if given) whose name or value
+/// matches the regex `pattern`, with the matching value lines highlighted.
+/// With `name_only`, just the names are printed, one per line.
+pub fn execute(
+    ctx: &Context,
+    pattern: &str,
+    entry_type: Option<EntryTypeArg>,
+    name_only: bool,
+) -> Result<()> {
+    let regex =
+        Regex::new(pattern).map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))?;
+    let entries = ctx.parse_config_file()?.entries;
+    let values: Vec<String> = entries.iter().map(Entry::canonical_value).collect();
+    let matches = find_matches(&entries, &values, &regex, entry_type.map(Into::into));
+
+    if name_only {
+        for m in &matches {
+            println!("{}", m.entry.name);
+        }
+        return Ok(());
+    }
+    if matches.is_empty() {
+        println!("{}", ctx.messages.no_entries_found);
+        return Ok(());
+    }
+
+    for m in &matches {
+        let line = m
+            .entry
+            .line_number
+            .map(|n| format!("(line {})", n))
+            .unwrap_or_default();
+        let shown: Vec<String> = match m.lines.as_slice() {
+            [] => vec![m.value.lines().next().unwrap_or("").trim().to_string()],
+            lines => lines.iter().map(|l| highlight(l, &regex)).collect(),
+        };
+        for text in shown {
+            println!(
+                "{} {} {}: {}",
+                m.entry
+                    .entry_type
+                    .to_string()
+                    .color(ctx.color_entry_type(m.entry.entry_type)),
+                highlight(&m.entry.name, &regex).white(),
+                line.dimmed(),
+                text
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Entries (of `entry_type`, if given) whose name or value matches `regex`,
+/// with value lines matched without their indentation (so `^git` finds a
+/// function body calling git). `values` are the canonical values of `entries`, in the same order.
+fn find_matches<'a>(
+    entries: &'a [Entry],
+    values: &'a [String],
+    regex: &Regex,
+    entry_type: Option<EntryType>,
+) -> Vec<Match<'a>> {
+    entries
+        .iter()
+        .zip(values)
+        .filter(|(e, _)| !e.is_blank())
+        .filter(|(e, _)| entry_type.is_none_or(|t| e.entry_type == t))
+        .filter_map(|(entry, value)| {
+            let lines: Vec<&str> = value
+                .lines()
+                .map(str::trim)
+                .filter(|l| regex.is_match(l))
+                .collect();
+            (!lines.is_empty() || regex.is_match(&entry.name)).then_some(Match {
+                entry,
+                value,
+                lines,
+            })
+        })
+        .collect()
+}
+
+/// `text` with every match of `regex` highlighted
+fn highlight(text: &str, regex: &Regex) -> String {
+    regex
+        .replace_all(text, |caps: &regex::Captures| {
+            caps[0].red().bold().to_string()
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    const CONTENT: &str = "\
+alias gs='git status'
+alias ll='ls -la'
+export EDITOR=vim
+gco() {
+    git checkout \"$@\"
+}
+alias gitk='gitk --all'
+";
+
+    fn names(pattern: &str, entry_type: Option<EntryType>) -> Vec<String> {
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(CONTENT)
+            .entries;
+        let values: Vec<String> = entries.iter().map(Entry::canonical_value).collect();
+        let regex = Regex::new(pattern).unwrap();
+        find_matches(&entries, &values, &regex, entry_type)
+            .iter()
+            .map(|m| m.entry.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_values_across_types() {
+        assert_eq!(names("git ", None), ["gs", "gco"]);
+        assert_eq!(names("^git", None), ["gs", "gco", "gitk"]);
+        assert_eq!(names("git ", Some(EntryType::Function)), ["gco"]);
+        assert!(names("emacs", None).is_empty());
+    }
+
+    #[test]
+    fn test_matches_names() {
+        // "gitk" matches by value as well; "ll" only by name
+        assert_eq!(names("^l+$", None), ["ll"]);
+    }
+
+    #[test]
+    fn test_highlight_keeps_text_without_color() {
+        colored::control::set_override(false);
+        let regex = Regex::new("git").unwrap();
+        assert_eq!(highlight("git status", &regex), "git status");
+        colored::control::unset_override();
+    }
+}
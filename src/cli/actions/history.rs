@@ -0,0 +1,30 @@
+//! History command implementation
+
+use anyhow::Result;
+
+use crate::cli::context::Context;
+use crate::model::Config;
+use crate::utils::history;
+
+/// Execute the history action: print the change log, or delete it with
+/// `clear`
+pub fn execute(ctx: &Context, clear: bool) -> Result<()> {
+    let path = Config::history_path();
+
+    if clear {
+        if history::clear(&path)? {
+            ctx.print_success("History cleared");
+        } else {
+            println!("No history to clear.");
+        }
+        return Ok(());
+    }
+
+    let log = history::read(&path)?;
+    if log.is_empty() {
+        println!("No changes recorded yet.");
+    } else {
+        print!("{}", log);
+    }
+    Ok(())
+}
@@ -3,17 +3,41 @@
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{Confirm, Select};
+use std::collections::{HashMap, HashSet};
 
-use crate::backup::BackupManager;
 use crate::cli::args::ConflictStrategy;
 use crate::cli::context::Context;
 use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType, ShellType};
 use crate::parser::get_parser;
+use crate::utils::bundle::Bundle;
 use crate::utils::http::{fetch_url, is_url};
 use crate::utils::path::expand_tilde;
+use crate::utils::shell_detect::{detect_from_file, ShellSource};
+use crate::utils::translate::{same_syntax, translate};
+
+/// Where imported entries are added to the config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    /// At the end of the file
+    Append,
+    /// At the top of the file, below a `#!` line
+    Prepend,
+    /// Right after the entry with this name
+    After(String),
+}
 
 /// Execute the import action
-pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
+///
+/// Imported entries are added together at `placement`. With `dry_run`, only
+/// the merge plan is printed; nothing is written and no backup is created.
+pub fn execute(
+    ctx: &Context,
+    source: &str,
+    placement: Placement,
+    yes: bool,
+    dry_run: bool,
+) -> Result<()> {
     // Fetch content from source
     let content = if is_url(source) {
         println!("Fetching from URL: {}", source.cyan());
@@ -26,11 +50,9 @@ pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
         std::fs::read_to_string(&path)?
     };
 
-    // Parse the source content
-    let parser = get_parser(ctx.shell_type);
-    let parse_result = parser.parse(&content);
+    let incoming = incoming_entries(ctx, source, &content)?;
 
-    if parse_result.entries.is_empty() {
+    if incoming.is_empty() {
         println!("{}", "No entries found in source.".yellow());
         return Ok(());
     }
@@ -39,9 +61,7 @@ pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
     println!();
     println!(
         "{}",
-        format!("Found {} entries:", parse_result.entries.len())
-            .green()
-            .bold()
+        format!("Found {} entries:", incoming.len()).green().bold()
     );
     println!();
 
@@ -53,24 +73,38 @@ pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
     );
     println!("{}", "─".repeat(60).dimmed());
 
-    for entry in &parse_result.entries {
-        let value = if entry.value.len() > 35 {
-            format!("{}...", &entry.value[..32])
-        } else {
-            entry.value.clone()
-        };
-        let value = value.replace('\n', "\\n");
-
+    for entry in &incoming {
         println!(
             "{:<10} {:<20} {}",
             format!("{}", entry.entry_type).green(),
             entry.name.white(),
-            value.dimmed()
+            preview_value(&entry.value).dimmed()
         );
     }
 
     println!();
 
+    // Parse current file to check for conflicts
+    let current_result = ctx.parse_config_file().unwrap_or_default();
+    let current_content = if ctx.config_file.exists() {
+        std::fs::read_to_string(&ctx.config_file)?
+    } else {
+        String::new()
+    };
+    let insert_at = insertion_line(&placement, &current_content, &current_result.entries)?;
+    let plan = plan_import(
+        &incoming,
+        &current_result.entries,
+        ctx.on_conflict,
+        yes,
+        &ctx.rename_suffix,
+    );
+
+    if dry_run {
+        print_plan(&plan);
+        return Ok(());
+    }
+
     // Confirm import
     if !yes
         && !Confirm::new()
@@ -82,102 +116,65 @@ pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Parse current file to check for conflicts
-    let current_result = ctx.parse_config_file().unwrap_or_default();
-    let current_entries: std::collections::HashMap<_, _> = current_result
-        .entries
-        .iter()
-        .map(|e| ((e.entry_type, &e.name), e))
-        .collect();
-
     // Create backup
-    let backup_manager = BackupManager::new(ctx.shell_type, &ctx.config);
-    if ctx.config_file.exists() {
-        backup_manager.create_backup(&ctx.config_file)?;
-    }
+    let backup = ctx.backup_config_file()?;
 
     // Process entries
     let formatter = get_formatter(ctx.shell_type);
-    let mut content = if ctx.config_file.exists() {
-        std::fs::read_to_string(&ctx.config_file)?
-    } else {
-        String::new()
-    };
+    let mut block = String::new();
 
     let mut imported = 0;
     let mut skipped = 0;
     let mut overwritten = 0;
+    let mut renamed = 0;
 
-    for entry in &parse_result.entries {
-        let key = (entry.entry_type, &entry.name);
-
-        if let Some(existing) = current_entries.get(&key) {
-            // Handle conflict
-            let action = match ctx.on_conflict {
-                ConflictStrategy::Skip => {
-                    skipped += 1;
-                    continue;
-                }
-                ConflictStrategy::Overwrite => "overwrite",
-                ConflictStrategy::Ask => {
-                    if yes {
-                        "skip"
-                    } else {
-                        let choices = ["Skip", "Overwrite", "Rename"];
-                        let selection = Select::new()
-                            .with_prompt(format!(
-                                "{} '{}' already exists. Current: '{}', New: '{}'",
-                                entry.entry_type,
-                                entry.name.cyan(),
-                                existing.value.dimmed(),
-                                entry.value.dimmed()
-                            ))
-                            .items(&choices)
-                            .default(0)
-                            .interact()?;
-
-                        match selection {
-                            0 => "skip",
-                            1 => "overwrite",
-                            _ => "skip",
-                        }
-                    }
-                }
-            };
+    for planned in &plan {
+        let mut entry = planned.entry;
+        let action = match (planned.action, planned.existing) {
+            (ImportAction::Ask, Some(existing)) => {
+                prompt_conflict(entry, existing, planned.renamed.as_ref())?
+            }
+            (action, _) => action,
+        };
 
-            match action {
-                "skip" => {
-                    skipped += 1;
-                    continue;
-                }
-                "overwrite" => {
-                    overwritten += 1;
-                }
-                _ => {
-                    skipped += 1;
-                    continue;
-                }
+        match (action, &planned.renamed) {
+            (ImportAction::Add, _) => {}
+            (ImportAction::Overwrite, _) => overwritten += 1,
+            (ImportAction::Rename, Some(new_entry)) => {
+                ctx.print_warning(&format!(
+                    "Renamed {} '{}' to '{}'",
+                    entry.entry_type, entry.name, new_entry.name
+                ));
+                entry = new_entry;
+                renamed += 1;
+            }
+            _ => {
+                skipped += 1;
+                continue;
             }
         }
 
-        // Append entry
-        let line = formatter.format_entry(entry);
-        if !content.is_empty() && !content.ends_with('\n') {
-            content.push('\n');
-        }
-        content.push_str(&line);
-        content.push('\n');
+        block.push_str(&formatter.format_entry(entry));
+        block.push('\n');
         imported += 1;
     }
 
     // Write back
-    std::fs::write(&ctx.config_file, &content)?;
+    let content = splice(&current_content, &block, insert_at);
+    ctx.save_config_file(&content)?;
+    ctx.record_history(
+        &format!(
+            "import {} ({} added, {} skipped)",
+            source, imported, skipped
+        ),
+        backup.as_deref(),
+    );
 
     // Summary
     println!();
     ctx.print_success(&format!(
-        "Imported {} entries ({} skipped, {} overwritten)",
-        imported, skipped, overwritten
+        "Imported {} entries ({} skipped, {} overwritten, {} renamed)",
+        imported, skipped, overwritten, renamed
     ));
 
     if imported > 0 || overwritten > 0 {
@@ -186,3 +183,443 @@ pub fn execute(ctx: &Context, source: &str, yes: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// The entries of `content`, read from `source`, in this shell's syntax.
+/// Bundles are regenerated and files of another shell are translated,
+/// unless `--force-shell` says to read them as they are.
+pub(super) fn incoming_entries(ctx: &Context, source: &str, content: &str) -> Result<Vec<Entry>> {
+    Ok(if Bundle::is_bundle(content) {
+        let bundle = Bundle::parse(content)?;
+        println!("Reading wenv bundle exported from {}", bundle.shell.cyan());
+        let (entries, skipped) = bundle.to_entries(ctx.shell_type)?;
+        for item in skipped {
+            ctx.print_warning(&format!(
+                "Skipped {} '{}': {}",
+                item.entry_type, item.name, item.reason
+            ));
+        }
+        entries
+    } else {
+        // --force-shell reads the source as the same shell, untranslated
+        let from = (ctx.shell_source != ShellSource::Forced)
+            .then(|| detect_from_file(std::path::Path::new(source)))
+            .flatten();
+        match from {
+            Some(from) if !same_syntax(from, ctx.shell_type) => {
+                println!(
+                    "Translating {} syntax to {}",
+                    from.name().cyan(),
+                    ctx.shell_type
+                );
+                translate_entries(ctx, content, from)
+            }
+            _ => ctx.parser().parse(content).entries,
+        }
+    })
+}
+
+/// `value` for the preview table: cut to 32 characters past 35, with
+/// newlines shown as `\n`
+fn preview_value(value: &str) -> String {
+    let value = if value.chars().count() > 35 {
+        format!("{}...", value.chars().take(32).collect::<String>())
+    } else {
+        value.to_string()
+    };
+    value.replace('\n', "\\n")
+}
+
+/// Number of lines of `content` that stay above the imported entries
+fn insertion_line(placement: &Placement, content: &str, entries: &[Entry]) -> Result<usize> {
+    Ok(match placement {
+        Placement::Append => content.lines().count(),
+        Placement::Prepend => usize::from(content.starts_with("#!")),
+        Placement::After(name) => {
+            let Some(entry) = entries.iter().find(|e| e.name == *name && !e.is_blank()) else {
+                anyhow::bail!("No entry named '{}' to import after", name);
+            };
+            // Blank lines the entry absorbed stay below the imported ones
+            let lines = crate::formatter::trim_trailing_blank_lines(&entry.value)
+                .lines()
+                .count();
+            entry.line_number.unwrap_or(1) + lines.max(1) - 1
+        }
+    })
+}
+
+/// `content` with `block` inserted after its first `line` lines
+fn splice(content: &str, block: &str, line: usize) -> String {
+    let mut lines = content.split_inclusive('\n');
+    let mut spliced: String = lines.by_ref().take(line).collect();
+    if !spliced.is_empty() && !spliced.ends_with('\n') {
+        spliced.push('\n');
+    }
+    spliced.push_str(block);
+    spliced.extend(lines);
+    spliced
+}
+
+/// Parse `content` as `from` shell syntax and translate its entries to the
+/// current shell, warning about the ones that have no equivalent
+fn translate_entries(ctx: &Context, content: &str, from: ShellType) -> Vec<Entry> {
+    get_parser(from)
+        .parse(content)
+        .entries
+        .iter()
+        .filter(|e| e.entry_type != EntryType::Comment && !e.is_blank())
+        .filter_map(|entry| {
+            let translated = translate(entry, from, ctx.shell_type);
+            if translated.is_none() {
+                ctx.print_warning(&format!(
+                    "Skipped {} '{}': no {} equivalent",
+                    entry.entry_type, entry.name, ctx.shell_type
+                ));
+            }
+            translated
+        })
+        .collect()
+}
+
+/// What importing a single entry will do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    /// No conflict, entry is appended
+    Add,
+    /// Conflicts with an existing entry, new one is appended anyway
+    Overwrite,
+    /// Conflicts with an existing entry, new one is dropped
+    Skip,
+    /// Conflicts with an existing entry, new one is appended under a new name
+    Rename,
+    /// Conflicts with an existing entry, user will be prompted
+    Ask,
+}
+
+impl ImportAction {
+    fn label(self) -> &'static str {
+        match self {
+            ImportAction::Add => "add",
+            ImportAction::Overwrite => "overwrite",
+            ImportAction::Skip => "skip",
+            ImportAction::Rename => "rename",
+            ImportAction::Ask => "ask",
+        }
+    }
+}
+
+/// An incoming entry paired with its planned action
+#[derive(Debug)]
+pub struct PlannedImport<'a> {
+    pub entry: &'a Entry,
+    /// The current entry with the same type and name, if any
+    pub existing: Option<&'a Entry>,
+    pub action: ImportAction,
+    /// The entry under a non-colliding name, for Rename (and Ask) conflicts
+    pub renamed: Option<Entry>,
+}
+
+/// Compute the merge plan for importing `incoming` into `current`.
+///
+/// Shared by `--dry-run` and the real import so both resolve conflicts the
+/// same way. `ConflictStrategy::Ask` stays [`ImportAction::Ask`] unless `yes`
+/// is set, in which case conflicts are skipped.
+///
+/// Renamed entries get `rename_suffix` appended to their name, plus a counter
+/// (`ll_imported2`, `ll_imported3`, ...) while that name is still taken by a
+/// current or incoming entry of the same type.
+pub fn plan_import<'a>(
+    incoming: &'a [Entry],
+    current: &'a [Entry],
+    strategy: ConflictStrategy,
+    yes: bool,
+    rename_suffix: &str,
+) -> Vec<PlannedImport<'a>> {
+    let current_entries: HashMap<_, _> = current
+        .iter()
+        .map(|e| ((e.entry_type, e.name.as_str()), e))
+        .collect();
+    let mut taken: HashSet<(EntryType, String)> = current
+        .iter()
+        .chain(incoming)
+        .map(|e| (e.entry_type, e.name.clone()))
+        .collect();
+
+    incoming
+        .iter()
+        .map(|entry| {
+            let existing = current_entries
+                .get(&(entry.entry_type, entry.name.as_str()))
+                .copied();
+            let mut action = match (existing, strategy) {
+                (None, _) => ImportAction::Add,
+                (Some(_), ConflictStrategy::Skip) => ImportAction::Skip,
+                (Some(_), ConflictStrategy::Overwrite) => ImportAction::Overwrite,
+                (Some(_), ConflictStrategy::Rename) => ImportAction::Rename,
+                (Some(_), ConflictStrategy::Ask) if yes => ImportAction::Skip,
+                (Some(_), ConflictStrategy::Ask) => ImportAction::Ask,
+            };
+
+            let renamed = match action {
+                ImportAction::Rename | ImportAction::Ask => {
+                    let new_name = unique_name(entry, rename_suffix, &taken);
+                    let renamed = entry.renamed(&new_name);
+                    if renamed.is_some() {
+                        taken.insert((entry.entry_type, new_name));
+                    }
+                    renamed
+                }
+                _ => None,
+            };
+            // Entries whose name can't be rewritten fall back to skipping
+            if action == ImportAction::Rename && renamed.is_none() {
+                action = ImportAction::Skip;
+            }
+
+            PlannedImport {
+                entry,
+                existing,
+                action,
+                renamed,
+            }
+        })
+        .collect()
+}
+
+/// First `{name}{suffix}`, `{name}{suffix}2`, ... not yet in `taken`
+fn unique_name(entry: &Entry, suffix: &str, taken: &HashSet<(EntryType, String)>) -> String {
+    let base = format!("{}{}", entry.name, suffix);
+    let mut candidate = base.clone();
+    let mut counter = 2;
+    while taken.contains(&(entry.entry_type, candidate.clone())) {
+        candidate = format!("{}{}", base, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+/// Render the merge plan, one line per incoming entry
+fn format_plan(plan: &[PlannedImport]) -> Vec<String> {
+    plan.iter()
+        .map(|p| {
+            let marker = match p.action {
+                ImportAction::Add => "+",
+                ImportAction::Overwrite => "~",
+                ImportAction::Skip => "-",
+                ImportAction::Rename => "*",
+                ImportAction::Ask => "?",
+            };
+            let line = format!(
+                "{} {:<10} {:<8} {}",
+                marker,
+                p.action.label(),
+                p.entry.entry_type.to_string(),
+                p.entry.name
+            );
+            match (&p.renamed, p.action) {
+                (Some(new_entry), ImportAction::Rename) => {
+                    format!("{} -> {}", line, new_entry.name)
+                }
+                _ => line,
+            }
+        })
+        .collect()
+}
+
+fn print_plan(plan: &[PlannedImport]) {
+    println!("{}", "Import plan (dry run):".bold());
+    for line in format_plan(plan) {
+        println!("  {}", line);
+    }
+
+    let count = |action| plan.iter().filter(|p| p.action == action).count();
+    println!();
+    println!(
+        "Would add {}, overwrite {}, rename {}, skip {}, ask {}. No changes written.",
+        count(ImportAction::Add),
+        count(ImportAction::Overwrite),
+        count(ImportAction::Rename),
+        count(ImportAction::Skip),
+        count(ImportAction::Ask)
+    );
+}
+
+/// Ask the user how to resolve a single conflict
+pub(super) fn prompt_conflict(
+    entry: &Entry,
+    existing: &Entry,
+    renamed: Option<&Entry>,
+) -> Result<ImportAction> {
+    let rename_choice = renamed.map(|e| format!("Rename to '{}'", e.name));
+    let mut choices = vec!["Skip".to_string(), "Overwrite".to_string()];
+    choices.extend(rename_choice);
+    let selection = Select::new()
+        .with_prompt(format!(
+            "{} '{}' already exists. Current: '{}', New: '{}'",
+            entry.entry_type,
+            entry.name.cyan(),
+            existing.value.dimmed(),
+            entry.value.dimmed()
+        ))
+        .items(&choices)
+        .default(0)
+        .interact()?;
+
+    Ok(match selection {
+        1 => ImportAction::Overwrite,
+        2 => ImportAction::Rename,
+        _ => ImportAction::Skip,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str, value: &str) -> Entry {
+        Entry::new(
+            EntryType::Alias,
+            name.to_string(),
+            format!("alias {}='{}'", name, value),
+        )
+    }
+
+    #[test]
+    fn test_preview_value_cuts_on_characters() {
+        assert_eq!(preview_value("alias ll='ls -la'"), "alias ll='ls -la'");
+        assert_eq!(preview_value("a\nb"), "a\\nb");
+
+        // Multi-byte characters straddling the cut don't panic
+        let value = format!("alias hi='{}'", "你好".repeat(20));
+        let preview = preview_value(&value);
+        assert_eq!(preview.chars().count(), 35);
+        assert!(preview.starts_with("alias hi='你好"), "{}", preview);
+        assert!(preview.ends_with("..."), "{}", preview);
+    }
+
+    #[test]
+    fn test_plan_by_strategy() {
+        let current = vec![alias("ll", "ls -l")];
+        let incoming = vec![alias("ll", "ls -la"), alias("gs", "git status")];
+
+        let actions = |strategy, yes| {
+            plan_import(&incoming, &current, strategy, yes, "_imported")
+                .iter()
+                .map(|p| p.action)
+                .collect::<Vec<_>>()
+        };
+
+        use ImportAction::*;
+        assert_eq!(actions(ConflictStrategy::Skip, false), [Skip, Add]);
+        assert_eq!(
+            actions(ConflictStrategy::Overwrite, false),
+            [Overwrite, Add]
+        );
+        assert_eq!(actions(ConflictStrategy::Ask, false), [Ask, Add]);
+        assert_eq!(actions(ConflictStrategy::Ask, true), [Skip, Add]);
+        assert_eq!(actions(ConflictStrategy::Rename, false), [Rename, Add]);
+    }
+
+    #[test]
+    fn test_rename_single_collision() {
+        let current = vec![alias("ll", "ls -l")];
+        let incoming = vec![alias("ll", "ls -la")];
+
+        let plan = plan_import(
+            &incoming,
+            &current,
+            ConflictStrategy::Rename,
+            false,
+            "_imported",
+        );
+
+        let renamed = plan[0].renamed.as_ref().unwrap();
+        assert_eq!(renamed.name, "ll_imported");
+        assert_eq!(renamed.value, "alias ll_imported='ls -la'");
+        assert_eq!(
+            format_plan(&plan),
+            ["* rename     alias    ll -> ll_imported"]
+        );
+    }
+
+    #[test]
+    fn test_rename_repeated_collisions() {
+        let current = vec![
+            alias("ll", "ls -l"),
+            alias("ll_imported", "ls -lh"),
+            alias("gs", "git status"),
+        ];
+        // `ll_imported2` is taken by another incoming entry
+        let incoming = vec![
+            alias("ll_imported2", "ls -1"),
+            alias("ll", "ls -la"),
+            alias("gs", "git status -s"),
+        ];
+
+        let plan = plan_import(
+            &incoming,
+            &current,
+            ConflictStrategy::Rename,
+            false,
+            "_imported",
+        );
+        let names: Vec<_> = plan
+            .iter()
+            .map(|p| p.renamed.as_ref().map(|e| e.name.as_str()))
+            .collect();
+
+        assert_eq!(names, [None, Some("ll_imported3"), Some("gs_imported")]);
+    }
+
+    #[test]
+    fn test_insertion_line_and_splice() {
+        let content = "#!/bin/bash\nalias ll='ls -l'\n\nexport EDITOR=vim";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+        let block = "alias gs='git status'\n";
+        let placed = |placement: Placement| {
+            let line = insertion_line(&placement, content, &entries).unwrap();
+            splice(content, block, line)
+        };
+
+        assert_eq!(
+            placed(Placement::Append),
+            "#!/bin/bash\nalias ll='ls -l'\n\nexport EDITOR=vim\nalias gs='git status'\n"
+        );
+        assert_eq!(
+            placed(Placement::Prepend),
+            "#!/bin/bash\nalias gs='git status'\nalias ll='ls -l'\n\nexport EDITOR=vim"
+        );
+        assert_eq!(
+            placed(Placement::After("ll".to_string())),
+            "#!/bin/bash\nalias ll='ls -l'\nalias gs='git status'\n\nexport EDITOR=vim"
+        );
+        assert!(insertion_line(&Placement::After("nope".to_string()), content, &entries).is_err());
+
+        // Without a shebang, prepending puts the entries on line 1
+        assert_eq!(
+            splice("alias ll='ls -l'\n", block, 0),
+            "alias gs='git status'\nalias ll='ls -l'\n"
+        );
+        assert_eq!(splice("", block, 0), block);
+    }
+
+    #[test]
+    fn test_plan_output() {
+        let current = vec![alias("ll", "ls -l")];
+        let incoming = vec![alias("ll", "ls -la"), alias("gs", "git status")];
+
+        let plan = plan_import(
+            &incoming,
+            &current,
+            ConflictStrategy::Overwrite,
+            false,
+            "_imported",
+        );
+
+        assert_eq!(
+            format_plan(&plan),
+            ["~ overwrite  alias    ll", "+ add        alias    gs",]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Info command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::context::Context;
+use crate::model::{Entry, EntryType};
+use crate::utils::expand::expand_entry;
+
+/// Split `target`, either `[NAME]` or `[TYPE, NAME]`, into type and name
+pub fn parse_target(target: &[String]) -> Result<(Option<EntryType>, &str)> {
+    match target {
+        [name] => Ok((None, name.as_str())),
+        [type_str, name] => {
+            let entry_type = type_str
+                .parse::<EntryType>()
+                .map_err(|e| anyhow::anyhow!(e))?;
+            Ok((Some(entry_type), name.as_str()))
+        }
+        _ => anyhow::bail!("Expected [TYPE] NAME"),
+    }
+}
+
+/// Find the entry addressed by `target`, either `[NAME]` or `[TYPE, NAME]`
+pub fn find_entry<'a>(ctx: &Context, entries: &'a [Entry], target: &[String]) -> Result<&'a Entry> {
+    let (entry_type, name) = parse_target(target)?;
+
+    entries
+        .iter()
+        .find(|e| e.name == name && entry_type.is_none_or(|t| e.entry_type == t))
+        .ok_or_else(|| {
+            let type_label = entry_type.map_or_else(|| "entry".to_string(), |t| t.to_string());
+            anyhow::anyhow!(ctx
+                .messages
+                .entry_not_found
+                .replacen("{}", &type_label, 1)
+                .replacen("{}", name, 1))
+        })
+}
+
+/// Execute the info action
+///
+/// `target` is either `[NAME]` or `[TYPE, NAME]`. With `value_only`, just the
+/// canonical value (or the expanded value, with `expand`) is printed. With
+/// several `--file` paths, the first file defining the entry is used and
+/// named.
+pub fn execute(ctx: &Context, target: &[String], expand: bool, value_only: bool) -> Result<()> {
+    let entries: Vec<Entry> = ctx
+        .parse_config_files()?
+        .into_iter()
+        .flat_map(|(_, result)| result.entries)
+        .collect();
+    let entries = &entries;
+    let entry = find_entry(ctx, entries, target)?;
+
+    if value_only {
+        let value = if expand {
+            expand_entry(entry, entries)
+        } else {
+            entry.canonical_value()
+        };
+        println!("{}", value);
+        return Ok(());
+    }
+
+    print_entry(ctx, entry);
+
+    if expand {
+        println!(
+            "{} {}",
+            ctx.messages.header_expanded.bold(),
+            expand_entry(entry, entries)
+        );
+    }
+
+    Ok(())
+}
+
+fn print_entry(ctx: &Context, entry: &Entry) {
+    let messages = ctx.messages;
+
+    let exported = if entry.exported {
+        format!(" ({})", messages.label_exported)
+    } else if entry.is_prompt_variable() {
+        format!(" ({})", messages.label_prompt)
+    } else {
+        String::new()
+    };
+    println!(
+        "{} {}{}",
+        entry.entry_type.to_string().dimmed(),
+        entry.name.cyan().bold(),
+        exported.dimmed()
+    );
+
+    match (entry.line_number, entry.end_line) {
+        (Some(start), Some(end)) if end > start => {
+            println!("{} {}-{}", messages.header_lines.bold(), start, end)
+        }
+        (Some(line), _) => println!("{} {}", messages.header_line.bold(), line),
+        _ => {}
+    }
+
+    if let Some(path) = &entry.source_file {
+        println!(
+            "{} {}",
+            messages.header_file_label.bold(),
+            ctx.display_path(path)
+        );
+    }
+
+    if let Some(comment) = &entry.attached_comment {
+        println!("{}", messages.header_comment.bold());
+        for line in comment.lines() {
+            println!("  {}", line.dimmed());
+        }
+    }
+
+    println!("{}", messages.header_raw.bold());
+    for line in entry.value.lines() {
+        println!("  {}", line);
+    }
+}
@@ -0,0 +1,70 @@
+//! Lint command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::checker::lint::{lint, LintInput, LINT_RULES};
+use crate::checker::Severity;
+use crate::cli::actions::check::issue_line;
+use crate::cli::context::Context;
+use crate::utils::path::{file_label, read_file};
+
+/// Execute the lint action
+///
+/// Runs the rules of [`LINT_RULES`] at the levels set in the `[lint]`
+/// config table, prints the findings in rule order and then a count per
+/// rule. Fails when any finding is at error level.
+pub fn execute(ctx: &Context) -> Result<()> {
+    let files = ctx.parse_config_files()?;
+    let several = files.len() > 1;
+
+    let mut findings = Vec::new();
+    for (path, result) in &files {
+        let content = ctx.timed("Read", || read_file(path))?;
+        let input = LintInput {
+            entries: &result.entries,
+            shell_type: ctx.shell_type,
+            path,
+            content: &content,
+        };
+        let label = if several {
+            file_label(path)
+        } else {
+            String::new()
+        };
+        findings.extend(
+            ctx.timed("Lint", || lint(&input, &ctx.config.lint))
+                .into_iter()
+                .map(|finding| (label.clone(), finding)),
+        );
+    }
+
+    if findings.is_empty() {
+        ctx.print_success("No lint findings");
+        return Ok(());
+    }
+
+    for (file, finding) in &findings {
+        println!("{}", issue_line(file, &finding.issue));
+    }
+
+    println!();
+    for rule in LINT_RULES {
+        let count = findings.iter().filter(|(_, f)| f.rule == rule.name).count();
+        if count > 0 {
+            println!("{:>4}  {}", count, rule.name);
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|(_, f)| f.issue.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    if errors == 0 {
+        println!("{}", format!("{} warning(s)", warnings).yellow());
+        Ok(())
+    } else {
+        anyhow::bail!("{} error(s), {} warning(s)", errors, warnings)
+    }
+}
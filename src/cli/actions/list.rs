@@ -0,0 +1,411 @@
+//! List command implementation
+
+use anyhow::Result;
+use colored::{Color, Colorize};
+use std::ops::Range;
+
+use crate::cli::args::{EntryTypeArg, SortKey};
+use crate::cli::context::Context;
+use crate::model::{Entry, EntryType};
+use crate::utils::colors::default_color;
+use crate::utils::path::file_label;
+use crate::utils::strings::{escape_control_chars, truncate_with_ellipsis};
+use crate::utils::template::Template;
+
+/// Narrowest value column before truncation stops being useful
+const MIN_VALUE_WIDTH: usize = 10;
+
+/// Window of the filtered entries to show (`--offset` / `--limit`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Page {
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl Page {
+    fn is_paged(&self) -> bool {
+        self.offset > 0 || self.limit.is_some()
+    }
+
+    /// Indices of the page within `total` entries, clamped to the end
+    fn range(&self, total: usize) -> Range<usize> {
+        let start = self.offset.min(total);
+        let end = self
+            .limit
+            .map_or(total, |limit| start.saturating_add(limit).min(total));
+        start..end
+    }
+
+    /// Footer such as `Showing 1-50 of 342`
+    fn footer(&self, total: usize) -> String {
+        let range = self.range(total);
+        if range.is_empty() {
+            format!("Showing 0 of {}", total)
+        } else {
+            format!("Showing {}-{} of {}", range.start + 1, range.end, total)
+        }
+    }
+}
+
+/// Execute the list action
+///
+/// With several `--file` paths, entries of every file are listed together
+/// and the line column names the file (`.bash_aliases:3`). With
+/// `include_disabled`, commented-out definitions are listed (dimmed) as the
+/// entries they define. With `template`, each entry is printed through it
+/// instead, without header or footer.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    ctx: &Context,
+    entry_type: Option<EntryTypeArg>,
+    tag: Option<&str>,
+    page: Page,
+    no_truncate: bool,
+    sort: SortKey,
+    include_disabled: bool,
+    template: Option<&Template>,
+) -> Result<()> {
+    let files = ctx.parse_config_files()?;
+    // Commented-out definitions, parallel to each file's entries
+    let disabled: Vec<Vec<Option<Entry>>> = files
+        .iter()
+        .map(|(_, result)| {
+            result
+                .entries
+                .iter()
+                .map(|e| {
+                    include_disabled
+                        .then(|| e.disabled_definition(ctx.shell_type))
+                        .flatten()
+                })
+                .collect()
+        })
+        .collect();
+    let filter_type: Option<EntryType> = entry_type.map(Into::into);
+
+    let mut entries: Vec<Listed> = files
+        .iter()
+        .zip(&disabled)
+        .flat_map(|((_, result), disabled)| {
+            result
+                .entries
+                .iter()
+                .zip(disabled)
+                .map(move |(entry, definition)| Listed {
+                    entry: definition.as_ref().unwrap_or(entry),
+                    disabled: definition.is_some(),
+                })
+        })
+        .filter(|l| !l.entry.is_blank())
+        .filter(|l| filter_type.is_none_or(|t| l.entry.entry_type == t))
+        .filter(|l| tag.is_none_or(|t| l.entry.has_tag(t)))
+        .collect();
+    sort_entries(&mut entries, sort);
+
+    if let Some(template) = template {
+        for listed in &entries[page.range(entries.len())] {
+            println!("{}", template.render(listed.entry));
+        }
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("{}", ctx.messages.no_entries_found);
+        return Ok(());
+    }
+
+    let rows: Vec<Row> = entries[page.range(entries.len())]
+        .iter()
+        .map(|listed| {
+            let mut row = Row::from_entry(listed.entry);
+            row.type_color = ctx.color_entry_type(listed.entry.entry_type);
+            if listed.entry.exported {
+                row.name = format!("{} ({})", row.name, ctx.messages.label_exported);
+            }
+            if listed.entry.is_prompt_variable() {
+                row.name = format!("{} ({})", row.name, ctx.messages.label_prompt);
+            }
+            // First line of the comment recorded with `parser.attach_comments`
+            if let Some(comment) = listed.entry.attached_comment.as_deref() {
+                let comment = escape_control_chars(comment.lines().next().unwrap_or(""));
+                row.value = format!("{}  {}", row.value, comment);
+            }
+            if listed.disabled {
+                row.name = format!("{} ({})", row.name, ctx.messages.label_disabled);
+                row.disabled = true;
+            }
+            row
+        })
+        .collect();
+    let widths = ColumnWidths::measure(ctx, &rows);
+
+    let value_width = if no_truncate {
+        None
+    } else {
+        terminal_size::terminal_size()
+            .map(|(terminal_size::Width(w), _)| value_column_width(w as usize, &widths))
+    };
+
+    println!(
+        "{:<tw$}  {:<nw$}  {}{:>lw$}  {}",
+        ctx.messages.header_type.bold().cyan(),
+        ctx.messages.header_name.bold().cyan(),
+        widths.file_cell(ctx.messages.header_file).bold().cyan(),
+        ctx.messages.header_line_num.bold().cyan(),
+        ctx.messages.header_value.bold().cyan(),
+        tw = widths.entry_type,
+        nw = widths.name,
+        lw = widths.line,
+    );
+
+    for row in &rows {
+        let value = match value_width {
+            Some(width) => truncate_with_ellipsis(&row.value, width),
+            None => row.value.clone(),
+        };
+        if row.disabled {
+            let line = format!(
+                "{:<tw$}  {:<nw$}  {}{:>lw$}  {}",
+                row.entry_type,
+                row.name,
+                widths.file_cell(&row.file),
+                row.line,
+                value,
+                tw = widths.entry_type,
+                nw = widths.name,
+                lw = widths.line,
+            );
+            println!("{}", line.dimmed());
+            continue;
+        }
+        println!(
+            "{:<tw$}  {:<nw$}  {}{:>lw$}  {}",
+            row.entry_type.color(row.type_color),
+            row.name.white(),
+            widths.file_cell(&row.file).dimmed(),
+            row.line.dimmed(),
+            value,
+            tw = widths.entry_type,
+            nw = widths.name,
+            lw = widths.line,
+        );
+    }
+
+    println!();
+    let footer = if page.is_paged() {
+        page.footer(entries.len())
+    } else {
+        ctx.messages
+            .total_entries
+            .replace("{}", &rows.len().to_string())
+    };
+    println!("{}", footer.dimmed());
+
+    Ok(())
+}
+
+/// An entry to list
+struct Listed<'a> {
+    entry: &'a Entry,
+    /// Whether `entry` is a commented-out definition
+    disabled: bool,
+}
+
+/// Order `entries` (in file order) by `key`; ties keep file order
+fn sort_entries(entries: &mut [Listed], key: SortKey) {
+    match key {
+        SortKey::Line => {}
+        SortKey::Name => entries.sort_by_cached_key(|l| l.entry.name.to_lowercase()),
+        SortKey::Type => {
+            entries.sort_by_cached_key(|l| (l.entry.entry_type, l.entry.name.to_lowercase()))
+        }
+    }
+}
+
+/// Display strings for one entry
+struct Row {
+    entry_type: String,
+    type_color: Color,
+    name: String,
+    /// Label of the entry's file, empty unless several files are listed
+    file: String,
+    line: String,
+    value: String,
+    /// Shown dimmed, as a commented-out definition
+    disabled: bool,
+}
+
+impl Row {
+    fn from_entry(entry: &Entry) -> Self {
+        let line = match (entry.line_number, entry.end_line) {
+            (Some(start), Some(end)) if end > start => format!("{}-{}", start, end),
+            (Some(start), _) => start.to_string(),
+            _ => String::new(),
+        };
+        // Raw escape characters, as prompts may hold, would act on the terminal
+        let canonical = escape_control_chars(&entry.canonical_value());
+        let mut lines = canonical.lines();
+        let mut value = lines.next().unwrap_or("").to_string();
+        if lines.next().is_some() {
+            // Multi-line entries show their size first: `[50] greet() { …`
+            value = format!("[{}] {} …", entry.line_count(), value);
+        }
+
+        Self {
+            entry_type: entry.entry_type.to_string(),
+            type_color: default_color(entry.entry_type),
+            name: entry.name.clone(),
+            file: entry
+                .source_file
+                .as_deref()
+                .map(file_label)
+                .unwrap_or_default(),
+            line,
+            value,
+            disabled: false,
+        }
+    }
+}
+
+/// Widths of the fixed (never truncated) columns
+struct ColumnWidths {
+    entry_type: usize,
+    name: usize,
+    /// 0 when no entry has a file to show, leaving the column out
+    file: usize,
+    line: usize,
+}
+
+impl ColumnWidths {
+    fn measure(ctx: &Context, rows: &[Row]) -> Self {
+        let max = |header: &str, f: fn(&Row) -> &str| {
+            rows.iter()
+                .map(|r| f(r).chars().count())
+                .chain([header.chars().count()])
+                .max()
+                .unwrap_or(0)
+        };
+        let file = if rows.iter().any(|r| !r.file.is_empty()) {
+            max(ctx.messages.header_file, |r| &r.file)
+        } else {
+            0
+        };
+        Self {
+            entry_type: max(ctx.messages.header_type, |r| &r.entry_type),
+            name: max(ctx.messages.header_name, |r| &r.name),
+            file,
+            line: max(ctx.messages.header_line_num, |r| &r.line),
+        }
+    }
+
+    /// `text` padded to the file column and its separator, or nothing
+    /// without a file column
+    fn file_cell(&self, text: &str) -> String {
+        if self.file == 0 {
+            String::new()
+        } else {
+            format!("{:<width$}  ", text, width = self.file)
+        }
+    }
+}
+
+/// Width left for the value column on a terminal `term_width` columns wide
+fn value_column_width(term_width: usize, widths: &ColumnWidths) -> usize {
+    // Two-space separators between the columns
+    let file = if widths.file == 0 { 0 } else { widths.file + 2 };
+    let fixed = widths.entry_type + widths.name + file + widths.line + 6;
+    term_width.saturating_sub(fixed).max(MIN_VALUE_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_column_width() {
+        let widths = ColumnWidths {
+            entry_type: 5,
+            name: 10,
+            file: 0,
+            line: 4,
+        };
+        assert_eq!(value_column_width(80, &widths), 55);
+        // Narrow terminals still leave a usable value column
+        assert_eq!(value_column_width(20, &widths), MIN_VALUE_WIDTH);
+    }
+
+    #[test]
+    fn test_page_range_and_footer() {
+        let page = |offset, limit| Page { offset, limit };
+
+        assert_eq!(page(0, None).range(5), 0..5);
+        assert_eq!(page(0, Some(2)).range(5), 0..2);
+        assert_eq!(page(4, Some(2)).range(5), 4..5);
+        assert_eq!(page(5, Some(2)).range(5), 5..5);
+        assert_eq!(page(9, None).range(5), 5..5);
+        assert_eq!(page(0, Some(0)).range(5), 0..0);
+        assert_eq!(page(1, Some(usize::MAX)).range(5), 1..5);
+
+        assert_eq!(page(0, Some(2)).footer(5), "Showing 1-2 of 5");
+        assert_eq!(page(4, Some(2)).footer(5), "Showing 5-5 of 5");
+        assert_eq!(page(9, None).footer(5), "Showing 0 of 5");
+    }
+
+    fn sorted_names(key: SortKey) -> Vec<String> {
+        let entries = [
+            Entry::new(EntryType::EnvVar, "PATH".into(), "export PATH=/bin".into()),
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into()),
+            Entry::new(
+                EntryType::EnvVar,
+                "EDITOR".into(),
+                "export EDITOR=vim".into(),
+            ),
+            Entry::new(
+                EntryType::Alias,
+                "Gs".into(),
+                "alias Gs='git status'".into(),
+            ),
+        ];
+        let mut listed: Vec<Listed> = entries
+            .iter()
+            .map(|entry| Listed {
+                entry,
+                disabled: false,
+            })
+            .collect();
+        sort_entries(&mut listed, key);
+        listed.iter().map(|l| l.entry.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_line_keeps_file_order() {
+        assert_eq!(sorted_names(SortKey::Line), ["PATH", "ll", "EDITOR", "Gs"]);
+    }
+
+    #[test]
+    fn test_sort_by_name_ignores_case() {
+        assert_eq!(sorted_names(SortKey::Name), ["EDITOR", "Gs", "ll", "PATH"]);
+    }
+
+    #[test]
+    fn test_sort_by_type_then_name() {
+        assert_eq!(sorted_names(SortKey::Type), ["Gs", "ll", "EDITOR", "PATH"]);
+    }
+
+    #[test]
+    fn test_row_uses_first_canonical_line_and_line_count() {
+        let entry = Entry::new(
+            EntryType::Function,
+            "greet".into(),
+            "greet() {\n  echo hi\n}".into(),
+        )
+        .with_line_number(3)
+        .with_end_line(5);
+        let row = Row::from_entry(&entry);
+        assert_eq!(row.line, "3-5");
+        assert_eq!(row.value, "[3] greet() { …");
+
+        let alias = Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into());
+        assert_eq!(Row::from_entry(&alias).value, "ls -la");
+    }
+}
@@ -1,5 +1,23 @@
 //! CLI actions module
 
+pub mod add;
+pub mod apply;
+pub mod backup;
+pub mod check;
+pub mod config;
+pub mod count;
+pub mod doctor;
 pub mod export;
+pub mod files;
+pub mod format;
+pub mod grep;
+pub mod history;
 pub mod import;
+pub mod info;
+pub mod lint;
+pub mod list;
+pub mod move_entry;
+pub mod normalize_paths;
+pub mod remove;
 pub mod source;
+pub mod sources;
@@ -0,0 +1,189 @@
+//! Move command implementation
+
+use anyhow::Result;
+
+use crate::cli::args::EntryTypeArg;
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType};
+use crate::utils::reorder::{block_range, move_entries, render_entries};
+
+/// Where to place the moved entry, relative to another entry's name
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    Before(&'a str),
+    After(&'a str),
+}
+
+impl Target<'_> {
+    fn name(&self) -> &str {
+        match self {
+            Target::Before(name) | Target::After(name) => name,
+        }
+    }
+}
+
+/// Execute the move action
+pub fn execute(ctx: &Context, entry_type: EntryTypeArg, name: &str, target: Target) -> Result<()> {
+    let entry_type: EntryType = entry_type.into();
+    let mut entries = ctx.parse_config_file()?.entries;
+
+    let Some(source) = entries
+        .iter()
+        .position(|e| e.entry_type == entry_type && e.name == name)
+    else {
+        anyhow::bail!(ctx
+            .messages
+            .entry_not_found
+            .replacen("{}", &entry_type.to_string(), 1)
+            .replacen("{}", name, 1));
+    };
+    let Some(anchor) = find_target(&entries, target.name(), entry_type) else {
+        anyhow::bail!(ctx
+            .messages
+            .entry_not_found
+            .replacen("{}", "entry", 1)
+            .replacen("{}", target.name(), 1));
+    };
+
+    let Some(new_order) = plan_move(&entries, source, anchor, target) else {
+        anyhow::bail!("Cannot move '{}' relative to itself", name);
+    };
+    if new_order.is_noop {
+        println!("'{}' is already in place.", name);
+        return Ok(());
+    }
+    move_entries(&mut entries, &new_order.indices, new_order.to);
+
+    let formatter = get_formatter(ctx.shell_type);
+    let content = ctx.timed("Format", || render_entries(&entries, formatter.as_ref()));
+
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&content)?;
+
+    let (verb, other) = match target {
+        Target::Before(other) => ("before", other),
+        Target::After(other) => ("after", other),
+    };
+    ctx.record_history(
+        &format!("move {} '{}' {} '{}'", entry_type, name, verb, other),
+        backup.as_deref(),
+    );
+    ctx.print_success(&format!(
+        "Moved {} '{}' {} '{}'",
+        entry_type, name, verb, other
+    ));
+    ctx.print_reload_hint();
+
+    Ok(())
+}
+
+/// Find the entry named `name` to move relative to. Code and Comment entries
+/// have generated names and are never targets; when several types share the
+/// name, one of the moved entry's type wins.
+fn find_target(entries: &[Entry], name: &str, preferred: EntryType) -> Option<usize> {
+    let candidates = || {
+        entries.iter().enumerate().filter(|(_, e)| {
+            e.name == name && !matches!(e.entry_type, EntryType::Code | EntryType::Comment)
+        })
+    };
+    candidates()
+        .find(|(_, e)| e.entry_type == preferred)
+        .or_else(|| candidates().next())
+        .map(|(i, _)| i)
+}
+
+/// Arguments for [`move_entries`] that realize a move
+#[derive(Debug, PartialEq, Eq)]
+struct MovePlan {
+    indices: Vec<usize>,
+    to: usize,
+    /// The entry already sits at the requested position
+    is_noop: bool,
+}
+
+/// Plan moving the block of `source` next to the block of `anchor`.
+/// Returns `None` when the two blocks overlap.
+fn plan_move(entries: &[Entry], source: usize, anchor: usize, target: Target) -> Option<MovePlan> {
+    let moved = block_range(entries, source);
+    let anchor_block = block_range(entries, anchor);
+    if moved.start < anchor_block.end && anchor_block.start < moved.end {
+        return None;
+    }
+
+    let position = match target {
+        Target::Before(_) => anchor_block.start,
+        Target::After(_) => anchor_block.end,
+    };
+    // Position in the list once the moved block has been cut out
+    let to = if moved.start < position {
+        position - moved.len()
+    } else {
+        position
+    };
+
+    Some(MovePlan {
+        is_noop: to == moved.start,
+        indices: moved.collect(),
+        to,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    fn parse(content: &str) -> Vec<Entry> {
+        crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries
+    }
+
+    fn moved(content: &str, source: &str, target: Target) -> String {
+        let mut entries = parse(content);
+        let source = entries.iter().position(|e| e.name == source).unwrap();
+        let anchor = find_target(&entries, target.name(), entries[source].entry_type).unwrap();
+        let plan = plan_move(&entries, source, anchor, target).unwrap();
+        move_entries(&mut entries, &plan.indices, plan.to);
+        let formatter = get_formatter(ShellType::Bash);
+        render_entries(&entries, formatter.as_ref())
+    }
+
+    #[test]
+    fn test_move_before() {
+        let content = "alias ll='ls -la'\nalias gp='$PAGER'\nexport PAGER=less\n";
+        assert_eq!(
+            moved(content, "PAGER", Target::Before("gp")),
+            "alias ll='ls -la'\nexport PAGER=less\nalias gp='$PAGER'\n"
+        );
+    }
+
+    #[test]
+    fn test_move_after_keeps_comment_and_blank_lines() {
+        let content = "# Pager\nexport PAGER=less\n\nalias ll='ls -la'\nalias gs='git status'\n";
+        assert_eq!(
+            moved(content, "PAGER", Target::After("gs")),
+            "alias ll='ls -la'\nalias gs='git status'\n# Pager\nexport PAGER=less\n\n"
+        );
+    }
+
+    #[test]
+    fn test_noop_and_self_moves() {
+        let entries = parse("alias a='1'\nalias b='2'\n");
+
+        let plan = plan_move(&entries, 0, 1, Target::Before("b")).unwrap();
+        assert!(plan.is_noop);
+        let plan = plan_move(&entries, 1, 0, Target::After("a")).unwrap();
+        assert!(plan.is_noop);
+        assert!(plan_move(&entries, 0, 0, Target::After("a")).is_none());
+    }
+
+    #[test]
+    fn test_find_target_prefers_same_type() {
+        let entries = parse("ll() { ls; }\nalias ll='ls -la'\nalias x='y'\n");
+        assert_eq!(find_target(&entries, "ll", EntryType::Alias), Some(1));
+        assert_eq!(find_target(&entries, "ll", EntryType::EnvVar), Some(0));
+        assert_eq!(find_target(&entries, "L1", EntryType::Code), None);
+    }
+}
@@ -0,0 +1,84 @@
+//! Normalize-paths command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType};
+use crate::utils::path_merge::{normalize_path_values, path_separator};
+use crate::utils::reorder::render_entries;
+
+/// Whether `entry` defines PATH itself
+fn is_path_definition(entry: &Entry) -> bool {
+    entry.entry_type == EntryType::EnvVar && entry.name.eq_ignore_ascii_case("PATH")
+}
+
+/// Execute the normalize-paths action
+///
+/// Replaces every PATH definition with a single one at the place of the
+/// first, built by [`normalize_path_values`]: repeated segments and, unless
+/// `keep_missing`, missing directories are dropped and reported. With
+/// `sort` the segments are sorted. With `dry_run`, the new definition is
+/// printed and the file is left alone.
+pub fn execute(ctx: &Context, keep_missing: bool, sort: bool, dry_run: bool) -> Result<()> {
+    let mut entries = ctx.parse_config_file()?.entries;
+    let indices: Vec<usize> = (0..entries.len())
+        .filter(|&i| is_path_definition(&entries[i]))
+        .collect();
+    let Some(&first) = indices.first() else {
+        println!("No PATH definition found.");
+        return Ok(());
+    };
+
+    let values: Vec<String> = indices
+        .iter()
+        .map(|&i| entries[i].canonical_value())
+        .collect();
+    let separator = path_separator(ctx.shell_type);
+    let normalized = normalize_path_values(&values, separator, keep_missing, sort);
+    let value = normalized.segments.join(&separator.to_string());
+
+    let formatter = get_formatter(ctx.shell_type);
+    let Some(definition) = formatter.definition(EntryType::EnvVar, &entries[first].name, &value)
+    else {
+        anyhow::bail!("Cannot write a PATH definition for {}", ctx.shell_type);
+    };
+
+    let before = render_entries(&entries, formatter.as_ref());
+    entries[first] = entries[first].redefined(&definition);
+    for &index in indices[1..].iter().rev() {
+        entries.remove(index);
+    }
+    let content = render_entries(&entries, formatter.as_ref());
+    if content == before {
+        println!("PATH is already normalized.");
+        return Ok(());
+    }
+
+    for segment in &normalized.duplicates {
+        println!("{} {} {}", "-".red(), segment, "(duplicate)".dimmed());
+    }
+    for segment in &normalized.missing {
+        println!("{} {} {}", "-".red(), segment, "(missing)".dimmed());
+    }
+
+    if dry_run {
+        println!("Would write:");
+        println!("{}", definition.green());
+        return Ok(());
+    }
+
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&content)?;
+    ctx.record_history("normalize-paths", backup.as_deref());
+
+    ctx.print_success(&format!(
+        "Normalized {} PATH definition(s) into one, removing {} segment(s)",
+        indices.len(),
+        normalized.duplicates.len() + normalized.missing.len()
+    ));
+    ctx.print_reload_hint();
+
+    Ok(())
+}
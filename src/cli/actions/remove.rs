@@ -0,0 +1,113 @@
+//! Remove command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::actions::info::find_entry;
+use crate::cli::context::Context;
+use crate::formatter::get_formatter;
+use crate::model::Entry;
+use crate::utils::reorder::{block_range, render_entries};
+
+/// Execute the remove action
+///
+/// `target` is either `[NAME]` or `[TYPE, NAME]`. The entry is removed
+/// together with a comment attached directly above it. With `dry_run`, the
+/// lines that would be removed are printed and the file is left alone.
+pub fn execute(ctx: &Context, target: &[String], dry_run: bool) -> Result<()> {
+    let mut entries = ctx.parse_config_file()?.entries;
+    let entry = find_entry(ctx, &entries, target)?;
+    let (entry_type, name) = (entry.entry_type, entry.name.clone());
+    let index = entries
+        .iter()
+        .position(|e| std::ptr::eq(e, entry))
+        .expect("entry comes from the list");
+    let range = block_range(&entries, index);
+
+    if dry_run {
+        println!(
+            "Would remove {} '{}' ({}):",
+            entry_type,
+            name,
+            line_span(&entries[range.clone()])
+        );
+        for line in preview_lines(&entries[range]) {
+            println!("{}", line.red());
+        }
+        return Ok(());
+    }
+
+    entries.drain(range);
+    let formatter = get_formatter(ctx.shell_type);
+    let content = ctx.timed("Format", || render_entries(&entries, formatter.as_ref()));
+
+    let backup = ctx.backup_config_file()?;
+    ctx.save_config_file(&content)?;
+    ctx.record_history(
+        &format!("remove {} '{}'", entry_type, name),
+        backup.as_deref(),
+    );
+
+    ctx.print_success(&format!("Removed {} '{}'", entry_type, name));
+    ctx.print_reload_hint();
+
+    Ok(())
+}
+
+/// `line 3` or `lines 3-7` for the lines covered by `block`
+fn line_span(block: &[Entry]) -> String {
+    let start = block.first().and_then(|e| e.line_number);
+    let end = block.last().and_then(|e| e.end_line.or(e.line_number));
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => format!("lines {}-{}", start, end),
+        (Some(line), _) => format!("line {}", line),
+        _ => "unknown lines".to_string(),
+    }
+}
+
+/// Raw lines of `block`, each prefixed with `-` and its line number
+fn preview_lines(block: &[Entry]) -> Vec<String> {
+    let width = block
+        .last()
+        .and_then(|e| e.end_line.or(e.line_number))
+        .map_or(1, |n| n.to_string().len());
+
+    block
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .value
+                .split('\n')
+                .enumerate()
+                .map(move |(i, line)| match entry.line_number {
+                    Some(start) => format!("- {:>width$} | {}", start + i, line),
+                    None => format!("- {}", line),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    #[test]
+    fn test_preview_covers_function_and_attached_comment() {
+        let entries = crate::parser::get_parser(ShellType::PowerShell)
+            .parse("Set-Alias ll ls\n# Greets\nfunction Greet {\n    Write-Host hi\n}\n")
+            .entries;
+        let range = block_range(&entries, 2);
+
+        assert_eq!(line_span(&entries[range.clone()]), "lines 2-5");
+        assert_eq!(
+            preview_lines(&entries[range]),
+            [
+                "- 2 | # Greets",
+                "- 3 | function Greet {",
+                "- 4 |     Write-Host hi",
+                "- 5 | }",
+            ]
+        );
+    }
+}
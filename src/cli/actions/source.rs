@@ -2,28 +2,49 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::env;
-use std::process::Command;
 
+use crate::cli::actions::info::find_entry;
 use crate::cli::context::Context;
+use crate::model::Entry;
+use crate::utils::editor::resolve_editor;
 
 /// Execute the source action (open config file in editor)
 pub fn execute(ctx: &Context) -> Result<()> {
-    let editor = env::var("EDITOR").unwrap_or_else(|_| {
-        if cfg!(windows) {
-            "notepad".to_string()
-        } else {
-            "vi".to_string()
-        }
-    });
+    open_config_file(ctx, None)
+}
+
+/// Open the config file at the line of the entry addressed by `target`
+/// (`[NAME]` or `[TYPE, NAME]`)
+pub fn execute_entry(ctx: &Context, target: &[String]) -> Result<()> {
+    let entries = ctx.parse_config_file()?.entries;
+    let entry = find_entry(ctx, &entries, target)?;
+    open_config_file(ctx, entry.line_number)
+}
+
+/// Open the config file at the start of the entry spanning `line`, or at
+/// `line` itself when no entry does
+pub fn execute_line(ctx: &Context, line: usize) -> Result<()> {
+    let entries = ctx.parse_config_file()?.entries;
+    let start = Entry::at_line(&entries, line)
+        .and_then(|entry| entry.line_number)
+        .unwrap_or(line);
+    open_config_file(ctx, Some(start))
+}
+
+fn open_config_file(ctx: &Context, line: Option<usize>) -> Result<()> {
+    let editor = resolve_editor(ctx.editor.as_deref())?;
 
+    let location = match line {
+        Some(line) => format!("{}:{}", ctx.display_path(&ctx.config_file), line),
+        None => ctx.display_path(&ctx.config_file),
+    };
     println!(
         "Opening {} in {}...",
-        ctx.config_file.display().to_string().cyan(),
-        editor.yellow()
+        location.cyan(),
+        editor.to_string().yellow()
     );
 
-    let status = Command::new(&editor).arg(&ctx.config_file).status()?;
+    let status = editor.open_at(&ctx.config_file, line)?;
 
     if !status.success() {
         anyhow::bail!("Editor exited with non-zero status");
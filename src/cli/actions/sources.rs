@@ -0,0 +1,66 @@
+//! Sources command implementation
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::context::Context;
+use crate::utils::source_tree::{source_tree, SourceNode, SourceStatus};
+
+/// Execute the sources action
+///
+/// Prints the files the config file sources. With `tree`, the sources of
+/// those files are followed too and drawn as a tree, marking missing files
+/// and cycles. Read-only.
+pub fn execute(ctx: &Context, tree: bool) -> Result<()> {
+    let entries = ctx.parse_config_file()?.entries;
+    let mut parse = |path: &std::path::Path| ctx.parse_file(path).ok().map(|r| r.entries);
+    let mut nodes = source_tree(&ctx.config_file, &entries, &mut parse);
+    if !tree {
+        nodes.iter_mut().for_each(|node| node.children.clear());
+    }
+
+    println!("{}", ctx.display_path(&ctx.config_file).bold());
+    if nodes.is_empty() {
+        println!("{}", "(sources no files)".dimmed());
+        return Ok(());
+    }
+    let mut lines = Vec::new();
+    render(ctx, &nodes, "", &mut lines);
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Append one line per node to `lines`, children indented below their
+/// parent with `prefix`
+fn render(ctx: &Context, nodes: &[SourceNode], prefix: &str, lines: &mut Vec<String>) {
+    for (index, node) in nodes.iter().enumerate() {
+        let last = index + 1 == nodes.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!("{}{}{}", prefix, branch, label(ctx, node)));
+        render(ctx, &node.children, &format!("{}{}", prefix, indent), lines);
+    }
+}
+
+/// `~/.bash_aliases (line 3)`, with missing files in red and cycles marked
+fn label(ctx: &Context, node: &SourceNode) -> String {
+    let name = match &node.path {
+        Some(path) => ctx.display_path(path),
+        None => node.target.clone(),
+    };
+    let line = node
+        .line
+        .map(|line| format!(" {}", format!("(line {})", line).dimmed()))
+        .unwrap_or_default();
+    match node.status {
+        SourceStatus::Found => format!("{}{}", name, line),
+        SourceStatus::Missing => format!("{}{} {}", name.red(), line, "missing".red()),
+        SourceStatus::Cycle => format!("{}{} {}", name, line, "↺ cycle".yellow()),
+        SourceStatus::Unresolved => format!("{}{} {}", name, line, "unresolved".dimmed()),
+    }
+}
@@ -1,21 +1,32 @@
 //! CLI argument definitions
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use std::io::IsTerminal;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
+use crate::utils::template::Template;
+
 #[derive(Parser)]
 #[command(name = "wenv")]
 #[command(about = "Shell configuration file manager")]
 #[command(version, author)]
 pub struct Cli {
-    /// Specify configuration file path
-    #[arg(short, long)]
-    pub file: Option<PathBuf>,
+    /// Specify configuration file path; repeat to list or check several
+    /// files together
+    #[arg(short, long, global = true)]
+    pub file: Vec<PathBuf>,
 
-    /// Specify shell type
-    #[arg(short, long)]
+    /// Specify shell type of the config file, overriding detection from
+    /// its shebang, content and name
+    #[arg(short, long, global = true)]
     pub shell: Option<ShellArg>,
 
+    /// Use exactly this shell for every file the command reads, import
+    /// sources included, with no detection at all; wins over --shell
+    #[arg(long, global = true, value_name = "SHELL")]
+    pub force_shell: Option<ShellArg>,
+
     /// Import entries from file or URL
     #[arg(short, long, value_name = "SOURCE", group = "action")]
     pub import: Option<String>,
@@ -24,23 +35,97 @@ pub struct Cli {
     #[arg(long, default_value = "ask")]
     pub on_conflict: ConflictStrategy,
 
-    /// Export entries to file
+    /// Suffix appended to conflicting names with --on-conflict rename
+    #[arg(long, value_name = "SUFFIX", default_value = "_imported")]
+    pub rename_suffix: String,
+
+    /// Export entries to file ("-" for stdout)
     #[arg(short, long, value_name = "OUTPUT", group = "action")]
     pub export: Option<PathBuf>,
 
-    /// Open source file in $EDITOR (same as "wenv .")
+    /// Export format: shell syntax, or a shareable bundle that imports into any shell
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "shell",
+        requires = "export"
+    )]
+    pub format: ExportFormat,
+
+    /// Open source file in an editor (same as "wenv .")
     #[arg(long, group = "action")]
     pub source: bool,
 
+    /// Show what would be imported without writing anything (for import)
+    #[arg(long, requires = "import")]
+    pub dry_run: bool,
+
+    /// Add imported entries at the end of the file (the default)
+    #[arg(long, requires = "import", group = "placement")]
+    pub append: bool,
+
+    /// Add imported entries at the top of the file, below a shebang line
+    #[arg(long, requires = "import", group = "placement")]
+    pub prepend: bool,
+
+    /// Add imported entries right after the entry with this name
+    #[arg(
+        long = "after",
+        value_name = "NAME",
+        requires = "import",
+        group = "placement"
+    )]
+    pub import_after: Option<String>,
+
+    /// Don't create a backup before modifying the file (changes can't be restored)
+    #[arg(long, global = true)]
+    pub no_backup: bool,
+
+    /// Keep every comment as its own entry instead of attaching a single
+    /// comment to the entry below it
+    #[arg(long, global = true)]
+    pub no_merge_comments: bool,
+
+    /// Editor command to open files with (default: $VISUAL, then $EDITOR)
+    #[arg(long, global = true, value_name = "CMD")]
+    pub editor: Option<String>,
+
+    /// Print backup paths, entry counts and step timings to stderr
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Don't print success messages, warnings or the reload hint (errors
+    /// are still printed to stderr)
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Parse several --file paths concurrently, one thread per file
+    #[arg(long, global = true)]
+    pub parallel: bool,
+
+    /// Print a table of time spent reading, parsing, checking and
+    /// formatting to stderr
+    #[arg(long, global = true, hide = true)]
+    pub profile: bool,
+
+    /// Show full paths instead of collapsing the home directory to `~`
+    /// (overrides `ui.collapse_home`)
+    #[arg(long, global = true)]
+    pub expand_home: bool,
+
+    /// When to use colored output
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorChoice,
+
     /// Skip confirmation prompts (for import)
     #[arg(short, long)]
     pub yes: bool,
 
-    /// Clear cached shell paths
+    /// Clear cached shell paths and parse results
     #[arg(long, group = "action")]
     pub clear_cache: bool,
 
-    /// Open wenv config file in $EDITOR
+    /// Open wenv config file in an editor
     #[arg(short = 'c', long, group = "action")]
     pub config: bool,
 
@@ -51,13 +136,399 @@ pub struct Cli {
     /// Positional argument: "." to open editor, or file path
     #[arg(value_name = "PATH_OR_DOT")]
     pub command: Option<String>,
+
+    #[command(subcommand)]
+    pub subcommand: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// List entries
+    List {
+        /// Only list entries of this type
+        #[arg(value_name = "TYPE")]
+        entry_type: Option<EntryTypeArg>,
+
+        /// Only list entries tagged with `# wenv:tags=...`
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Show at most N entries (applied after filtering)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Skip the first N entries (applied after filtering)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        offset: usize,
+
+        /// Show full values instead of truncating to the terminal width
+        #[arg(long)]
+        no_truncate: bool,
+
+        /// Order of the listed entries
+        #[arg(long, value_enum, default_value_t)]
+        sort: SortKey,
+
+        /// List commented-out definitions (e.g. `# alias ll='ls -la'`) as
+        /// the entries they define, dimmed
+        #[arg(long)]
+        include_disabled: bool,
+
+        /// Print each entry through a template instead of the table, e.g.
+        /// '{type}\t{name}\t{value}'. Placeholders: {type}, {name},
+        /// {value}, {line}, {end_line}, {comment}; {{ and }} are literal
+        /// braces
+        #[arg(long, value_name = "TEMPLATE")]
+        output_template: Option<Template>,
+    },
+
+    /// Print the number of entries, optionally of one type
+    Count {
+        /// Only count entries of this type
+        #[arg(value_name = "TYPE")]
+        entry_type: Option<EntryTypeArg>,
+    },
+
+    /// Find entries whose name or value matches a regular expression
+    Grep {
+        /// Regular expression to search for
+        pattern: String,
+
+        /// Only search entries of this type
+        #[arg(long = "type", value_name = "TYPE")]
+        entry_type: Option<EntryTypeArg>,
+
+        /// Print only the names of matching entries
+        #[arg(long)]
+        name_only: bool,
+    },
+
+    /// Open the interactive interface (the default without a command)
+    Tui {
+        /// Start editing this entry, optionally preceded by its type
+        /// (e.g. "alias ll")
+        #[arg(long, value_name = "[TYPE] NAME", num_args = 1..=2, conflicts_with = "view")]
+        edit: Option<Vec<String>>,
+
+        /// Browse without being able to change anything
+        #[arg(long)]
+        view: bool,
+    },
+
+    /// Show details of a single entry
+    Info {
+        /// Entry name, optionally preceded by its type (e.g. "alias ll")
+        #[arg(value_name = "[TYPE] NAME", num_args = 1..=2, required = true)]
+        target: Vec<String>,
+
+        /// Also show the value with known aliases/env vars substituted
+        #[arg(long)]
+        expand: bool,
+
+        /// Print only the value, without quotes or decoration (for scripting)
+        #[arg(long)]
+        value_only: bool,
+    },
+
+    /// Open the config file in an editor at an entry's line
+    Edit {
+        /// Entry name, optionally preceded by its type (e.g. "alias ll")
+        #[arg(
+            value_name = "[TYPE] NAME",
+            num_args = 1..=2,
+            required_unless_present = "line",
+            conflicts_with = "line"
+        )]
+        target: Vec<String>,
+
+        /// Open the entry spanning this line instead, e.g. a line reported
+        /// by `wenv check`; without one, open the file at the line itself
+        #[arg(long, value_name = "LINE")]
+        line: Option<usize>,
+    },
+
+    /// Add an alias or environment variable, or overwrite one of the same name
+    Add {
+        /// Type of the entry to add (alias or env)
+        #[arg(value_name = "TYPE")]
+        entry_type: EntryTypeArg,
+
+        /// The definition, e.g. 'll=ls -la' (quote values with spaces)
+        #[arg(
+            value_name = "NAME=VALUE",
+            num_args = 1..,
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        definition: Vec<String>,
+
+        /// Add quotes to an unquoted value and overwrite an existing entry
+        /// without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Remove an entry (and a comment attached directly above it)
+    Remove {
+        /// Entry name, optionally preceded by its type (e.g. "alias ll")
+        #[arg(value_name = "[TYPE] NAME", num_args = 1..=2, required = true)]
+        target: Vec<String>,
+
+        /// Show the lines that would be removed without changing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Merge the entries of a snippet file into the config file, handling
+    /// entries that already exist per --on-conflict
+    Apply {
+        /// Snippet file to merge, in the syntax of any supported shell
+        #[arg(value_name = "FILE")]
+        snippet: PathBuf,
+
+        /// Show the changes without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply without asking for confirmation (conflicts with
+        /// --on-conflict ask are skipped)
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Move an entry before or after another entry
+    #[command(group(
+        ArgGroup::new("position")
+            .args(["before", "after"])
+            .required(true)
+    ))]
+    Move {
+        /// Type of the entry to move
+        #[arg(value_name = "TYPE")]
+        entry_type: EntryTypeArg,
+
+        /// Name of the entry to move
+        name: String,
+
+        /// Place it right before the entry with this name
+        #[arg(long, value_name = "NAME")]
+        before: Option<String>,
+
+        /// Place it right after the entry with this name
+        #[arg(long, value_name = "NAME")]
+        after: Option<String>,
+    },
+
+    /// Merge all PATH definitions into one, dropping repeated segments and
+    /// missing directories
+    NormalizePaths {
+        /// Keep segments whose directory does not exist
+        #[arg(long)]
+        keep_missing: bool,
+
+        /// Sort the segments (the inherited $PATH stays last) instead of
+        /// keeping their lookup order
+        #[arg(long)]
+        sort: bool,
+
+        /// Show the new definition without changing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// List the files the config file sources (read-only)
+    Sources {
+        /// Follow sourced files recursively and draw a tree, marking missing
+        /// files and cycles
+        #[arg(long)]
+        tree: bool,
+    },
+
+    /// List the conventional config files of the shell and which exist
+    /// (read-only)
+    Files,
+
+    /// Check the config file for duplicates, undefined variables and PATH problems
+    Check {
+        /// Also warn about trailing whitespace and mixed tab/space indentation
+        #[arg(long)]
+        style: bool,
+
+        /// Also warn about PATH segments naming directories that don't exist
+        #[arg(long)]
+        missing_dirs: bool,
+    },
+
+    /// Run the lint rules enabled in the [lint] config table
+    Lint,
+
+    /// Format the config file, or only the entries within a line range
+    Format {
+        /// Only format entries lying entirely within these lines (1-based,
+        /// inclusive); entries sticking out of the range are skipped
+        #[arg(long, value_name = "START:END", value_parser = parse_line_range)]
+        range: Option<RangeInclusive<usize>>,
+    },
+
+    /// Check the environment wenv runs in (read-only)
+    Doctor,
+
+    /// Show the log of changes wenv made to configuration files
+    History {
+        /// Delete the log
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Manage backups
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommand,
+    },
+
+    /// Read or change wenv settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+impl Commands {
+    /// Whether the command operates on the configuration file
+    pub fn requires_config_file(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Backup { .. }
+                | Commands::Config { .. }
+                | Commands::Doctor
+                | Commands::Files
+                | Commands::History { .. }
+        )
+    }
+
+    /// Names of the commands [`Self::accepts_several_files`] accepts
+    pub const SEVERAL_FILES_COMMANDS: &'static [&'static str] = &["list", "info", "check", "lint"];
+
+    /// Whether the command works on several `--file` paths at once
+    pub fn accepts_several_files(&self) -> bool {
+        matches!(
+            self,
+            Commands::List { .. } | Commands::Info { .. } | Commands::Check { .. } | Commands::Lint
+        )
+    }
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommand {
+    /// Remove old backups
+    #[command(group(
+        ArgGroup::new("criteria")
+            .args(["keep", "older_than"])
+            .required(true)
+            .multiple(true)
+    ))]
+    Prune {
+        /// Keep the newest N backups
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
+
+        /// Only remove backups older than this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+
+        /// List the backups that would be removed without deleting them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show entry-level changes between a backup and the current file
+    Diff {
+        /// Backup ID (or a unique part of it, e.g. the timestamp)
+        #[arg(long, value_name = "ID")]
+        since: String,
+
+        /// Show each step through newer backups instead of one cumulative diff
+        #[arg(long)]
+        chain: bool,
+    },
+
+    /// Back up the config file now, or with --watch whenever it changes
+    Auto {
+        /// Keep running and back up after every change, until Ctrl-C
+        #[arg(long)]
+        watch: bool,
+    },
+
+    /// Move backups kept per shell by older versions (bash/, zsh/) into the
+    /// shared sh/ directory
+    Migrate {
+        /// List the backups that would move without moving them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the value of a setting (e.g. `backup.max_count`)
+    Get {
+        /// Dotted setting key
+        key: String,
+    },
+
+    /// Change a setting and save the config file
+    Set {
+        /// Dotted setting key
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Print the path of the wenv config file
+    Path,
+
+    /// Write a config file listing every setting with its default and a
+    /// comment explaining it
+    Init {
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Configure the global `colored` override for this choice
+    pub fn apply(self) {
+        let enabled = match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        };
+        colored::control::set_override(enabled);
+    }
 }
 
 #[derive(Clone, Copy, ValueEnum)]
 pub enum ShellArg {
     Bash,
     Zsh,
+    Sh,
     Pwsh,
+    Dotenv,
 }
 
 impl From<ShellArg> for crate::model::ShellType {
@@ -65,7 +536,9 @@ impl From<ShellArg> for crate::model::ShellType {
         match arg {
             ShellArg::Bash => crate::model::ShellType::Bash,
             ShellArg::Zsh => crate::model::ShellType::Zsh,
+            ShellArg::Sh => crate::model::ShellType::PosixSh,
             ShellArg::Pwsh => crate::model::ShellType::PowerShell,
+            ShellArg::Dotenv => crate::model::ShellType::DotEnv,
         }
     }
 }
@@ -80,6 +553,14 @@ pub enum EntryTypeArg {
     Env,
     #[value(alias = "s")]
     Source,
+    #[value(alias = "arr")]
+    Array,
+    #[value(alias = "unalias")]
+    Unset,
+    Trap,
+    #[value(alias = "unsetopt")]
+    Setopt,
+    Zstyle,
     #[value(alias = "c")]
     Code,
     #[value(alias = "cm")]
@@ -93,16 +574,115 @@ impl From<EntryTypeArg> for crate::model::EntryType {
             EntryTypeArg::Func => crate::model::EntryType::Function,
             EntryTypeArg::Env => crate::model::EntryType::EnvVar,
             EntryTypeArg::Source => crate::model::EntryType::Source,
+            EntryTypeArg::Array => crate::model::EntryType::Array,
+            EntryTypeArg::Unset => crate::model::EntryType::Unset,
+            EntryTypeArg::Trap => crate::model::EntryType::Trap,
+            EntryTypeArg::Setopt => crate::model::EntryType::Setopt,
+            EntryTypeArg::Zstyle => crate::model::EntryType::Zstyle,
             EntryTypeArg::Code => crate::model::EntryType::Code,
             EntryTypeArg::Comment => crate::model::EntryType::Comment,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// File order
+    #[default]
+    Line,
+    /// Alphabetical by name, ignoring case
+    Name,
+    /// Grouped by entry type, then by name
+    Type,
+}
+
+#[derive(Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Entries in the config file's own syntax
+    #[default]
+    Shell,
+    /// A TOML "wenv bundle" of types, names and canonical values
+    Bundle,
+}
+
 #[derive(Clone, Copy, ValueEnum, Default)]
 pub enum ConflictStrategy {
     #[default]
     Ask,
     Skip,
     Overwrite,
+    /// Keep both by importing the new entry under a suffixed name
+    Rename,
+}
+
+/// Parse a `START:END` line range (1-based, inclusive)
+fn parse_line_range(s: &str) -> Result<RangeInclusive<usize>, String> {
+    let (start, end) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected START:END, got '{}'", s))?;
+    let number = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("'{}' is not a line number", n))
+    };
+    let (start, end) = (number(start)?, number(end)?);
+    if start > end {
+        return Err(format!("range {}:{} ends before it starts", start, end));
+    }
+    Ok(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("3:10"), Ok(3..=10));
+        assert_eq!(parse_line_range("7:7"), Ok(7..=7));
+        assert!(parse_line_range("10:3").is_err());
+        assert!(parse_line_range("0:3").is_err());
+        assert!(parse_line_range("3").is_err());
+        assert!(parse_line_range("a:b").is_err());
+    }
+
+    #[test]
+    fn test_several_files_commands_match_accepts_several_files() {
+        let cases: [&[&str]; 6] = [
+            &["list"],
+            &["info", "ll"],
+            &["check"],
+            &["lint"],
+            &["count"],
+            &["format"],
+        ];
+        for args in cases {
+            let cli =
+                Cli::try_parse_from(["wenv", "-f", "a", "-f", "b"].iter().chain(args)).unwrap();
+            assert_eq!(
+                cli.subcommand.unwrap().accepts_several_files(),
+                Commands::SEVERAL_FILES_COMMANDS.contains(&args[0]),
+                "{}",
+                args[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_entry_type_arg_covers_every_entry_type() {
+        let mut types: Vec<crate::model::EntryType> = EntryTypeArg::value_variants()
+            .iter()
+            .map(|&arg| arg.into())
+            .collect();
+        types.sort();
+        assert_eq!(types, crate::model::EntryType::ALL);
+
+        // `--type` takes each type's display name
+        for entry_type in crate::model::EntryType::ALL {
+            let arg = EntryTypeArg::from_str(&entry_type.to_string(), true).unwrap();
+            assert_eq!(crate::model::EntryType::from(arg), entry_type);
+        }
+    }
 }
@@ -2,20 +2,48 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use crate::backup::BackupManager;
+use crate::cache::{ParseCache, ParseCacheKey};
 use crate::cli::args::{Cli, ConflictStrategy};
 use crate::i18n::{init_messages, Messages};
-use crate::model::{Config, ShellType};
-use crate::utils::shell_detect::get_shell_type;
+use crate::model::{Config, EntryType, ShellType};
+use crate::parser::{get_configured_parser, Parser};
+use crate::utils::history;
+use crate::utils::reload::reload_command;
+use crate::utils::shell_detect::{resolve_shell_type, ShellSource};
 
 /// Common context for command execution
 pub struct Context {
     pub config: Config,
     pub shell_type: ShellType,
+    /// How `shell_type` was decided
+    pub shell_source: ShellSource,
     pub config_file: PathBuf,
+    /// Every file given with `--file`, starting with `config_file`
+    pub config_files: Vec<PathBuf>,
     pub on_conflict: ConflictStrategy,
+    pub rename_suffix: String,
+    /// Whether backups are created before writes (`backup.enabled` and not `--no-backup`)
+    pub backup_enabled: bool,
+    /// Attach a single comment to the entry below it (off with `--no-merge-comments`)
+    pub merge_comments: bool,
+    /// `--editor` override for external editing
+    pub editor: Option<String>,
+    /// Print backup paths, entry counts and step timings to stderr (`-v`)
+    pub verbose: bool,
+    /// Suppress success messages, warnings and the reload hint (`-q`)
+    pub quiet: bool,
+    /// Parse several `--file` paths concurrently (`--parallel`)
+    pub parallel: bool,
+    /// Show home-relative paths as `~/...` (`ui.collapse_home`, off with `--expand-home`)
+    pub collapse_home: bool,
     pub messages: &'static Messages,
+    /// Time per step, in order of first use (`--profile`)
+    profile: Option<RefCell<Vec<(String, Duration)>>>,
 }
 
 impl Context {
@@ -23,40 +51,323 @@ impl Context {
         let config = crate::config::load_or_create_config()?;
         let messages = init_messages(&config.ui.language);
 
-        // Get path from -f option or positional argument (except ".")
-        let provided_path: Option<PathBuf> = cli.file.clone().or_else(|| {
-            cli.command
-                .as_ref()
-                .filter(|c| c.as_str() != ".")
-                .map(PathBuf::from)
-        });
+        // Get paths from -f options or positional argument (except ".")
+        let mut provided_paths = cli.file.clone();
+        if provided_paths.is_empty() {
+            provided_paths.extend(
+                cli.command
+                    .as_ref()
+                    .filter(|c| c.as_str() != ".")
+                    .map(PathBuf::from),
+            );
+        }
+
+        // The first file decides the shell all files are parsed as
+        let (shell_type, shell_source) = match cli.force_shell {
+            Some(shell) => (shell.into(), ShellSource::Forced),
+            None => resolve_shell_type(
+                cli.shell.map(|s| s.into()),
+                provided_paths.first().map(PathBuf::as_path),
+            ),
+        };
+        if provided_paths.is_empty() {
+            provided_paths.push(shell_type.default_config_path());
+        }
+        let config_file = provided_paths[0].clone();
 
-        let shell_type = get_shell_type(cli.shell.map(|s| s.into()), provided_path.as_deref());
-        let config_file = provided_path.unwrap_or_else(|| shell_type.default_config_path());
+        let backup_enabled = config.backup.enabled && !cli.no_backup;
+        let collapse_home = config.ui.collapse_home && !cli.expand_home;
 
         Ok(Self {
+            backup_enabled,
             config,
             shell_type,
+            shell_source,
             config_file,
+            config_files: provided_paths,
             on_conflict: cli.on_conflict,
+            rename_suffix: cli.rename_suffix.clone(),
+            merge_comments: !cli.no_merge_comments,
+            editor: cli.editor.clone(),
+            verbose: cli.verbose,
+            quiet: cli.quiet,
+            parallel: cli.parallel,
+            collapse_home,
             messages,
+            profile: cli.profile.then(RefCell::default),
         })
     }
 
-    /// Read and parse the configuration file
+    /// `path` for display, with the home directory collapsed to `~` unless
+    /// turned off
+    pub fn display_path(&self, path: &Path) -> String {
+        if self.collapse_home {
+            crate::utils::path::collapse_home(path)
+        } else {
+            path.display().to_string()
+        }
+    }
+
+    /// Color of `entry_type` in output, from the `[colors]` config table
+    pub fn color_entry_type(&self, entry_type: EntryType) -> colored::Color {
+        crate::utils::colors::entry_type_color(&self.config.colors, entry_type)
+    }
+
+    /// Get a backup manager, or `None` when backups are disabled
+    pub fn get_backup_manager(&self) -> Option<BackupManager> {
+        self.backup_enabled
+            .then(|| BackupManager::new(self.shell_type, &self.config))
+    }
+
+    /// Parser for the current shell, honoring `--no-merge-comments` and
+    /// `parser.attach_comments`
+    pub fn parser(&self) -> Box<dyn Parser> {
+        get_configured_parser(
+            self.shell_type,
+            self.merge_comments,
+            self.config.parser.attach_comments,
+        )
+    }
+
+    /// Back up the configuration file before a write, unless backups are
+    /// disabled or the file doesn't exist yet. Returns the backup path.
+    pub fn backup_config_file(&self) -> Result<Option<PathBuf>> {
+        let Some(backup_manager) = self.get_backup_manager() else {
+            return Ok(None);
+        };
+        if !self.config_file.exists() {
+            return Ok(None);
+        }
+        let path = backup_manager.create_backup(&self.config_file)?;
+        self.print_verbose(&format!("Backup created: {}", path.display()));
+        Ok(Some(path))
+    }
+
+    /// Write rewritten `content` to the configuration file (see
+    /// [`save_config_content`](crate::utils::path::save_config_content))
+    pub fn save_config_file(&self, content: &str) -> Result<()> {
+        self.timed("Write", || {
+            crate::utils::path::save_config_content(
+                &self.config_file,
+                content,
+                self.config.format.preserve_trailing_blanks,
+            )
+        })
+    }
+
+    /// Append `action` on the configuration file to the change history,
+    /// with the backup taken before it. A log that can't be written only
+    /// produces a warning.
+    pub fn record_history(&self, action: &str, backup: Option<&Path>) {
+        let now = time::OffsetDateTime::now_utc();
+        let timestamp = now
+            .replace_nanosecond(0)
+            .unwrap_or(now)
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default();
+        let record = history::format_record(&timestamp, action, &self.config_file, backup);
+        if let Err(e) = history::append(&Config::history_path(), &record) {
+            self.print_warning(&format!("Could not write history: {}", e));
+        }
+    }
+
+    /// Read and parse the configuration file. With `cache.enabled`, the
+    /// result of an unchanged file is reused from the parse cache.
     pub fn parse_config_file(&self) -> Result<crate::model::ParseResult> {
-        let content = crate::utils::path::read_file(&self.config_file)?;
-        let parser = crate::parser::get_parser(self.shell_type);
-        Ok(parser.parse(&content))
+        self.parse_file(&self.config_file)
+    }
+
+    /// Parse every file given with `--file`, in order. With several files,
+    /// every entry's `source_file` is set to the file it came from.
+    /// `--parallel` parses them on a thread each; results keep the order of
+    /// the files all the same.
+    pub fn parse_config_files(&self) -> Result<Vec<(&Path, crate::model::ParseResult)>> {
+        let several = self.config_files.len() > 1;
+        let results = if self.parallel && several {
+            self.timed("Parse files", || self.parse_files_parallel())
+        } else {
+            self.config_files
+                .iter()
+                .map(|path| self.parse_file(path))
+                .collect()
+        };
+        self.config_files
+            .iter()
+            .zip(results)
+            .map(|(path, result)| {
+                let mut result = result?;
+                if several {
+                    for entry in &mut result.entries {
+                        entry.source_file = Some(path.clone());
+                    }
+                }
+                Ok((path.as_path(), result))
+            })
+            .collect()
+    }
+
+    /// Parse `path` as the shell of the config file
+    pub fn parse_file(&self, path: &Path) -> Result<crate::model::ParseResult> {
+        let start = Instant::now();
+        let result = self.parse_file_untimed(path)?;
+        Ok(self.finish_parse(path, result, start.elapsed()))
+    }
+
+    /// Parse every `--file` on a thread of its own, in the order given.
+    /// Threads share only the parser and the parse cache, so reading and
+    /// parsing are timed as a whole rather than per step.
+    fn parse_files_parallel(&self) -> Vec<Result<crate::model::ParseResult>> {
+        let parser = self.parser();
+        let parser = parser.as_ref();
+        let jobs: Vec<_> = self
+            .config_files
+            .iter()
+            .map(|path| (path, self.parse_cache_for(path)))
+            .collect();
+        let outcomes: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = jobs
+                .into_iter()
+                .map(|(path, cached)| {
+                    scope.spawn(move || {
+                        let start = Instant::now();
+                        let result = match cached.as_ref().and_then(|(c, key)| c.get(key)) {
+                            Some(result) => Ok(result),
+                            None => crate::utils::path::read_file(path).map(|content| {
+                                let result = parser.parse(&content);
+                                if let Some((cache, key)) = &cached {
+                                    let _ = cache.put(key, &result);
+                                }
+                                result
+                            }),
+                        };
+                        (result, start.elapsed())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("parser thread panicked"))
+                .collect()
+        });
+        self.config_files
+            .iter()
+            .zip(outcomes)
+            .map(|(path, (result, elapsed))| Ok(self.finish_parse(path, result?, elapsed)))
+            .collect()
+    }
+
+    /// Drop the parse warnings `parser.ignore_warning_codes` lists from the
+    /// result of parsing `path`, reporting it in verbose mode
+    fn finish_parse(
+        &self,
+        path: &Path,
+        mut result: crate::model::ParseResult,
+        elapsed: Duration,
+    ) -> crate::model::ParseResult {
+        let ignored = &self.config.parser.ignore_warning_codes;
+        result
+            .warnings
+            .retain(|w| !ignored.iter().any(|code| code == w.code.as_str()));
+        self.print_verbose(&format!(
+            "Parsed {} entries from {} in {:.1?}",
+            result.entries.len(),
+            path.display(),
+            elapsed
+        ));
+        result
+    }
+
+    /// The parse cache and the key of `path` in it, with `cache.enabled`
+    fn parse_cache_for(&self, path: &Path) -> Option<(ParseCache, ParseCacheKey)> {
+        self.config
+            .cache
+            .enabled
+            .then(|| {
+                ParseCacheKey::for_file(
+                    path,
+                    self.shell_type,
+                    self.merge_comments,
+                    self.config.parser.attach_comments,
+                )
+            })
+            .flatten()
+            .map(|key| (ParseCache::new(), key))
+    }
+
+    fn parse_file_untimed(&self, path: &Path) -> Result<crate::model::ParseResult> {
+        let cached = self.parse_cache_for(path);
+        if let Some(result) = cached.as_ref().and_then(|(cache, key)| cache.get(key)) {
+            self.print_verbose("Parse result taken from cache");
+            return Ok(result);
+        }
+
+        let content = self.timed("Read", || crate::utils::path::read_file(path))?;
+        let result = self.timed("Parse", || self.parser().parse(&content));
+        if let Some((cache, key)) = &cached {
+            // A cache that can't be written only costs the next parse
+            let _ = cache.put(key, &result);
+        }
+        Ok(result)
+    }
+
+    /// Run `f`, reporting how long `step` took in verbose mode and adding
+    /// it to the `--profile` table
+    pub fn timed<T>(&self, step: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        let elapsed = start.elapsed();
+        self.print_verbose(&format!("{} took {:.1?}", step, elapsed));
+        if let Some(profile) = &self.profile {
+            let mut profile = profile.borrow_mut();
+            match profile.iter_mut().find(|(name, _)| name == step) {
+                Some((_, total)) => *total += elapsed,
+                None => profile.push((step.to_string(), elapsed)),
+            }
+        }
+        value
+    }
+
+    /// Print the time spent per step to stderr with `--profile`
+    pub fn print_profile(&self) {
+        let Some(profile) = &self.profile else {
+            return;
+        };
+        let profile = profile.borrow();
+        let total: Duration = profile.iter().map(|(_, elapsed)| *elapsed).sum();
+        let width = profile
+            .iter()
+            .map(|(step, _)| step.len())
+            .chain(["Total".len()])
+            .max()
+            .unwrap_or(0);
+
+        eprintln!("{:<width$}  {:>10}", "Phase".bold(), "Time".bold());
+        for (step, elapsed) in profile.iter() {
+            eprintln!("{:<width$}  {:>10}", step, format!("{:.2?}", elapsed));
+        }
+        eprintln!("{:<width$}  {:>10}", "Total", format!("{:.2?}", total));
+    }
+
+    /// Print a diagnostic message to stderr in verbose mode
+    pub fn print_verbose(&self, message: &str) {
+        if self.verbose {
+            eprintln!("{}", message.dimmed());
+        }
     }
 
-    /// Print a success message
+    /// Print a success message, unless quiet
     pub fn print_success(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         println!("{} {}", "✓".green(), message);
     }
 
-    /// Print a warning message
+    /// Print a warning message, unless quiet
     pub fn print_warning(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         println!("{} {}", "⚠".yellow(), message);
     }
 
@@ -65,11 +376,14 @@ impl Context {
         eprintln!("{} {}", "✗".red(), message);
     }
 
-    /// Print reload hint after modifying configuration
+    /// Print reload hint after modifying configuration, unless quiet
     pub fn print_reload_hint(&self) {
-        let reload_cmd = match self.shell_type {
-            ShellType::Bash | ShellType::Zsh => format!("source {}", self.config_file.display()),
-            ShellType::PowerShell => format!(". {}", self.config_file.display()),
+        if self.quiet {
+            return;
+        }
+        let path = self.display_path(&self.config_file);
+        let Some(reload_cmd) = reload_command(self.shell_type, &path) else {
+            return;
         };
         println!(
             "{} {}",
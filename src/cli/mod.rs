@@ -4,5 +4,5 @@ pub mod actions;
 pub mod args;
 pub mod context;
 
-pub use args::Cli;
+pub use args::{Cli, Commands};
 pub use context::Context;
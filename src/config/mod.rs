@@ -3,7 +3,9 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
-use crate::model::Config;
+use crate::checker::lint::{LintRule, LINT_RULES};
+use crate::model::{Config, EntryType, LintLevel, QuoteStyle, WarningCode};
+use crate::utils::colors::{color_name, entry_type_color, parse_color};
 
 /// Ensure the configuration directory exists
 pub fn ensure_config_dir() -> Result<PathBuf> {
@@ -33,3 +35,308 @@ pub fn save_config(config: &Config) -> Result<()> {
     ensure_config_dir()?;
     config.save()
 }
+
+/// Keys accepted by [`get_value`] and [`set_value`]
+pub const CONFIG_KEYS: &[&str] = &[
+    "ui.language",
+    "ui.collapse_home",
+    "format.indent",
+    "format.group_by_type",
+    "format.sort_alphabetically",
+    "format.blank_lines_between_groups",
+    "format.order",
+    "format.quote_style",
+    "format.align_equals",
+    "format.section_headers",
+    "format.preserve_trailing_blanks",
+    "backup.enabled",
+    "backup.max_count",
+    "backup.dir",
+    "cache.enabled",
+    "checker.ignore_codes",
+    "parser.ignore_warning_codes",
+    "parser.attach_comments",
+    "lint.duplicate",
+    "lint.shadowed-builtin",
+    "lint.missing-source",
+    "lint.unused-var",
+    "lint.style",
+    "colors.alias",
+    "colors.func",
+    "colors.env",
+    "colors.source",
+    "colors.array",
+    "colors.unset",
+    "colors.trap",
+    "colors.setopt",
+    "colors.zstyle",
+    "colors.code",
+    "colors.comment",
+];
+
+/// Read a single config field by its dotted key (e.g. `backup.max_count`)
+pub fn get_value(config: &Config, key: &str) -> Result<String> {
+    Ok(match key {
+        "ui.language" => config.ui.language.clone(),
+        "ui.collapse_home" => config.ui.collapse_home.to_string(),
+        "format.indent" => config.format.indent.to_string(),
+        "format.group_by_type" => config.format.group_by_type.to_string(),
+        "format.sort_alphabetically" => config.format.sort_alphabetically.to_string(),
+        "format.blank_lines_between_groups" => config.format.blank_lines_between_groups.to_string(),
+        "format.order" => config.format.order.types.join(","),
+        "format.quote_style" => match config.format.quote_style {
+            QuoteStyle::Preserve => "preserve",
+            QuoteStyle::Single => "single",
+            QuoteStyle::Double => "double",
+        }
+        .to_string(),
+        "format.align_equals" => config.format.align_equals.to_string(),
+        "format.section_headers" => config.format.section_headers.to_string(),
+        "format.preserve_trailing_blanks" => config.format.preserve_trailing_blanks.to_string(),
+        "backup.enabled" => config.backup.enabled.to_string(),
+        "backup.max_count" => config.backup.max_count.to_string(),
+        "backup.dir" => config
+            .backup
+            .dir
+            .as_ref()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default(),
+        "cache.enabled" => config.cache.enabled.to_string(),
+        "checker.ignore_codes" => config.checker.ignore_codes.join(","),
+        "parser.ignore_warning_codes" => config.parser.ignore_warning_codes.join(","),
+        "parser.attach_comments" => config.parser.attach_comments.to_string(),
+        _ => match (lint_rule(key), color_type(key)) {
+            (Some(rule), _) => rule.level(&config.lint).to_string(),
+            (_, Some(entry_type)) => color_name(entry_type_color(&config.colors, entry_type)),
+            _ => return Err(unknown_key(key)),
+        },
+    })
+}
+
+/// Set a single config field from its string form, rejecting values of the
+/// wrong type. An empty `backup.dir` resets it to the default location, and
+/// an empty `colors.<type>` to the type's default color.
+pub fn set_value(config: &mut Config, key: &str, value: &str) -> Result<()> {
+    match key {
+        "ui.language" => config.ui.language = value.to_string(),
+        "ui.collapse_home" => config.ui.collapse_home = parse_bool(key, value)?,
+        "format.indent" => config.format.indent = parse_number(key, value)?,
+        "format.group_by_type" => config.format.group_by_type = parse_bool(key, value)?,
+        "format.sort_alphabetically" => config.format.sort_alphabetically = parse_bool(key, value)?,
+        "format.blank_lines_between_groups" => {
+            config.format.blank_lines_between_groups = parse_number(key, value)?
+        }
+        "format.order" => {
+            let types: Vec<String> = value
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            for t in &types {
+                t.parse::<EntryType>()
+                    .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+            }
+            config.format.order.types = types;
+        }
+        "format.quote_style" => {
+            config.format.quote_style = match value.to_lowercase().as_str() {
+                "preserve" => QuoteStyle::Preserve,
+                "single" => QuoteStyle::Single,
+                "double" => QuoteStyle::Double,
+                _ => anyhow::bail!(
+                    "Invalid value for {}: expected preserve, single or double, got '{}'",
+                    key,
+                    value
+                ),
+            }
+        }
+        "format.align_equals" => config.format.align_equals = parse_bool(key, value)?,
+        "format.section_headers" => config.format.section_headers = parse_bool(key, value)?,
+        "format.preserve_trailing_blanks" => {
+            config.format.preserve_trailing_blanks = parse_bool(key, value)?
+        }
+        "backup.enabled" => config.backup.enabled = parse_bool(key, value)?,
+        "backup.max_count" => config.backup.max_count = parse_number(key, value)?,
+        "backup.dir" => {
+            config.backup.dir = (!value.is_empty()).then(|| PathBuf::from(value));
+        }
+        "cache.enabled" => config.cache.enabled = parse_bool(key, value)?,
+        "checker.ignore_codes" => {
+            config.checker.ignore_codes = parse_codes(key, value, crate::checker::ISSUE_CODES)?
+        }
+        "parser.ignore_warning_codes" => {
+            let known: Vec<&str> = WarningCode::ALL.iter().map(|c| c.as_str()).collect();
+            config.parser.ignore_warning_codes = parse_codes(key, value, &known)?
+        }
+        "parser.attach_comments" => config.parser.attach_comments = parse_bool(key, value)?,
+        _ => {
+            if let Some(entry_type) = color_type(key) {
+                return set_color(config, key, entry_type, value);
+            }
+            let Some(rule) = lint_rule(key) else {
+                return Err(unknown_key(key));
+            };
+            let level: LintLevel = value
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+            config.lint.rules.insert(rule.name.to_string(), level);
+        }
+    }
+    Ok(())
+}
+
+/// The lint rule a `lint.<rule>` key sets
+fn lint_rule(key: &str) -> Option<&'static LintRule> {
+    let name = key.strip_prefix("lint.")?;
+    LINT_RULES.iter().find(|rule| rule.name == name)
+}
+
+/// Set the color of `entry_type`, dropping the type's other spellings in
+/// `[colors]` (e.g. `function` for `func`); an empty `value` unsets it
+fn set_color(config: &mut Config, key: &str, entry_type: EntryType, value: &str) -> Result<()> {
+    if !value.is_empty() && parse_color(value).is_none() {
+        anyhow::bail!(
+            "Invalid value for {}: expected a color name (e.g. \"bright blue\") or #rrggbb, got '{}'",
+            key,
+            value
+        );
+    }
+    let types = &mut config.colors.types;
+    types.retain(|name, _| name.parse::<EntryType>().ok() != Some(entry_type));
+    if !value.is_empty() {
+        types.insert(entry_type.to_string(), value.to_string());
+    }
+    Ok(())
+}
+
+/// The entry type a `colors.<type>` key sets
+fn color_type(key: &str) -> Option<EntryType> {
+    let name = key.strip_prefix("colors.")?;
+    EntryType::ALL.into_iter().find(|t| t.to_string() == name)
+}
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Unknown config key '{}'. Known keys: {}",
+        key,
+        CONFIG_KEYS.join(", ")
+    )
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => anyhow::bail!(
+            "Invalid value for {}: expected true or false, got '{}'",
+            key,
+            value
+        ),
+    }
+}
+
+fn parse_number(key: &str, value: &str) -> Result<usize> {
+    value.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid value for {}: expected a non-negative number, got '{}'",
+            key,
+            value
+        )
+    })
+}
+
+/// Comma-separated codes, each one of `known`
+fn parse_codes(key: &str, value: &str, known: &[&str]) -> Result<Vec<String>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(|code| {
+            if known.contains(&code) {
+                Ok(code.to_string())
+            } else {
+                anyhow::bail!(
+                    "Invalid value for {}: unknown code '{}'. Known codes: {}",
+                    key,
+                    code,
+                    known.join(", ")
+                )
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_set_round_trip() {
+        let mut config = Config::default();
+        let cases = [
+            ("ui.language", "zh-TW"),
+            ("format.indent", "4"),
+            ("format.sort_alphabetically", "false"),
+            ("format.order", "alias,env,func"),
+            ("format.quote_style", "single"),
+            ("format.align_equals", "true"),
+            ("format.section_headers", "true"),
+            ("format.preserve_trailing_blanks", "true"),
+            ("backup.enabled", "false"),
+            ("backup.max_count", "5"),
+            ("backup.dir", "~/wenv-backups"),
+            ("cache.enabled", "true"),
+            (
+                "checker.ignore_codes",
+                "undefined-variable,trailing-whitespace",
+            ),
+            ("parser.ignore_warning_codes", "unclosed-function"),
+            ("parser.attach_comments", "true"),
+            ("lint.duplicate", "off"),
+            ("lint.style", "error"),
+            ("colors.alias", "bright blue"),
+            ("colors.func", "#0072b2"),
+        ];
+        for (key, value) in cases {
+            set_value(&mut config, key, value).unwrap();
+            assert_eq!(get_value(&config, key).unwrap(), value, "{}", key);
+        }
+
+        // Survives saving as TOML
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        for (key, value) in cases {
+            assert_eq!(get_value(&parsed, key).unwrap(), value, "{}", key);
+        }
+
+        set_value(&mut config, "backup.dir", "").unwrap();
+        assert!(config.backup.dir.is_none());
+        set_value(&mut config, "colors.alias", "").unwrap();
+        assert_eq!(get_value(&config, "colors.alias").unwrap(), "green");
+    }
+
+    #[test]
+    fn test_set_rejects_wrong_types() {
+        let mut config = Config::default();
+        assert!(set_value(&mut config, "backup.max_count", "many").is_err());
+        assert!(set_value(&mut config, "backup.max_count", "-1").is_err());
+        assert!(set_value(&mut config, "backup.enabled", "maybe").is_err());
+        assert!(set_value(&mut config, "format.quote_style", "backtick").is_err());
+        assert!(set_value(&mut config, "format.order", "alias,widgets").is_err());
+        assert!(set_value(&mut config, "checker.ignore_codes", "no-such-check").is_err());
+        assert!(set_value(&mut config, "lint.style", "loud").is_err());
+        assert!(set_value(&mut config, "lint.no-such-rule", "off").is_err());
+        assert!(set_value(&mut config, "colors.alias", "plaid").is_err());
+        assert!(set_value(&mut config, "colors.aliases", "red").is_err());
+        assert!(set_value(
+            &mut config,
+            "parser.ignore_warning_codes",
+            "duplicate-definition"
+        )
+        .is_err());
+        assert_eq!(config.backup.max_count, 20);
+        assert_eq!(config.format.order.types.len(), 4);
+
+        let error = get_value(&config, "backup.count").unwrap_err().to_string();
+        assert!(error.contains("backup.max_count"), "{}", error);
+    }
+}
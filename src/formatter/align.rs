@@ -0,0 +1,138 @@
+//! Column alignment of single-line definitions (`format.align_equals`)
+//!
+//! Within a group, every single-line definition is padded so the names end
+//! in the same column, lining up the `=` (or the value) below each other:
+//!
+//! ```text
+//! alias  gs='git status'      $env:EDITOR  = "code"
+//! alias gco='git checkout'    $env:GOPATH  = "~/go"
+//! alias  ll='ls -la'          $env:PAGER   = "less"
+//! ```
+//!
+//! Bash allows no space around `=`, so it pads before the name; PowerShell
+//! pads after it. Multi-line definitions, and lines the formatter cannot
+//! split, keep their text and do not count toward the width.
+
+use super::trim_trailing_blank_lines;
+
+/// A definition line split around its name
+#[derive(Debug, PartialEq)]
+pub struct Definition {
+    /// Text before the name, e.g. `alias ` or `$env:`
+    pub head: String,
+    pub name: String,
+    /// Text after the name without its leading whitespace, e.g. `='ls'`
+    pub tail: String,
+}
+
+/// Where the padding goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// `alias  ll='ls'`: the keyword keeps one space, names right-align
+    BeforeName,
+    /// `$env:A   = "x"`: one space plus padding after the name
+    AfterName,
+}
+
+/// The definition line of a rendered entry, split by `split`, when the
+/// entry is one line after its leading `#` comments (blank lines it
+/// absorbed after itself aside)
+fn definition_line(
+    rendered: &str,
+    split: &impl Fn(&str) -> Option<Definition>,
+) -> Option<(usize, Definition)> {
+    let lines: Vec<&str> = trim_trailing_blank_lines(rendered).lines().collect();
+    let index = lines
+        .iter()
+        .position(|l| !l.trim_start().starts_with('#'))?;
+    if index + 1 != lines.len() {
+        return None;
+    }
+    Some((index, split(lines[index])?))
+}
+
+/// Align the single-line definitions among `rendered` (the rendered entries
+/// of one group) to the widest name.
+pub fn align_definitions(
+    rendered: &mut [String],
+    padding: Padding,
+    split: impl Fn(&str) -> Option<Definition>,
+) {
+    let definitions: Vec<_> = rendered
+        .iter()
+        .map(|r| definition_line(r, &split))
+        .collect();
+    let Some(width) = definitions
+        .iter()
+        .flatten()
+        .map(|(_, d)| d.name.chars().count())
+        .max()
+    else {
+        return;
+    };
+
+    for (text, definition) in rendered.iter_mut().zip(definitions) {
+        let Some((index, d)) = definition else {
+            continue;
+        };
+        let pad = " ".repeat(width - d.name.chars().count());
+        let line = match padding {
+            Padding::BeforeName => format!("{} {}{}{}", d.head.trim_end(), pad, d.name, d.tail),
+            Padding::AfterName => format!("{}{}{} {}", d.head, d.name, pad, d.tail),
+        };
+        let body = trim_trailing_blank_lines(text);
+        let mut lines: Vec<&str> = body.lines().take(index).collect();
+        lines.push(&line);
+        *text = lines.join("\n") + &text[body.len()..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `key=value` split at the `=`
+    fn split(line: &str) -> Option<Definition> {
+        let (name, value) = line.split_once('=')?;
+        Some(Definition {
+            head: "set ".to_string(),
+            name: name.trim_start_matches("set ").trim().to_string(),
+            tail: format!("={}", value),
+        })
+    }
+
+    fn align(rendered: &[&str], padding: Padding) -> Vec<String> {
+        let mut rendered: Vec<String> = rendered.iter().map(|r| r.to_string()).collect();
+        align_definitions(&mut rendered, padding, split);
+        rendered
+    }
+
+    #[test]
+    fn test_pads_to_the_widest_name() {
+        let rendered = ["set a=1", "# comment\nset abc=2", "set   ab=3"];
+        assert_eq!(
+            align(&rendered, Padding::BeforeName),
+            ["set   a=1", "# comment\nset abc=2", "set  ab=3"]
+        );
+        assert_eq!(
+            align(&rendered, Padding::AfterName),
+            ["set a   =1", "# comment\nset abc =2", "set ab  =3"]
+        );
+    }
+
+    #[test]
+    fn test_multi_line_entries_are_left_alone() {
+        let rendered = ["set a=1", "set longest='x\ny'", "code without equals"];
+        assert_eq!(
+            align(&rendered, Padding::BeforeName),
+            ["set a=1", "set longest='x\ny'", "code without equals"]
+        );
+
+        // Absorbed blank lines are not a second line, and are kept
+        let rendered = ["set a=1\n\n", "set ab=2"];
+        assert_eq!(
+            align(&rendered, Padding::BeforeName),
+            ["set  a=1\n\n", "set ab=2"]
+        );
+    }
+}
@@ -1,11 +1,26 @@
 //! Bash configuration file formatter
 
-use crate::formatter::find_attached_comments;
+use crate::formatter::align::{align_definitions, Definition, Padding};
+use crate::formatter::quote::apply_quote_style;
+use crate::formatter::{
+    find_attached_comments, group_type_order, strip_section_headers, trim_trailing_blank_lines,
+    write_around_guards, GROUPED_TYPES,
+};
 use crate::model::{Config, Entry, EntryType, ShellType};
 use crate::utils::dependency;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::{self, Write};
 
 use super::Formatter;
 
+lazy_static! {
+    /// `alias name=...` / `export NAME=...` without options, split into the
+    /// keyword, the name and everything from `=` on
+    static ref ALIGNABLE_RE: Regex =
+        Regex::new(r"^(\s*(?:alias|export)\s+)([^\s=-][^\s=]*)(=.*)$").unwrap();
+}
+
 /// Bash configuration file formatter
 pub struct BashFormatter {
     /// Indentation style (e.g., "    " for 4 spaces, "\t" for tab)
@@ -25,6 +40,34 @@ impl BashFormatter {
         Self { indent_style }
     }
 
+    /// Format an entry, applying config-driven rewrites (quote style).
+    /// Prompt variables are left as written.
+    fn render_entry(&self, entry: &Entry, config: &Config) -> String {
+        let raw = self.format_entry(entry);
+        match entry.entry_type {
+            EntryType::Alias | EntryType::EnvVar if !entry.is_prompt_variable() => {
+                apply_quote_style(&raw, config.format.quote_style)
+            }
+            _ => raw,
+        }
+    }
+
+    /// Render the entries of one group (all of one type), aligning alias
+    /// and env var names when `format.align_equals` is set
+    fn render_group(&self, entries: &[&Entry], config: &Config) -> Vec<String> {
+        let mut rendered: Vec<String> = entries
+            .iter()
+            .map(|entry| self.render_entry(entry, config))
+            .collect();
+        let alignable = entries
+            .first()
+            .is_some_and(|e| matches!(e.entry_type, EntryType::Alias | EntryType::EnvVar));
+        if config.format.align_equals && alignable {
+            align_definitions(&mut rendered, Padding::BeforeName, split_definition);
+        }
+        rendered
+    }
+
     #[allow(dead_code)]
     fn format_alias(&self, entry: &Entry) -> String {
         let value = &entry.value;
@@ -119,195 +162,204 @@ impl BashFormatter {
             lines.last().unwrap()
         )
     }
-}
 
-impl Default for BashFormatter {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Formatter for BashFormatter {
-    fn format(&self, entries: &[Entry], config: &Config) -> String {
-        let mut output = String::new();
+    /// Group entries by type (for format command with grouping enabled).
+    /// Strategy: Output types in configured order, keep Comment/Code in
+    /// original positions
+    fn write_grouped(
+        &self,
+        entries: &[Entry],
+        config: &Config,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        // Find comments attached to entries
+        let attached_comments = find_attached_comments(entries);
+
+        // Group parseable entries by type
+        let mut grouped: std::collections::HashMap<EntryType, Vec<&Entry>> =
+            std::collections::HashMap::new();
+
+        for entry in entries {
+            if GROUPED_TYPES.contains(&entry.entry_type) {
+                grouped.entry(entry.entry_type).or_default().push(entry);
+            }
+        }
 
-        if !config.format.group_by_type {
-            // Output in original order (by line number)
-            let mut sorted_entries: Vec<_> = entries.iter().collect();
-            sorted_entries.sort_by_key(|e| e.line_number.unwrap_or(0));
+        // Sort grouped entries
+        for (entry_type, type_entries) in grouped.iter_mut() {
+            if config.format.sort_alphabetically {
+                if *entry_type == EntryType::EnvVar {
+                    // Use topological sort for environment variables to respect dependencies
+                    let sorted = dependency::topological_sort(type_entries, true);
+                    *type_entries = sorted;
+                } else {
+                    // Simple alphabetical sort for other types
+                    type_entries.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+            } else if *entry_type == EntryType::EnvVar {
+                // Even without alphabetical sorting, preserve dependency order
+                let sorted = dependency::topological_sort(type_entries, false);
+                *type_entries = sorted;
+            }
+        }
 
-            for entry in sorted_entries {
+        // Build type order from config
+        let type_order = group_type_order(&config.format.order.types);
+
+        // Collect Comment/Code (and other ungrouped) entries for output in
+        // original order
+        let mut code_comments: Vec<&Entry> = entries
+            .iter()
+            .filter(|e| !GROUPED_TYPES.contains(&e.entry_type))
+            .filter(|e| {
+                // Skip comments attached to other entries
+                if e.entry_type == EntryType::Comment {
+                    let entry_line = e.line_number.unwrap_or(0);
+                    !attached_comments
+                        .values()
+                        .any(|comments| comments.iter().any(|c| c.line_number == Some(entry_line)))
+                } else {
+                    true
+                }
+            })
+            .collect();
+        code_comments.sort_by_key(|e| e.line_number.unwrap_or(0));
+
+        // Output Code/Comment entries that appear before any structured entries
+        let first_structured_line = entries
+            .iter()
+            .filter(|e| GROUPED_TYPES.contains(&e.entry_type))
+            .filter_map(|e| e.line_number)
+            .min()
+            .unwrap_or(usize::MAX);
+
+        for entry in &code_comments {
+            let line = entry.line_number.unwrap_or(0);
+            if line < first_structured_line {
                 if entry.entry_type == EntryType::Code && entry.value.is_empty() {
-                    // Handle grouped blank lines
                     if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
                         for _ in 0..(end - start + 1) {
-                            output.push('\n');
+                            writeln!(output)?;
                         }
                     } else {
-                        output.push('\n');
+                        writeln!(output)?;
                     }
                 } else {
-                    output.push_str(&self.format_entry(entry));
-                    output.push('\n');
-                }
-            }
-        } else {
-            // Group entries by type (for format command with grouping enabled)
-            // Strategy: Output types in configured order, keep Comment/Code in original positions
-
-            // Find comments attached to entries
-            let attached_comments = find_attached_comments(entries);
-
-            // Group parseable entries by type
-            let mut grouped: std::collections::HashMap<EntryType, Vec<&Entry>> =
-                std::collections::HashMap::new();
-
-            for entry in entries {
-                match entry.entry_type {
-                    EntryType::Alias
-                    | EntryType::EnvVar
-                    | EntryType::Source
-                    | EntryType::Function => {
-                        grouped.entry(entry.entry_type).or_default().push(entry);
-                    }
-                    _ => {}
+                    writeln!(output, "{}", self.render_entry(entry, config))?;
                 }
             }
+        }
 
-            // Sort grouped entries
-            for (entry_type, type_entries) in grouped.iter_mut() {
-                if config.format.sort_alphabetically {
-                    if *entry_type == EntryType::EnvVar {
-                        // Use topological sort for environment variables to respect dependencies
-                        let sorted = dependency::topological_sort(type_entries, true);
-                        *type_entries = sorted;
-                    } else {
-                        // Simple alphabetical sort for other types
-                        type_entries.sort_by(|a, b| a.name.cmp(&b.name));
+        // Output structured entries by configured type order
+        let blank_lines = config.format.blank_lines_between_groups;
+        let mut first_group = true;
+
+        for entry_type in &type_order {
+            if let Some(type_entries) = grouped.get(entry_type) {
+                if !type_entries.is_empty() {
+                    // Add blank lines between groups
+                    if !first_group {
+                        for _ in 0..blank_lines {
+                            writeln!(output)?;
+                        }
                     }
-                } else if *entry_type == EntryType::EnvVar {
-                    // Even without alphabetical sorting, preserve dependency order
-                    let sorted = dependency::topological_sort(type_entries, false);
-                    *type_entries = sorted;
-                }
-            }
+                    first_group = false;
 
-            // Build type order from config
-            let type_order: Vec<EntryType> = config
-                .format
-                .order
-                .types
-                .iter()
-                .filter_map(|s| s.parse::<EntryType>().ok())
-                .collect();
-
-            // Collect Comment/Code entries for output in original order
-            let mut code_comments: Vec<&Entry> = entries
-                .iter()
-                .filter(|e| e.entry_type == EntryType::Code || e.entry_type == EntryType::Comment)
-                .filter(|e| {
-                    // Skip comments attached to other entries
-                    if e.entry_type == EntryType::Comment {
-                        let entry_line = e.line_number.unwrap_or(0);
-                        !attached_comments.values().any(|comments| {
-                            comments.iter().any(|c| c.line_number == Some(entry_line))
-                        })
-                    } else {
-                        true
+                    if config.format.section_headers {
+                        writeln!(output, "{}", self.section_header(*entry_type))?;
                     }
-                })
-                .collect();
-            code_comments.sort_by_key(|e| e.line_number.unwrap_or(0));
-
-            // Output Code/Comment entries that appear before any structured entries
-            let first_structured_line = entries
-                .iter()
-                .filter(|e| {
-                    matches!(
-                        e.entry_type,
-                        EntryType::Alias
-                            | EntryType::EnvVar
-                            | EntryType::Source
-                            | EntryType::Function
-                    )
-                })
-                .filter_map(|e| e.line_number)
-                .min()
-                .unwrap_or(usize::MAX);
-
-            for entry in &code_comments {
-                let line = entry.line_number.unwrap_or(0);
-                if line < first_structured_line {
-                    if entry.entry_type == EntryType::Code && entry.value.is_empty() {
-                        if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
-                            for _ in 0..(end - start + 1) {
-                                output.push('\n');
+                    let rendered = self.render_group(type_entries, config);
+                    for (grouped_entry, rendered) in type_entries.iter().zip(&rendered) {
+                        // Output attached comments before the entry
+                        if let Some(comments) =
+                            attached_comments.get(&grouped_entry.line_number.unwrap_or(0))
+                        {
+                            for comment in comments {
+                                writeln!(output, "{}", self.format_entry(comment))?;
                             }
-                        } else {
-                            output.push('\n');
                         }
-                    } else {
-                        output.push_str(&self.format_entry(entry));
-                        output.push('\n');
+
+                        writeln!(output, "{}", trim_trailing_blank_lines(rendered))?;
                     }
                 }
             }
+        }
 
-            // Output structured entries by configured type order
-            let blank_lines = config.format.blank_lines_between_groups;
-            let mut first_group = true;
-
-            for entry_type in &type_order {
-                if let Some(type_entries) = grouped.get(entry_type) {
-                    if !type_entries.is_empty() {
-                        // Add blank lines between groups
-                        if !first_group {
-                            for _ in 0..blank_lines {
-                                output.push('\n');
-                            }
-                        }
-                        first_group = false;
-
-                        for grouped_entry in type_entries {
-                            // Output attached comments before the entry
-                            if let Some(comments) =
-                                attached_comments.get(&grouped_entry.line_number.unwrap_or(0))
-                            {
-                                for comment in comments {
-                                    output.push_str(&self.format_entry(comment));
-                                    output.push('\n');
-                                }
-                            }
-
-                            output.push_str(&self.format_entry(grouped_entry));
-                            output.push('\n');
-                        }
-                    }
-                }
+        // Output remaining Code/Comment entries (those not before first structured entry)
+        // These are output after all structured entries have been grouped.
+        // Standalone blank lines are dropped: grouping owns the spacing now, and
+        // keeping them would grow the file on every format pass.
+        let remaining: Vec<_> = code_comments
+            .iter()
+            .filter(|e| e.line_number.unwrap_or(0) >= first_structured_line)
+            .filter(|e| !e.is_blank())
+            .collect();
+        if !remaining.is_empty() && !first_group {
+            for _ in 0..blank_lines {
+                writeln!(output)?;
             }
+        }
+        for entry in remaining {
+            writeln!(output, "{}", self.render_entry(entry, config))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for BashFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for BashFormatter {
+    fn format(&self, entries: &[Entry], config: &Config) -> String {
+        let mut output = Vec::new();
+        self.format_to_writer(entries, config, &mut output)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("entries are valid UTF-8")
+    }
+
+    fn format_to_writer(
+        &self,
+        entries: &[Entry],
+        config: &Config,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        if !config.format.group_by_type {
+            // Output in original order (by line number)
+            let mut sorted_entries: Vec<_> = entries.iter().collect();
+            sorted_entries.sort_by_key(|e| e.line_number.unwrap_or(0));
 
-            // Output remaining Code/Comment entries (those not before first structured entry)
-            // These are output after all structured entries have been grouped
-            for entry in &code_comments {
-                let line = entry.line_number.unwrap_or(0);
-                if line >= first_structured_line {
+            // Runs of consecutive entries of one type are the groups
+            for run in sorted_entries.chunk_by(|a, b| a.entry_type == b.entry_type) {
+                let rendered = self.render_group(run, config);
+                for (entry, rendered) in run.iter().zip(rendered) {
                     if entry.entry_type == EntryType::Code && entry.value.is_empty() {
+                        // Handle grouped blank lines
                         if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
                             for _ in 0..(end - start + 1) {
-                                output.push('\n');
+                                writeln!(output)?;
                             }
                         } else {
-                            output.push('\n');
+                            writeln!(output)?;
                         }
                     } else {
-                        output.push_str(&self.format_entry(entry));
-                        output.push('\n');
+                        writeln!(output, "{}", rendered)?;
                     }
                 }
             }
+        } else {
+            // Headers of the last pass are dropped and rewritten by grouping
+            let entries = strip_section_headers(entries);
+            let blank_lines = config.format.blank_lines_between_groups;
+            write_around_guards(&entries, blank_lines, output, |entries, output| {
+                self.write_grouped(entries, config, output)
+            })?;
         }
 
-        output
+        Ok(())
     }
 
     fn format_entry(&self, entry: &Entry) -> String {
@@ -316,11 +368,53 @@ impl Formatter for BashFormatter {
         entry.value.clone()
     }
 
+    fn definition(&self, entry_type: EntryType, name: &str, value: &str) -> Option<String> {
+        match entry_type {
+            EntryType::Alias => Some(format!("alias {}={}", name, single_quote(value))),
+            EntryType::EnvVar => Some(format!("export {}={}", name, quote_word(value))),
+            EntryType::Source => Some(format!("source {}", quote_word(value))),
+            _ => None,
+        }
+    }
+
     fn shell_type(&self) -> ShellType {
         ShellType::Bash
     }
 }
 
+/// Split an `alias`/`export` line for [`align_definitions`]
+fn split_definition(line: &str) -> Option<Definition> {
+    let caps = ALIGNABLE_RE.captures(line)?;
+    Some(Definition {
+        head: caps[1].to_string(),
+        name: caps[2].to_string(),
+        tail: caps[3].to_string(),
+    })
+}
+
+/// `'value'`, with embedded single quotes written as `'\''`
+fn single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// `value` as-is when it is a single plain word, otherwise double-quoted so
+/// `$VAR` references still expand
+fn quote_word(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:~@%+,=$".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('`', "\\`");
+        format!("\"{}\"", escaped)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -648,6 +742,105 @@ line3'";
         assert_eq!(formatter.format_entry(&entry), "source ~/.aliases");
     }
 
+    #[test]
+    fn test_format_applies_quote_style() {
+        use crate::model::QuoteStyle;
+
+        let content = "alias ll=\"ls -la\"\nexport PATH=\"$HOME/bin:$PATH\"\n";
+        let result = crate::parser::get_parser(ShellType::Bash).parse(content);
+
+        let mut config = Config::default();
+        config.format.group_by_type = false;
+        config.format.quote_style = QuoteStyle::Single;
+
+        let formatted = BashFormatter::new().format(&result.entries, &config);
+        assert_eq!(
+            formatted,
+            "alias ll='ls -la'\nexport PATH=\"$HOME/bin:$PATH\"\n"
+        );
+    }
+
+    #[test]
+    fn test_format_leaves_prompt_variables_as_written() {
+        use crate::model::QuoteStyle;
+
+        let ps1 = r#"export PS1="\[\e[32m\]\u@\h\[\e[0m\]:\w\$ ""#;
+        let content = format!(
+            "{}\nPS2=\"> \"\nexport PROMPT_COMMAND=\"history -a\"\nexport EDITOR=\"nvim\"\n",
+            ps1
+        );
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(&content)
+            .entries;
+        // A bare assignment is a prompt variable too, not plain code
+        let ps2 = entries.iter().find(|e| e.name == "PS2").unwrap();
+        assert!(ps2.is_prompt_variable());
+
+        for quote_style in [QuoteStyle::Single, QuoteStyle::Double] {
+            let mut config = Config::default();
+            config.format.quote_style = quote_style;
+            let formatted = BashFormatter::new().format(&entries, &config);
+            assert!(formatted.contains(ps1), "{:?}: {}", quote_style, formatted);
+            assert!(formatted.contains("PS2=\"> \""), "{}", formatted);
+            assert!(
+                formatted.contains("export PROMPT_COMMAND=\"history -a\""),
+                "{}",
+                formatted
+            );
+        }
+        // Other variables still follow the quote style
+        let mut config = Config::default();
+        config.format.quote_style = QuoteStyle::Single;
+        let formatted = BashFormatter::new().format(&entries, &config);
+        assert!(formatted.contains("export EDITOR='nvim'"), "{}", formatted);
+    }
+
+    #[test]
+    fn test_format_preserves_inline_comments() {
+        use crate::model::QuoteStyle;
+
+        let content = "alias ll='ls -la' # my listing\nexport EDITOR=\"nvim\" # editor\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+
+        let cases = [
+            (
+                QuoteStyle::Preserve,
+                "alias ll='ls -la' # my listing",
+                "export EDITOR=\"nvim\" # editor",
+            ),
+            (
+                QuoteStyle::Single,
+                "alias ll='ls -la' # my listing",
+                "export EDITOR='nvim' # editor",
+            ),
+            (
+                QuoteStyle::Double,
+                "alias ll=\"ls -la\" # my listing",
+                "export EDITOR=\"nvim\" # editor",
+            ),
+        ];
+        for (quote_style, alias, export) in cases {
+            let mut config = Config::default();
+            config.format.quote_style = quote_style;
+
+            let formatted = BashFormatter::new().format(&entries, &config);
+            assert!(
+                formatted.contains(alias),
+                "{:?}: {}",
+                quote_style,
+                formatted
+            );
+            assert!(
+                formatted.contains(export),
+                "{:?}: {}",
+                quote_style,
+                formatted
+            );
+        }
+    }
+
     #[test]
     fn test_format_order_from_config() {
         use crate::model::{ShellType, TypeOrder};
@@ -724,4 +917,157 @@ greet() { echo hello; }
             "alias should come before env (order: source, func, alias, env)"
         );
     }
+
+    #[test]
+    fn test_format_keeps_tag_comment_with_entry() {
+        let content = "alias zz='z'\n# wenv:tags=work\nalias aa='a'\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+
+        // Sorting moves the tagged entry; its tag comment must move with it
+        let formatted = BashFormatter::new().format(&entries, &Config::default());
+        assert!(
+            formatted.contains("# wenv:tags=work\nalias aa='a'\nalias zz='z'"),
+            "{}",
+            formatted
+        );
+    }
+
+    #[test]
+    fn test_format_grouped_keeps_arrays() {
+        let content = "alias ll='ls -la'
+plugins=(\n  git\n)\nexport EDITOR=vim\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+
+        // The default order doesn't list arrays; they still get a group
+        let formatted = BashFormatter::new().format(&entries, &Config::default());
+        assert_eq!(
+            formatted,
+            "export EDITOR=vim\n\nalias ll='ls -la'\n\nplugins=(\n  git\n)\n"
+        );
+    }
+
+    #[test]
+    fn test_format_grouped_keeps_unset_after_definitions() {
+        let content = "alias z='a'\nunalias z\nalias b='c'\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+
+        let formatted = BashFormatter::new().format(&entries, &Config::default());
+        assert_eq!(formatted, "alias b='c'\nalias z='a'\n\nunalias z\n");
+    }
+
+    #[test]
+    fn test_definition_quoting() {
+        let formatter = BashFormatter::new();
+        let def = |t, n, v| formatter.definition(t, n, v).unwrap();
+
+        assert_eq!(
+            def(EntryType::Alias, "hi", "echo 'hi'"),
+            r#"alias hi='echo '\''hi'\'''"#
+        );
+        assert_eq!(
+            def(EntryType::EnvVar, "GOPATH", "~/go"),
+            "export GOPATH=~/go"
+        );
+        assert_eq!(
+            def(EntryType::EnvVar, "MSG", r#"say "hi" $USER"#),
+            r#"export MSG="say \"hi\" $USER""#
+        );
+        assert!(formatter
+            .definition(EntryType::Function, "f", "f() { :; }")
+            .is_none());
+    }
+
+    #[test]
+    fn test_format_aligns_equals() {
+        let content = "alias ll='ls -la'\nalias gco='git checkout'\n\
+                       alias long_multiline='echo one\necho two'\n\
+                       export EDITOR=vim\nexport PAGER=less\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+        let mut config = Config::default();
+        config.format.align_equals = true;
+
+        let formatted = BashFormatter::new().format(&entries, &config);
+
+        // Multi-line definitions neither move nor widen the column
+        assert_eq!(
+            formatted,
+            "export EDITOR=vim\nexport  PAGER=less\n\n\
+             alias gco='git checkout'\nalias  ll='ls -la'\n\
+             alias long_multiline='echo one\necho two'\n"
+        );
+        let reparsed = crate::parser::get_parser(ShellType::Bash)
+            .parse(&formatted)
+            .entries;
+        assert_eq!(BashFormatter::new().format(&reparsed, &config), formatted);
+        assert!(reparsed
+            .iter()
+            .any(|e| e.name == "ll" && e.canonical_value() == "ls -la"));
+    }
+
+    #[test]
+    fn test_format_aligns_runs_in_file_order() {
+        let content = "alias a=x\nalias abc=y\necho hi\nalias ab=z\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+        let mut config = Config::default();
+        config.format.group_by_type = false;
+        config.format.align_equals = true;
+
+        assert_eq!(
+            BashFormatter::new().format(&entries, &config),
+            "alias   a=x\nalias abc=y\necho hi\nalias ab=z\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_shebang_first() {
+        let content = "#!/bin/bash\nalias zz='echo z'\nexport B=1\nalias aa='echo a'\n";
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries;
+
+        for group_by_type in [true, false] {
+            let mut config = Config::default();
+            config.format.group_by_type = group_by_type;
+            let formatted = BashFormatter::new().format(&entries, &config);
+            assert!(formatted.starts_with("#!/bin/bash\n"), "{}", formatted);
+            assert_eq!(formatted.matches("#!").count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_section_headers_are_idempotent() {
+        let content = "# Listing\nalias ll='ls -la'\nexport EDITOR=vim\nalias gs='git status'\n";
+        let mut config = Config::default();
+        config.format.section_headers = true;
+        let format = |content: &str, config: &Config| {
+            let entries = crate::parser::get_parser(ShellType::Bash)
+                .parse(content)
+                .entries;
+            BashFormatter::new().format(&entries, config)
+        };
+
+        let once = format(content, &config);
+        assert_eq!(
+            once,
+            "# === Environment Variables === wenv:section\nexport EDITOR=vim\n\n\
+             # === Aliases === wenv:section\nalias gs='git status'\n# Listing\nalias ll='ls -la'\n"
+        );
+        assert_eq!(format(&once, &config), once);
+
+        // Turning the option off removes them again
+        config.format.section_headers = false;
+        let plain = format(&once, &config);
+        assert!(!plain.contains("wenv:section"), "{}", plain);
+        assert!(plain.contains("# Listing\nalias ll='ls -la'"));
+    }
 }
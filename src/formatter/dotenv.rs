@@ -0,0 +1,162 @@
+//! `.env` file formatter
+
+use crate::model::{Config, Entry, EntryType, ShellType};
+use std::io::{self, Write};
+
+use super::Formatter;
+
+/// `.env` file formatter
+///
+/// Keeps entries in file order (a `.env` file has no groups to sort into)
+/// and writes them back cleanly: variables as `KEY=value` without `export`,
+/// quoted only when needed, comments and lines without indentation, and
+/// runs of blank lines collapsed to one.
+pub struct DotEnvFormatter;
+
+impl DotEnvFormatter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn render_entry(&self, entry: &Entry) -> String {
+        match entry.entry_type {
+            EntryType::EnvVar => {
+                let definition = self
+                    .definition(EntryType::EnvVar, &entry.name, &entry.canonical_value())
+                    .expect("env vars have a definition");
+                let comments = entry
+                    .value
+                    .lines()
+                    .map(str::trim)
+                    .take_while(|line| line.starts_with('#'));
+                comments
+                    .chain([definition.as_str()])
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            EntryType::Comment => entry
+                .value
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => entry.value.clone(),
+        }
+    }
+}
+
+impl Default for DotEnvFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for DotEnvFormatter {
+    fn format(&self, entries: &[Entry], config: &Config) -> String {
+        let mut output = Vec::new();
+        self.format_to_writer(entries, config, &mut output)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("entries are valid UTF-8")
+    }
+
+    fn format_to_writer(
+        &self,
+        entries: &[Entry],
+        _config: &Config,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut sorted_entries: Vec<_> = entries.iter().collect();
+        sorted_entries.sort_by_key(|e| e.line_number.unwrap_or(0));
+
+        let mut pending_blank = false;
+        let mut written = false;
+        for entry in sorted_entries {
+            if entry.is_blank() {
+                pending_blank = written;
+                continue;
+            }
+            if pending_blank {
+                writeln!(output)?;
+                pending_blank = false;
+            }
+            writeln!(output, "{}", self.render_entry(entry))?;
+            written = true;
+        }
+        Ok(())
+    }
+
+    fn format_entry(&self, entry: &Entry) -> String {
+        entry.value.clone()
+    }
+
+    fn definition(&self, entry_type: EntryType, name: &str, value: &str) -> Option<String> {
+        match entry_type {
+            EntryType::EnvVar => Some(format!("{}={}", name, quote_value(value))),
+            _ => None,
+        }
+    }
+
+    fn shell_type(&self) -> ShellType {
+        ShellType::DotEnv
+    }
+}
+
+/// `value` as-is when it needs no quotes, otherwise single-quoted, or
+/// double-quoted when it contains a single quote itself
+fn quote_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| !c.is_whitespace() && !"#'\"\\`".contains(c));
+    if plain {
+        value.to_string()
+    } else if !value.contains('\'') {
+        format!("'{}'", value)
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::get_parser;
+
+    fn format(content: &str) -> String {
+        let entries = get_parser(ShellType::DotEnv).parse(content).entries;
+        DotEnvFormatter::new().format(&entries, &Config::default())
+    }
+
+    #[test]
+    fn test_format_normalizes_assignments() {
+        assert_eq!(
+            format(
+                "export API_KEY=abc\nNAME = \"plain\"\nGREETING=\"hello world\"\nQUOTE=\"it's\"\n"
+            ),
+            "API_KEY=abc\nNAME=plain\nGREETING='hello world'\nQUOTE=\"it's\"\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments_and_collapses_blank_lines() {
+        assert_eq!(
+            format("\n  # Database\nDB_HOST=localhost\n\n\n\n# Cache\nCACHE_TTL=60\n\n"),
+            "# Database\nDB_HOST=localhost\n\n# Cache\nCACHE_TTL=60\n"
+        );
+    }
+
+    #[test]
+    fn test_format_is_stable() {
+        let once = format("# Keys\nexport A='x y'\n\n\nB=2\n");
+        assert_eq!(format(&once), once);
+    }
+
+    #[test]
+    fn test_definition() {
+        let formatter = DotEnvFormatter::new();
+        assert_eq!(
+            formatter.definition(EntryType::EnvVar, "EMPTY", ""),
+            Some("EMPTY=".to_string())
+        );
+        assert_eq!(formatter.definition(EntryType::Alias, "ll", "ls"), None);
+    }
+}
@@ -1,23 +1,53 @@
 //! Formatter module for shell configuration files
 
+pub mod align;
 mod bash;
+mod dotenv;
 pub mod indent;
 mod pwsh;
+pub mod quote;
+pub mod range;
 
 pub use bash::BashFormatter;
+pub use dotenv::DotEnvFormatter;
 pub use pwsh::PowerShellFormatter;
 
 use crate::model::{Config, Entry, EntryType, ShellType};
 use std::collections::HashMap;
+use std::io::{self, Write};
 
 /// Trait for shell configuration formatters
 pub trait Formatter {
     /// Format entries into shell configuration format
     fn format(&self, entries: &[Entry], config: &Config) -> String;
 
+    /// Like [`format`](Self::format), but write the output to `w` as it is
+    /// produced instead of building one string for the whole file
+    fn format_to_writer(
+        &self,
+        entries: &[Entry],
+        config: &Config,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        w.write_all(self.format(entries, config).as_bytes())
+    }
+
     /// Format a single entry
     fn format_entry(&self, entry: &Entry) -> String;
 
+    /// Shell syntax defining an entry from its canonical value (see
+    /// [`Entry::canonical_value`]), e.g. `("alias", "ll", "ls -la")` becomes
+    /// `alias ll='ls -la'`. Returns `None` for types that can't be generated
+    /// from a value alone.
+    fn definition(&self, entry_type: EntryType, name: &str, value: &str) -> Option<String>;
+
+    /// Comment line written above each group of `entry_type` entries when
+    /// `format.section_headers` is set. It must end in [`SECTION_MARKER`]
+    /// so the next pass can replace it.
+    fn section_header(&self, entry_type: EntryType) -> String {
+        format!("# === {} === {}", section_title(entry_type), SECTION_MARKER)
+    }
+
     /// Get the shell type this formatter handles
     fn shell_type(&self) -> ShellType;
 }
@@ -25,9 +55,181 @@ pub trait Formatter {
 /// Get a formatter for the specified shell type
 pub fn get_formatter(shell_type: ShellType) -> Box<dyn Formatter> {
     match shell_type {
-        ShellType::Bash | ShellType::Zsh => Box::new(BashFormatter::new()),
+        ShellType::Bash | ShellType::Zsh | ShellType::PosixSh => Box::new(BashFormatter::new()),
         ShellType::PowerShell => Box::new(PowerShellFormatter::new()),
+        ShellType::DotEnv => Box::new(DotEnvFormatter::new()),
+    }
+}
+
+/// Drop blank lines a structured entry absorbed after itself.
+///
+/// When grouping by type the formatter owns the spacing between entries and
+/// groups. Keeping absorbed trailing blanks would add to that spacing on
+/// every pass, so formatting would never converge.
+pub fn trim_trailing_blank_lines(value: &str) -> &str {
+    let mut end = value.len();
+    while let Some(pos) = value[..end].rfind('\n') {
+        if !value[pos + 1..end].trim().is_empty() {
+            break;
+        }
+        end = pos;
+    }
+    &value[..end]
+}
+
+/// Part of a file as seen by group-by-type formatting
+#[derive(Debug)]
+pub enum Segment<'a> {
+    /// Entries that may be grouped and sorted among themselves
+    Entries(&'a [Entry]),
+    /// A `wenv:ignore` block, kept verbatim and in place (see [`Entry::is_guarded`])
+    Guarded(&'a Entry),
+}
+
+/// Split `entries` (in file order) at `wenv:ignore` blocks.
+pub fn guarded_segments(entries: &[Entry]) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.is_guarded() {
+            if start < i {
+                segments.push(Segment::Entries(&entries[start..i]));
+            }
+            segments.push(Segment::Guarded(entry));
+            start = i + 1;
+        }
+    }
+    if start < entries.len() {
+        segments.push(Segment::Entries(&entries[start..]));
     }
+    segments
+}
+
+/// Group-by-type output that leaves `wenv:ignore` blocks in place.
+///
+/// The entries between blocks are grouped separately by `write_grouped`, so
+/// nothing moves across a block. Blocks are written verbatim, separated from
+/// the grouped parts by `blank_lines` blank lines; blank lines the grouped
+/// parts start or end with are dropped so the spacing doesn't grow on every
+/// pass.
+pub fn write_around_guards(
+    entries: &[Entry],
+    blank_lines: usize,
+    output: &mut dyn Write,
+    write_grouped: impl Fn(&[Entry], &mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let segments = guarded_segments(entries);
+    if let [Segment::Entries(entries)] = segments.as_slice() {
+        return write_grouped(entries, output);
+    }
+
+    let mut first = true;
+    for segment in segments {
+        let text = match segment {
+            Segment::Entries(entries) => {
+                let mut buffer = Vec::new();
+                write_grouped(entries, &mut buffer)?;
+                let text = String::from_utf8(buffer).expect("entries are valid UTF-8");
+                let text = trim_trailing_blank_lines(&text);
+                let body_start = text
+                    .split_inclusive('\n')
+                    .take_while(|line| line.trim().is_empty())
+                    .map(str::len)
+                    .sum::<usize>();
+                text[body_start..].to_string()
+            }
+            Segment::Guarded(entry) => entry.value.clone(),
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+        if !first {
+            for _ in 0..blank_lines {
+                writeln!(output)?;
+            }
+        }
+        first = false;
+        writeln!(output, "{}", text)?;
+    }
+    Ok(())
+}
+
+/// Entry types that group-by-type formatting moves into groups
+pub const GROUPED_TYPES: [EntryType; 5] = [
+    EntryType::EnvVar,
+    EntryType::Array,
+    EntryType::Alias,
+    EntryType::Function,
+    EntryType::Source,
+];
+
+/// Group order from `[format.order] types`. Grouped types the config does
+/// not list are appended so their entries are never dropped from output.
+pub fn group_type_order(types: &[String]) -> Vec<EntryType> {
+    let mut order: Vec<EntryType> = types
+        .iter()
+        .filter_map(|s| s.parse::<EntryType>().ok())
+        .filter(|t| GROUPED_TYPES.contains(t))
+        .collect();
+    for entry_type in GROUPED_TYPES {
+        if !order.contains(&entry_type) {
+            order.push(entry_type);
+        }
+    }
+    order
+}
+
+/// Marker ending the section headers written by `format.section_headers`
+pub const SECTION_MARKER: &str = "wenv:section";
+
+/// Name of a group in its section header
+fn section_title(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::EnvVar => "Environment Variables",
+        EntryType::Array => "Arrays",
+        EntryType::Alias => "Aliases",
+        EntryType::Function => "Functions",
+        EntryType::Source => "Sources",
+        EntryType::Unset => "Removals",
+        EntryType::Trap => "Traps",
+        EntryType::Setopt => "Shell Options",
+        EntryType::Zstyle => "Styles",
+        EntryType::Code => "Code",
+        EntryType::Comment => "Comments",
+    }
+}
+
+/// Whether `line` is a section header written by a previous format pass
+pub fn is_section_header(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('#') && line.ends_with(SECTION_MARKER)
+}
+
+/// `entries` without the section headers a previous format pass wrote, so
+/// grouping can write them afresh. A header is parsed as a Comment of its
+/// own or merged into the top of the entry below; either way it leads the
+/// value, so only leading header lines are dropped.
+pub fn strip_section_headers(entries: &[Entry]) -> Vec<Entry> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            if entry.is_guarded() {
+                return Some(entry.clone());
+            }
+            let lines: Vec<&str> = entry.value.split('\n').collect();
+            let headers = lines.iter().take_while(|l| is_section_header(l)).count();
+            if headers == 0 {
+                return Some(entry.clone());
+            }
+            if headers == lines.len() {
+                return None;
+            }
+            let mut entry = entry.clone();
+            entry.value = lines[headers..].join("\n");
+            entry.line_number = entry.line_number.map(|line| line + headers);
+            Some(entry)
+        })
+        .collect()
 }
 
 /// Find comments that are attached to entries (comments immediately before an entry).
@@ -44,8 +246,8 @@ pub fn find_attached_comments(entries: &[Entry]) -> HashMap<usize, Vec<Entry>> {
     for i in 0..sorted_entries.len() {
         let entry = sorted_entries[i];
 
-        // Only process Comment entries
-        if entry.entry_type != EntryType::Comment {
+        // Only process Comment entries; a shebang stays first
+        if entry.entry_type != EntryType::Comment || entry.is_shebang() {
             continue;
         }
 
@@ -76,3 +278,157 @@ pub fn find_attached_comments(entries: &[Entry]) -> HashMap<usize, Vec<Entry>> {
 
     attached_comments
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_trailing_blank_lines() {
+        assert_eq!(trim_trailing_blank_lines("alias a='x'"), "alias a='x'");
+        assert_eq!(trim_trailing_blank_lines("alias a='x'\n"), "alias a='x'");
+        assert_eq!(
+            trim_trailing_blank_lines("alias a='x'\n  \n"),
+            "alias a='x'"
+        );
+        // Blank lines inside a value are kept
+        assert_eq!(
+            trim_trailing_blank_lines("# c\n\nalias a='x\n\ny'\n"),
+            "# c\n\nalias a='x\n\ny'"
+        );
+    }
+
+    #[test]
+    fn test_guarded_block_is_preserved_by_format() {
+        let cases = [
+            (
+                ShellType::Bash,
+                "export  B=2",
+                "alias b='2'\nexport A=1\n\n{block}\n\nalias a='1'\nexport C=3\n",
+            ),
+            (
+                ShellType::PowerShell,
+                "$env:B  =  2",
+                "Set-Alias b two\n$env:A = \"1\"\n{block}\nSet-Alias a one\n",
+            ),
+        ];
+        for (shell, env, layout) in cases {
+            let block = format!(
+                "# >>> wenv:ignore >>>\n# managed by conda\n{}\nalias   z='zz'\n\n__setup() {{\n    echo  'x'\n}}\n# <<< wenv:ignore <<<",
+                env
+            );
+            let content = layout.replace("{block}", &block);
+            let parser = crate::parser::get_parser(shell);
+            let formatter = get_formatter(shell);
+            for group_by_type in [true, false] {
+                let mut config = Config::default();
+                config.format.group_by_type = group_by_type;
+                config.format.sort_alphabetically = true;
+
+                let once = formatter.format(&parser.parse(&content).entries, &config);
+                assert!(once.contains(&format!("{}\n", block)), "{}", once);
+                let twice = formatter.format(&parser.parse(&once).entries, &config);
+                assert_eq!(once, twice, "{} group_by_type={}", shell, group_by_type);
+
+                // Nothing moves across the block
+                let (before, after) = once.split_at(once.find(&block).unwrap());
+                assert!(
+                    !before.contains("lias a") && !after.contains("lias b"),
+                    "{}",
+                    once
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_conda_init_block_is_preserved_by_format() {
+        let conda = r#"# >>> conda initialize >>>
+# !! Contents within this block are managed by 'conda init' !!
+__conda_setup="$('/home/me/miniconda3/bin/conda' 'shell.bash' 'hook' 2> /dev/null)"
+if [ $? -eq 0 ]; then
+    eval "$__conda_setup"
+else
+    if [ -f "/home/me/miniconda3/etc/profile.d/conda.sh" ]; then
+        . "/home/me/miniconda3/etc/profile.d/conda.sh"
+    else
+        export PATH="/home/me/miniconda3/bin:$PATH"
+    fi
+fi
+unset __conda_setup
+# <<< conda initialize <<<"#;
+        let content = format!(
+            "alias zz='ls'\nexport EDITOR=vim\n\n{}\n\nalias aa='ls -a'\n",
+            conda
+        );
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse(&content)
+            .entries;
+        let mut config = Config::default();
+        config.format.sort_alphabetically = true;
+
+        let formatted = get_formatter(ShellType::Bash).format(&entries, &config);
+        assert_eq!(
+            formatted,
+            format!(
+                "export EDITOR=vim\n\nalias zz='ls'\n\n{}\n\nalias aa='ls -a'\n",
+                conda
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_to_writer_matches_format() {
+        let cases = [
+            (
+                ShellType::Bash,
+                "# tools\nalias b='2'\n\nexport A=1\nalias a='1'\necho hi\ngreet() {\n  echo hi\n}\n",
+            ),
+            (
+                ShellType::PowerShell,
+                "Set-Alias b two\n\n$env:A = \"1\"\n# note\nWrite-Host hi\nfunction greet { 'hi' }\n",
+            ),
+        ];
+        for (shell, content) in cases {
+            let entries = crate::parser::get_parser(shell).parse(content).entries;
+            let formatter = get_formatter(shell);
+            for group_by_type in [true, false] {
+                let mut config = Config::default();
+                config.format.group_by_type = group_by_type;
+
+                let mut written = Vec::new();
+                formatter
+                    .format_to_writer(&entries, &config, &mut written)
+                    .unwrap();
+                assert_eq!(
+                    String::from_utf8(written).unwrap(),
+                    formatter.format(&entries, &config),
+                    "{} group_by_type={}",
+                    shell,
+                    group_by_type
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_to_writer_reports_write_errors() {
+        struct Full;
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let entries = crate::parser::get_parser(ShellType::Bash)
+            .parse("alias a='1'\n")
+            .entries;
+        let error = get_formatter(ShellType::Bash)
+            .format_to_writer(&entries, &Config::default(), &mut Full)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+    }
+}
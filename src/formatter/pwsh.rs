@@ -1,11 +1,29 @@
 //! PowerShell configuration file formatter
 
-use crate::formatter::find_attached_comments;
+use crate::formatter::align::{align_definitions, Definition, Padding};
+use crate::formatter::{
+    find_attached_comments, group_type_order, strip_section_headers, trim_trailing_blank_lines,
+    write_around_guards, GROUPED_TYPES,
+};
 use crate::model::{Config, Entry, EntryType, ShellType};
 use crate::utils::dependency;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::{self, Write};
 
 use super::Formatter;
 
+lazy_static! {
+    /// `Set-Alias [-Name] name value`, split before and after the name
+    static ref ALIGNABLE_ALIAS_RE: Regex =
+        Regex::new(r"^(\s*(?i:Set-Alias|New-Alias)\s+(?i:-Name\s+)?)([^\s-]\S*)\s+(\S.*)$")
+            .unwrap();
+
+    /// `$env:NAME = value` on one line, split before and after the name
+    static ref ALIGNABLE_ENV_RE: Regex =
+        Regex::new(r"^(\s*\$env:)(\w+)\s*=\s*(\S.*)$").unwrap();
+}
+
 /// PowerShell configuration file formatter
 pub struct PowerShellFormatter;
 
@@ -14,6 +32,24 @@ impl PowerShellFormatter {
         Self
     }
 
+    /// Render the entries of one group (all of one type), aligning alias
+    /// and env var names when `format.align_equals` is set
+    fn render_group(&self, entries: &[&Entry], config: &Config) -> Vec<String> {
+        let mut rendered: Vec<String> = entries.iter().map(|e| self.format_entry(e)).collect();
+        if config.format.align_equals {
+            match entries.first().map(|e| e.entry_type) {
+                Some(EntryType::Alias) => {
+                    align_definitions(&mut rendered, Padding::AfterName, split_alias)
+                }
+                Some(EntryType::EnvVar) => {
+                    align_definitions(&mut rendered, Padding::AfterName, split_env)
+                }
+                _ => {}
+            }
+        }
+        rendered
+    }
+
     #[allow(dead_code)]
     fn format_alias(&self, entry: &Entry) -> String {
         format!("Set-Alias {} '{}'", entry.name, entry.value)
@@ -40,193 +76,202 @@ impl PowerShellFormatter {
         // With the new architecture, value already contains complete syntax
         entry.value.clone()
     }
-}
-
-impl Default for PowerShellFormatter {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl Formatter for PowerShellFormatter {
-    fn format(&self, entries: &[Entry], config: &Config) -> String {
-        let mut output = String::new();
+    /// Group entries by type (for format command with grouping enabled).
+    /// Strategy: Output types in configured order, keep Comment/Code in
+    /// original positions
+    fn write_grouped(
+        &self,
+        entries: &[Entry],
+        config: &Config,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        // Find comments attached to entries
+        let attached_comments = find_attached_comments(entries);
+
+        // Group parseable entries by type
+        let mut grouped: std::collections::HashMap<EntryType, Vec<&Entry>> =
+            std::collections::HashMap::new();
+
+        for entry in entries {
+            if GROUPED_TYPES.contains(&entry.entry_type) {
+                grouped.entry(entry.entry_type).or_default().push(entry);
+            }
+        }
 
-        if !config.format.group_by_type {
-            let mut sorted_entries: Vec<_> = entries.iter().collect();
-            sorted_entries.sort_by_key(|e| e.line_number.unwrap_or(0));
+        // Sort grouped entries
+        for (entry_type, type_entries) in grouped.iter_mut() {
+            if config.format.sort_alphabetically {
+                if *entry_type == EntryType::EnvVar {
+                    // Use topological sort for environment variables to respect dependencies
+                    let sorted = dependency::topological_sort(type_entries, true);
+                    *type_entries = sorted;
+                } else {
+                    // Simple alphabetical sort for other types
+                    type_entries.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+            } else if *entry_type == EntryType::EnvVar {
+                // Even without alphabetical sorting, preserve dependency order
+                let sorted = dependency::topological_sort(type_entries, false);
+                *type_entries = sorted;
+            }
+        }
 
-            for entry in sorted_entries {
+        // Build type order from config
+        let type_order = group_type_order(&config.format.order.types);
+
+        // Collect Comment/Code (and other ungrouped) entries for output in
+        // original order
+        let mut code_comments: Vec<&Entry> = entries
+            .iter()
+            .filter(|e| !GROUPED_TYPES.contains(&e.entry_type))
+            .filter(|e| {
+                // Skip comments attached to other entries
+                if e.entry_type == EntryType::Comment {
+                    let entry_line = e.line_number.unwrap_or(0);
+                    !attached_comments
+                        .values()
+                        .any(|comments| comments.iter().any(|c| c.line_number == Some(entry_line)))
+                } else {
+                    true
+                }
+            })
+            .collect();
+        code_comments.sort_by_key(|e| e.line_number.unwrap_or(0));
+
+        // Output Code/Comment entries that appear before any structured entries
+        let first_structured_line = entries
+            .iter()
+            .filter(|e| GROUPED_TYPES.contains(&e.entry_type))
+            .filter_map(|e| e.line_number)
+            .min()
+            .unwrap_or(usize::MAX);
+
+        for entry in &code_comments {
+            let line = entry.line_number.unwrap_or(0);
+            if line < first_structured_line {
                 if entry.entry_type == EntryType::Code && entry.value.is_empty() {
                     if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
                         for _ in 0..(end - start + 1) {
-                            output.push('\n');
+                            writeln!(output)?;
                         }
                     } else {
-                        output.push('\n');
+                        writeln!(output)?;
                     }
                 } else {
-                    output.push_str(&self.format_entry(entry));
-                    output.push('\n');
-                }
-            }
-        } else {
-            // Group entries by type (for format command with grouping enabled)
-            // Strategy: Output types in configured order, keep Comment/Code in original positions
-
-            // Find comments attached to entries
-            let attached_comments = find_attached_comments(entries);
-
-            // Group parseable entries by type
-            let mut grouped: std::collections::HashMap<EntryType, Vec<&Entry>> =
-                std::collections::HashMap::new();
-
-            for entry in entries {
-                match entry.entry_type {
-                    EntryType::Alias
-                    | EntryType::EnvVar
-                    | EntryType::Source
-                    | EntryType::Function => {
-                        grouped.entry(entry.entry_type).or_default().push(entry);
-                    }
-                    _ => {}
+                    writeln!(output, "{}", self.format_entry(entry))?;
                 }
             }
+        }
 
-            // Sort grouped entries
-            for (entry_type, type_entries) in grouped.iter_mut() {
-                if config.format.sort_alphabetically {
-                    if *entry_type == EntryType::EnvVar {
-                        // Use topological sort for environment variables to respect dependencies
-                        let sorted = dependency::topological_sort(type_entries, true);
-                        *type_entries = sorted;
-                    } else {
-                        // Simple alphabetical sort for other types
-                        type_entries.sort_by(|a, b| a.name.cmp(&b.name));
+        // Output structured entries by configured type order
+        let blank_lines = config.format.blank_lines_between_groups;
+        let mut first_group = true;
+
+        for entry_type in &type_order {
+            if let Some(type_entries) = grouped.get(entry_type) {
+                if !type_entries.is_empty() {
+                    // Add blank lines between groups
+                    if !first_group {
+                        for _ in 0..blank_lines {
+                            writeln!(output)?;
+                        }
                     }
-                } else if *entry_type == EntryType::EnvVar {
-                    // Even without alphabetical sorting, preserve dependency order
-                    let sorted = dependency::topological_sort(type_entries, false);
-                    *type_entries = sorted;
-                }
-            }
+                    first_group = false;
 
-            // Build type order from config
-            let type_order: Vec<EntryType> = config
-                .format
-                .order
-                .types
-                .iter()
-                .filter_map(|s| s.parse::<EntryType>().ok())
-                .collect();
-
-            // Collect Comment/Code entries for output in original order
-            let mut code_comments: Vec<&Entry> = entries
-                .iter()
-                .filter(|e| e.entry_type == EntryType::Code || e.entry_type == EntryType::Comment)
-                .filter(|e| {
-                    // Skip comments attached to other entries
-                    if e.entry_type == EntryType::Comment {
-                        let entry_line = e.line_number.unwrap_or(0);
-                        !attached_comments.values().any(|comments| {
-                            comments.iter().any(|c| c.line_number == Some(entry_line))
-                        })
-                    } else {
-                        true
+                    if config.format.section_headers {
+                        writeln!(output, "{}", self.section_header(*entry_type))?;
                     }
-                })
-                .collect();
-            code_comments.sort_by_key(|e| e.line_number.unwrap_or(0));
-
-            // Output Code/Comment entries that appear before any structured entries
-            let first_structured_line = entries
-                .iter()
-                .filter(|e| {
-                    matches!(
-                        e.entry_type,
-                        EntryType::Alias
-                            | EntryType::EnvVar
-                            | EntryType::Source
-                            | EntryType::Function
-                    )
-                })
-                .filter_map(|e| e.line_number)
-                .min()
-                .unwrap_or(usize::MAX);
-
-            for entry in &code_comments {
-                let line = entry.line_number.unwrap_or(0);
-                if line < first_structured_line {
-                    if entry.entry_type == EntryType::Code && entry.value.is_empty() {
-                        if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
-                            for _ in 0..(end - start + 1) {
-                                output.push('\n');
+                    let rendered = self.render_group(type_entries, config);
+                    for (grouped_entry, rendered) in type_entries.iter().zip(&rendered) {
+                        // Output attached comments before the entry
+                        if let Some(comments) =
+                            attached_comments.get(&grouped_entry.line_number.unwrap_or(0))
+                        {
+                            for comment in comments {
+                                writeln!(output, "{}", self.format_entry(comment))?;
                             }
-                        } else {
-                            output.push('\n');
                         }
-                    } else {
-                        output.push_str(&self.format_entry(entry));
-                        output.push('\n');
+
+                        writeln!(output, "{}", trim_trailing_blank_lines(rendered))?;
                     }
                 }
             }
+        }
 
-            // Output structured entries by configured type order
-            let blank_lines = config.format.blank_lines_between_groups;
-            let mut first_group = true;
-
-            for entry_type in &type_order {
-                if let Some(type_entries) = grouped.get(entry_type) {
-                    if !type_entries.is_empty() {
-                        // Add blank lines between groups
-                        if !first_group {
-                            for _ in 0..blank_lines {
-                                output.push('\n');
-                            }
-                        }
-                        first_group = false;
-
-                        for grouped_entry in type_entries {
-                            // Output attached comments before the entry
-                            if let Some(comments) =
-                                attached_comments.get(&grouped_entry.line_number.unwrap_or(0))
-                            {
-                                for comment in comments {
-                                    output.push_str(&self.format_entry(comment));
-                                    output.push('\n');
-                                }
-                            }
-
-                            output.push_str(&self.format_entry(grouped_entry));
-                            output.push('\n');
-                        }
-                    }
-                }
+        // Output remaining Code/Comment entries (those not before first structured entry)
+        // These are output after all structured entries have been grouped.
+        // Standalone blank lines are dropped: grouping owns the spacing now, and
+        // keeping them would grow the file on every format pass.
+        let remaining: Vec<_> = code_comments
+            .iter()
+            .filter(|e| e.line_number.unwrap_or(0) >= first_structured_line)
+            .filter(|e| !e.is_blank())
+            .collect();
+        if !remaining.is_empty() && !first_group {
+            for _ in 0..blank_lines {
+                writeln!(output)?;
             }
+        }
+        for entry in remaining {
+            writeln!(output, "{}", self.format_entry(entry))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PowerShellFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for PowerShellFormatter {
+    fn format(&self, entries: &[Entry], config: &Config) -> String {
+        let mut output = Vec::new();
+        self.format_to_writer(entries, config, &mut output)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("entries are valid UTF-8")
+    }
 
-            // Output remaining Code/Comment entries (those not before first structured entry)
-            // These are output after all structured entries have been grouped
-            for entry in &code_comments {
-                let line = entry.line_number.unwrap_or(0);
-                if line >= first_structured_line {
+    fn format_to_writer(
+        &self,
+        entries: &[Entry],
+        config: &Config,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        if !config.format.group_by_type {
+            let mut sorted_entries: Vec<_> = entries.iter().collect();
+            sorted_entries.sort_by_key(|e| e.line_number.unwrap_or(0));
+
+            // Runs of consecutive entries of one type are the groups
+            for run in sorted_entries.chunk_by(|a, b| a.entry_type == b.entry_type) {
+                let rendered = self.render_group(run, config);
+                for (entry, rendered) in run.iter().zip(rendered) {
                     if entry.entry_type == EntryType::Code && entry.value.is_empty() {
                         if let (Some(start), Some(end)) = (entry.line_number, entry.end_line) {
                             for _ in 0..(end - start + 1) {
-                                output.push('\n');
+                                writeln!(output)?;
                             }
                         } else {
-                            output.push('\n');
+                            writeln!(output)?;
                         }
                     } else {
-                        output.push_str(&self.format_entry(entry));
-                        output.push('\n');
+                        writeln!(output, "{}", rendered)?;
                     }
                 }
             }
+        } else {
+            // Headers of the last pass are dropped and rewritten by grouping
+            let entries = strip_section_headers(entries);
+            let blank_lines = config.format.blank_lines_between_groups;
+            write_around_guards(&entries, blank_lines, output, |entries, output| {
+                self.write_grouped(entries, config, output)
+            })?;
         }
 
-        output
+        Ok(())
     }
 
     fn format_entry(&self, entry: &Entry) -> String {
@@ -234,11 +279,54 @@ impl Formatter for PowerShellFormatter {
         entry.value.clone()
     }
 
+    fn definition(&self, entry_type: EntryType, name: &str, value: &str) -> Option<String> {
+        match entry_type {
+            // Set-Alias can only point at a single command; anything with
+            // arguments becomes a function that forwards extra arguments
+            EntryType::Alias if !value.is_empty() && !value.contains(char::is_whitespace) => {
+                Some(format!("Set-Alias -Name {} -Value {}", name, value))
+            }
+            EntryType::Alias => Some(format!("function {} {{ {} @args }}", name, value)),
+            EntryType::EnvVar if value.contains('\n') => {
+                Some(format!("$env:{} = @\"\n{}\n\"@", name, value))
+            }
+            EntryType::EnvVar => Some(format!("$env:{} = {}", name, double_quote(value))),
+            EntryType::Source => Some(format!(". {}", double_quote(value))),
+            _ => None,
+        }
+    }
+
     fn shell_type(&self) -> ShellType {
         ShellType::PowerShell
     }
 }
 
+/// Split a `Set-Alias` line for [`align_definitions`]
+fn split_alias(line: &str) -> Option<Definition> {
+    let caps = ALIGNABLE_ALIAS_RE.captures(line)?;
+    Some(Definition {
+        head: caps[1].to_string(),
+        name: caps[2].to_string(),
+        tail: caps[3].to_string(),
+    })
+}
+
+/// Split a `$env:NAME = value` line for [`align_definitions`]; the tail is
+/// normalized to `= value`
+fn split_env(line: &str) -> Option<Definition> {
+    let caps = ALIGNABLE_ENV_RE.captures(line)?;
+    Some(Definition {
+        head: caps[1].to_string(),
+        name: caps[2].to_string(),
+        tail: format!("= {}", &caps[3]),
+    })
+}
+
+/// `"value"`, escaping backticks and double quotes with a backtick
+fn double_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('`', "``").replace('"', "`\""))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,4 +389,101 @@ E:\bin
         );
         assert_eq!(formatter.format_entry(&entry), ". .\\aliases.ps1");
     }
+
+    #[test]
+    fn test_grouped_format_drops_standalone_blank_lines() {
+        // Regression: blank Code entries between structured entries were all
+        // emitted at the end of the file, adding blank lines on every pass
+        let content = "Set-Alias ll Get-ChildItem\n\n$env:EDITOR = \"code\"\n\n. .\\aliases.ps1\n";
+        let parser = crate::parser::get_parser(ShellType::PowerShell);
+        let formatter = PowerShellFormatter::new();
+        let config = Config::default();
+
+        let once = formatter.format(&parser.parse(content).entries, &config);
+        let twice = formatter.format(&parser.parse(&once).entries, &config);
+
+        assert!(!once.ends_with("\n\n"));
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_format_preserves_inline_comments() {
+        let content = "Set-Alias ll Get-ChildItem # listing\n\
+                       Set-Alias -Name gs -Value git-status # status\n\
+                       $env:EDITOR = \"code\" # editor\n";
+        let entries = crate::parser::get_parser(ShellType::PowerShell)
+            .parse(content)
+            .entries;
+
+        let formatted = PowerShellFormatter::new().format(&entries, &Config::default());
+
+        assert!(formatted.contains("Set-Alias ll Get-ChildItem # listing\n"));
+        assert!(formatted.contains("Set-Alias -Name gs -Value git-status # status\n"));
+        assert!(formatted.contains("$env:EDITOR = \"code\" # editor\n"));
+    }
+
+    #[test]
+    fn test_format_keeps_tag_comment_with_entry() {
+        let content = "Set-Alias zz z\n# wenv:tags=work\nSet-Alias aa a\n";
+        let entries = crate::parser::get_parser(ShellType::PowerShell)
+            .parse(content)
+            .entries;
+
+        // Sorting moves the tagged entry; its tag comment must move with it
+        let formatted = PowerShellFormatter::new().format(&entries, &Config::default());
+        assert!(
+            formatted.contains("# wenv:tags=work\nSet-Alias aa a\nSet-Alias zz z"),
+            "{}",
+            formatted
+        );
+    }
+
+    #[test]
+    fn test_format_aligns_equals() {
+        let content = "Set-Alias ll Get-ChildItem\nSet-Alias -Name which -Value Get-Command\n\
+                       $env:EDITOR=\"code\"\n$env:GO = \"~/go\"\n\
+                       $env:PATH_EXTRA = @\"\nC:\\bin\n\"@\n";
+        let entries = crate::parser::get_parser(ShellType::PowerShell)
+            .parse(content)
+            .entries;
+        let mut config = Config::default();
+        config.format.align_equals = true;
+
+        // The multi-line PATH_EXTRA doesn't count toward the width
+        let formatted = PowerShellFormatter::new().format(&entries, &config);
+
+        assert!(
+            formatted
+                .contains("$env:EDITOR = \"code\"\n$env:GO     = \"~/go\"\n$env:PATH_EXTRA = @\""),
+            "{}",
+            formatted
+        );
+        assert!(
+            formatted.contains("Set-Alias ll    Get-ChildItem\nSet-Alias -Name which -Value"),
+            "{}",
+            formatted
+        );
+    }
+
+    #[test]
+    fn test_section_headers_are_idempotent() {
+        let content = "Set-Alias ll Get-ChildItem\n$env:EDITOR = \"code\"\n";
+        let mut config = Config::default();
+        config.format.section_headers = true;
+        let format = |content: &str| {
+            let entries = crate::parser::get_parser(ShellType::PowerShell)
+                .parse(content)
+                .entries;
+            PowerShellFormatter::new().format(&entries, &config)
+        };
+
+        let once = format(content);
+        assert!(
+            once.starts_with("# === Environment Variables === wenv:section\n$env:EDITOR"),
+            "{}",
+            once
+        );
+        assert!(once.contains("\n# === Aliases === wenv:section\nSet-Alias ll"));
+        assert_eq!(format(&once), once);
+    }
 }
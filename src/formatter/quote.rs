@@ -0,0 +1,140 @@
+//! Quote style normalization for Bash alias/export values
+//!
+//! Rewrites `alias name='value'` / `export NAME="value"` to the configured
+//! [`QuoteStyle`]. Conservative by design: a line is only rewritten when the
+//! quoted value contains nothing whose meaning depends on the quote type
+//! (`$`, backticks, backslashes, `!` or any quote character). Anything else,
+//! including unquoted and multi-line values, is left untouched.
+
+use crate::model::QuoteStyle;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// Matches a single-line quoted alias/export assignment.
+    ///
+    /// Captures:
+    /// - Group 1: everything up to and including `=`
+    /// - Group 2: opening quote
+    /// - Group 3: value (only characters that are safe in either quote style)
+    /// - Group 4: closing quote
+    /// - Group 5: optional trailing inline comment
+    static ref QUOTED_ASSIGN_RE: Regex = Regex::new(
+        r#"^(\s*(?:alias(?:\s+-\w+)*|export)\s+[\w.:-]+=)(['"])([^'"\\$`!]*)(['"])(\s+#.*)?$"#
+    ).unwrap();
+}
+
+/// Apply a quote style to every alias/export line of a raw value.
+///
+/// Lines that cannot be rewritten safely are returned unchanged.
+pub fn apply_quote_style(value: &str, style: QuoteStyle) -> String {
+    let quote = match style {
+        QuoteStyle::Preserve => return value.to_string(),
+        QuoteStyle::Single => '\'',
+        QuoteStyle::Double => '"',
+    };
+
+    value
+        .split('\n')
+        .map(|line| requote_line(line, quote).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rewrite one line to use `quote`, or `None` if it is not safe to do so.
+fn requote_line(line: &str, quote: char) -> Option<String> {
+    let caps = QUOTED_ASSIGN_RE.captures(line)?;
+    if caps[2] != caps[4] {
+        return None;
+    }
+    Some(format!(
+        "{}{}{}{}{}",
+        &caps[1],
+        quote,
+        &caps[3],
+        quote,
+        caps.get(5).map_or("", |m| m.as_str())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_is_noop() {
+        let value = "alias ll=\"ls -la\"";
+        assert_eq!(apply_quote_style(value, QuoteStyle::Preserve), value);
+    }
+
+    #[test]
+    fn test_double_to_single() {
+        assert_eq!(
+            apply_quote_style("alias ll=\"ls -la\"", QuoteStyle::Single),
+            "alias ll='ls -la'"
+        );
+        assert_eq!(
+            apply_quote_style("export EDITOR=\"nvim\"", QuoteStyle::Single),
+            "export EDITOR='nvim'"
+        );
+    }
+
+    #[test]
+    fn test_single_to_double_keeps_options_and_comment() {
+        assert_eq!(
+            apply_quote_style("alias -g G='| grep' # global", QuoteStyle::Double),
+            "alias -g G=\"| grep\" # global"
+        );
+    }
+
+    #[test]
+    fn test_leading_comment_lines_untouched() {
+        let value = "# alias old='x'\nalias ll=\"ls -la\"";
+        assert_eq!(
+            apply_quote_style(value, QuoteStyle::Single),
+            "# alias old='x'\nalias ll='ls -la'"
+        );
+    }
+
+    #[test]
+    fn test_expansion_not_forced_to_single() {
+        let cases = [
+            "export PATH=\"$HOME/bin:$PATH\"",
+            "alias now=\"date `whoami`\"",
+            "alias say=\"echo hi\\!\"",
+            "alias bang=\"echo !!\"",
+        ];
+        for value in cases {
+            assert_eq!(apply_quote_style(value, QuoteStyle::Single), value);
+        }
+    }
+
+    #[test]
+    fn test_literal_dollar_not_forced_to_double() {
+        let value = "alias price='echo $5'";
+        assert_eq!(apply_quote_style(value, QuoteStyle::Double), value);
+    }
+
+    #[test]
+    fn test_conflicting_quotes_preserved() {
+        let cases = [
+            "alias greet=\"echo 'hi'\"",
+            "alias greet='echo \"hi\"'",
+            "alias dq='it'\\''s'",
+        ];
+        for value in cases {
+            assert_eq!(apply_quote_style(value, QuoteStyle::Single), value);
+            assert_eq!(apply_quote_style(value, QuoteStyle::Double), value);
+        }
+    }
+
+    #[test]
+    fn test_unquoted_and_multiline_preserved() {
+        assert_eq!(
+            apply_quote_style("export EDITOR=nvim", QuoteStyle::Double),
+            "export EDITOR=nvim"
+        );
+        let multiline = "alias x='echo one\necho two'";
+        assert_eq!(apply_quote_style(multiline, QuoteStyle::Double), multiline);
+    }
+}
@@ -0,0 +1,193 @@
+//! Formatting a line range ("format selection")
+//!
+//! Only entries lying entirely within the range are formatted. An entry that
+//! sticks out of the range is skipped, not expanded to, so every line
+//! outside the range stays byte for byte; callers should report the skipped
+//! entries. Blank lines at the edges of the formatted span are kept as they
+//! were.
+
+use std::ops::RangeInclusive;
+
+use super::{trim_trailing_blank_lines, Formatter};
+use crate::model::{Config, Entry};
+
+/// Result of [`format_range`]
+#[derive(Debug)]
+pub struct RangeFormat {
+    /// The whole file with the range formatted
+    pub content: String,
+    /// Lines (1-based) that were replaced, `None` when no entry lies
+    /// entirely within the range
+    pub formatted: Option<RangeInclusive<usize>>,
+    /// Entries only partly within the range, left untouched
+    pub skipped: Vec<Entry>,
+}
+
+/// Lines (1-based, inclusive) covered by `entry`, leaving out blank lines
+/// it absorbed after itself
+fn span(entry: &Entry) -> Option<(usize, usize)> {
+    let start = entry.line_number?;
+    let lines = trim_trailing_blank_lines(&entry.value)
+        .lines()
+        .count()
+        .max(1);
+    let end = entry.end_line.unwrap_or(start).max(start);
+    Some((start, end.min(start + lines - 1)))
+}
+
+/// Format the entries of `content` lying entirely within `lines` (1-based,
+/// inclusive) and splice the result back into `content`.
+///
+/// `entries` are the entries parsed from `content`, in file order.
+pub fn format_range(
+    content: &str,
+    entries: &[Entry],
+    lines: RangeInclusive<usize>,
+    formatter: &dyn Formatter,
+    config: &Config,
+) -> RangeFormat {
+    let mut inside = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in entries {
+        let Some((start, end)) = span(entry) else {
+            continue;
+        };
+        if lines.contains(&start) && lines.contains(&end) {
+            inside.push(entry.clone());
+        } else if start <= *lines.end() && end >= *lines.start() {
+            skipped.push(entry.clone());
+        }
+    }
+
+    let covered = inside.first().and_then(span).zip(inside.last());
+    let Some(((start, _), last)) = covered else {
+        return RangeFormat {
+            content: content.to_string(),
+            formatted: None,
+            skipped,
+        };
+    };
+
+    // Blank lines the last entry absorbed are replaced too, as far as the
+    // range reaches; they are kept below as trailing blank lines
+    let file_lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let end = last
+        .end_line
+        .or(last.line_number)
+        .unwrap_or(start)
+        .min(*lines.end())
+        .min(file_lines.len());
+    let replaced = &file_lines[start - 1..end];
+
+    let is_blank = |line: &&&str| line.trim().is_empty();
+    let leading = replaced.iter().take_while(is_blank).count();
+    let trailing = replaced[leading..]
+        .iter()
+        .rev()
+        .take_while(is_blank)
+        .count();
+
+    let formatted = formatter.format(&inside, config);
+    let body: Vec<&str> = formatted.lines().collect();
+    let first = body.iter().position(|line| !line.trim().is_empty());
+    let last = body.iter().rposition(|line| !line.trim().is_empty());
+
+    let mut output: String = file_lines[..start - 1].concat();
+    output.extend(replaced[..leading].iter().copied());
+    if let (Some(first), Some(last)) = (first, last) {
+        output.push_str(&body[first..=last].join("\n"));
+        let kept_newline = replaced.last().is_some_and(|line| line.ends_with('\n'));
+        if trailing > 0 || kept_newline {
+            output.push('\n');
+        }
+    }
+    output.extend(replaced[replaced.len() - trailing..].iter().copied());
+    output.extend(file_lines[end..].iter().copied());
+
+    RangeFormat {
+        content: output,
+        formatted: Some(start..=end),
+        skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::get_formatter;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn format_lines(content: &str, lines: RangeInclusive<usize>) -> RangeFormat {
+        let entries = get_parser(ShellType::Bash).parse(content).entries;
+        let formatter = get_formatter(ShellType::Bash);
+        format_range(
+            content,
+            &entries,
+            lines,
+            formatter.as_ref(),
+            &Config::default(),
+        )
+    }
+
+    const CONTENT: &str = "\
+alias zz='echo z'
+alias yy='echo y'
+
+alias b=two
+alias a=one
+
+greet() {
+echo hi
+}
+";
+
+    #[test]
+    fn test_only_the_range_is_formatted() {
+        let result = format_lines(CONTENT, 4..=5);
+
+        assert_eq!(result.formatted, Some(4..=5));
+        assert!(result.skipped.is_empty());
+        assert_eq!(
+            result.content,
+            "\
+alias zz='echo z'
+alias yy='echo y'
+
+alias a=one
+alias b=two
+
+greet() {
+echo hi
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_partly_covered_entries_are_skipped() {
+        let result = format_lines(CONTENT, 4..=8);
+
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].name, "greet");
+        // The function keeps its original (unindented) body
+        assert!(result.content.ends_with("\ngreet() {\necho hi\n}\n"));
+        assert!(result.content.contains("alias a=one\nalias b=two\n"));
+    }
+
+    #[test]
+    fn test_range_without_whole_entries_changes_nothing() {
+        let result = format_lines(CONTENT, 8..=8);
+
+        assert_eq!(result.formatted, None);
+        assert_eq!(result.content, CONTENT);
+    }
+
+    #[test]
+    fn test_whole_file_range_keeps_missing_final_newline() {
+        let content = "alias b=two\nalias a=one";
+        let result = format_lines(content, 1..=usize::MAX);
+
+        assert_eq!(result.content, "alias a=one\nalias b=two");
+    }
+}
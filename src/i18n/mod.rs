@@ -3,6 +3,7 @@
 //! Provides English UI messages.
 
 use serde::Deserialize;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 
 /// All translatable messages in the application
@@ -23,10 +24,16 @@ pub struct Messages {
     pub header_name: &'static str,
     pub header_value: &'static str,
     pub header_line_num: &'static str,
+    pub header_file: &'static str,
     pub header_line: &'static str,
     pub header_lines: &'static str,
+    pub header_file_label: &'static str,
     pub header_comment: &'static str,
     pub header_raw: &'static str,
+    pub header_expanded: &'static str,
+    pub label_exported: &'static str,
+    pub label_prompt: &'static str,
+    pub label_disabled: &'static str,
 
     // === Check Command ===
     pub no_issues_found: &'static str,
@@ -39,10 +46,15 @@ pub struct Messages {
     // === Add/Remove/Edit ===
     pub already_exists_skip: &'static str,
     pub already_exists_value: &'static str,
+    pub already_exists_equivalent: &'static str,
     pub overwrite_prompt: &'static str,
     pub remove_prompt: &'static str,
     pub invalid_alias_format: &'static str,
     pub invalid_env_format: &'static str,
+    pub invalid_alias_name: &'static str,
+    pub alias_value_empty_warning: &'static str,
+    pub alias_value_unquoted: &'static str,
+    pub add_quotes_prompt: &'static str,
 
     // === Backup ===
     pub backup_created: &'static str,
@@ -114,6 +126,8 @@ pub struct Messages {
     pub tui_status_selecting_type: &'static str,
     pub tui_status_editing: &'static str,
     pub tui_status_moving: &'static str,
+    pub tui_status_view_only: &'static str,
+    pub tui_status_read_only: &'static str,
 
     // TUI search popup
     pub tui_search_title: &'static str,
@@ -121,6 +135,10 @@ pub struct Messages {
     pub tui_search_matches: &'static str,
     pub tui_search_no_matches: &'static str,
     pub tui_search_hint: &'static str,
+    pub tui_search_ignore_case: &'static str,
+    pub tui_search_match_case: &'static str,
+    pub tui_search_regex: &'static str,
+    pub tui_search_invalid: &'static str,
 
     // TUI detail/edit labels (shared)
     pub label_type: &'static str,
@@ -142,12 +160,15 @@ pub struct Messages {
     pub tui_type_func_desc: &'static str,
     pub tui_type_env_desc: &'static str,
     pub tui_type_source_desc: &'static str,
+    pub tui_type_setopt_desc: &'static str,
+    pub tui_type_zstyle_desc: &'static str,
     pub tui_type_code_desc: &'static str,
     pub tui_type_comment_desc: &'static str,
     pub tui_edit_submit: &'static str,
 
     // TUI dynamic messages
     pub tui_msg_selection_cleared: &'static str,
+    pub tui_msg_read_only: &'static str,
     pub tui_msg_use_arrows_to_move: &'static str,
     pub tui_msg_moving_entries: &'static str,
     pub tui_msg_move_cancelled: &'static str,
@@ -169,6 +190,8 @@ pub struct Messages {
     pub tui_msg_entry_updated: &'static str,
     pub tui_msg_file_saved: &'static str,
     pub tui_msg_file_saved_bypassed: &'static str,
+    pub tui_msg_saved_reload: &'static str,
+    pub tui_msg_saved_reload_copied: &'static str,
     pub tui_msg_undo_successful: &'static str,
     pub tui_msg_nothing_to_undo: &'static str,
     pub tui_msg_redo_successful: &'static str,
@@ -182,7 +205,11 @@ pub struct Messages {
     pub tui_msg_entries_copied: &'static str,
     pub tui_msg_entry_copied: &'static str,
     pub tui_msg_entry_pasted: &'static str,
+    pub tui_msg_entry_duplicated: &'static str,
     pub tui_msg_clipboard_empty: &'static str,
+    pub tui_msg_apply_command_copied: &'static str,
+    pub tui_msg_clipboard_unavailable: &'static str,
+    pub tui_msg_nothing_to_apply: &'static str,
 
     // TUI help detailed shortcuts
     pub tui_help_nav_updown: &'static str,
@@ -198,10 +225,12 @@ pub struct Messages {
     pub tui_help_select_range: &'static str,
     pub tui_help_format_file: &'static str,
     pub tui_help_save: &'static str,
+    pub tui_help_save_reload: &'static str,
     pub tui_help_undo: &'static str,
     pub tui_help_redo: &'static str,
     pub tui_help_copy: &'static str,
     pub tui_help_paste: &'static str,
+    pub tui_help_duplicate: &'static str,
     pub tui_help_help_key: &'static str,
 
     // TUI edit hints
@@ -259,10 +288,16 @@ struct MessagesToml {
     header_name: String,
     header_value: String,
     header_line_num: String,
+    header_file: String,
     header_line: String,
     header_lines: String,
+    header_file_label: String,
     header_comment: String,
     header_raw: String,
+    header_expanded: String,
+    label_exported: String,
+    label_prompt: String,
+    label_disabled: String,
 
     // === Check Command ===
     no_issues_found: String,
@@ -275,10 +310,15 @@ struct MessagesToml {
     // === Add/Remove/Edit ===
     already_exists_skip: String,
     already_exists_value: String,
+    already_exists_equivalent: String,
     overwrite_prompt: String,
     remove_prompt: String,
     invalid_alias_format: String,
     invalid_env_format: String,
+    invalid_alias_name: String,
+    alias_value_empty_warning: String,
+    alias_value_unquoted: String,
+    add_quotes_prompt: String,
 
     // === Backup ===
     backup_created: String,
@@ -345,6 +385,8 @@ struct MessagesToml {
     tui_status_selecting_type: String,
     tui_status_editing: String,
     tui_status_moving: String,
+    tui_status_view_only: String,
+    tui_status_read_only: String,
 
     // TUI search popup
     tui_search_title: String,
@@ -352,6 +394,10 @@ struct MessagesToml {
     tui_search_matches: String,
     tui_search_no_matches: String,
     tui_search_hint: String,
+    tui_search_ignore_case: String,
+    tui_search_match_case: String,
+    tui_search_regex: String,
+    tui_search_invalid: String,
 
     // TUI detail/edit labels (shared)
     label_type: String,
@@ -378,12 +424,15 @@ struct MessagesToml {
     tui_type_func_desc: String,
     tui_type_env_desc: String,
     tui_type_source_desc: String,
+    tui_type_setopt_desc: String,
+    tui_type_zstyle_desc: String,
     tui_type_code_desc: String,
     tui_type_comment_desc: String,
     tui_edit_submit: String,
 
     // TUI dynamic messages
     tui_msg_selection_cleared: String,
+    tui_msg_read_only: String,
     tui_msg_use_arrows_to_move: String,
     tui_msg_moving_entries: String,
     tui_msg_move_cancelled: String,
@@ -405,6 +454,8 @@ struct MessagesToml {
     tui_msg_entry_updated: String,
     tui_msg_file_saved: String,
     tui_msg_file_saved_bypassed: String,
+    tui_msg_saved_reload: String,
+    tui_msg_saved_reload_copied: String,
     tui_msg_undo_successful: String,
     tui_msg_nothing_to_undo: String,
     tui_msg_redo_successful: String,
@@ -418,7 +469,11 @@ struct MessagesToml {
     tui_msg_entries_copied: String,
     tui_msg_entry_copied: String,
     tui_msg_entry_pasted: String,
+    tui_msg_entry_duplicated: String,
     tui_msg_clipboard_empty: String,
+    tui_msg_apply_command_copied: String,
+    tui_msg_clipboard_unavailable: String,
+    tui_msg_nothing_to_apply: String,
 
     // TUI help detailed shortcuts
     tui_help_nav_updown: String,
@@ -434,10 +489,12 @@ struct MessagesToml {
     tui_help_select_range: String,
     tui_help_format_file: String,
     tui_help_save: String,
+    tui_help_save_reload: String,
     tui_help_undo: String,
     tui_help_redo: String,
     tui_help_copy: String,
     tui_help_paste: String,
+    tui_help_duplicate: String,
     tui_help_help_key: String,
 
     // TUI edit hints
@@ -502,10 +559,16 @@ impl From<MessagesToml> for Messages {
             header_name: leak!(toml.header_name),
             header_value: leak!(toml.header_value),
             header_line_num: leak!(toml.header_line_num),
+            header_file: leak!(toml.header_file),
             header_line: leak!(toml.header_line),
             header_lines: leak!(toml.header_lines),
+            header_file_label: leak!(toml.header_file_label),
             header_comment: leak!(toml.header_comment),
             header_raw: leak!(toml.header_raw),
+            header_expanded: leak!(toml.header_expanded),
+            label_exported: leak!(toml.label_exported),
+            label_prompt: leak!(toml.label_prompt),
+            label_disabled: leak!(toml.label_disabled),
 
             // === Check Command ===
             no_issues_found: leak!(toml.no_issues_found),
@@ -518,10 +581,15 @@ impl From<MessagesToml> for Messages {
             // === Add/Remove/Edit ===
             already_exists_skip: leak!(toml.already_exists_skip),
             already_exists_value: leak!(toml.already_exists_value),
+            already_exists_equivalent: leak!(toml.already_exists_equivalent),
             overwrite_prompt: leak!(toml.overwrite_prompt),
             remove_prompt: leak!(toml.remove_prompt),
             invalid_alias_format: leak!(toml.invalid_alias_format),
             invalid_env_format: leak!(toml.invalid_env_format),
+            invalid_alias_name: leak!(toml.invalid_alias_name),
+            alias_value_empty_warning: leak!(toml.alias_value_empty_warning),
+            alias_value_unquoted: leak!(toml.alias_value_unquoted),
+            add_quotes_prompt: leak!(toml.add_quotes_prompt),
 
             // === Backup ===
             backup_created: leak!(toml.backup_created),
@@ -588,6 +656,8 @@ impl From<MessagesToml> for Messages {
             tui_status_selecting_type: leak!(toml.tui_status_selecting_type),
             tui_status_editing: leak!(toml.tui_status_editing),
             tui_status_moving: leak!(toml.tui_status_moving),
+            tui_status_view_only: leak!(toml.tui_status_view_only),
+            tui_status_read_only: leak!(toml.tui_status_read_only),
 
             // TUI search popup
             tui_search_title: leak!(toml.tui_search_title),
@@ -595,6 +665,10 @@ impl From<MessagesToml> for Messages {
             tui_search_matches: leak!(toml.tui_search_matches),
             tui_search_no_matches: leak!(toml.tui_search_no_matches),
             tui_search_hint: leak!(toml.tui_search_hint),
+            tui_search_ignore_case: leak!(toml.tui_search_ignore_case),
+            tui_search_match_case: leak!(toml.tui_search_match_case),
+            tui_search_regex: leak!(toml.tui_search_regex),
+            tui_search_invalid: leak!(toml.tui_search_invalid),
 
             // TUI detail/edit labels (shared)
             label_type: leak!(toml.label_type),
@@ -621,12 +695,15 @@ impl From<MessagesToml> for Messages {
             tui_type_func_desc: leak!(toml.tui_type_func_desc),
             tui_type_env_desc: leak!(toml.tui_type_env_desc),
             tui_type_source_desc: leak!(toml.tui_type_source_desc),
+            tui_type_setopt_desc: leak!(toml.tui_type_setopt_desc),
+            tui_type_zstyle_desc: leak!(toml.tui_type_zstyle_desc),
             tui_type_code_desc: leak!(toml.tui_type_code_desc),
             tui_type_comment_desc: leak!(toml.tui_type_comment_desc),
             tui_edit_submit: leak!(toml.tui_edit_submit),
 
             // TUI dynamic messages
             tui_msg_selection_cleared: leak!(toml.tui_msg_selection_cleared),
+            tui_msg_read_only: leak!(toml.tui_msg_read_only),
             tui_msg_use_arrows_to_move: leak!(toml.tui_msg_use_arrows_to_move),
             tui_msg_moving_entries: leak!(toml.tui_msg_moving_entries),
             tui_msg_move_cancelled: leak!(toml.tui_msg_move_cancelled),
@@ -648,6 +725,8 @@ impl From<MessagesToml> for Messages {
             tui_msg_entry_updated: leak!(toml.tui_msg_entry_updated),
             tui_msg_file_saved: leak!(toml.tui_msg_file_saved),
             tui_msg_file_saved_bypassed: leak!(toml.tui_msg_file_saved_bypassed),
+            tui_msg_saved_reload: leak!(toml.tui_msg_saved_reload),
+            tui_msg_saved_reload_copied: leak!(toml.tui_msg_saved_reload_copied),
             tui_msg_undo_successful: leak!(toml.tui_msg_undo_successful),
             tui_msg_nothing_to_undo: leak!(toml.tui_msg_nothing_to_undo),
             tui_msg_redo_successful: leak!(toml.tui_msg_redo_successful),
@@ -661,7 +740,11 @@ impl From<MessagesToml> for Messages {
             tui_msg_entries_copied: leak!(toml.tui_msg_entries_copied),
             tui_msg_entry_copied: leak!(toml.tui_msg_entry_copied),
             tui_msg_entry_pasted: leak!(toml.tui_msg_entry_pasted),
+            tui_msg_entry_duplicated: leak!(toml.tui_msg_entry_duplicated),
             tui_msg_clipboard_empty: leak!(toml.tui_msg_clipboard_empty),
+            tui_msg_apply_command_copied: leak!(toml.tui_msg_apply_command_copied),
+            tui_msg_clipboard_unavailable: leak!(toml.tui_msg_clipboard_unavailable),
+            tui_msg_nothing_to_apply: leak!(toml.tui_msg_nothing_to_apply),
 
             // TUI help detailed shortcuts
             tui_help_nav_updown: leak!(toml.tui_help_nav_updown),
@@ -677,10 +760,12 @@ impl From<MessagesToml> for Messages {
             tui_help_select_range: leak!(toml.tui_help_select_range),
             tui_help_format_file: leak!(toml.tui_help_format_file),
             tui_help_save: leak!(toml.tui_help_save),
+            tui_help_save_reload: leak!(toml.tui_help_save_reload),
             tui_help_undo: leak!(toml.tui_help_undo),
             tui_help_redo: leak!(toml.tui_help_redo),
             tui_help_copy: leak!(toml.tui_help_copy),
             tui_help_paste: leak!(toml.tui_help_paste),
+            tui_help_duplicate: leak!(toml.tui_help_duplicate),
             tui_help_help_key: leak!(toml.tui_help_help_key),
 
             // TUI edit hints
@@ -728,13 +813,26 @@ const EMBEDDED_EN: &str = include_str!("../../assets/i18n/en.toml");
 /// Global messages instance
 static MESSAGES: OnceLock<Messages> = OnceLock::new();
 
+/// Path of the external language file for `lang`, or `None` for the
+/// embedded English messages
+pub fn language_file(lang: &str) -> Option<PathBuf> {
+    (lang != "en").then(|| {
+        crate::Config::config_dir()
+            .join("i18n")
+            .join(format!("{}.toml", lang))
+    })
+}
+
+/// Check that a language file's content parses into a complete message set
+pub fn validate_language_file(content: &str) -> anyhow::Result<()> {
+    toml::from_str::<MessagesToml>(content)?;
+    Ok(())
+}
+
 /// Load messages from external file or embedded English TOML
 fn load_messages_from_toml(lang: &str) -> Messages {
     // If not English, try external file first
-    if lang != "en" {
-        let config_dir = crate::Config::config_dir();
-        let lang_file = config_dir.join("i18n").join(format!("{}.toml", lang));
-
+    if let Some(lang_file) = language_file(lang) {
         match std::fs::read_to_string(&lang_file) {
             Ok(content) => match toml::from_str::<MessagesToml>(&content) {
                 Ok(toml_messages) => return toml_messages.into(),
@@ -779,4 +877,26 @@ mod tests {
         let messages = super::init_messages("en");
         assert_eq!(messages.no_entries_found, "No entries found.");
     }
+
+    #[test]
+    fn test_validate_language_file() {
+        assert!(super::validate_language_file(super::EMBEDDED_EN).is_ok());
+        assert!(super::validate_language_file("no_entries_found = \"x\"").is_err());
+        assert!(super::language_file("en").is_none());
+        assert!(super::language_file("zh-TW")
+            .unwrap()
+            .ends_with("i18n/zh-TW.toml"));
+    }
+
+    #[test]
+    fn test_bundled_language_files_are_complete() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("languages");
+        for file in std::fs::read_dir(dir).unwrap() {
+            let path = file.unwrap().path();
+            let content = std::fs::read_to_string(&path).unwrap();
+            if let Err(e) = super::validate_language_file(&content) {
+                panic!("{}: {}", path.display(), e);
+            }
+        }
+    }
 }
@@ -11,7 +11,35 @@
 //! - Export entries
 //! - Format configuration files
 //! - Automatic backups
+//!
+//! # Library use
+//!
+//! The CLI and TUI are built on a library surface that other tools can use
+//! directly:
+//!
+//! - [`analyze`] / [`analyze_as`]: parse a file and run the checkers in one
+//!   call, returning an [`Analysis`]
+//! - [`get_parser`] / [`get_unmerged_parser`] / [`get_configured_parser`]:
+//!   parse content as a given [`ShellType`] into [`Entry`] values
+//! - [`get_shell_type`] / [`resolve_shell_type`]: detect the shell of a file
+//! - [`check_all`]: run the duplicate, dead definition, undefined variable and PATH
+//!   checkers
+//! - [`path_merge`]: split and merge `PATH`-style definitions
+//! - [`get_formatter`]: regenerate entries as shell syntax
+//!
+//! ```no_run
+//! let analysis = wenv::analyze("/home/me/.bashrc")?;
+//! println!("{} ({})", analysis.shell_type, analysis.shell_source.describe());
+//! for entry in analysis.entries_of(wenv::EntryType::Alias) {
+//!     println!("{} = {}", entry.name, entry.canonical_value());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! The `cli` and `tui` modules hold the command-line front end and are not
+//! meant to be used as a library.
 
+pub mod analyze;
 pub mod backup;
 pub mod cache;
 pub mod checker;
@@ -24,7 +52,10 @@ pub mod parser;
 pub mod tui;
 pub mod utils;
 
+pub use analyze::{analyze, analyze_as, Analysis};
 pub use checker::check_all;
 pub use formatter::{get_formatter, Formatter};
 pub use model::{Config, Entry, EntryType, ParseResult, ShellType};
-pub use parser::{get_parser, Parser};
+pub use parser::{get_configured_parser, get_parser, get_unmerged_parser, Parser};
+pub use utils::path_merge;
+pub use utils::shell_detect::{get_shell_type, resolve_shell_type, ShellSource};
@@ -4,15 +4,18 @@ use anyhow::Result;
 use clap::Parser;
 use dialoguer::Confirm;
 
-use wenv::cli::{actions, Cli, Context};
+use wenv::cli::args::{BackupCommand, ConfigCommand};
+use wenv::cli::{actions, Cli, Commands, Context};
 use wenv::tui::TuiApp;
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    cli.color.apply();
 
     // Handle --clear-cache early (doesn't require config file)
     if cli.clear_cache {
         wenv::cache::PathCache::clear()?;
+        wenv::cache::ParseCache::new().clear()?;
         println!("Cache cleared successfully.");
         return Ok(());
     }
@@ -20,49 +23,190 @@ fn main() -> Result<()> {
     // Handle --config early (opens wenv config file in editor)
     if cli.config {
         let config_path = wenv::Config::config_path();
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| {
-            if cfg!(windows) {
-                "notepad".to_string()
-            } else {
-                "vi".to_string()
-            }
-        });
-        std::process::Command::new(&editor)
-            .arg(&config_path)
-            .status()?;
+        wenv::utils::editor::resolve_editor(cli.editor.as_deref())?.open(&config_path)?;
         return Ok(());
     }
 
     let ctx = Context::from_cli(&cli)?;
 
+    if ctx.config_files.len() > 1
+        && !cli
+            .subcommand
+            .as_ref()
+            .is_some_and(|c| c.accepts_several_files())
+    {
+        anyhow::bail!(
+            "Several --file paths are only supported by {}",
+            Commands::SEVERAL_FILES_COMMANDS.join(", ")
+        );
+    }
+
     // Check if config file exists, prompt to create if missing
-    if !ctx.config_file.exists() {
+    let needs_file = cli
+        .subcommand
+        .as_ref()
+        .is_none_or(|c| c.requires_config_file());
+    if needs_file && !ctx.config_file.exists() {
         if Confirm::new()
             .with_prompt(format!(
                 "Config file '{}' not found. Create it?",
-                ctx.config_file.display()
+                ctx.display_path(&ctx.config_file)
             ))
             .default(true)
             .interact()?
         {
             wenv::utils::path::write_file(&ctx.config_file, "")?;
-            ctx.print_success(&format!("Created: {}", ctx.config_file.display()));
+            ctx.print_success(&format!("Created: {}", ctx.display_path(&ctx.config_file)));
         } else {
             anyhow::bail!("Config file not found. Use --file to specify a different path.");
         }
     }
 
+    let result = run(&cli, &ctx);
+    ctx.print_profile();
+    result
+}
+
+/// Run the subcommand, quick action or TUI that `cli` asks for
+fn run(cli: &Cli, ctx: &Context) -> Result<()> {
+    // Subcommands: execute and exit
+    if let Some(command) = &cli.subcommand {
+        return match command {
+            Commands::List {
+                entry_type,
+                tag,
+                limit,
+                offset,
+                no_truncate,
+                sort,
+                include_disabled,
+                output_template,
+            } => actions::list::execute(
+                ctx,
+                *entry_type,
+                tag.as_deref(),
+                actions::list::Page {
+                    offset: *offset,
+                    limit: *limit,
+                },
+                *no_truncate,
+                *sort,
+                *include_disabled,
+                output_template.as_ref(),
+            ),
+            Commands::Count { entry_type } => actions::count::execute(ctx, *entry_type),
+            Commands::Grep {
+                pattern,
+                entry_type,
+                name_only,
+            } => actions::grep::execute(ctx, pattern, *entry_type, *name_only),
+            Commands::Tui { edit, view } => {
+                let target = edit
+                    .as_deref()
+                    .map(actions::info::parse_target)
+                    .transpose()?;
+                let mut app = launch_tui(ctx)?.with_view_only(*view);
+                if let Some((entry_type, name)) = target {
+                    app = app.with_editing(entry_type, name);
+                }
+                app.run()
+            }
+            Commands::Info {
+                target,
+                expand,
+                value_only,
+            } => actions::info::execute(ctx, target, *expand, *value_only),
+            Commands::Edit { target, line } => match line {
+                Some(line) => actions::source::execute_line(ctx, *line),
+                None => actions::source::execute_entry(ctx, target),
+            },
+            Commands::Add {
+                entry_type,
+                definition,
+                yes,
+            } => actions::add::execute(ctx, *entry_type, definition, *yes),
+            Commands::Remove { target, dry_run } => actions::remove::execute(ctx, target, *dry_run),
+            Commands::Apply {
+                snippet,
+                dry_run,
+                yes,
+            } => actions::apply::execute(ctx, snippet, *yes, *dry_run),
+            Commands::Move {
+                entry_type,
+                name,
+                before,
+                after,
+            } => {
+                let target = match (before, after) {
+                    (Some(target), _) => actions::move_entry::Target::Before(target),
+                    (_, Some(target)) => actions::move_entry::Target::After(target),
+                    _ => unreachable!("clap requires --before or --after"),
+                };
+                actions::move_entry::execute(ctx, *entry_type, name, target)
+            }
+            Commands::NormalizePaths {
+                keep_missing,
+                sort,
+                dry_run,
+            } => actions::normalize_paths::execute(ctx, *keep_missing, *sort, *dry_run),
+            Commands::Sources { tree } => actions::sources::execute(ctx, *tree),
+            Commands::Files => actions::files::execute(ctx),
+            Commands::Check {
+                style,
+                missing_dirs,
+            } => actions::check::execute(ctx, *style, *missing_dirs),
+            Commands::Lint => actions::lint::execute(ctx),
+            Commands::Format { range } => actions::format::execute(ctx, range.clone()),
+            Commands::Doctor => actions::doctor::execute(ctx),
+            Commands::History { clear } => actions::history::execute(ctx, *clear),
+            Commands::Backup { action } => match action {
+                BackupCommand::Prune {
+                    keep,
+                    older_than,
+                    dry_run,
+                } => actions::backup::prune(ctx, *keep, *older_than, *dry_run),
+                BackupCommand::Diff { since, chain } => actions::backup::diff(ctx, since, *chain),
+                BackupCommand::Auto { watch } => actions::backup::auto(ctx, *watch),
+                BackupCommand::Migrate { dry_run } => actions::backup::migrate(ctx, *dry_run),
+            },
+            Commands::Config { action } => match action {
+                ConfigCommand::Get { key } => actions::config::get(key),
+                ConfigCommand::Set { key, value } => actions::config::set(ctx, key, value),
+                ConfigCommand::Path => actions::config::path(),
+                ConfigCommand::Init { force } => actions::config::init(ctx, *force),
+            },
+        };
+    }
+
     // Quick actions: execute and exit
     if let Some(source) = &cli.import {
-        return actions::import::execute(&ctx, source, cli.yes);
+        let placement = match (&cli.import_after, cli.prepend) {
+            (Some(name), _) => actions::import::Placement::After(name.clone()),
+            (None, true) => actions::import::Placement::Prepend,
+            // `--append`, or no placement flag at all
+            (None, false) => actions::import::Placement::Append,
+        };
+        return actions::import::execute(ctx, source, placement, cli.yes, cli.dry_run);
     }
     if let Some(output) = &cli.export {
-        return actions::export::execute(&ctx, cli.r#type, output);
+        return actions::export::execute(ctx, cli.r#type, cli.format, output);
     }
     if cli.command.as_deref() == Some(".") || cli.source {
-        return actions::source::execute(&ctx);
+        return actions::source::execute(ctx);
     }
 
     // Default: launch TUI
-    TuiApp::new(ctx.config_file, ctx.shell_type, ctx.messages)?.run()
+    launch_tui(ctx)?.run()
+}
+
+/// The TUI for the context's config file
+fn launch_tui(ctx: &Context) -> Result<TuiApp> {
+    Ok(
+        TuiApp::new(ctx.config_file.clone(), ctx.shell_type, ctx.messages)?
+            .with_no_backup(!ctx.backup_enabled)
+            .with_editor(ctx.editor.clone())
+            .with_colors(ctx.config.colors.clone())
+            .with_merge_comments(ctx.merge_comments)
+            .with_attached_comments(ctx.config.parser.attach_comments),
+    )
 }
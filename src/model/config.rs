@@ -1,8 +1,11 @@
 //! Application configuration structures
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use super::Severity;
+
 /// Main configuration structure
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Config {
@@ -14,6 +17,14 @@ pub struct Config {
     pub backup: BackupConfig,
     #[serde(default)]
     pub cache: CacheConfig,
+    #[serde(default)]
+    pub checker: CheckerConfig,
+    #[serde(default)]
+    pub parser: ParserConfig,
+    #[serde(default)]
+    pub lint: LintConfig,
+    #[serde(default)]
+    pub colors: ColorsConfig,
 }
 
 /// UI configuration options
@@ -21,16 +32,24 @@ pub struct Config {
 pub struct UiConfig {
     #[serde(default = "default_language")]
     pub language: String,
+    /// Show paths under the home directory as `~/...`
+    #[serde(default = "default_collapse_home")]
+    pub collapse_home: bool,
 }
 
 fn default_language() -> String {
     "en".to_string()
 }
 
+fn default_collapse_home() -> bool {
+    true
+}
+
 impl Default for UiConfig {
     fn default() -> Self {
         UiConfig {
             language: default_language(),
+            collapse_home: default_collapse_home(),
         }
     }
 }
@@ -43,6 +62,34 @@ pub struct FormatConfig {
     pub sort_alphabetically: bool,
     pub blank_lines_between_groups: usize,
     pub order: TypeOrder,
+    /// Quote style for alias/env values (applied only when safe)
+    #[serde(default)]
+    pub quote_style: QuoteStyle,
+    /// Pad single-line alias/env definitions so their `=` (or value) lines
+    /// up within each group
+    #[serde(default)]
+    pub align_equals: bool,
+    /// Write a `# === Aliases === wenv:section` header above each group
+    /// (only with `group_by_type`)
+    #[serde(default)]
+    pub section_headers: bool,
+    /// Write blank lines at the end of the file as they are instead of
+    /// ending it in exactly one newline
+    #[serde(default)]
+    pub preserve_trailing_blanks: bool,
+}
+
+/// Preferred quoting for alias and environment variable values
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStyle {
+    /// Keep the original quotes
+    #[default]
+    Preserve,
+    /// Rewrite to single quotes
+    Single,
+    /// Rewrite to double quotes
+    Double,
 }
 
 /// Type ordering for formatted output
@@ -54,18 +101,114 @@ pub struct TypeOrder {
 /// Backup configuration
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BackupConfig {
+    /// Create a backup before every write. Disabling this means a bad edit,
+    /// import or format cannot be undone from `~/.config/wenv/backups`.
+    #[serde(default = "default_backup_enabled")]
+    pub enabled: bool,
     pub max_count: usize,
+    /// Directory to keep backups in instead of `~/.config/wenv/backups`.
+    /// `~` is expanded; backups still go in a per-shell subdirectory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+}
+
+fn default_backup_enabled() -> bool {
+    true
 }
 
 /// Cache configuration (auto-generated, but user-editable)
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CacheConfig {
+    /// Reuse parse results of unchanged files (keyed by path, mtime and size)
+    #[serde(default)]
+    pub enabled: bool,
     /// PowerShell Core profile path (auto-detected)
     pub pwsh_profile: Option<String>,
     /// Windows PowerShell profile path (auto-detected)
     pub powershell_profile: Option<String>,
 }
 
+/// Checker configuration
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CheckerConfig {
+    /// Issue codes `wenv check` leaves out (e.g. `undefined-variable`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_codes: Vec<String>,
+}
+
+/// How a `wenv lint` rule reports its findings
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// The rule does not run
+    Off,
+    Warning,
+    /// Findings make `wenv lint` fail
+    Error,
+}
+
+impl LintLevel {
+    /// Severity of the rule's findings, `None` when off
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Off => None,
+            LintLevel::Warning => Some(Severity::Warning),
+            LintLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+impl std::fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintLevel::Off => write!(f, "off"),
+            LintLevel::Warning => write!(f, "warning"),
+            LintLevel::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::str::FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(LintLevel::Off),
+            "warning" | "warn" => Ok(LintLevel::Warning),
+            "error" => Ok(LintLevel::Error),
+            _ => Err(format!("expected off, warning or error, got '{}'", s)),
+        }
+    }
+}
+
+/// `wenv lint` configuration: the level of each rule by name, e.g.
+/// `shadowed-builtin = "off"`. Rules not listed keep their default level.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LintConfig {
+    #[serde(flatten)]
+    pub rules: BTreeMap<String, LintLevel>,
+}
+
+/// Entry type colors in the TUI and CLI output: a color name by entry
+/// type, e.g. `alias = "bright blue"`. Types not listed keep their default.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ColorsConfig {
+    #[serde(flatten)]
+    pub types: BTreeMap<String, String>,
+}
+
+/// Parser configuration
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ParserConfig {
+    /// Parse warning codes to drop (e.g. `unclosed-function`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_warning_codes: Vec<String>,
+    /// Record the comment directly above each entry, shown by `info`,
+    /// `list` and the TUI
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub attach_comments: bool,
+}
+
 impl Default for FormatConfig {
     fn default() -> Self {
         FormatConfig {
@@ -74,6 +217,10 @@ impl Default for FormatConfig {
             sort_alphabetically: true,
             blank_lines_between_groups: 1,
             order: TypeOrder::default(),
+            quote_style: QuoteStyle::default(),
+            align_equals: false,
+            section_headers: false,
+            preserve_trailing_blanks: false,
         }
     }
 }
@@ -88,7 +235,11 @@ impl Default for TypeOrder {
 
 impl Default for BackupConfig {
     fn default() -> Self {
-        BackupConfig { max_count: 20 }
+        BackupConfig {
+            enabled: true,
+            max_count: 20,
+            dir: None,
+        }
     }
 }
 
@@ -114,6 +265,11 @@ impl Config {
         Self::config_dir().join("backups")
     }
 
+    /// Get the change history log path
+    pub fn history_path() -> PathBuf {
+        Self::config_dir().join("history.log")
+    }
+
     /// Load configuration from file, or return default if file doesn't exist
     pub fn load() -> anyhow::Result<Self> {
         let path = Self::config_path();
@@ -147,6 +303,19 @@ impl Config {
         Ok(config)
     }
 
+    /// The default configuration as TOML, with every option documented in
+    /// a comment. Parses into [`Config::default`].
+    pub const COMMENTED_TEMPLATE: &'static str = include_str!("../../assets/config.toml");
+
+    /// Write [`Self::COMMENTED_TEMPLATE`] to `path`, creating its directory
+    pub fn write_commented_template(path: &std::path::Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, Self::COMMENTED_TEMPLATE)?;
+        Ok(())
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> anyhow::Result<()> {
         let path = Self::config_path();
@@ -185,4 +354,52 @@ mod tests {
         let parsed: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.format.indent, config.format.indent);
     }
+
+    #[test]
+    fn test_quote_style_parsing() {
+        let config: Config = toml::from_str(
+            "[format]\nindent = 2\ngroup_by_type = true\nsort_alphabetically = true\n\
+             blank_lines_between_groups = 1\nquote_style = \"single\"\n\
+             [format.order]\ntypes = [\"alias\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.format.quote_style, QuoteStyle::Single);
+        assert_eq!(Config::default().format.quote_style, QuoteStyle::Preserve);
+    }
+
+    #[test]
+    fn test_backup_enabled_defaults_on() {
+        let config: Config = toml::from_str("[backup]\nmax_count = 5\n").unwrap();
+        assert!(config.backup.enabled);
+
+        let config: Config = toml::from_str("[backup]\nenabled = false\nmax_count = 5\n").unwrap();
+        assert!(!config.backup.enabled);
+    }
+
+    #[test]
+    fn test_backup_dir_optional() {
+        assert!(Config::default().backup.dir.is_none());
+        let toml_str = toml::to_string(&Config::default()).unwrap();
+        assert!(!toml_str.contains("dir"));
+
+        let config: Config =
+            toml::from_str("[backup]\nmax_count = 5\ndir = \"~/sync/wenv-backups\"\n").unwrap();
+        assert_eq!(
+            config.backup.dir,
+            Some(PathBuf::from("~/sync/wenv-backups"))
+        );
+    }
+
+    #[test]
+    fn test_commented_template_parses_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wenv").join("config.toml");
+        Config::write_commented_template(&path).unwrap();
+
+        let parsed: Config = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            toml::to_string(&parsed).unwrap(),
+            toml::to_string(&Config::default()).unwrap()
+        );
+    }
 }
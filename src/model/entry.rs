@@ -1,18 +1,45 @@
 //! Entry data structures for shell configuration items
 
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-/// Entry type enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use super::ShellType;
+use crate::utils::quoting::{extract_comment, shell_word_end, strip_quotes, unquote_shell_word};
+
+/// Entry type enumeration, ordered as declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum EntryType {
     Alias,
     Function,
     EnvVar,
     Source,
+    Array,   // Bash indexed/associative arrays: `arr=(a b)`
+    Unset,   // Removal of a definition: `unalias ll`, `unset VAR`, `Remove-Item Env:VAR`
+    Trap,    // Bash signal handler: `trap 'cleanup' EXIT`, named by its signals
+    Setopt,  // Zsh option switch: `setopt autocd`, `unsetopt beep`, named by its options
+    Zstyle,  // Zsh style: `zstyle ':completion:*' menu select`, named by pattern and style
     Code,    // Raw code lines or control structures
     Comment, // Pure comment lines
 }
 
+impl EntryType {
+    /// Every entry type, in declaration order
+    pub const ALL: [EntryType; 11] = [
+        EntryType::Alias,
+        EntryType::Function,
+        EntryType::EnvVar,
+        EntryType::Source,
+        EntryType::Array,
+        EntryType::Unset,
+        EntryType::Trap,
+        EntryType::Setopt,
+        EntryType::Zstyle,
+        EntryType::Code,
+        EntryType::Comment,
+    ];
+}
+
 impl std::fmt::Display for EntryType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -20,6 +47,11 @@ impl std::fmt::Display for EntryType {
             EntryType::Function => write!(f, "func"),
             EntryType::EnvVar => write!(f, "env"),
             EntryType::Source => write!(f, "source"),
+            EntryType::Array => write!(f, "array"),
+            EntryType::Unset => write!(f, "unset"),
+            EntryType::Trap => write!(f, "trap"),
+            EntryType::Setopt => write!(f, "setopt"),
+            EntryType::Zstyle => write!(f, "zstyle"),
             EntryType::Code => write!(f, "code"),
             EntryType::Comment => write!(f, "comment"),
         }
@@ -35,6 +67,11 @@ impl std::str::FromStr for EntryType {
             "func" | "function" => Ok(EntryType::Function),
             "env" | "envvar" => Ok(EntryType::EnvVar),
             "source" => Ok(EntryType::Source),
+            "array" | "arr" => Ok(EntryType::Array),
+            "unset" | "unalias" => Ok(EntryType::Unset),
+            "trap" => Ok(EntryType::Trap),
+            "setopt" | "unsetopt" => Ok(EntryType::Setopt),
+            "zstyle" => Ok(EntryType::Zstyle),
             "code" => Ok(EntryType::Code),
             "comment" => Ok(EntryType::Comment),
             _ => Err(format!("Unknown entry type: {}", s)),
@@ -69,6 +106,27 @@ impl std::str::FromStr for EntryType {
 /// - `entry_type`: The structured entry type
 /// - `value`: Combined content (e.g., `"# comment\n\nalias foo='bar'"`)
 /// - `name`: Extracted from the structured part (e.g., `foo`)
+///
+/// # Tags
+/// A comment of the form `# wenv:tags=work,git` directly above a structured
+/// entry attaches tags to it, whether merged into `value` or kept as a
+/// separate attached Comment entry. They are read into `tags` when the entry
+/// is added to a [`ParseResult`]; the comment itself is left in place, so
+/// formatting keeps it with the entry.
+///
+/// # Attached Comments
+/// Parsers built `with_attached_comments(true)` also record the comment
+/// directly above each structured entry in `attached_comment`, by the same
+/// rule as tags: a comment merged into `value` stays there as well, so the
+/// raw value can still be written back unchanged.
+///
+/// # Exported Functions
+/// A top-level `export -f NAME` line (Bash) stays a Code entry of its own and
+/// sets `exported` on the function `NAME` defined above it.
+///
+/// # Provenance
+/// Parsers leave `source_file` unset. Commands working on several `--file`
+/// paths at once set it to the file each entry came from.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entry {
     pub entry_type: EntryType,
@@ -76,6 +134,17 @@ pub struct Entry {
     pub value: String,
     pub line_number: Option<usize>,
     pub end_line: Option<usize>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Function exported to child processes with `export -f`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exported: bool,
+    /// File the entry was read from, when several files are read together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<PathBuf>,
+    /// The comment directly above the entry, when the parser records it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attached_comment: Option<String>,
 }
 
 impl Entry {
@@ -86,6 +155,10 @@ impl Entry {
             value,
             line_number: None,
             end_line: None,
+            tags: Vec::new(),
+            exported: false,
+            source_file: None,
+            attached_comment: None,
         }
     }
 
@@ -99,6 +172,11 @@ impl Entry {
         self
     }
 
+    pub fn with_source_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.source_file = Some(path.into());
+        self
+    }
+
     /// Merge another entry into this one, extending the line range and combining content.
     ///
     /// # Merging Rules
@@ -150,16 +228,427 @@ impl Entry {
         }
     }
 
+    /// Whether `other` defines the same thing: the same type and name.
+    ///
+    /// Comment and Code entries have names generated from their position, so
+    /// they are compared by line range instead.
+    pub fn same_identity(&self, other: &Entry) -> bool {
+        if self.entry_type != other.entry_type {
+            return false;
+        }
+        match self.entry_type {
+            EntryType::Comment | EntryType::Code => {
+                let range = |e: &Entry| (e.line_number, e.end_line.or(e.line_number));
+                range(self) == range(other)
+            }
+            _ => self.name == other.name,
+        }
+    }
+
+    /// Combine two definitions of the same entry (see [`same_identity`]),
+    /// keeping the one the shell ends up with: the later one in the file.
+    ///
+    /// The later definition's value and position win; without line numbers,
+    /// `other` counts as later. Tags of both are kept, and the result is
+    /// exported when either is.
+    ///
+    /// [`same_identity`]: Entry::same_identity
+    pub fn merge_preferring_latest(self, other: Entry) -> Entry {
+        let (earlier, mut latest) = match (self.line_number, other.line_number) {
+            (Some(a), Some(b)) if a > b => (other, self),
+            _ => (self, other),
+        };
+        for tag in earlier.tags {
+            if !latest.tags.contains(&tag) {
+                latest.tags.push(tag);
+            }
+        }
+        latest.exported |= earlier.exported;
+        latest
+    }
+
+    /// Whether this entry carries `tag` (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Number of lines the entry spans in its file, from `line_number` to
+    /// `end_line`. Entries not read from a file count the lines of their
+    /// value.
+    pub fn line_count(&self) -> usize {
+        match (self.line_number, self.end_line) {
+            (Some(start), Some(end)) if end >= start => end - start + 1,
+            (Some(_), _) => 1,
+            (None, _) => self.value.lines().count().max(1),
+        }
+    }
+
+    /// Whether `line` (1-based) lies within the lines the entry spans in its
+    /// file. Entries not read from a file cover no line.
+    pub fn covers_line(&self, line: usize) -> bool {
+        self.line_number
+            .is_some_and(|start| (start..start + self.line_count()).contains(&line))
+    }
+
+    /// The entry spanning `line` (1-based) of the file `entries` were read
+    /// from, e.g. a line number reported by `wenv check`
+    pub fn at_line(entries: &[Entry], line: usize) -> Option<&Entry> {
+        entries.iter().find(|e| e.covers_line(line))
+    }
+
+    /// Whether this is one of the variables shells build their prompt from
+    /// (`PS1`, `PROMPT_COMMAND`, Zsh's `PROMPT`...), exported or not. Their
+    /// values are full of escapes and quoting that must survive formatting
+    /// as written.
+    pub fn is_prompt_variable(&self) -> bool {
+        self.entry_type == EntryType::EnvVar && PROMPT_VARIABLES.contains(&self.name.as_str())
+    }
+
     /// Check if this is a blank line entry (Code with empty or whitespace-only value).
     pub fn is_blank(&self) -> bool {
         self.entry_type == EntryType::Code && self.value.trim().is_empty()
     }
+
+    /// Check if this is a commented-out definition, e.g.
+    /// `# alias ll='ls -la'`. See [`Entry::disabled_definition`].
+    pub fn is_disabled(&self, shell_type: ShellType) -> bool {
+        self.disabled_definition(shell_type).is_some()
+    }
+
+    /// The entry a Comment would define with its `#` markers removed, for
+    /// comments that are nothing but one commented-out definition (what the
+    /// TUI's toggle-comment produces). Prose comments give `None`.
+    ///
+    /// The returned entry has the uncommented value but this comment's
+    /// lines, so it can be shown in place of the comment.
+    pub fn disabled_definition(&self, shell_type: ShellType) -> Option<Entry> {
+        if self.entry_type != EntryType::Comment {
+            return None;
+        }
+        let body: Vec<&str> = self
+            .value
+            .lines()
+            .map(|line| {
+                let line = line.trim_start();
+                let line = line.strip_prefix('#').unwrap_or(line);
+                line.strip_prefix(' ').unwrap_or(line)
+            })
+            .collect();
+        let parsed = crate::parser::get_parser(shell_type).parse(&body.join("\n"));
+        if !parsed.warnings.is_empty() {
+            return None;
+        }
+
+        let mut definitions = parsed.entries.into_iter().filter(|e| !e.is_blank());
+        let definition = definitions.next()?;
+        if definitions.next().is_some()
+            || matches!(definition.entry_type, EntryType::Code | EntryType::Comment)
+        {
+            return None;
+        }
+        Some(Entry {
+            line_number: self.line_number,
+            end_line: self.end_line,
+            source_file: self.source_file.clone(),
+            ..definition
+        })
+    }
+
+    /// Check if this is a preserved region (`# >>> wenv:ignore >>>`, conda
+    /// init, ...), which formatting keeps verbatim and in place. See
+    /// [`crate::parser::regions`].
+    pub fn is_guarded(&self) -> bool {
+        self.entry_type == EntryType::Code
+            && self
+                .value
+                .lines()
+                .next()
+                .and_then(crate::parser::regions::region_starting_at)
+                .is_some()
+    }
+
+    /// Check if this is the `#!` line opening the file, which parsers keep
+    /// as its own Comment and formatting always writes first.
+    pub fn is_shebang(&self) -> bool {
+        self.entry_type == EntryType::Comment
+            && self.line_number == Some(1)
+            && self.value.starts_with("#!")
+            && !self.value.contains('\n')
+    }
+
+    /// Check if this is a PowerShell module import (`Import-Module`,
+    /// `using module`) rather than a dot-sourced file.
+    pub fn is_module_import(&self) -> bool {
+        self.entry_type == EntryType::Source
+            && crate::parser::pwsh_module_argument(strip_leading_comments(&self.value)).is_some()
+    }
+
+    /// The entry types whose definition of `name` an Unset entry removes:
+    /// aliases for `unalias`/`Remove-Alias`/`Alias:`, functions for
+    /// `unset -f`/`Function:`, variables and arrays otherwise. Empty for
+    /// other entries.
+    pub fn unset_types(&self) -> &'static [EntryType] {
+        if self.entry_type != EntryType::Unset {
+            return &[];
+        }
+        let body = strip_leading_comments(&self.value).trim().to_lowercase();
+        if body.starts_with("unalias")
+            || body.starts_with("remove-alias")
+            || body.contains("alias:")
+        {
+            &[EntryType::Alias]
+        } else if body.starts_with("unset -f") || body.contains("function:") {
+            &[EntryType::Function]
+        } else {
+            &[EntryType::EnvVar, EntryType::Array]
+        }
+    }
+
+    /// Get the normalized payload of this entry, independent of quoting style.
+    ///
+    /// Since `value` holds the complete raw syntax, two entries that mean the same
+    /// thing can differ textually (`alias ll='ls -la'` vs `alias ll="ls -la"`).
+    /// This strips merged leading comments/blank lines, the leading keyword and
+    /// surrounding quotes so such entries compare equal.
+    ///
+    /// | Type | Raw value | Canonical |
+    /// |------|-----------|-----------|
+    /// | Alias | `alias ll='ls -la'` | `ls -la` |
+    /// | Alias | `Set-Alias -Name ll -Value Get-ChildItem` | `Get-ChildItem` |
+    /// | EnvVar | `export EDITOR="vim"` | `vim` |
+    /// | EnvVar | `$env:EDITOR = 'code'` | `code` |
+    /// | Source | `source "~/.aliases"` | `~/.aliases` |
+    /// | Source | `Import-Module -Name 'posh-git'` | `posh-git` |
+    /// | Array | `declare -a arr=(a b)` | `(a b)` |
+    ///
+    /// Functions return their definition without leading comments; Code and
+    /// Comment entries return their value with trailing whitespace removed.
+    pub fn canonical_value(&self) -> String {
+        let body = match self.entry_type {
+            EntryType::Code | EntryType::Comment => return self.value.trim_end().to_string(),
+            _ => strip_leading_comments(&self.value),
+        };
+
+        match self.entry_type {
+            EntryType::Alias => canonical_alias(body),
+            EntryType::EnvVar => canonical_env(body),
+            EntryType::Source => canonical_source(body),
+            EntryType::Array => canonical_array(body),
+            _ => body.trim_end().to_string(),
+        }
+    }
+
+    /// The definition as written, without the comments merged above it or
+    /// the blank lines it absorbed: `alias ll='ls -la'`, a whole function.
+    /// Comment entries have none.
+    pub fn definition(&self) -> &str {
+        match self.entry_type {
+            EntryType::Comment => "",
+            EntryType::Code => self.value.trim(),
+            _ => strip_leading_comments(&self.value).trim_end(),
+        }
+    }
+
+    /// Return a copy of this entry with its definition renamed to `new_name`.
+    ///
+    /// Only the name at the definition site is rewritten (e.g. the `ll` in
+    /// `alias ll='ls -l'`, or `$env:FOO` in `$env:FOO = "x"`); merged comments
+    /// and the value itself are left untouched. Returns `None` for Code and
+    /// Comment entries, or when the name cannot be located.
+    pub fn renamed(&self, new_name: &str) -> Option<Entry> {
+        if matches!(self.entry_type, EntryType::Code | EntryType::Comment) {
+            return None;
+        }
+
+        let body = strip_leading_comments(&self.value);
+        let offset = self.value.len() - body.len();
+        let first_line = body.split('\n').next().unwrap_or("");
+
+        let name_re = regex::Regex::new(&format!(
+            r"(?:^|[\s:]){}(?:[\s=({{]|$)",
+            regex::escape(&self.name)
+        ))
+        .ok()?;
+        let m = name_re.find(first_line)?;
+        // The match may include one boundary char on either side
+        let start = offset + m.start() + first_line[m.start()..].find(&self.name)?;
+        let end = start + self.name.len();
+
+        let mut renamed = self.clone();
+        renamed.value.replace_range(start..end, new_name);
+        renamed.name = new_name.to_string();
+        Some(renamed)
+    }
+
+    /// Return a copy of this entry with its definition replaced by
+    /// `definition`, keeping merged leading comments and the blank lines it
+    /// absorbed after itself.
+    pub fn redefined(&self, definition: &str) -> Entry {
+        let body = strip_leading_comments(&self.value);
+        let head = &self.value[..self.value.len() - body.len()];
+        let trailing =
+            &self.value[crate::formatter::trim_trailing_blank_lines(&self.value).len()..];
+
+        let mut redefined = self.clone();
+        redefined.value = format!("{}{}{}", head, definition, trailing);
+        redefined
+    }
+}
+
+/// Variables Bash and Zsh read their prompts from
+pub const PROMPT_VARIABLES: &[&str] = &[
+    "PS0",
+    "PS1",
+    "PS2",
+    "PS3",
+    "PS4",
+    "PROMPT_COMMAND",
+    "PROMPT",
+    "PROMPT2",
+    "PROMPT3",
+    "PROMPT4",
+    "RPROMPT",
+    "RPS1",
+    "RPS2",
+];
+
+lazy_static! {
+    /// `# wenv:tags=a,b` (also accepts `tag=` and a space after the colon)
+    static ref TAGS_RE: regex::Regex =
+        regex::Regex::new(r"^#\s*wenv:\s*tags?\s*=\s*(.*)$").unwrap();
+}
+
+/// Collect tags from `# wenv:tags=...` lines among the leading comments of
+/// `value` into `tags`, skipping duplicates.
+fn collect_leading_tags(value: &str, tags: &mut Vec<String>) {
+    for line in value.split('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if !trimmed.starts_with('#') {
+            break;
+        }
+        if let Some(caps) = TAGS_RE.captures(trimmed) {
+            for tag in caps[1].split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Whether `prev` is a comment entry ending right above `entry`, the same
+/// rule the formatters use to keep a comment with the entry below it
+fn is_attached(prev: &Entry, entry: &Entry) -> bool {
+    prev.entry_type == EntryType::Comment
+        && matches!(
+            (prev.end_line.or(prev.line_number), entry.line_number),
+            (Some(end), Some(start)) if end + 1 == start
+        )
+}
+
+/// The comment lines merged at the start of `value`, without the blank
+/// lines around them
+fn leading_comments(value: &str) -> Option<String> {
+    let lines: Vec<&str> = value
+        .split('\n')
+        .take_while(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Skip leading comment and blank lines merged into a structured entry.
+fn strip_leading_comments(value: &str) -> &str {
+    let mut offset = 0;
+    for line in value.split('\n') {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            return value[offset..].trim_start();
+        }
+        offset += line.len() + 1;
+    }
+    ""
+}
+
+/// Strip an inline comment and surrounding quotes from a single payload.
+fn clean_payload(payload: &str) -> String {
+    let (code, _comment) = extract_comment(payload, '#');
+    strip_quotes(&code)
+}
+
+fn canonical_alias(body: &str) -> String {
+    // Bash/Zsh: alias [-g|-s] name=value
+    if let Some(rest) = body.strip_prefix("alias") {
+        let mut rest = rest.trim_start();
+        while rest.starts_with('-') {
+            rest = rest
+                .split_once(char::is_whitespace)
+                .map(|(_, r)| r.trim_start())
+                .unwrap_or("");
+        }
+        return match rest.split_once('=') {
+            // One shell word, whatever mix of quotes it uses
+            Some((_, value)) => match shell_word_end(value) {
+                Some(end) => unquote_shell_word(&value[..end]),
+                None => clean_payload(value),
+            },
+            None => rest.trim().to_string(),
+        };
+    }
+
+    // PowerShell: Set-Alias/New-Alias [-Name] name [-Value] value
+    crate::parser::pwsh_alias_arguments(body)
+        .map(|(_, value)| value)
+        .unwrap_or_default()
+}
+
+fn canonical_env(body: &str) -> String {
+    // PowerShell: $env:NAME = value (single-line or Here-String)
+    if body.starts_with("$env:") {
+        let value = body.split_once('=').map(|(_, v)| v.trim()).unwrap_or("");
+        if value.starts_with("@\"") || value.starts_with("@'") {
+            let lines: Vec<&str> = value.split('\n').collect();
+            if lines.len() >= 2 {
+                return lines[1..lines.len() - 1].join("\n");
+            }
+        }
+        return clean_payload(value);
+    }
+
+    // Bash/Zsh: export NAME=value
+    let rest = body.strip_prefix("export").unwrap_or(body).trim_start();
+    match rest.split_once('=') {
+        Some((_, value)) => clean_payload(value),
+        None => rest.trim().to_string(),
+    }
+}
+
+fn canonical_source(body: &str) -> String {
+    if let Some(module) = crate::parser::pwsh_module_argument(body) {
+        return module;
+    }
+    let path = body
+        .strip_prefix("source")
+        .or_else(|| body.strip_prefix('.'))
+        .unwrap_or(body);
+    clean_payload(path)
+}
+
+fn canonical_array(body: &str) -> String {
+    match body.split_once('=') {
+        Some((_, value)) => value.trim_end().to_string(),
+        None => body.trim_end().to_string(),
+    }
 }
 
 /// Parse result containing entries and warnings
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ParseResult {
     pub entries: Vec<Entry>,
+    #[serde(default)]
     pub warnings: Vec<ParseWarning>,
 }
 
@@ -171,10 +660,38 @@ impl ParseResult {
         }
     }
 
-    pub fn add_entry(&mut self, entry: Entry) {
+    pub fn add_entry(&mut self, mut entry: Entry) {
+        if !matches!(entry.entry_type, EntryType::Code | EntryType::Comment) {
+            entry.tags.clear();
+            // PowerShell never merges comments into structured entries, so
+            // the comment entry above is where its tags come from
+            if let Some(prev) = self.entries.last().filter(|prev| is_attached(prev, &entry)) {
+                collect_leading_tags(&prev.value, &mut entry.tags);
+            }
+            collect_leading_tags(&entry.value, &mut entry.tags);
+        }
         self.entries.push(entry);
     }
 
+    /// Set `attached_comment` on every structured entry with a comment
+    /// directly above it: the comment lines merged into its value, or else a
+    /// Comment entry ending on the line before it
+    pub fn record_attached_comments(&mut self) {
+        for i in 0..self.entries.len() {
+            let entry = &self.entries[i];
+            if matches!(entry.entry_type, EntryType::Code | EntryType::Comment) {
+                continue;
+            }
+            let comment = leading_comments(&entry.value).or_else(|| {
+                i.checked_sub(1)
+                    .map(|j| &self.entries[j])
+                    .filter(|prev| is_attached(prev, entry))
+                    .map(|prev| prev.value.trim_end().to_string())
+            });
+            self.entries[i].attached_comment = comment;
+        }
+    }
+
     pub fn add_warning(&mut self, warning: ParseWarning) {
         self.warnings.push(warning);
     }
@@ -186,21 +703,101 @@ impl Default for ParseResult {
     }
 }
 
+/// How serious a parse warning or check issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Stable identifier of a kind of parse warning, for tooling and for
+/// suppressing warnings by code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCode {
+    /// A function body without its closing brace
+    UnclosedFunction,
+    /// A here-document (or PowerShell here-string) without its terminator
+    UnclosedHeredoc,
+    /// A multi-line alias without its closing quote
+    UnclosedAlias,
+    /// A multi-line export without its closing quote
+    UnclosedExport,
+    /// Any other construct left open at end of file
+    UnclosedBlock,
+    /// A preserved region (`# >>> wenv:ignore >>>`, ...) without its end marker
+    UnclosedRegion,
+    /// A quoted `.env` value without its closing quote
+    UnclosedQuote,
+    /// A `.env` line that is not a `KEY=value` assignment
+    InvalidAssignment,
+}
+
+impl WarningCode {
+    /// Every code, in declaration order
+    pub const ALL: [WarningCode; 8] = [
+        WarningCode::UnclosedFunction,
+        WarningCode::UnclosedHeredoc,
+        WarningCode::UnclosedAlias,
+        WarningCode::UnclosedExport,
+        WarningCode::UnclosedBlock,
+        WarningCode::UnclosedRegion,
+        WarningCode::UnclosedQuote,
+        WarningCode::InvalidAssignment,
+    ];
+
+    /// Kebab-case code, e.g. `unclosed-function`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningCode::UnclosedFunction => "unclosed-function",
+            WarningCode::UnclosedHeredoc => "unclosed-heredoc",
+            WarningCode::UnclosedAlias => "unclosed-alias",
+            WarningCode::UnclosedExport => "unclosed-export",
+            WarningCode::UnclosedBlock => "unclosed-block",
+            WarningCode::UnclosedRegion => "unclosed-region",
+            WarningCode::UnclosedQuote => "unclosed-quote",
+            WarningCode::InvalidAssignment => "invalid-assignment",
+        }
+    }
+
+    /// Severity of warnings with this code. Errors leave the rest of the
+    /// file unparsable for the shell; warnings only affect wenv.
+    pub fn severity(&self) -> Severity {
+        match self {
+            WarningCode::UnclosedRegion | WarningCode::InvalidAssignment => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Warning generated during parsing
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ParseWarning {
+    pub code: WarningCode,
+    pub severity: Severity,
     pub line_number: usize,
     pub message: String,
     pub raw_line: String,
 }
 
 impl ParseWarning {
+    /// Warning with the severity of its `code`
     pub fn new(
+        code: WarningCode,
         line_number: usize,
         message: impl Into<String>,
         raw_line: impl Into<String>,
     ) -> Self {
         Self {
+            code,
+            severity: code.severity(),
             line_number,
             message: message.into(),
             raw_line: raw_line.into(),
@@ -212,12 +809,28 @@ impl ParseWarning {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_prompt_variable() {
+        let env = |name: &str| Entry::new(EntryType::EnvVar, name.into(), String::new());
+        assert!(env("PS1").is_prompt_variable());
+        assert!(env("PROMPT_COMMAND").is_prompt_variable());
+        assert!(env("RPROMPT").is_prompt_variable());
+        assert!(!env("PATH").is_prompt_variable());
+        assert!(!env("ps1").is_prompt_variable());
+        assert!(!Entry::new(EntryType::Alias, "PS1".into(), String::new()).is_prompt_variable());
+    }
+
     #[test]
     fn test_entry_type_display() {
         assert_eq!(format!("{}", EntryType::Alias), "alias");
         assert_eq!(format!("{}", EntryType::Function), "func");
         assert_eq!(format!("{}", EntryType::EnvVar), "env");
         assert_eq!(format!("{}", EntryType::Source), "source");
+        assert_eq!(format!("{}", EntryType::Array), "array");
+        assert_eq!(format!("{}", EntryType::Unset), "unset");
+        assert_eq!(format!("{}", EntryType::Trap), "trap");
+        assert_eq!(format!("{}", EntryType::Setopt), "setopt");
+        assert_eq!(format!("{}", EntryType::Zstyle), "zstyle");
     }
 
     #[test]
@@ -230,6 +843,55 @@ mod tests {
         );
         assert_eq!("env".parse::<EntryType>().unwrap(), EntryType::EnvVar);
         assert_eq!("source".parse::<EntryType>().unwrap(), EntryType::Source);
+        assert_eq!("arr".parse::<EntryType>().unwrap(), EntryType::Array);
+        assert_eq!("unalias".parse::<EntryType>().unwrap(), EntryType::Unset);
+        assert_eq!("trap".parse::<EntryType>().unwrap(), EntryType::Trap);
+        assert_eq!("unsetopt".parse::<EntryType>().unwrap(), EntryType::Setopt);
+        assert_eq!("zstyle".parse::<EntryType>().unwrap(), EntryType::Zstyle);
+    }
+
+    #[test]
+    fn test_entry_type_round_trip() {
+        // In declaration order, each once
+        assert!(EntryType::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+        for entry_type in EntryType::ALL {
+            // Adding a variant fails here until it is listed in ALL
+            match entry_type {
+                EntryType::Alias
+                | EntryType::Function
+                | EntryType::EnvVar
+                | EntryType::Source
+                | EntryType::Array
+                | EntryType::Unset
+                | EntryType::Trap
+                | EntryType::Setopt
+                | EntryType::Zstyle
+                | EntryType::Code
+                | EntryType::Comment => {}
+            }
+            assert_eq!(entry_type.to_string().parse::<EntryType>(), Ok(entry_type));
+        }
+    }
+
+    #[test]
+    fn test_unset_types() {
+        let unset = |value: &str| Entry::new(EntryType::Unset, "x".into(), value.into());
+        assert_eq!(unset("unalias x").unset_types(), [EntryType::Alias]);
+        assert_eq!(
+            unset("Remove-Item Alias:x").unset_types(),
+            [EntryType::Alias]
+        );
+        assert_eq!(unset("unset -f x").unset_types(), [EntryType::Function]);
+        assert_eq!(
+            unset("# no longer needed\nunset x").unset_types(),
+            [EntryType::EnvVar, EntryType::Array]
+        );
+        assert_eq!(
+            unset("Remove-Item Env:x").unset_types(),
+            [EntryType::EnvVar, EntryType::Array]
+        );
+        let alias = Entry::new(EntryType::Alias, "x".into(), "alias x=y".into());
+        assert!(alias.unset_types().is_empty());
     }
 
     #[test]
@@ -241,6 +903,22 @@ mod tests {
         assert_eq!(entry.line_number, Some(10));
     }
 
+    #[test]
+    fn test_line_count() {
+        let alias =
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls'".into()).with_line_number(3);
+        assert_eq!(alias.line_count(), 1);
+        assert_eq!(alias.clone().with_end_line(3).line_count(), 1);
+
+        let function = Entry::new(EntryType::Function, "f".into(), "f() {\n  :\n}".into())
+            .with_line_number(10)
+            .with_end_line(59);
+        assert_eq!(function.line_count(), 50);
+
+        let unsaved = Entry::new(EntryType::Function, "f".into(), "f() {\n  :\n}".into());
+        assert_eq!(unsaved.line_count(), 3);
+    }
+
     #[test]
     fn test_entry_is_blank() {
         let blank = Entry::new(EntryType::Code, "L1".into(), String::new()).with_line_number(1);
@@ -300,4 +978,404 @@ mod tests {
 
         assert_eq!(entry.value, "line1\nline2");
     }
+
+    #[test]
+    fn test_canonical_value_bash_alias() {
+        let single = Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into());
+        let double = Entry::new(EntryType::Alias, "ll".into(), "alias ll=\"ls -la\"".into());
+        let global = Entry::new(EntryType::Alias, "G".into(), "alias -g G='| grep'".into());
+
+        assert_eq!(single.canonical_value(), "ls -la");
+        assert_eq!(double.canonical_value(), "ls -la");
+        assert_eq!(global.canonical_value(), "| grep");
+    }
+
+    #[test]
+    fn test_canonical_value_ignores_merged_comment_and_inline_comment() {
+        let entry = Entry::new(
+            EntryType::Alias,
+            "ll".into(),
+            "# list files\n\nalias ll='ls -la' # long".into(),
+        );
+        assert_eq!(entry.canonical_value(), "ls -la");
+    }
+
+    #[test]
+    fn test_canonical_value_pwsh_alias() {
+        let simple = Entry::new(
+            EntryType::Alias,
+            "ll".into(),
+            "Set-Alias ll Get-ChildItem".into(),
+        );
+        let named = Entry::new(
+            EntryType::Alias,
+            "ll".into(),
+            "Set-Alias -Name ll -Value 'Get-ChildItem'".into(),
+        );
+        let scoped = Entry::new(
+            EntryType::Alias,
+            "ll".into(),
+            "New-Alias -Scope Global ll Get-ChildItem".into(),
+        );
+
+        assert_eq!(simple.canonical_value(), "Get-ChildItem");
+        assert_eq!(named.canonical_value(), "Get-ChildItem");
+        assert_eq!(scoped.canonical_value(), "Get-ChildItem");
+
+        let described = Entry::new(
+            EntryType::Alias,
+            "gst".into(),
+            "New-Alias -Description 'Git status' -Value Get-GitStatus gst".into(),
+        );
+        assert_eq!(described.canonical_value(), "Get-GitStatus");
+
+        // Quoted arguments holding spaces stay one argument
+        let spaced = Entry::new(
+            EntryType::Alias,
+            "x".into(),
+            "Set-Alias -Name x -Value 'a b'".into(),
+        );
+        let double = Entry::new(EntryType::Alias, "x".into(), "Set-Alias x \"a b\"".into());
+        let commented = Entry::new(
+            EntryType::Alias,
+            "x".into(),
+            "New-Alias -Name x -Value 'a # b' # note".into(),
+        );
+        assert_eq!(spaced.canonical_value(), "a b");
+        assert_eq!(double.canonical_value(), spaced.canonical_value());
+        assert_eq!(commented.canonical_value(), "a # b");
+    }
+
+    #[test]
+    fn test_canonical_value_env() {
+        let bash = Entry::new(
+            EntryType::EnvVar,
+            "EDITOR".into(),
+            "export EDITOR=\"vim\"".into(),
+        );
+        let bash_bare = Entry::new(
+            EntryType::EnvVar,
+            "EDITOR".into(),
+            "export EDITOR=vim".into(),
+        );
+        let pwsh = Entry::new(
+            EntryType::EnvVar,
+            "EDITOR".into(),
+            "$env:EDITOR = 'vim'".into(),
+        );
+        let here_string = Entry::new(
+            EntryType::EnvVar,
+            "PATH".into(),
+            "$env:PATH = @\"\nC:\\bin\nD:\\tools\n\"@".into(),
+        );
+
+        assert_eq!(bash.canonical_value(), "vim");
+        assert_eq!(bash_bare.canonical_value(), "vim");
+        assert_eq!(pwsh.canonical_value(), "vim");
+        assert_eq!(here_string.canonical_value(), "C:\\bin\nD:\\tools");
+    }
+
+    #[test]
+    fn test_same_identity() {
+        let alias = |name: &str| Entry::new(EntryType::Alias, name.into(), String::new());
+        assert!(alias("ll").same_identity(&alias("ll")));
+        assert!(!alias("ll").same_identity(&alias("la")));
+
+        // Functions by name, wherever they are
+        let func = |line| {
+            Entry::new(EntryType::Function, "greet".into(), "greet() { :; }".into())
+                .with_line_number(line)
+        };
+        assert!(func(1).same_identity(&func(9)));
+        assert!(!func(1).same_identity(&Entry::new(
+            EntryType::Alias,
+            "greet".into(),
+            String::new()
+        )));
+
+        // Comments by line range, whatever their generated name
+        let comment = |name: &str, start, end| {
+            Entry::new(EntryType::Comment, name.into(), "# note".into())
+                .with_line_number(start)
+                .with_end_line(end)
+        };
+        assert!(comment("#L3-L4", 3, 4).same_identity(&comment("#L1", 3, 4)));
+        assert!(!comment("#L3-L4", 3, 4).same_identity(&comment("#L3-L4", 3, 5)));
+    }
+
+    #[test]
+    fn test_merge_preferring_latest() {
+        let mut first =
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls'".into()).with_line_number(2);
+        first.tags = vec!["nav".into()];
+        let mut second = Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into())
+            .with_line_number(8);
+        second.tags = vec!["shell".into(), "nav".into()];
+
+        let merged = first.clone().merge_preferring_latest(second.clone());
+        assert_eq!(merged.value, "alias ll='ls -la'");
+        assert_eq!(merged.line_number, Some(8));
+        assert_eq!(merged.tags, ["shell", "nav"]);
+
+        // The order of the arguments doesn't matter when lines are known
+        let merged = second.merge_preferring_latest(first);
+        assert_eq!(merged.value, "alias ll='ls -la'");
+
+        // Without lines, the argument is the later definition
+        let mut func = Entry::new(EntryType::Function, "f".into(), "f() { a; }".into());
+        func.exported = true;
+        let newer = Entry::new(EntryType::Function, "f".into(), "f() { b; }".into());
+        let merged = func.merge_preferring_latest(newer);
+        assert_eq!(merged.value, "f() { b; }");
+        assert!(merged.exported);
+    }
+
+    #[test]
+    fn test_canonical_value_source() {
+        let bash = Entry::new(
+            EntryType::Source,
+            "aliases".into(),
+            "source \"~/.aliases\"".into(),
+        );
+        let dot = Entry::new(EntryType::Source, "aliases".into(), ". ~/.aliases".into());
+        let pwsh = Entry::new(
+            EntryType::Source,
+            "aliases".into(),
+            ". '.\\aliases.ps1'".into(),
+        );
+
+        assert_eq!(bash.canonical_value(), "~/.aliases");
+        assert_eq!(dot.canonical_value(), "~/.aliases");
+        assert_eq!(pwsh.canonical_value(), ".\\aliases.ps1");
+        assert!(!pwsh.is_module_import());
+    }
+
+    #[test]
+    fn test_canonical_value_module_import() {
+        let import = Entry::new(
+            EntryType::Source,
+            "posh-git".into(),
+            "# Git prompt\nImport-Module -Name 'posh-git' -ArgumentList $false".into(),
+        );
+        let using = Entry::new(
+            EntryType::Source,
+            "MyMod".into(),
+            "using module ./MyMod.psm1".into(),
+        );
+
+        assert_eq!(import.canonical_value(), "posh-git");
+        assert_eq!(using.canonical_value(), "./MyMod.psm1");
+        assert!(import.is_module_import());
+        assert!(using.is_module_import());
+    }
+
+    #[test]
+    fn test_canonical_value_array() {
+        let plain = Entry::new(EntryType::Array, "arr".into(), "arr=(a b c)".into());
+        let assoc = Entry::new(
+            EntryType::Array,
+            "colors".into(),
+            "# palette\ndeclare -A colors=(\n  [red]=1\n)\n".into(),
+        );
+
+        assert_eq!(plain.canonical_value(), "(a b c)");
+        assert_eq!(assoc.canonical_value(), "(\n  [red]=1\n)");
+    }
+
+    #[test]
+    fn test_redefined_keeps_comments_and_trailing_blanks() {
+        let path = Entry::new(
+            EntryType::EnvVar,
+            "PATH".into(),
+            "# tools\nexport PATH=\"$HOME/bin:$PATH\"\n\n".into(),
+        );
+        assert_eq!(
+            path.redefined("export PATH=/opt/bin:$PATH").value,
+            "# tools\nexport PATH=/opt/bin:$PATH\n\n"
+        );
+    }
+
+    #[test]
+    fn test_renamed_rewrites_definition_only() {
+        let alias = Entry::new(
+            EntryType::Alias,
+            "ls".into(),
+            "# colors\nalias ls='ls --color'".into(),
+        );
+        let renamed = alias.renamed("ls_imported").unwrap();
+        assert_eq!(renamed.name, "ls_imported");
+        assert_eq!(renamed.value, "# colors\nalias ls_imported='ls --color'");
+
+        let cases = [
+            (
+                EntryType::EnvVar,
+                "FOO",
+                "export FOO=\"$FOO:x\"",
+                "export BAR=\"$FOO:x\"",
+            ),
+            (EntryType::EnvVar, "FOO", "$env:FOO = 'x'", "$env:BAR = 'x'"),
+            (
+                EntryType::Alias,
+                "FOO",
+                "Set-Alias -Name FOO -Value x",
+                "Set-Alias -Name BAR -Value x",
+            ),
+            (
+                EntryType::Function,
+                "FOO",
+                "FOO() {\n  FOO_HELPER\n}",
+                "BAR() {\n  FOO_HELPER\n}",
+            ),
+            (
+                EntryType::Function,
+                "FOO",
+                "function FOO {\n}",
+                "function BAR {\n}",
+            ),
+        ];
+        for (entry_type, name, value, expected) in cases {
+            let entry = Entry::new(entry_type, name.into(), value.into());
+            assert_eq!(entry.renamed("BAR").unwrap().value, expected);
+        }
+
+        let code = Entry::new(EntryType::Code, "L1".into(), "echo hi".into());
+        assert!(code.renamed("x").is_none());
+    }
+
+    #[test]
+    fn test_canonical_value_function_and_code() {
+        let func = Entry::new(
+            EntryType::Function,
+            "greet".into(),
+            "# Say hi\ngreet() {\n    echo hi\n}\n".into(),
+        );
+        let code = Entry::new(EntryType::Code, "L1".into(), "echo hello\n\n".into());
+
+        assert_eq!(func.canonical_value(), "greet() {\n    echo hi\n}");
+        assert_eq!(code.canonical_value(), "echo hello");
+    }
+
+    #[test]
+    fn test_collect_leading_tags() {
+        let tags = |value: &str| {
+            let mut tags = Vec::new();
+            collect_leading_tags(value, &mut tags);
+            tags
+        };
+        assert_eq!(
+            tags("# wenv:tags=work, git\nalias gs='git status'"),
+            vec!["work", "git"]
+        );
+        assert_eq!(tags("# wenv: tag=work\n\nexport A=1"), vec!["work"]);
+        // Only leading comments count, not ones inside the definition
+        assert!(tags("f() {\n  # wenv:tags=work\n}").is_empty());
+        assert!(tags("# just a comment\nalias a='b'").is_empty());
+    }
+
+    #[test]
+    fn test_add_entry_reads_tags() {
+        let mut result = ParseResult::new();
+        result.add_entry(Entry::new(
+            EntryType::Alias,
+            "gs".into(),
+            "# wenv:tags=git,Work\nalias gs='git status'".into(),
+        ));
+        result.add_entry(
+            Entry::new(EntryType::Comment, "#L3".into(), "# wenv:tags=git".into())
+                .with_line_number(3),
+        );
+        // Separate comment entry directly above
+        result.add_entry(
+            Entry::new(EntryType::Alias, "gl".into(), "alias gl='git log'".into())
+                .with_line_number(4),
+        );
+        // Not adjacent to the comment
+        result.add_entry(
+            Entry::new(EntryType::Comment, "#L5".into(), "# wenv:tags=git".into())
+                .with_line_number(5),
+        );
+        result.add_entry(
+            Entry::new(EntryType::Alias, "ga".into(), "alias ga='git add'".into())
+                .with_line_number(7),
+        );
+
+        assert_eq!(result.entries[0].tags, vec!["git", "Work"]);
+        assert!(result.entries[0].has_tag("work"));
+        assert!(result.entries[1].tags.is_empty());
+        assert_eq!(result.entries[2].tags, vec!["git"]);
+        assert!(result.entries[4].tags.is_empty());
+    }
+
+    #[test]
+    fn test_commented_alias_is_disabled() {
+        let comment = Entry::new(
+            EntryType::Comment,
+            "L4".into(),
+            "# alias ll='ls -la'".into(),
+        )
+        .with_line_number(4);
+
+        assert!(comment.is_disabled(ShellType::Bash));
+        let definition = comment.disabled_definition(ShellType::Bash).unwrap();
+        assert_eq!(definition.entry_type, EntryType::Alias);
+        assert_eq!(definition.name, "ll");
+        assert_eq!(definition.canonical_value(), "ls -la");
+        assert_eq!(definition.line_number, Some(4));
+
+        // A whole commented-out function counts too
+        let function = Entry::new(
+            EntryType::Comment,
+            "L1-L3".into(),
+            "# greet() {\n#   echo hi\n# }".into(),
+        );
+        let definition = function.disabled_definition(ShellType::Bash).unwrap();
+        assert_eq!(
+            (definition.entry_type, definition.name.as_str()),
+            (EntryType::Function, "greet")
+        );
+    }
+
+    #[test]
+    fn test_prose_comment_is_not_disabled() {
+        let comment = |value: &str| Entry::new(EntryType::Comment, "L1".into(), value.into());
+
+        assert!(!comment("# Listing shortcuts").is_disabled(ShellType::Bash));
+        assert!(!comment("# TODO: move these to .bash_aliases").is_disabled(ShellType::Bash));
+        // A note followed by a commented definition is still a note
+        assert!(!comment("# Listing\n# alias ll='ls -la'").is_disabled(ShellType::Bash));
+        // Only comments can be disabled entries
+        let alias = Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into());
+        assert!(!alias.is_disabled(ShellType::Bash));
+    }
+
+    #[test]
+    fn test_at_line_maps_lines_to_entries() {
+        let entries = vec![
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls'".into()).with_line_number(1),
+            Entry::new(
+                EntryType::Function,
+                "greet".into(),
+                "greet() {\n  echo hi\n}".into(),
+            )
+            .with_line_number(2)
+            .with_end_line(4),
+            Entry::new(
+                EntryType::EnvVar,
+                "EDITOR".into(),
+                "export EDITOR=vim".into(),
+            )
+            .with_line_number(6),
+        ];
+        let name_at = |line| Entry::at_line(&entries, line).map(|e| e.name.as_str());
+        assert_eq!(name_at(1), Some("ll"));
+        assert_eq!(name_at(2), Some("greet"));
+        assert_eq!(name_at(3), Some("greet"));
+        assert_eq!(name_at(4), Some("greet"));
+        assert_eq!(name_at(5), None);
+        assert_eq!(name_at(6), Some("EDITOR"));
+        assert_eq!(name_at(7), None);
+
+        let unread = Entry::new(EntryType::Alias, "x".into(), "alias x=y".into());
+        assert!(!unread.covers_line(1));
+    }
 }
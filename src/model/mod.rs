@@ -4,6 +4,11 @@ mod config;
 mod entry;
 mod shell;
 
-pub use config::{BackupConfig, CacheConfig, Config, FormatConfig, TypeOrder};
-pub use entry::{Entry, EntryType, ParseResult, ParseWarning};
+pub use config::{
+    BackupConfig, CacheConfig, CheckerConfig, ColorsConfig, Config, FormatConfig, LintConfig,
+    LintLevel, ParserConfig, QuoteStyle, TypeOrder,
+};
+pub use entry::{
+    Entry, EntryType, ParseResult, ParseWarning, Severity, WarningCode, PROMPT_VARIABLES,
+};
 pub use shell::ShellType;
@@ -10,7 +10,11 @@ use std::process::Command;
 pub enum ShellType {
     Bash,
     Zsh,
+    /// POSIX `sh` (`/etc/profile`, `~/.profile`)
+    PosixSh,
     PowerShell,
+    /// Plain `.env` files: `KEY=value` lines and comments, no shell code
+    DotEnv,
 }
 
 impl ShellType {
@@ -24,6 +28,9 @@ impl ShellType {
             if shell.contains("bash") {
                 return Some(ShellType::Bash);
             }
+            if shell.ends_with("/sh") || shell.ends_with("/dash") {
+                return Some(ShellType::PosixSh);
+            }
             if shell.contains("pwsh") || shell.contains("powershell") {
                 return Some(ShellType::PowerShell);
             }
@@ -64,6 +71,10 @@ impl ShellType {
             ShellType::Zsh => dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("~"))
                 .join(".zshrc"),
+            ShellType::PosixSh => dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("~"))
+                .join(".profile"),
+            ShellType::DotEnv => PathBuf::from(".env"),
             ShellType::PowerShell => {
                 // Prioritize $PROFILE environment variable (only available in PowerShell sessions)
                 if let Ok(profile_path) = env::var("PROFILE") {
@@ -116,24 +127,38 @@ impl ShellType {
                 }
 
                 // Fallback to standard paths
-                #[cfg(windows)]
-                {
-                    dirs::document_dir()
-                        .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from("~")))
-                        .join("PowerShell")
-                        .join("Microsoft.PowerShell_profile.ps1")
-                }
-                #[cfg(not(windows))]
-                {
-                    dirs::config_dir()
-                        .unwrap_or_else(|| {
-                            dirs::home_dir()
-                                .unwrap_or_else(|| PathBuf::from("~"))
-                                .join(".config")
-                        })
-                        .join("powershell")
-                        .join("Microsoft.PowerShell_profile.ps1")
-                }
+                powershell_user_dir().join("Microsoft.PowerShell_profile.ps1")
+            }
+        }
+    }
+
+    /// The files this shell conventionally reads its configuration from,
+    /// most commonly edited first. Unlike [`Self::default_config_path`] no
+    /// shell is queried, so for PowerShell these are the standard locations
+    /// of the four `$PROFILE` variants (current user and all users, current
+    /// host and all hosts).
+    pub fn candidate_config_paths(&self) -> Vec<PathBuf> {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("~"));
+        match self {
+            ShellType::Bash => [".bashrc", ".bash_profile", ".profile"]
+                .iter()
+                .map(|name| home.join(name))
+                .collect(),
+            ShellType::Zsh => [".zshrc", ".zshenv", ".zprofile"]
+                .iter()
+                .map(|name| home.join(name))
+                .collect(),
+            ShellType::PosixSh => vec![home.join(".profile")],
+            ShellType::DotEnv => vec![PathBuf::from(".env")],
+            ShellType::PowerShell => {
+                let user = powershell_user_dir();
+                let all_users = powershell_home_dir();
+                vec![
+                    user.join("Microsoft.PowerShell_profile.ps1"),
+                    user.join("profile.ps1"),
+                    all_users.join("Microsoft.PowerShell_profile.ps1"),
+                    all_users.join("profile.ps1"),
+                ]
             }
         }
     }
@@ -143,11 +168,52 @@ impl ShellType {
         match self {
             ShellType::Bash => "bash",
             ShellType::Zsh => "zsh",
+            ShellType::PosixSh => "sh",
             ShellType::PowerShell => "pwsh",
+            ShellType::DotEnv => "dotenv",
         }
     }
 }
 
+/// Where PowerShell keeps the profiles of the current user
+fn powershell_user_dir() -> PathBuf {
+    #[cfg(windows)]
+    {
+        dirs::document_dir()
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from("~")))
+            .join("PowerShell")
+    }
+    #[cfg(not(windows))]
+    {
+        dirs::config_dir()
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("~"))
+                    .join(".config")
+            })
+            .join("powershell")
+    }
+}
+
+/// `$PSHOME`, where PowerShell keeps the profiles of all users
+fn powershell_home_dir() -> PathBuf {
+    if let Ok(home) = env::var("PSHOME") {
+        return PathBuf::from(home);
+    }
+    #[cfg(windows)]
+    {
+        PathBuf::from(r"C:\Program Files\PowerShell\7")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/usr/local/microsoft/powershell/7")
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        PathBuf::from("/opt/microsoft/powershell/7")
+    }
+}
+
 impl std::fmt::Display for ShellType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
@@ -161,7 +227,9 @@ impl std::str::FromStr for ShellType {
         match s.to_lowercase().as_str() {
             "bash" => Ok(ShellType::Bash),
             "zsh" => Ok(ShellType::Zsh),
+            "sh" | "posix" => Ok(ShellType::PosixSh),
             "pwsh" | "powershell" => Ok(ShellType::PowerShell),
+            "dotenv" | "env" => Ok(ShellType::DotEnv),
             _ => Err(format!("Unknown shell type: {}", s)),
         }
     }
@@ -175,18 +243,22 @@ mod tests {
     fn test_shell_type_name() {
         assert_eq!(ShellType::Bash.name(), "bash");
         assert_eq!(ShellType::Zsh.name(), "zsh");
+        assert_eq!(ShellType::PosixSh.name(), "sh");
         assert_eq!(ShellType::PowerShell.name(), "pwsh");
+        assert_eq!(ShellType::DotEnv.name(), "dotenv");
     }
 
     #[test]
     fn test_shell_type_from_str() {
         assert_eq!("bash".parse::<ShellType>().unwrap(), ShellType::Bash);
         assert_eq!("zsh".parse::<ShellType>().unwrap(), ShellType::Zsh);
+        assert_eq!("sh".parse::<ShellType>().unwrap(), ShellType::PosixSh);
         assert_eq!("pwsh".parse::<ShellType>().unwrap(), ShellType::PowerShell);
         assert_eq!(
             "powershell".parse::<ShellType>().unwrap(),
             ShellType::PowerShell
         );
+        assert_eq!("dotenv".parse::<ShellType>().unwrap(), ShellType::DotEnv);
     }
 
     #[test]
@@ -197,4 +269,54 @@ mod tests {
         let zsh_path = ShellType::Zsh.default_config_path();
         assert!(zsh_path.to_string_lossy().contains(".zshrc"));
     }
+
+    #[test]
+    fn test_candidate_config_paths() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            ShellType::Bash.candidate_config_paths(),
+            [
+                home.join(".bashrc"),
+                home.join(".bash_profile"),
+                home.join(".profile")
+            ]
+        );
+        assert_eq!(
+            ShellType::Zsh.candidate_config_paths(),
+            [
+                home.join(".zshrc"),
+                home.join(".zshenv"),
+                home.join(".zprofile")
+            ]
+        );
+        assert_eq!(
+            ShellType::PosixSh.candidate_config_paths(),
+            [home.join(".profile")]
+        );
+        assert_eq!(
+            ShellType::DotEnv.candidate_config_paths(),
+            [PathBuf::from(".env")]
+        );
+    }
+
+    #[test]
+    fn test_candidate_config_paths_powershell() {
+        let paths = ShellType::PowerShell.candidate_config_paths();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Microsoft.PowerShell_profile.ps1",
+                "profile.ps1",
+                "Microsoft.PowerShell_profile.ps1",
+                "profile.ps1"
+            ]
+        );
+        assert_eq!(paths[0].parent(), paths[1].parent());
+        assert_eq!(paths[2].parent(), paths[3].parent());
+        assert_ne!(paths[0].parent(), paths[2].parent());
+    }
 }
@@ -27,9 +27,47 @@
 //! - When `count_control_end` > 0, decrease depth
 //! - Lines inside control structures are collected into `CodeBlockBuilder`
 
+/// The `;`-separated commands of a line, each with a leading `case` arm
+/// pattern (`*.txt)`, `(start)`) or `then`/`do`/`else` removed so the
+/// command after it is seen.
+fn commands(line: &str) -> impl Iterator<Item = &str> {
+    line.split(';').map(|part| {
+        let command = strip_case_pattern(part.trim());
+        ["then", "do", "else"]
+            .iter()
+            .find_map(|k| {
+                command
+                    .strip_prefix(k)
+                    .filter(|rest| rest.starts_with(char::is_whitespace))
+            })
+            .map_or(command, str::trim_start)
+    })
+}
+
+/// `command` without a leading `case` arm pattern. Only a `)` reached before
+/// any `(` ends a pattern, so `f()` and `$(cmd)` are left alone.
+fn strip_case_pattern(command: &str) -> &str {
+    let pattern = command.strip_prefix('(').unwrap_or(command);
+    match pattern.find(['(', ')']) {
+        Some(end) if end > 0 && pattern[end..].starts_with(')') => pattern[end + 1..].trim_start(),
+        _ => command,
+    }
+}
+
+/// Whether `command` starts with the word `keyword` (`done < file`,
+/// `fi)`), not merely with the same letters (`ifconfig`, `done=1`)
+fn starts_with_keyword(command: &str, keyword: &str) -> bool {
+    command.strip_prefix(keyword).is_some_and(|rest| {
+        rest.chars()
+            .next()
+            .is_none_or(|c| c.is_whitespace() || matches!(c, ';' | ')' | '|' | '&' | '<' | '>'))
+    })
+}
+
 /// Count the number of control structure openings in a line.
 ///
-/// Matches: `if`, `while`, `until`, `for`, `case`, `select`
+/// Matches: `if`, `while`, `until`, `for`, `case`, `select`, also after a
+/// `case` arm pattern (`start) if [ -n "$x" ]; then`)
 ///
 /// # Arguments
 ///
@@ -47,30 +85,16 @@
 /// assert_eq!(count_control_start("echo hello"), 0);
 /// ```
 pub fn count_control_start(line: &str) -> usize {
-    let mut count = 0;
-    // Split by semicolon and check each part
-    for part in line.split(';') {
-        let part = part.trim();
-        let starts_control = part.starts_with("if ")
-            || part == "if"
-            || part.starts_with("while ")
-            || part == "while"
-            || part.starts_with("until ")
-            || part == "until"
-            || part.starts_with("for ")
-            || part.starts_with("case ")
-            || part.starts_with("select ");
-
-        if starts_control {
-            count += 1;
-        }
-    }
-    count
+    const KEYWORDS: [&str; 6] = ["if", "while", "until", "for", "case", "select"];
+    commands(line)
+        .filter(|command| KEYWORDS.iter().any(|k| starts_with_keyword(command, k)))
+        .count()
 }
 
 /// Count the number of control structure closings in a line.
 ///
-/// Matches: `fi`, `done`, `esac`
+/// Matches: `fi`, `done`, `esac` at the start of a command, so a one-line
+/// `for ...; do if ...; then ...; fi; done` closes both structures.
 ///
 /// # Arguments
 ///
@@ -78,7 +102,7 @@ pub fn count_control_start(line: &str) -> usize {
 ///
 /// # Returns
 ///
-/// Number of control structure end keywords found (max 1 per line for simple cases).
+/// Number of control structure end keywords found.
 ///
 /// # Examples
 ///
@@ -89,34 +113,10 @@ pub fn count_control_start(line: &str) -> usize {
 /// assert_eq!(count_control_end("echo done"), 0);  // 'done' not at start
 /// ```
 pub fn count_control_end(line: &str) -> usize {
-    let mut count = 0;
-    // Split by semicolon and check each part
-    for part in line.split(';') {
-        let part = part.trim();
-        let ends_control = part == "fi"
-            || part.starts_with("fi ")
-            || part.starts_with("fi;")
-            || part == "done"
-            || part.starts_with("done ")
-            || part.starts_with("done;")
-            || part == "esac"
-            || part.starts_with("esac ")
-            || part.starts_with("esac;");
-
-        if ends_control {
-            count += 1;
-        }
-    }
-    // Also check the original line for these keywords at word boundaries
-    // This handles cases like "fi" at end of line
-    let words: Vec<&str> = line.split_whitespace().collect();
-    for word in words {
-        let word = word.trim_end_matches(';');
-        if count == 0 && (word == "fi" || word == "done" || word == "esac") {
-            count += 1;
-        }
-    }
-    count.min(1) // Avoid double counting - max 1 per line for simple cases
+    const KEYWORDS: [&str; 3] = ["fi", "done", "esac"];
+    commands(line)
+        .filter(|command| KEYWORDS.iter().any(|k| starts_with_keyword(command, k)))
+        .count()
 }
 
 #[cfg(test)]
@@ -169,4 +169,31 @@ mod tests {
         assert_eq!(count_control_end("echo hello"), 0);
         assert_eq!(count_control_end("export PATH=value"), 0);
     }
+
+    #[test]
+    fn test_keywords_after_case_arm_pattern() {
+        assert_eq!(count_control_start("start) if [ -n \"$x\" ]; then"), 1);
+        assert_eq!(count_control_start("(stop) while true; do"), 1);
+        assert_eq!(count_control_end("*.txt) echo text ;;"), 0);
+        // Opened and closed within the arm
+        let arm = "*) for f in *; do echo \"$f\"; done ;;";
+        assert_eq!((count_control_start(arm), count_control_end(arm)), (1, 1));
+        // Function definitions and command substitutions are not patterns
+        assert_eq!(count_control_start("f() { if true; then :; fi; }"), 0);
+    }
+
+    #[test]
+    fn test_keywords_are_whole_words() {
+        assert_eq!(count_control_start("ifconfig eth0"), 0);
+        assert_eq!(count_control_end("echo done"), 0);
+        assert_eq!(count_control_end("done=1"), 0);
+        assert_eq!(count_control_end("done | sort"), 1);
+        assert_eq!(count_control_end("$(for x in a; do echo; done)"), 0);
+    }
+
+    #[test]
+    fn test_one_line_nested_structures() {
+        let line = "for f in *; do if [ -d \"$f\" ]; then echo; fi; done";
+        assert_eq!((count_control_start(line), count_control_end(line)), (2, 2));
+    }
 }
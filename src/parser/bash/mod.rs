@@ -11,7 +11,12 @@
 //! | Function | `func() { ... }` | ✅ Brace counting |
 //! | Source | `source file` or `. file` | ❌ |
 //! | Comment | `# text` | ✅ Adjacent merging |
+//! | Comment | `#!/bin/bash` on line 1 ([`Entry::is_shebang`]) | ❌ Never merged |
 //! | Code | Control structures, other | ✅ Keyword tracking |
+//! | Code | `# >>> wenv:ignore >>>`, conda init, ... ([`regions`](crate::parser::regions)) | ✅ Verbatim to the region's end |
+//!
+//! A top-level `export -f name` line stays Code and marks the function
+//! `name` defined above it as exported ([`Entry::exported`]).
 //!
 //! ## Module Structure
 //!
@@ -35,21 +40,33 @@
 //! | Function | `func() {` | brace_count = 0 |
 //! | Code Block | `if`/`while`/`for`/`case` | `fi`/`done`/`esac` |
 //! | Alias/Env | Odd single quotes | Even single quotes |
+//! | Here-document | `<<EOF`, `<<-EOF`, `<<'EOF'` | Terminator line |
 //! | Comment | Line starts with `#` | Non-`#` line |
+//!
+//! Here-document bodies are taken verbatim: no brace, quote or control keyword
+//! counting is done until the terminator line, even when the here-document is
+//! nested inside a function or control block.
 
 pub mod control;
 pub mod parsers;
 pub mod patterns;
 
-use crate::model::{Entry, EntryType, ParseResult, ShellType};
+use crate::model::{Entry, EntryType, ParseResult, ShellType, WarningCode};
 use crate::parser::builders::{
-    count_braces_outside_quotes, count_parens_outside_quotes, CommentBlockBuilder,
+    count_braces_outside_quotes, count_parens_outside_quotes, split_lines, CommentBlockBuilder,
+    QuoteState, QuotedValueBuilder,
 };
 use crate::parser::pending::{BoundaryType, MergeType, PendingBlock};
+use crate::parser::regions::find_region;
 use crate::parser::Parser;
 
 use control::{count_control_end, count_control_start};
-use parsers::{detect_function_start, try_parse_alias, try_parse_env, try_parse_source};
+use parsers::{
+    detect_function_start, detect_heredoc_start, is_heredoc_end, try_parse_alias, try_parse_array,
+    try_parse_env, try_parse_setopt, try_parse_source, try_parse_trap, try_parse_unset,
+    try_parse_zstyle,
+};
+use patterns::EXPORT_FUNC_RE;
 
 use crate::parser::ParseEvent;
 
@@ -71,12 +88,64 @@ use crate::parser::ParseEvent;
 ///     println!("{}: {}", entry.entry_type, entry.name);
 /// }
 /// ```
-pub struct BashParser;
+pub struct BashParser {
+    /// Reject Bash/Zsh-only function forms (`function name {`, `() {`)
+    posix: bool,
+    /// Merge a single comment into the entry or code below it
+    merge_comments: bool,
+    /// Record the comment above each structured entry in `attached_comment`
+    attach_comments: bool,
+}
 
 impl BashParser {
     /// Create a new Bash parser instance.
     pub fn new() -> Self {
-        Self
+        Self {
+            posix: false,
+            merge_comments: true,
+            attach_comments: false,
+        }
+    }
+
+    /// Create a parser restricted to POSIX `sh` function syntax.
+    ///
+    /// Only `name() { ... }` starts a function; the `function` keyword and
+    /// anonymous `() {` forms are treated as plain code.
+    pub fn posix() -> Self {
+        Self {
+            posix: true,
+            ..Self::new()
+        }
+    }
+
+    /// Enable or disable merging a single comment into the entry below it.
+    ///
+    /// With merging off, every comment becomes a standalone Comment entry,
+    /// giving a strictly positional view of the file.
+    pub fn with_comment_merging(mut self, merge_comments: bool) -> Self {
+        self.merge_comments = merge_comments;
+        self
+    }
+
+    /// Also record the comment directly above each structured entry in its
+    /// `attached_comment`, whether or not it merged into the entry's value.
+    pub fn with_attached_comments(mut self, attach_comments: bool) -> Self {
+        self.attach_comments = attach_comments;
+        self
+    }
+
+    /// Whether a pending block is a single comment that merges down
+    fn merges_down(&self, pending: &PendingBlock) -> bool {
+        self.merge_comments && pending.comment_count == 1
+    }
+
+    /// Detect a function start, honoring POSIX restrictions.
+    fn detect_function(&self, line: &str) -> Option<(String, bool)> {
+        let (name, is_anonymous) = detect_function_start(line)?;
+        if self.posix && (is_anonymous || line.split_whitespace().next() == Some("function")) {
+            return None;
+        }
+        Some((name, is_anonymous))
     }
 
     /// Convert an entry to a pending block that can absorb trailing blanks.
@@ -130,7 +199,7 @@ impl BashParser {
                     ),
                     entry,
                 )
-            } else if pending.comment_count == 1 {
+            } else if parser.merges_down(&pending) {
                 // Single comment → merge downward
                 let pending_content = pending.raw_content();
                 let merged_value = format!("{}\n{}", pending_content, entry.value);
@@ -185,7 +254,18 @@ impl BashParser {
                 // Store complete function definition in value (Raw Value Architecture)
                 (name, raw_content)
             }
-            EntryType::Alias | EntryType::EnvVar => {
+            EntryType::Trap => {
+                // The signals follow the handler, on its last line
+                let name = parsers::trap_signals(&raw_content)
+                    .unwrap_or_else(|| format!("L{}", block.start_line));
+                (name, raw_content)
+            }
+            EntryType::Alias
+            | EntryType::EnvVar
+            | EntryType::Array
+            | EntryType::Unset
+            | EntryType::Setopt
+            | EntryType::Zstyle => {
                 let name = block
                     .name
                     .unwrap_or_else(|| format!("L{}", block.start_line));
@@ -233,6 +313,40 @@ impl BashParser {
     }
 }
 
+/// Set `exported` on functions named by a later top-level `export -f` line.
+///
+/// Only Code entries consisting of the `export -f` line (plus merged comments
+/// or blank lines) count; the same text inside a function body or control
+/// block is part of a larger entry and is ignored.
+fn mark_exported_functions(entries: &mut [Entry]) {
+    for i in 0..entries.len() {
+        if entries[i].entry_type != EntryType::Code {
+            continue;
+        }
+        let mut lines = entries[i]
+            .value
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let (Some(line), None) = (lines.next(), lines.next()) else {
+            continue;
+        };
+        let Some(caps) = EXPORT_FUNC_RE.captures(line) else {
+            continue;
+        };
+        let names: Vec<String> = caps[1].split_whitespace().map(str::to_string).collect();
+        for name in names {
+            if let Some(func) = entries[..i]
+                .iter_mut()
+                .rev()
+                .find(|e| e.entry_type == EntryType::Function && e.name == name)
+            {
+                func.exported = true;
+            }
+        }
+    }
+}
+
 impl Default for BashParser {
     fn default() -> Self {
         Self::new()
@@ -254,31 +368,60 @@ impl Parser for BashParser {
         // Track control structure depth (needed for control block detection)
         let mut control_depth: usize = 0;
 
+        // Here-document opened inside a function/control block: (terminator, strip_tabs)
+        let mut nested_heredoc: Option<(String, bool)> = None;
+
         // === Main parsing loop ===
-        // Use split('\n') instead of lines() to preserve trailing empty lines.
-        // lines() treats '\n' as a line terminator, so "a\nb\n" → ["a", "b"]
-        // But for raw_line format (where '\n' is a separator), we need "a\nb\n" → ["a", "b", ""]
-        // However, a file ending with '\n' is just proper file termination, not an extra line.
-        // So we strip the final empty element only if content ends with '\n'.
-        let lines_vec: Vec<&str> = content.split('\n').collect();
-        let lines_to_process: &[&str] = if lines_vec.last() == Some(&"") && content.ends_with('\n')
-        {
-            &lines_vec[..lines_vec.len() - 1]
-        } else {
-            &lines_vec[..]
-        };
+        // Trailing blank lines are kept; a final '\n' only terminates the last line
+        let lines_to_process = split_lines(content);
 
         for (line_num, line) in lines_to_process.iter().enumerate() {
             let line_number = line_num + 1;
             let trimmed = line.trim();
 
+            // A shebang is its own entry, never merged into the one below
+            if line_number == 1 && line.starts_with("#!") {
+                result.add_entry(
+                    Entry::new(EntryType::Comment, "L1".to_string(), line.to_string())
+                        .with_line_number(1),
+                );
+                continue;
+            }
+
             // ------------------------------------------------------------------
             // Handle active multi-line block (function, control, alias, env)
             // ------------------------------------------------------------------
             if let Some(ref mut block) = active_block {
                 block.add_line(line, line_number);
 
+                // Inside a nested here-document body: skip all counting
+                if let Some((terminator, strip_tabs)) = &nested_heredoc {
+                    if is_heredoc_end(line, terminator, *strip_tabs) {
+                        nested_heredoc = None;
+                    }
+                    continue;
+                }
+
                 match &mut block.boundary {
+                    // Top-level here-document: complete on terminator line
+                    BoundaryType::Heredoc {
+                        terminator,
+                        strip_tabs,
+                    } if is_heredoc_end(line, terminator, *strip_tabs) => {
+                        let completed = active_block.take().unwrap();
+                        pending_entry = Some(PendingBlock {
+                            lines: completed.lines,
+                            start_line: completed.start_line,
+                            end_line: completed.end_line,
+                            boundary: BoundaryType::AdjacentMerging {
+                                merge_type: MergeType::CodeWithBlanks,
+                            },
+                            entry_hint: Some(EntryType::Code),
+                            name: None,
+                            value: None,
+                            comment_count: 0,
+                        });
+                    }
                     BoundaryType::BraceCounting {
                         ref mut brace_count,
                     } => {
@@ -296,8 +439,13 @@ impl Parser for BashParser {
                     BoundaryType::QuoteCounting {
                         ref mut quote_count,
                     } => {
-                        // Multi-line alias or env
-                        *quote_count += line.matches('\'').count();
+                        // Multi-line alias or env, inside quotes while the count is odd
+                        let state = if *quote_count % 2 == 1 {
+                            QuoteState::Single
+                        } else {
+                            QuoteState::Unquoted
+                        };
+                        *quote_count += QuotedValueBuilder::count_single_quotes_from(line, state);
 
                         if *quote_count % 2 == 0 {
                             let completed = active_block.take().unwrap();
@@ -325,13 +473,22 @@ impl Parser for BashParser {
                         // Control structure
                         let end_count = count_control_end(trimmed);
                         let start_count = count_control_start(trimmed);
-                        *depth = (*depth).saturating_sub(end_count);
-                        *depth += start_count;
+                        // Openings first: `if ...; then ...; fi` is balanced
+                        *depth = (*depth + start_count).saturating_sub(end_count);
 
                         if *depth == 0 {
                             // Reset external control_depth to sync state
                             control_depth = 0;
 
+                            // `done <<EOF`: the block continues with a here-document body
+                            if let Some((terminator, strip_tabs)) = detect_heredoc_start(trimmed) {
+                                block.boundary = BoundaryType::Heredoc {
+                                    terminator,
+                                    strip_tabs,
+                                };
+                                continue;
+                            }
+
                             // Make result pending for trailing blank absorption
                             let completed = active_block.take().unwrap();
                             pending_entry = Some(PendingBlock {
@@ -348,26 +505,108 @@ impl Parser for BashParser {
                             });
                         }
                     }
+                    BoundaryType::Guarded { end_line } if *end_line == line_number => {
+                        let entry = self.build_entry_from_pending(active_block.take().unwrap());
+                        result.add_entry(entry);
+                    }
                     _ => {}
                 }
+
+                // Track here-documents opened inside function/control bodies
+                if let Some(block) = &active_block {
+                    if matches!(
+                        block.boundary,
+                        BoundaryType::BraceCounting { .. } | BoundaryType::KeywordTracking { .. }
+                    ) {
+                        nested_heredoc = detect_heredoc_start(trimmed);
+                    }
+                }
                 continue;
             }
 
+            // ------------------------------------------------------------------
+            // Preserved region (`# >>> wenv:ignore >>>`, conda init, ...)
+            // ------------------------------------------------------------------
+            if let Some(region) = find_region(&lines_to_process, line_num) {
+                if let Some(e) = self.flush_pending_comment_code(&mut pending_entry) {
+                    result.add_entry(e);
+                }
+                if !region.closed {
+                    result.add_warning(crate::model::ParseWarning::new(
+                        WarningCode::UnclosedRegion,
+                        line_number,
+                        format!("Unclosed {} block at end of file", region.region.name),
+                        "",
+                    ));
+                }
+                let block = PendingBlock::guarded(line_number, line, region.end + 1);
+                if region.end == line_num {
+                    // Unclosed marker on the last line
+                    result.add_entry(self.build_entry_from_pending(block));
+                } else {
+                    active_block = Some(block);
+                }
+                continue;
+            }
+
+            // ------------------------------------------------------------------
+            // Check for here-document start (e.g., `cat <<EOF > file`)
+            // ------------------------------------------------------------------
+            if !CommentBlockBuilder::is_standalone_comment(trimmed)
+                && count_control_start(trimmed) == 0
+                && self.detect_function(trimmed).is_none()
+            {
+                if let Some((terminator, strip_tabs)) = detect_heredoc_start(trimmed) {
+                    let (merged_first_line, start_line) =
+                        if let Some(pending) = pending_entry.take() {
+                            if pending.is_structured_entry() || !self.merges_down(&pending) {
+                                // Structured entry, multiple comments or blanks - don't merge
+                                result.add_entry(self.build_entry_from_pending(pending));
+                                (line.to_string(), line_number)
+                            } else {
+                                // Single comment can merge down
+                                let merged = format!("{}\n{}", pending.raw_content(), line);
+                                (merged, pending.start_line)
+                            }
+                        } else {
+                            (line.to_string(), line_number)
+                        };
+
+                    active_block = Some(PendingBlock {
+                        lines: vec![merged_first_line],
+                        start_line,
+                        end_line: line_number,
+                        boundary: BoundaryType::Heredoc {
+                            terminator,
+                            strip_tabs,
+                        },
+                        entry_hint: Some(EntryType::Code),
+                        name: None,
+                        value: None,
+                        comment_count: 0,
+                    });
+                    continue;
+                }
+            }
+
             // ------------------------------------------------------------------
             // Check for control structure start/continuation
             // ------------------------------------------------------------------
             let prev_depth = control_depth;
-            control_depth = control_depth.saturating_sub(count_control_end(trimmed));
-            control_depth += count_control_start(trimmed);
+            // Openings first, so a one-line `if ...; fi` doesn't open a block
+            control_depth = (control_depth + count_control_start(trimmed))
+                .saturating_sub(count_control_end(trimmed));
 
             if control_depth > 0 || (prev_depth > 0 && control_depth == 0) {
                 // Start control block - merge pending Comment/Code if present
                 if active_block.is_none() && prev_depth == 0 && control_depth > 0 {
                     if let Some(pending) = pending_entry.take() {
-                        if matches!(
-                            pending.entry_hint,
-                            Some(EntryType::Comment) | Some(EntryType::Code)
-                        ) {
+                        let mergeable = match pending.entry_hint {
+                            Some(EntryType::Comment) => self.merge_comments,
+                            Some(EntryType::Code) => true,
+                            _ => false,
+                        };
+                        if mergeable {
                             // Seed block with pending content
                             let mut lines = pending.lines;
                             lines.push(line.to_string());
@@ -393,6 +632,7 @@ impl Parser for BashParser {
                         active_block =
                             Some(PendingBlock::control(line_number, line, control_depth));
                     }
+                    nested_heredoc = detect_heredoc_start(trimmed);
                 }
                 continue;
             }
@@ -476,7 +716,7 @@ impl Parser for BashParser {
                             if pending.is_structured_entry() {
                                 result.add_entry(self.build_entry_from_pending(pending));
                                 (first_line, line_number)
-                            } else if pending.comment_count == 1 {
+                            } else if self.merges_down(&pending) {
                                 // Single comment can merge down
                                 let merged = format!("{}\n{}", pending.raw_content(), first_line);
                                 (merged, pending.start_line)
@@ -531,7 +771,7 @@ impl Parser for BashParser {
                             if pending.is_structured_entry() {
                                 result.add_entry(self.build_entry_from_pending(pending));
                                 (first_line, line_number)
-                            } else if pending.comment_count == 1 {
+                            } else if self.merges_down(&pending) {
                                 // Single comment can merge down
                                 let merged = format!("{}\n{}", pending.raw_content(), first_line);
                                 (merged, pending.start_line)
@@ -560,6 +800,60 @@ impl Parser for BashParser {
                 ParseEvent::None => {}
             }
 
+            // Try array (`arr=(...)`, `declare -A map=(...)`)
+            match try_parse_array(trimmed, line_number) {
+                ParseEvent::Complete(entry) => {
+                    // Merge pending entry (if exists) with this structured entry
+                    let (pending_entry_to_add, merged) =
+                        Self::merge_pending_with_structured(pending_entry.take(), entry, self);
+                    if let Some(pending_e) = pending_entry_to_add {
+                        result.add_entry(pending_e);
+                    }
+                    // Set merged entry as pending to absorb trailing blanks
+                    pending_entry = Some(Self::entry_to_trailing_pending(merged));
+                    continue;
+                }
+                ParseEvent::Started {
+                    entry_type,
+                    name,
+                    boundary,
+                    first_line,
+                } => {
+                    // For multi-line array, check pending merge rules
+                    let (merged_first_line, start_line) =
+                        if let Some(pending) = pending_entry.take() {
+                            // If pending has stored value, it's a trailing structured entry - flush it
+                            if pending.is_structured_entry() {
+                                result.add_entry(self.build_entry_from_pending(pending));
+                                (first_line, line_number)
+                            } else if self.merges_down(&pending) {
+                                // Single comment can merge down
+                                let merged = format!("{}\n{}", pending.raw_content(), first_line);
+                                (merged, pending.start_line)
+                            } else {
+                                // Multiple comments or pure blank - don't merge
+                                result.add_entry(self.build_entry_from_pending(pending));
+                                (first_line, line_number)
+                            }
+                        } else {
+                            (first_line, line_number)
+                        };
+
+                    active_block = Some(PendingBlock {
+                        lines: vec![merged_first_line],
+                        start_line,
+                        end_line: line_number,
+                        boundary,
+                        entry_hint: Some(entry_type),
+                        name: Some(name),
+                        value: None,
+                        comment_count: 0,
+                    });
+                    continue;
+                }
+                ParseEvent::None => {}
+            }
+
             // Try source
             match try_parse_source(trimmed, line_number) {
                 ParseEvent::Complete(entry) => {
@@ -581,8 +875,83 @@ impl Parser for BashParser {
                 ParseEvent::None => {}
             }
 
+            // Try unset (`unalias ll`, `unset VAR`)
+            if let ParseEvent::Complete(entry) = try_parse_unset(trimmed, line_number) {
+                let (pending_entry_to_add, merged) =
+                    Self::merge_pending_with_structured(pending_entry.take(), entry, self);
+                if let Some(pending_e) = pending_entry_to_add {
+                    result.add_entry(pending_e);
+                }
+                pending_entry = Some(Self::entry_to_trailing_pending(merged));
+                continue;
+            }
+
+            // Try Zsh options and styles (`setopt autocd`, `zstyle ':completion:*' menu select`)
+            let zsh_event = match try_parse_setopt(trimmed, line_number) {
+                ParseEvent::None => try_parse_zstyle(trimmed, line_number),
+                event => event,
+            };
+            if let ParseEvent::Complete(entry) = zsh_event {
+                let (pending_entry_to_add, merged) =
+                    Self::merge_pending_with_structured(pending_entry.take(), entry, self);
+                if let Some(pending_e) = pending_entry_to_add {
+                    result.add_entry(pending_e);
+                }
+                pending_entry = Some(Self::entry_to_trailing_pending(merged));
+                continue;
+            }
+
+            // Try trap (`trap 'cleanup' EXIT`)
+            match try_parse_trap(trimmed, line_number) {
+                ParseEvent::Complete(entry) => {
+                    let (pending_entry_to_add, merged) =
+                        Self::merge_pending_with_structured(pending_entry.take(), entry, self);
+                    if let Some(pending_e) = pending_entry_to_add {
+                        result.add_entry(pending_e);
+                    }
+                    pending_entry = Some(Self::entry_to_trailing_pending(merged));
+                    continue;
+                }
+                ParseEvent::Started {
+                    entry_type,
+                    name,
+                    boundary,
+                    first_line,
+                } => {
+                    // For a multi-line handler, check pending merge rules
+                    let (merged_first_line, start_line) =
+                        if let Some(pending) = pending_entry.take() {
+                            if pending.is_structured_entry() {
+                                result.add_entry(self.build_entry_from_pending(pending));
+                                (first_line, line_number)
+                            } else if self.merges_down(&pending) {
+                                let merged = format!("{}\n{}", pending.raw_content(), first_line);
+                                (merged, pending.start_line)
+                            } else {
+                                result.add_entry(self.build_entry_from_pending(pending));
+                                (first_line, line_number)
+                            }
+                        } else {
+                            (first_line, line_number)
+                        };
+
+                    active_block = Some(PendingBlock {
+                        lines: vec![merged_first_line],
+                        start_line,
+                        end_line: line_number,
+                        boundary,
+                        entry_hint: Some(entry_type),
+                        name: Some(name),
+                        value: None,
+                        comment_count: 0,
+                    });
+                    continue;
+                }
+                ParseEvent::None => {}
+            }
+
             // Try function
-            if let Some((func_name, is_anonymous)) = detect_function_start(trimmed) {
+            if let Some((func_name, is_anonymous)) = self.detect_function(trimmed) {
                 let (open, close) = count_braces_outside_quotes(trimmed);
                 let brace_count = (open as i32).saturating_sub(close as i32);
 
@@ -622,7 +991,7 @@ impl Parser for BashParser {
                             if pending.is_structured_entry() {
                                 result.add_entry(self.build_entry_from_pending(pending));
                                 (line.to_string(), line_number)
-                            } else if pending.comment_count == 1 {
+                            } else if self.merges_down(&pending) {
                                 // Single comment can merge down
                                 let merged = format!("{}\n{}", pending.raw_content(), line);
                                 (merged, pending.start_line)
@@ -646,6 +1015,7 @@ impl Parser for BashParser {
                         comment_count: 0,
                     };
                     active_block = Some(func_block);
+                    nested_heredoc = detect_heredoc_start(trimmed);
                 }
                 continue;
             }
@@ -663,7 +1033,7 @@ impl Parser for BashParser {
                     if pending.is_structured_entry() {
                         result.add_entry(self.build_entry_from_pending(pending));
                         (line.to_string(), line_number)
-                    } else if pending.comment_count == 1 {
+                    } else if self.merges_down(&pending) {
                         // Single comment can merge down
                         let merged = format!("{}\n{}", pending.raw_content(), line);
                         (merged, pending.start_line)
@@ -696,7 +1066,7 @@ impl Parser for BashParser {
             match &mut pending_entry {
                 Some(pending) if pending.entry_hint == Some(EntryType::Comment) => {
                     // Only single comment can merge down to code
-                    if pending.comment_count == 1 {
+                    if self.merges_down(pending) {
                         // Comment + non-blank Code → merge and upgrade to Code
                         pending.add_line(line, line_number);
                         pending.upgrade_to_code();
@@ -737,13 +1107,40 @@ impl Parser for BashParser {
 
         // Warn about unclosed active block
         if let Some(block) = active_block {
-            let msg = match block.entry_hint {
-                Some(EntryType::Function) => "Unclosed function definition at end of file",
-                Some(EntryType::Alias) => "Unclosed multi-line alias at end of file",
-                Some(EntryType::EnvVar) => "Unclosed multi-line export at end of file",
-                _ => "Unclosed block at end of file",
+            let (code, msg) = match block.entry_hint {
+                _ if nested_heredoc.is_some()
+                    || matches!(block.boundary, BoundaryType::Heredoc { .. }) =>
+                {
+                    (
+                        WarningCode::UnclosedHeredoc,
+                        "Unclosed here-document at end of file",
+                    )
+                }
+                Some(EntryType::Function) => (
+                    WarningCode::UnclosedFunction,
+                    "Unclosed function definition at end of file",
+                ),
+                Some(EntryType::Alias) => (
+                    WarningCode::UnclosedAlias,
+                    "Unclosed multi-line alias at end of file",
+                ),
+                Some(EntryType::EnvVar) => (
+                    WarningCode::UnclosedExport,
+                    "Unclosed multi-line export at end of file",
+                ),
+                _ => (WarningCode::UnclosedBlock, "Unclosed block at end of file"),
             };
-            result.add_warning(crate::model::ParseWarning::new(block.start_line, msg, ""));
+            result.add_warning(crate::model::ParseWarning::new(
+                code,
+                block.start_line,
+                msg,
+                "",
+            ));
+        }
+
+        mark_exported_functions(&mut result.entries);
+        if self.attach_comments {
+            result.record_attached_comments();
         }
 
         result
@@ -754,8 +1151,6 @@ impl Parser for BashParser {
     }
 }
 
-impl BashParser {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -868,6 +1263,166 @@ mod tests {
         assert_eq!(funcs[0].end_line, Some(4));
     }
 
+    #[test]
+    fn test_function_declaration_forms() {
+        let parser = BashParser::new();
+        for content in [
+            "function foo {\n  echo hi\n}\n",
+            "function foo() {\n  echo hi\n}\n",
+            "function foo () {\n  echo hi\n}\n",
+            "foo() {\n  echo hi\n}\n",
+            "foo ()\n{\n  echo hi\n}\n",
+            "function foo\n{\n  echo hi\n}\n",
+        ] {
+            let result = parser.parse(content);
+            assert_eq!(result.entries.len(), 1, "{:?}", content);
+            let func = &result.entries[0];
+            assert_eq!(func.entry_type, EntryType::Function, "{:?}", content);
+            assert_eq!(func.name, "foo", "{:?}", content);
+            assert_eq!(func.value, content.trim_end(), "{:?}", content);
+            assert_eq!(func.line_number, Some(1));
+            assert_eq!(func.end_line, Some(content.lines().count()));
+        }
+    }
+
+    #[test]
+    fn test_eof_without_newline_matches_terminated_file() {
+        let parser = BashParser::new();
+        for content in [
+            "greet() {\n  echo hi\n}",
+            "# Greets\ngreet() {\n  if true; then\n    echo hi\n  fi\n}",
+            "alias a=b\ngreet()\n{\n  echo hi\n} # done",
+            "alias ll='ls\n  -la'",
+            "# Listing\nalias ll=\"ls \\\n  -la\"",
+            "export PATH=\"$HOME/bin:\n$PATH\"",
+            "files=(\n  a\n  b\n)",
+        ] {
+            let unterminated = parser.parse(content);
+            let terminated = parser.parse(&format!("{}\n", content));
+            assert_eq!(unterminated.entries.len(), terminated.entries.len());
+            for (a, b) in unterminated.entries.iter().zip(&terminated.entries) {
+                assert_eq!(a.entry_type, b.entry_type, "{:?}", content);
+                assert_eq!(a.value, b.value, "{:?}", content);
+                assert_eq!(
+                    (a.line_number, a.end_line),
+                    (b.line_number, b.end_line),
+                    "{:?}",
+                    content
+                );
+            }
+
+            let last = unterminated.entries.last().unwrap();
+            assert_eq!(
+                last.end_line,
+                Some(content.lines().count()),
+                "{:?}",
+                content
+            );
+            assert!(content.ends_with(&last.value), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn test_export_f_marks_function() {
+        let parser = BashParser::new();
+        let content = "greet() {\n  echo hi\n}\nbye() { echo bye; }\nlocal_only() { :; }\n\n# share with subshells\nexport -f greet bye\n";
+        let result = parser.parse(content);
+
+        let exported: Vec<_> = result
+            .entries
+            .iter()
+            .filter(|e| e.exported)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(exported, ["greet", "bye"]);
+
+        // The export line itself is kept as code
+        let export = result.entries.last().unwrap();
+        assert_eq!(export.entry_type, EntryType::Code);
+        assert!(export.value.ends_with("export -f greet bye"));
+    }
+
+    #[test]
+    fn test_export_f_inside_other_constructs_is_ignored() {
+        let parser = BashParser::new();
+        for content in [
+            "greet() { echo hi; }\nsetup() {\n  export -f greet\n}\n",
+            "greet() { echo hi; }\nif true; then\n  export -f greet\nfi\n",
+            "greet() { echo hi; }\n[ -n \"$BASH\" ] && export -f greet\n",
+            "export -f greet\ngreet() { echo hi; }\n",
+            "greet() { echo hi; }\nexport -fn greet\n",
+        ] {
+            let result = parser.parse(content);
+            assert!(result.entries.iter().all(|e| !e.exported), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn test_one_line_function_keyword_form() {
+        let parser = BashParser::new();
+        let result = parser.parse("function foo { echo hi; }\nfunction bar() { echo bye; }\n");
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].entry_type, EntryType::Function);
+        assert_eq!(result.entries[0].name, "foo");
+        assert_eq!(result.entries[0].value, "function foo { echo hi; }");
+        assert_eq!(result.entries[1].name, "bar");
+        assert_eq!(result.entries[1].end_line, Some(2));
+    }
+
+    #[test]
+    fn test_function_names_with_dashes() {
+        let parser = BashParser::new();
+        let result = parser.parse("function git-clean {\n  git clean -fd\n}\nmy.util() { :; }\n");
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["git-clean", "my.util"]);
+        assert!(result
+            .entries
+            .iter()
+            .all(|e| e.entry_type == EntryType::Function));
+    }
+
+    #[test]
+    fn test_comment_merging_can_be_disabled() {
+        let content = "# list files\nalias ll='ls -la'\n# greet\ngreet() {\n  echo hi\n}\n# note\necho done\n# loop\nfor f in a; do\n  echo $f\ndone\n";
+        let describe = |parser: BashParser| {
+            parser
+                .parse(content)
+                .entries
+                .iter()
+                .map(|e| (e.entry_type, e.line_number, e.end_line))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            describe(BashParser::new()),
+            [
+                (EntryType::Alias, Some(1), Some(2)),
+                (EntryType::Function, Some(3), Some(6)),
+                (EntryType::Code, Some(7), Some(8)),
+                (EntryType::Code, Some(9), Some(12)),
+            ]
+        );
+        assert_eq!(
+            describe(BashParser::new().with_comment_merging(false)),
+            [
+                (EntryType::Comment, Some(1), Some(1)),
+                (EntryType::Alias, Some(2), Some(2)),
+                (EntryType::Comment, Some(3), Some(3)),
+                (EntryType::Function, Some(4), Some(6)),
+                (EntryType::Comment, Some(7), Some(7)),
+                (EntryType::Code, Some(8), Some(8)),
+                (EntryType::Comment, Some(9), Some(9)),
+                (EntryType::Code, Some(10), Some(12)),
+            ]
+        );
+
+        let unmerged = BashParser::new().with_comment_merging(false).parse(content);
+        assert_eq!(unmerged.entries[1].value, "alias ll='ls -la'");
+        assert_eq!(unmerged.entries[0].value, "# list files");
+    }
+
     #[test]
     fn test_adjacent_comments_merged_with_alias() {
         // NEW BEHAVIOR: Multiple comments don't merge with alias
@@ -1249,6 +1804,97 @@ mod tests {
         assert_eq!(alias.value, "alias -g ll='ls -la'");
     }
 
+    #[test]
+    fn test_alias_with_escaped_and_nested_quotes() {
+        let content = r#"alias json='python -c "import json,sys; print(json.load(sys.stdin))"'
+alias q="echo \"hi there\""
+alias say="echo it's"
+alias esc="echo \"it's\""
+alias gl='git log --pretty=format:'"'"'%h %s'"'"''
+alias its='echo it'\''s fine'
+alias cols='awk '"'"'{print $1}'"'"''  # first column
+alias jqk="jq -r 'keys[]'"
+alias win='cd C:\'
+alias last=done
+"#;
+        let result = BashParser::new().parse(content);
+
+        let aliases: Vec<(&str, String)> = result
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.canonical_value()))
+            .collect();
+        assert_eq!(
+            aliases,
+            [
+                (
+                    "json",
+                    r#"python -c "import json,sys; print(json.load(sys.stdin))""#.to_string()
+                ),
+                ("q", r#"echo "hi there""#.to_string()),
+                ("say", "echo it's".to_string()),
+                ("esc", r#"echo "it's""#.to_string()),
+                ("gl", "git log --pretty=format:'%h %s'".to_string()),
+                ("its", "echo it's fine".to_string()),
+                ("cols", "awk '{print $1}'".to_string()),
+                ("jqk", "jq -r 'keys[]'".to_string()),
+                ("win", r"cd C:\".to_string()),
+                ("last", "done".to_string()),
+            ]
+        );
+        // One line each: nothing swallowed the next alias
+        let lines: Vec<_> = result.entries.iter().map(|e| e.line_number).collect();
+        assert_eq!(lines, (1..=10).map(Some).collect::<Vec<_>>());
+        assert!(result
+            .entries
+            .iter()
+            .all(|e| e.entry_type == EntryType::Alias));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_case_statement_is_one_code_block() {
+        let content = r#"case "$TERM" in
+  xterm*|rxvt*)
+    PS1="\u@\h"
+    ;;
+  *.txt) echo text ;;
+  (screen) echo screen ;;
+  start) if [ -n "$x" ]; then
+      echo yes
+    fi
+    ;;
+  *) for f in *; do echo "$f"; done ;;
+esac
+plugins=(
+  git
+)
+alias after=1
+"#;
+        let result = BashParser::new().parse(content);
+
+        assert_eq!(result.entries.len(), 3);
+        let case = &result.entries[0];
+        assert_eq!(case.entry_type, EntryType::Code);
+        assert_eq!((case.line_number, case.end_line), (Some(1), Some(12)));
+        assert_eq!(result.entries[1].line_number, Some(13));
+        assert_eq!(result.entries[1].end_line, Some(15));
+        assert_eq!(result.entries[2].name, "after");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_one_line_control_structures() {
+        let content = "case $x in a) echo a ;; esac\nif true; then echo; fi\nalias after=1\n";
+        let result = BashParser::new().parse(content);
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(result.entries[0].entry_type, EntryType::Code);
+        assert_eq!(result.entries[1].entry_type, EntryType::Code);
+        assert_eq!(result.entries[2].name, "after");
+        assert!(result.warnings.is_empty());
+    }
+
     #[test]
     fn test_multiple_comments_dont_merge_down() {
         // Rule 2 & 4: Multiple comments form independent blocks
@@ -1310,6 +1956,29 @@ mod tests {
         assert!(result.entries[0].value.contains("alias a='b'"));
     }
 
+    #[test]
+    fn test_single_comment_merges_down_and_is_attached() {
+        let content = "# list files\n\nalias ll='ls -la'\n# g\n# git\nalias gs='git status'\nalias gd='git diff'\n";
+        let result = BashParser::new()
+            .with_attached_comments(true)
+            .parse(content);
+        let attached = |name: &str| {
+            let entry = result.entries.iter().find(|e| e.name == name).unwrap();
+            entry.attached_comment.clone()
+        };
+
+        // Still merged into the raw value, and known separately
+        let ll = result.entries.iter().find(|e| e.name == "ll").unwrap();
+        assert_eq!(ll.value, "# list files\n\nalias ll='ls -la'");
+        assert_eq!(attached("ll").as_deref(), Some("# list files"));
+        // A comment block stays its own entry and is attached from above
+        assert_eq!(attached("gs").as_deref(), Some("# g\n# git"));
+        assert_eq!(attached("gd"), None);
+
+        let plain = BashParser::new().parse(content);
+        assert!(plain.entries.iter().all(|e| e.attached_comment.is_none()));
+    }
+
     #[test]
     fn test_single_vs_multiple_comment_distinction() {
         // Rule 4: Distinguish between single and multiple comments
@@ -1418,4 +2087,300 @@ mod tests {
         assert_eq!(result.entries[1].entry_type, EntryType::Alias);
         assert_eq!(result.entries[1].line_number, Some(6));
     }
+
+    #[test]
+    fn test_heredoc_with_control_keywords() {
+        let parser = BashParser::new();
+        let content =
+            "cat <<EOF > ~/.notes\nif you read this\nfi done esac\nEOF\nalias ll='ls -la'";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        assert!(result.warnings.is_empty());
+
+        let heredoc = &result.entries[0];
+        assert_eq!(heredoc.entry_type, EntryType::Code);
+        assert_eq!(heredoc.line_number, Some(1));
+        assert_eq!(heredoc.end_line, Some(4));
+        assert_eq!(
+            heredoc.value,
+            "cat <<EOF > ~/.notes\nif you read this\nfi done esac\nEOF"
+        );
+
+        assert_eq!(result.entries[1].entry_type, EntryType::Alias);
+        assert_eq!(result.entries[1].line_number, Some(5));
+    }
+
+    #[test]
+    fn test_heredoc_dash_and_quoted_forms() {
+        let parser = BashParser::new();
+        let content = "cat <<-END\n\twhile true\n\tEND\ncat <<'EOF'\n$HOME {\nEOF";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].end_line, Some(3));
+        assert_eq!(result.entries[1].line_number, Some(4));
+        assert_eq!(result.entries[1].end_line, Some(6));
+    }
+
+    #[test]
+    fn test_heredoc_inside_function() {
+        let parser = BashParser::new();
+        let content = "setup() {\n    cat <<EOF\n}\nif then\nEOF\n}\nalias x='y'";
+        let result = parser.parse(content);
+
+        let funcs: Vec<_> = result
+            .entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::Function)
+            .collect();
+        assert_eq!(funcs.len(), 1);
+        assert_eq!(funcs[0].end_line, Some(6));
+        assert!(result.entries.iter().any(|e| e.name == "x"));
+    }
+
+    #[test]
+    fn test_heredoc_after_done() {
+        let parser = BashParser::new();
+        let content = "while read -r line; do\n    echo \"$line\"\ndone <<EOF\nif\nEOF\necho after";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].end_line, Some(5));
+        assert_eq!(result.entries[1].value, "echo after");
+    }
+
+    #[test]
+    fn test_unclosed_heredoc_warning() {
+        let parser = BashParser::new();
+        let result = parser.parse("cat <<EOF\nnever closed");
+
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("Unclosed here-document")));
+        assert_eq!(result.warnings[0].code, WarningCode::UnclosedHeredoc);
+        assert_eq!(result.warnings[0].severity, crate::model::Severity::Error);
+    }
+
+    #[test]
+    fn test_unclosed_function_warning_code() {
+        let parser = BashParser::new();
+        let result = parser.parse("alias ll='ls'\ngreet() {\n    echo hi\n");
+
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::UnclosedFunction);
+        assert_eq!(result.warnings[0].code.as_str(), "unclosed-function");
+        assert_eq!(result.warnings[0].line_number, 2);
+    }
+
+    #[test]
+    fn test_guarded_block_is_one_code_entry() {
+        let parser = BashParser::new();
+        let block = "# >>> wenv:ignore >>>\n__conda_setup=\"$(conda shell.bash hook)\"\nif [ $? -eq 0 ]; then\n    eval \"$__conda_setup\"\n\nalias x='unterminated\n# <<< wenv:ignore <<<";
+        let content = format!("# Conda\n{}\nalias ll='ls -la'\n", block);
+        let result = parser.parse(&content);
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(result.entries[0].entry_type, EntryType::Comment);
+        let guarded = &result.entries[1];
+        assert_eq!(guarded.entry_type, EntryType::Code);
+        assert!(guarded.is_guarded());
+        assert_eq!(guarded.value, block);
+        assert_eq!((guarded.line_number, guarded.end_line), (Some(2), Some(8)));
+        assert_eq!(result.entries[2].name, "ll");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unclosed_guarded_block_keeps_lines() {
+        let parser = BashParser::new();
+        let result = parser.parse("# >>> wenv:ignore >>>\nalias a='1'\n");
+
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(
+            result.entries[0].value,
+            "# >>> wenv:ignore >>>\nalias a='1'"
+        );
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("Unclosed wenv:ignore block")));
+        assert_eq!(result.warnings[0].code, WarningCode::UnclosedRegion);
+        assert_eq!(result.warnings[0].severity, crate::model::Severity::Warning);
+    }
+
+    #[test]
+    fn test_nvm_block_is_one_code_entry() {
+        let parser = BashParser::new();
+        let nvm = "export NVM_DIR=\"$HOME/.nvm\"\n[ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"  # This loads nvm\n[ -s \"$NVM_DIR/bash_completion\" ] && \\. \"$NVM_DIR/bash_completion\"  # This loads nvm bash_completion";
+        let result = parser.parse(&format!("{}\n\nexport NVM_DIR=/opt/nvm\n", nvm));
+
+        assert!(result.entries[0].is_guarded());
+        assert_eq!(result.entries[0].value, nvm);
+        // Without the loader lines, the export is an ordinary variable
+        let last = result.entries.last().unwrap();
+        assert_eq!(last.entry_type, EntryType::EnvVar);
+        assert!(!last.is_guarded());
+    }
+
+    #[test]
+    fn test_tag_comment_attaches_tags() {
+        let parser = BashParser::new();
+        let result = parser.parse("# wenv:tags=git,work\nalias gs='git status'\n\n# wenv:tags=work\ngreet() {\n  echo hi\n}\nalias ll='ls -la'\n");
+        let tags = |name: &str| {
+            result
+                .entries
+                .iter()
+                .find(|e| e.name == name)
+                .map(|e| e.tags.clone())
+                .unwrap()
+        };
+
+        assert_eq!(tags("gs"), vec!["git", "work"]);
+        assert_eq!(tags("greet"), vec!["work"]);
+        assert_eq!(tags("ll"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_single_line_arrays() {
+        let parser = BashParser::new();
+        let result =
+            parser.parse("arr=(a b c)\ndeclare -A colors=([red]=1 [green]=2)\nlocal -a empty=()\n");
+
+        assert_eq!(result.entries.len(), 3);
+        for (entry, name) in result.entries.iter().zip(["arr", "colors", "empty"]) {
+            assert_eq!(entry.entry_type, EntryType::Array);
+            assert_eq!(entry.name, name);
+        }
+        assert_eq!(
+            result.entries[1].value,
+            "declare -A colors=([red]=1 [green]=2)"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_array() {
+        let parser = BashParser::new();
+        let content = "# Oh My Zsh plugins\nplugins=(\n  git\n  docker\n)\n\necho done";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        let array = &result.entries[0];
+        assert_eq!(array.entry_type, EntryType::Array);
+        assert_eq!(array.name, "plugins");
+        assert_eq!(array.line_number, Some(1));
+        assert_eq!(array.end_line, Some(6));
+        assert_eq!(
+            array.value,
+            "# Oh My Zsh plugins\nplugins=(\n  git\n  docker\n)\n"
+        );
+        assert_eq!(array.canonical_value(), "(\n  git\n  docker\n)");
+    }
+
+    #[test]
+    fn test_multi_line_associative_array() {
+        let parser = BashParser::new();
+        let content = "declare -A hosts=(\n  [web]=\"10.0.0.1\"  # (primary)\n  [db]=10.0.0.2\n)\nalias ll='ls -la'";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].entry_type, EntryType::Array);
+        assert_eq!(result.entries[0].name, "hosts");
+        assert_eq!(result.entries[0].end_line, Some(4));
+        assert_eq!(result.entries[1].name, "ll");
+    }
+
+    #[test]
+    fn test_shebang_is_its_own_entry() {
+        let parser = BashParser::new();
+        let result = parser.parse(
+            "#!/usr/bin/env bash
+alias ll='ls -la'
+",
+        );
+
+        assert_eq!(result.entries.len(), 2);
+        assert!(result.entries[0].is_shebang());
+        assert_eq!(result.entries[0].value, "#!/usr/bin/env bash");
+        assert_eq!(result.entries[1].value, "alias ll='ls -la'");
+
+        // Only the first line can be a shebang
+        let result = parser.parse(
+            "alias ll='ls -la'
+#!/bin/bash
+alias gs='git status'
+",
+        );
+        assert!(!result.entries.iter().any(Entry::is_shebang));
+    }
+
+    #[test]
+    fn test_trap_entries() {
+        let parser = BashParser::new();
+        let content = "trap 'rm -f \"$tmp\"' EXIT\ntrap -- cleanup INT TERM  # stop\ntrap -p\n";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(result.entries[0].entry_type, EntryType::Trap);
+        assert_eq!(result.entries[0].name, "EXIT");
+        assert_eq!(result.entries[1].entry_type, EntryType::Trap);
+        assert_eq!(result.entries[1].name, "INT TERM");
+        // Printing the traps sets none
+        assert_eq!(result.entries[2].entry_type, EntryType::Code);
+    }
+
+    #[test]
+    fn test_multiline_trap() {
+        let parser = BashParser::new();
+        let content =
+            "# clean up\ntrap '\n  rm -f \"$tmp\"\n  echo bye\n' EXIT HUP\nalias ll='ls'\n";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 2);
+        let trap = &result.entries[0];
+        assert_eq!(trap.entry_type, EntryType::Trap);
+        assert_eq!(trap.name, "EXIT HUP");
+        assert_eq!(trap.line_number, Some(1));
+        assert_eq!(trap.end_line, Some(5));
+        assert_eq!(result.entries[1].name, "ll");
+    }
+
+    #[test]
+    fn test_zsh_option_and_style_entries() {
+        let parser = BashParser::new();
+        let content =
+            "# completion\nzstyle ':completion:*' menu select\nsetopt autocd  extendedglob\n\
+                       unsetopt BEEP # quiet\nsetopt\nzstyle -L\n";
+        let result = parser.parse(content);
+
+        let entries: Vec<_> = result
+            .entries
+            .iter()
+            .map(|e| (e.entry_type, e.name.as_str()))
+            .collect();
+        assert_eq!(entries[0], (EntryType::Zstyle, ":completion:* menu"));
+        assert_eq!(entries[1], (EntryType::Setopt, "autocd extendedglob"));
+        assert_eq!(entries[2], (EntryType::Setopt, "BEEP"));
+        // Listing options or styles defines none
+        assert_eq!(entries[3].0, EntryType::Code);
+        assert_eq!(entries[4].0, EntryType::Code);
+        assert_eq!(result.entries[0].line_number, Some(1));
+    }
+
+    #[test]
+    fn test_unset_entries() {
+        let parser = BashParser::new();
+        let content = "# not on servers\nunalias ll\nunset -f greet  # old\nunset A B\n";
+        let result = parser.parse(content);
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(result.entries[0].entry_type, EntryType::Unset);
+        assert_eq!(result.entries[0].name, "ll");
+        assert_eq!(result.entries[0].value, "# not on servers\nunalias ll");
+        assert_eq!(result.entries[1].entry_type, EntryType::Unset);
+        assert_eq!(result.entries[1].name, "greet");
+        // Several names at once stay Code
+        assert_eq!(result.entries[2].entry_type, EntryType::Code);
+    }
 }
@@ -8,6 +8,11 @@
 //! - `try_parse_alias(line, line_num) -> ParseEvent`
 //! - `try_parse_env(line, line_num) -> ParseEvent`
 //! - `try_parse_source(line, line_num) -> ParseEvent`
+//! - `try_parse_array(line, line_num) -> ParseEvent`
+//! - `try_parse_unset(line, line_num) -> ParseEvent`
+//! - `try_parse_trap(line, line_num) -> ParseEvent`
+//! - `try_parse_setopt(line, line_num) -> ParseEvent`
+//! - `try_parse_zstyle(line, line_num) -> ParseEvent`
 //!
 //! Returns:
 //! - `ParseEvent::Complete(entry)` for single-line entries
@@ -26,15 +31,21 @@
 
 use super::patterns::*;
 use crate::model::{Entry, EntryType};
-use crate::parser::builders::{extract_comment, strip_quotes, QuotedValueBuilder};
+use crate::parser::builders::{
+    count_parens_outside_quotes, extract_comment, scan_quotes, shell_word_end, strip_quotes,
+    unquote_shell_word, QuoteState, QuotedValueBuilder,
+};
 use crate::parser::{BoundaryType, ParseEvent};
 
 /// Try to parse a line as an alias.
 ///
-/// This handles three cases:
-/// 1. Complete single-quoted alias: `alias name='value'`
-/// 2. Complete double-quoted alias: `alias name="value"`
-/// 3. Start of multi-line alias: `alias name='unclosed...`
+/// The value is read as one shell word following Bash quoting, so nested
+/// quote types (`'python -c "..."'`), escaped quotes (`"echo \"hi\""`) and
+/// the `'\''` / `'"'"'` idioms for a single quote inside single quotes all
+/// stay within the alias. This handles three cases:
+/// 1. Complete alias, optionally followed by a comment: `alias name='value'`
+/// 2. Start of multi-line alias (a single quote left open): `alias name='unclosed...`
+/// 3. Anything else after the value (`alias a=b c=d`) is not an alias
 ///
 /// # Arguments
 ///
@@ -47,48 +58,33 @@ use crate::parser::{BoundaryType, ParseEvent};
 /// - `ParseEvent::Started { ... }` for multi-line alias start
 /// - `ParseEvent::None` if line is not an alias
 pub fn try_parse_alias(line: &str, line_num: usize) -> ParseEvent {
-    // Try complete single-quoted alias first
-    if let Some(caps) = ALIAS_SINGLE_RE.captures(line) {
-        return ParseEvent::Complete(
-            Entry::new(EntryType::Alias, caps[1].to_string(), line.to_string())
-                .with_line_number(line_num),
-        );
-    }
-
-    // Try complete double-quoted alias
-    if let Some(caps) = ALIAS_DOUBLE_RE.captures(line) {
-        return ParseEvent::Complete(
-            Entry::new(EntryType::Alias, caps[1].to_string(), line.to_string())
-                .with_line_number(line_num),
-        );
-    }
+    let Some(caps) = ALIAS_START_RE.captures(line) else {
+        return ParseEvent::None;
+    };
+    let name = caps[1].to_string();
+    let value = &line[caps.get(0).map_or(0, |m| m.end())..];
 
-    // Check for multi-line alias start FIRST (before noquote)
-    // This is critical: ALIAS_NOQUOTE_RE would incorrectly match `alias foo='123` as
-    // a complete alias with value `'123`, preventing multi-line detection
-    if let Some(caps) = ALIAS_MULTILINE_START_RE.captures(line) {
-        // Verify it has an unclosed single quote
-        if QuotedValueBuilder::has_unclosed_single_quote(line) {
-            let name = caps[1].to_string();
-            let quote_count = line.chars().filter(|&c| c == '\'').count();
-            return ParseEvent::Started {
-                entry_type: EntryType::Alias,
-                name,
-                boundary: BoundaryType::QuoteCounting { quote_count },
-                first_line: line.to_string(),
-            };
+    match shell_word_end(value) {
+        Some(end) => {
+            let rest = value[end..].trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                ParseEvent::Complete(
+                    Entry::new(EntryType::Alias, name, line.to_string()).with_line_number(line_num),
+                )
+            } else {
+                ParseEvent::None
+            }
         }
+        None if QuotedValueBuilder::has_unclosed_single_quote(line) => ParseEvent::Started {
+            entry_type: EntryType::Alias,
+            name,
+            boundary: BoundaryType::QuoteCounting {
+                quote_count: QuotedValueBuilder::count_single_quotes(line),
+            },
+            first_line: line.to_string(),
+        },
+        None => ParseEvent::None,
     }
-
-    // Try unquoted alias (LAST - after all quoted/multi-line checks)
-    if let Some(caps) = ALIAS_NOQUOTE_RE.captures(line) {
-        return ParseEvent::Complete(
-            Entry::new(EntryType::Alias, caps[1].to_string(), line.to_string())
-                .with_line_number(line_num),
-        );
-    }
-
-    ParseEvent::None
 }
 
 /// Try to parse a line as an environment variable export.
@@ -107,13 +103,15 @@ pub fn try_parse_alias(line: &str, line_num: usize) -> ParseEvent {
 /// - `ParseEvent::Complete(entry)` for complete exports
 /// - `ParseEvent::Started { ... }` for multi-line export start
 /// - `ParseEvent::None` if line is not an export
+///
+/// Bare assignments to prompt variables (`PS1='...'`) count as exports too.
 pub fn try_parse_env(line: &str, line_num: usize) -> ParseEvent {
     // Check for multi-line export start FIRST
     // (before the general EXPORT_RE which would match but not handle multi-line)
     if let Some(caps) = EXPORT_MULTILINE_START_RE.captures(line) {
         if QuotedValueBuilder::has_unclosed_single_quote(line) {
             let name = caps[1].to_string();
-            let quote_count = line.chars().filter(|&c| c == '\'').count();
+            let quote_count = QuotedValueBuilder::count_single_quotes(line);
             return ParseEvent::Started {
                 entry_type: EntryType::EnvVar,
                 name,
@@ -133,6 +131,24 @@ pub fn try_parse_env(line: &str, line_num: usize) -> ParseEvent {
         );
     }
 
+    // Prompt variables are read by the shell without `export`
+    if let Some(caps) = PROMPT_ASSIGN_RE.captures(line) {
+        let name = caps[1].to_string();
+        if caps[2].starts_with('\'') && QuotedValueBuilder::has_unclosed_single_quote(line) {
+            return ParseEvent::Started {
+                entry_type: EntryType::EnvVar,
+                name,
+                boundary: BoundaryType::QuoteCounting {
+                    quote_count: QuotedValueBuilder::count_single_quotes(line),
+                },
+                first_line: line.to_string(),
+            };
+        }
+        return ParseEvent::Complete(
+            Entry::new(EntryType::EnvVar, name, line.to_string()).with_line_number(line_num),
+        );
+    }
+
     ParseEvent::None
 }
 
@@ -168,6 +184,220 @@ pub fn try_parse_source(line: &str, line_num: usize) -> ParseEvent {
     ParseEvent::None
 }
 
+/// Try to parse a line as an array assignment.
+///
+/// Matches:
+/// - `arr=(a b c)`
+/// - `declare -a arr=(a b c)`, `declare -A map=([key]=value)`
+/// - Multi-line arrays whose closing `)` is on a later line
+///
+/// # Arguments
+///
+/// - `line`: The trimmed line to parse
+/// - `line_num`: 1-based line number
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` if the parentheses close on this line
+/// - `ParseEvent::Started { ... }` for multi-line array start
+/// - `ParseEvent::None` if line is not an array assignment
+pub fn try_parse_array(line: &str, line_num: usize) -> ParseEvent {
+    let Some(caps) = ARRAY_START_RE.captures(line) else {
+        return ParseEvent::None;
+    };
+    let name = caps[1].to_string();
+
+    let (open, close) = count_parens_outside_quotes(line);
+    if open > close {
+        return ParseEvent::Started {
+            entry_type: EntryType::Array,
+            name,
+            boundary: BoundaryType::ParenthesisCounting {
+                parenthesis_count: (open - close) as i32,
+            },
+            first_line: line.to_string(),
+        };
+    }
+
+    ParseEvent::Complete(
+        Entry::new(EntryType::Array, name, line.to_string()).with_line_number(line_num),
+    )
+}
+
+/// Try to parse a line as the removal of a definition.
+///
+/// Matches:
+/// - `unalias name`
+/// - `unset VAR`, `unset -v VAR`, `unset -f name`
+///
+/// Lines removing several names at once (`unset A B`, `unalias -a`) are
+/// left as Code.
+///
+/// # Arguments
+///
+/// - `line`: The trimmed line to parse
+/// - `line_num`: 1-based line number
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` if the line removes one definition
+/// - `ParseEvent::None` otherwise
+pub fn try_parse_unset(line: &str, line_num: usize) -> ParseEvent {
+    let Some(caps) = UNALIAS_RE
+        .captures(line)
+        .or_else(|| UNSET_RE.captures(line))
+    else {
+        return ParseEvent::None;
+    };
+    ParseEvent::Complete(
+        Entry::new(EntryType::Unset, caps[1].to_string(), line.to_string())
+            .with_line_number(line_num),
+    )
+}
+
+/// Try to parse a line as a `trap` command setting a signal handler.
+///
+/// Matches `trap 'handler' SIG...`, named by its signals (`EXIT`,
+/// `INT TERM`). A handler leaving a single quote open starts a multi-line
+/// trap, completed by quote counting; its signals follow the closing quote.
+/// `trap -p`, `trap -l` and a bare `trap` only print and are left as Code.
+///
+/// # Arguments
+///
+/// - `line`: The trimmed line to parse
+/// - `line_num`: 1-based line number
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` for a single-line trap
+/// - `ParseEvent::Started { ... }` for a multi-line trap start
+/// - `ParseEvent::None` otherwise
+pub fn try_parse_trap(line: &str, line_num: usize) -> ParseEvent {
+    let Some(start) = TRAP_START_RE.find(line) else {
+        return ParseEvent::None;
+    };
+    if line[start.end()..].starts_with('-') && !line[start.end()..].starts_with("- ") {
+        return ParseEvent::None;
+    }
+    if let Some(signals) = trap_signals(line) {
+        return ParseEvent::Complete(
+            Entry::new(EntryType::Trap, signals, line.to_string()).with_line_number(line_num),
+        );
+    }
+    if QuotedValueBuilder::has_unclosed_single_quote(line) {
+        return ParseEvent::Started {
+            entry_type: EntryType::Trap,
+            // Known once the closing quote is reached
+            name: String::new(),
+            boundary: BoundaryType::QuoteCounting {
+                quote_count: QuotedValueBuilder::count_single_quotes(line),
+            },
+            first_line: line.to_string(),
+        };
+    }
+    ParseEvent::None
+}
+
+/// The signals of the `trap` command in `text`, separated by a space:
+/// `INT TERM` for `trap 'cleanup' INT TERM`. `text` may span several lines,
+/// starting with comments. `None` when the command is incomplete or names
+/// no signals.
+pub fn trap_signals(text: &str) -> Option<String> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("trap") {
+            break;
+        }
+        offset += line.len();
+    }
+    let command = text.get(offset..)?.trim_start();
+    let handler = &command[TRAP_START_RE.find(command)?.end()..];
+    let rest = &handler[shell_word_end(handler)?..];
+    let rest = rest.lines().next().unwrap_or("");
+    let (signals, _comment) = extract_comment(rest, '#');
+    let signals = signals.trim();
+    TRAP_SIGNALS_RE
+        .is_match(signals)
+        .then(|| signals.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Try to parse a line as a Zsh `setopt` or `unsetopt` command.
+///
+/// Named by its options, separated by a space: `autocd extendedglob` for
+/// `setopt autocd extendedglob`. A bare `setopt` only prints and is left as
+/// Code.
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` for an option switch
+/// - `ParseEvent::None` otherwise
+pub fn try_parse_setopt(line: &str, line_num: usize) -> ParseEvent {
+    match setopt_options(line) {
+        Some(options) => ParseEvent::Complete(
+            Entry::new(EntryType::Setopt, options, line.to_string()).with_line_number(line_num),
+        ),
+        None => ParseEvent::None,
+    }
+}
+
+/// The options of the `setopt`/`unsetopt` command in `text`, separated by
+/// a space. `text` may start with comments.
+pub fn setopt_options(text: &str) -> Option<String> {
+    let command = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let caps = SETOPT_RE.captures(command)?;
+    Some(caps[1].split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Try to parse a line as a Zsh `zstyle` command defining a style.
+///
+/// Named by its pattern and style, unquoted: `:completion:* menu` for
+/// `zstyle ':completion:*' menu select`. Queries and deletions (`zstyle -L`,
+/// `zstyle -d`) and lines leaving a quote open are left as Code.
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` for a style definition
+/// - `ParseEvent::None` otherwise
+pub fn try_parse_zstyle(line: &str, line_num: usize) -> ParseEvent {
+    if scan_quotes(line, QuoteState::Unquoted, |_, _, _| {}) != QuoteState::Unquoted {
+        return ParseEvent::None;
+    }
+    match zstyle_name(line) {
+        Some(name) => ParseEvent::Complete(
+            Entry::new(EntryType::Zstyle, name, line.to_string()).with_line_number(line_num),
+        ),
+        None => ParseEvent::None,
+    }
+}
+
+/// The pattern and style of the `zstyle` command in `text`, unquoted and
+/// separated by a space. `text` may start with comments. `None` for
+/// commands with options, or naming no style.
+pub fn zstyle_name(text: &str) -> Option<String> {
+    let command = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let rest = &command[ZSTYLE_START_RE.find(command)?.end()..];
+    if rest.starts_with('-') {
+        return None;
+    }
+    let pattern_end = shell_word_end(rest)?;
+    let after = rest[pattern_end..].trim_start();
+    if after.is_empty() || after.starts_with('#') {
+        return None;
+    }
+    let style_end = shell_word_end(after)?;
+    Some(format!(
+        "{} {}",
+        unquote_shell_word(&rest[..pattern_end]),
+        unquote_shell_word(&after[..style_end])
+    ))
+}
+
 /// Detect if a line starts a function definition.
 ///
 /// Matches:
@@ -204,6 +434,54 @@ pub fn detect_function_start(line: &str) -> Option<(String, bool)> {
     None
 }
 
+/// Detect if a line opens a here-document.
+///
+/// Matches `<<WORD`, `<<-WORD`, `<<'WORD'` and `<<"WORD"` outside of quotes.
+///
+/// # Arguments
+///
+/// - `line`: The trimmed line to check
+///
+/// # Returns
+///
+/// `Some((terminator, strip_tabs))` if the line starts a here-document,
+/// where `strip_tabs` is true for the `<<-` form. `None` otherwise.
+pub fn detect_heredoc_start(line: &str) -> Option<(String, bool)> {
+    use super::patterns::HEREDOC_START_RE;
+
+    let (code, _comment) = extract_comment(line, '#');
+    let caps = HEREDOC_START_RE.captures(&code)?;
+
+    // Ignore `<<` inside a quoted string
+    let prefix = &code[..caps.get(0)?.start()];
+    if prefix.matches('\'').count() % 2 == 1 || prefix.matches('"').count() % 2 == 1 {
+        return None;
+    }
+
+    let terminator = caps.get(2).or(caps.get(3)).or(caps.get(4))?.as_str();
+    Some((terminator.to_string(), &caps[1] == "-"))
+}
+
+/// Check if a line terminates a here-document.
+///
+/// # Arguments
+///
+/// - `line`: The untrimmed line to check
+/// - `terminator`: The terminator word from [`detect_heredoc_start`]
+/// - `strip_tabs`: Whether leading tabs are allowed (`<<-` form)
+///
+/// # Returns
+///
+/// `true` if this line ends the here-document.
+pub fn is_heredoc_end(line: &str, terminator: &str, strip_tabs: bool) -> bool {
+    let line = line.trim_end_matches('\r');
+    if strip_tabs {
+        line.trim_start_matches('\t') == terminator
+    } else {
+        line == terminator
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,7 +620,46 @@ mod tests {
             detect_function_start("function test {"),
             Some(("test".to_string(), false))
         );
+        assert_eq!(
+            detect_function_start("function spaced () {"),
+            Some(("spaced".to_string(), false))
+        );
+        assert_eq!(
+            detect_function_start("function_helper() {"),
+            Some(("function_helper".to_string(), false))
+        );
+        assert_eq!(
+            detect_function_start("git-clean() {"),
+            Some(("git-clean".to_string(), false))
+        );
         assert_eq!(detect_function_start("() {"), Some((String::new(), true)));
         assert_eq!(detect_function_start("echo hello"), None);
     }
+
+    #[test]
+    fn test_detect_heredoc_start() {
+        assert_eq!(
+            detect_heredoc_start("cat <<EOF > ~/.config/x"),
+            Some(("EOF".into(), false))
+        );
+        assert_eq!(
+            detect_heredoc_start("cat <<-END"),
+            Some(("END".into(), true))
+        );
+        assert_eq!(
+            detect_heredoc_start("cat << 'EOF'"),
+            Some(("EOF".into(), false))
+        );
+        assert_eq!(detect_heredoc_start("echo \"a <<EOF\""), None);
+        assert_eq!(detect_heredoc_start("echo hi # <<EOF"), None);
+        assert_eq!(detect_heredoc_start("read x <<< \"$y\""), None);
+    }
+
+    #[test]
+    fn test_is_heredoc_end() {
+        assert!(is_heredoc_end("EOF", "EOF", false));
+        assert!(!is_heredoc_end("  EOF", "EOF", false));
+        assert!(is_heredoc_end("\t\tEOF", "EOF", true));
+        assert!(!is_heredoc_end("EOF2", "EOF", false));
+    }
 }
@@ -28,71 +28,127 @@ lazy_static! {
     // Alias Patterns
     // =========================================================================
 
-    /// Matches single-quoted alias: `alias name='value'` or `alias -g name='value'`
+    /// Matches an alias up to its value: `alias name=` or `alias -g name=`
     ///
     /// Captures:
     /// - Group 1: alias name (allows special chars like `.`, `~`, `-`)
-    /// - Group 2: alias value (content between single quotes)
     ///
-    /// Note: This only matches complete single-line aliases.
-    /// Multi-line aliases are handled by `QuotedValueBuilder`.
+    /// The value after `=` is one shell word that may mix quoting styles
+    /// (`'it'\''s'`, `'a'"$b"`, `"say \"hi\""`). A regex can't follow that,
+    /// so the word is delimited with
+    /// [`shell_word_end`](crate::parser::builders::shell_word_end) instead.
     /// Supports alias options like `-g`, `-a`, etc.
-    pub static ref ALIAS_SINGLE_RE: Regex = Regex::new(
-        r#"^alias(?:\s+-[a-zA-Z0-9]+)*\s+([^\s=]+)='([^']*)'(?:\s*(?:#.*)?)?$"#
+    pub static ref ALIAS_START_RE: Regex = Regex::new(
+        r#"^alias(?:\s+-[a-zA-Z0-9]+)*\s+([^\s=]+)="#
     ).unwrap();
 
-    /// Matches double-quoted alias: `alias name="value"` or `alias -a name="value"`
+    // =========================================================================
+    // Export (Environment Variable) Patterns
+    // =========================================================================
+
+    /// Matches export statement: `export VAR=value` or `export VAR="value"`
     ///
     /// Captures:
-    /// - Group 1: alias name
-    /// - Group 2: alias value
-    ///
-    /// Supports alias options like `-g`, `-a`, etc.
-    pub static ref ALIAS_DOUBLE_RE: Regex = Regex::new(
-        r#"^alias(?:\s+-[a-zA-Z0-9]+)*\s+([^\s=]+)="([^"]*)"(?:\s*(?:#.*)?)?$"#
+    /// - Group 1: variable name (word characters only)
+    /// - Group 2: value (everything after `=`)
+    pub static ref EXPORT_RE: Regex = Regex::new(
+        r#"^export\s+(\w+)=(.*)$"#
     ).unwrap();
 
-    /// Matches unquoted alias: `alias name=value` or `alias -g name=value`
+    /// Matches the start of a potentially multi-line export.
     ///
     /// Captures:
-    /// - Group 1: alias name
-    /// - Group 2: alias value (single word, no spaces)
+    /// - Group 1: variable name
+    pub static ref EXPORT_MULTILINE_START_RE: Regex = Regex::new(
+        r#"^export\s+(\w+)='"#
+    ).unwrap();
+
+    /// Matches a bare assignment to a prompt variable: `PS1='\u@\h \$ '`.
+    /// Shells read these without `export`, so they count as environment
+    /// variables (see [`crate::model::PROMPT_VARIABLES`]).
     ///
-    /// Supports alias options like `-g`, `-a`, etc.
-    pub static ref ALIAS_NOQUOTE_RE: Regex = Regex::new(
-        r#"^alias(?:\s+-[a-zA-Z0-9]+)*\s+([^\s=]+)=(\S+)(?:\s*(?:#.*)?)?$"#
+    /// Captures:
+    /// - Group 1: variable name
+    /// - Group 2: value (everything after `=`)
+    pub static ref PROMPT_ASSIGN_RE: Regex = Regex::new(
+        r#"^(PS[0-4]|PROMPT_COMMAND|PROMPT[234]?|RPROMPT|RPS[12])=(.*)$"#
     ).unwrap();
 
-    /// Matches the start of a potentially multi-line single-quoted alias.
+    /// Matches a function export: `export -f name [name...]`
     ///
     /// Captures:
-    /// - Group 1: alias name
+    /// - Group 1: the function names, separated by whitespace
+    pub static ref EXPORT_FUNC_RE: Regex = Regex::new(
+        r#"^export\s+-f\s+(\w[\w.:-]*(?:\s+\w[\w.:-]*)*)\s*(?:#.*)?$"#
+    ).unwrap();
+
+    // =========================================================================
+    // Array Patterns
+    // =========================================================================
+
+    /// Matches an array assignment: `arr=(a b c)` or `declare -A map=([k]=v)`
     ///
-    /// This pattern matches `alias name='...` where the quote is not closed.
-    /// Supports alias options like `-g`, `-a`, etc.
-    pub static ref ALIAS_MULTILINE_START_RE: Regex = Regex::new(
-        r#"^alias(?:\s+-[a-zA-Z0-9]+)*\s+([^\s=]+)='"#
+    /// Also accepts `typeset`, `local` and `readonly` with options.
+    ///
+    /// Captures:
+    /// - Group 1: array name
+    pub static ref ARRAY_START_RE: Regex = Regex::new(
+        r#"^(?:(?:declare|typeset|local|readonly)(?:\s+-[a-zA-Z]+)*\s+)?([A-Za-z_]\w*)=\("#
     ).unwrap();
 
     // =========================================================================
-    // Export (Environment Variable) Patterns
+    // Unset Patterns
     // =========================================================================
 
-    /// Matches export statement: `export VAR=value` or `export VAR="value"`
+    /// Matches the removal of one alias: `unalias ll`
     ///
     /// Captures:
-    /// - Group 1: variable name (word characters only)
-    /// - Group 2: value (everything after `=`)
-    pub static ref EXPORT_RE: Regex = Regex::new(
-        r#"^export\s+(\w+)=(.*)$"#
+    /// - Group 1: alias name
+    pub static ref UNALIAS_RE: Regex = Regex::new(
+        r#"^unalias\s+([^\s=;&|#'"$-][^\s=;&|#'"$]*)\s*(?:#.*)?$"#
     ).unwrap();
 
-    /// Matches the start of a potentially multi-line export.
+    /// Matches the removal of one variable or function: `unset VAR`,
+    /// `unset -v VAR`, `unset -f name`
     ///
     /// Captures:
-    /// - Group 1: variable name
-    pub static ref EXPORT_MULTILINE_START_RE: Regex = Regex::new(
-        r#"^export\s+(\w+)='"#
+    /// - Group 1: variable or function name
+    pub static ref UNSET_RE: Regex = Regex::new(
+        r#"^unset\s+(?:-[fv]\s+)?([A-Za-z_][\w.:-]*)\s*(?:#.*)?$"#
+    ).unwrap();
+
+    // =========================================================================
+    // Trap Patterns
+    // =========================================================================
+
+    /// Matches the start of a `trap` command setting a handler, up to the
+    /// handler: `trap 'cleanup' EXIT`, `trap -- 'cleanup' EXIT`
+    pub static ref TRAP_START_RE: Regex = Regex::new(
+        r#"^trap\s+(?:--\s+)?"#
+    ).unwrap();
+
+    /// Matches a list of signals: `EXIT`, `INT TERM`, `SIGINT 15`
+    pub static ref TRAP_SIGNALS_RE: Regex = Regex::new(
+        r#"^[A-Za-z0-9_+]+(?:\s+[A-Za-z0-9_+]+)*$"#
+    ).unwrap();
+
+    // =========================================================================
+    // Zsh Option and Style Patterns
+    // =========================================================================
+
+    /// Matches `setopt`/`unsetopt` with option names and an optional
+    /// trailing comment: `setopt autocd extendedglob`, `unsetopt BEEP`
+    ///
+    /// Captures:
+    /// - Group 1: the option names
+    pub static ref SETOPT_RE: Regex = Regex::new(
+        r#"^(?:un)?setopt\s+([A-Za-z_][\w]*(?:\s+[A-Za-z_][\w]*)*)\s*(?:#.*)?$"#
+    ).unwrap();
+
+    /// Matches the start of a `zstyle` command defining a style, up to its
+    /// pattern. Options such as `-d` or `-L` query or delete styles instead.
+    pub static ref ZSTYLE_START_RE: Regex = Regex::new(
+        r#"^zstyle\s+(?:--\s+)?"#
     ).unwrap();
 
     // =========================================================================
@@ -113,18 +169,22 @@ lazy_static! {
 
     /// Matches function with parentheses: `name() {` or `function name() {`
     ///
+    /// Names may contain `-`, `.` and `:` as Bash and Zsh allow
+    /// (`git-clean() {`).
+    ///
     /// Captures:
     /// - Group 1: function name
     pub static ref FUNC_START_RE: Regex = Regex::new(
-        r#"^(?:function\s+)?(\w+)\s*\(\s*\)\s*\{?"#
+        r#"^(?:function\s+)?(\w[\w.:-]*)\s*\(\s*\)\s*\{?"#
     ).unwrap();
 
-    /// Matches function with `function` keyword (no parentheses): `function name {`
+    /// Matches function with `function` keyword (no parentheses): `function name {`,
+    /// or `function name` with the brace on the next line
     ///
     /// Captures:
     /// - Group 1: function name
     pub static ref FUNC_KEYWORD_RE: Regex = Regex::new(
-        r#"^function\s+(\w+)\s*\{"#
+        r#"^function\s+(\w[\w.:-]*)\s*(?:\{|$)"#
     ).unwrap();
 
     /// Matches anonymous function: `() {`
@@ -134,6 +194,21 @@ lazy_static! {
     pub static ref ANON_FUNC_RE: Regex = Regex::new(
         r#"^\(\s*\)\s*\{"#
     ).unwrap();
+
+    // =========================================================================
+    // Here-Document Patterns
+    // =========================================================================
+
+    /// Matches a here-document redirection: `<<EOF`, `<<-EOF`, `<<'EOF'`, `<<"EOF"`
+    ///
+    /// Here-strings (`<<<`) are not matched.
+    ///
+    /// Captures:
+    /// - Group 1: `-` if tabs should be stripped (`<<-`)
+    /// - Group 2/3/4: terminator word (single-quoted / double-quoted / bare)
+    pub static ref HEREDOC_START_RE: Regex = Regex::new(
+        r#"(?:^|[^<])<<(-?)\s*(?:'([A-Za-z_]\w*)'|"([A-Za-z_]\w*)"|\\?([A-Za-z_]\w*))"#
+    ).unwrap();
 }
 
 #[cfg(test)]
@@ -141,60 +216,34 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_alias_single_re() {
-        let caps = ALIAS_SINGLE_RE.captures("alias ll='ls -la'").unwrap();
+    fn test_alias_start_re() {
+        let caps = ALIAS_START_RE.captures("alias ll='ls -la'").unwrap();
         assert_eq!(&caps[1], "ll");
-        assert_eq!(&caps[2], "ls -la");
-    }
+        assert_eq!(caps.get(0).unwrap().end(), "alias ll=".len());
 
-    #[test]
-    fn test_alias_double_re() {
-        let caps = ALIAS_DOUBLE_RE
-            .captures(r#"alias gs="git status""#)
-            .unwrap();
-        assert_eq!(&caps[1], "gs");
-        assert_eq!(&caps[2], "git status");
+        let caps = ALIAS_START_RE.captures("alias complex='echo").unwrap();
+        assert_eq!(&caps[1], "complex");
+        assert!(ALIAS_START_RE.captures("alias").is_none());
     }
 
     #[test]
     fn test_alias_special_names() {
-        assert!(ALIAS_SINGLE_RE.captures("alias ..='cd ..'").is_some());
-        assert!(ALIAS_SINGLE_RE.captures("alias ~='cd ~'").is_some());
-        assert!(ALIAS_SINGLE_RE.captures("alias ...='cd ../..'").is_some());
-    }
-
-    #[test]
-    fn test_alias_multiline_start() {
-        let caps = ALIAS_MULTILINE_START_RE
-            .captures("alias complex='echo")
-            .unwrap();
-        assert_eq!(&caps[1], "complex");
+        assert!(ALIAS_START_RE.captures("alias ..='cd ..'").is_some());
+        assert!(ALIAS_START_RE.captures("alias ~='cd ~'").is_some());
+        assert!(ALIAS_START_RE.captures("alias ...='cd ../..'").is_some());
     }
 
     #[test]
     fn test_alias_with_options() {
-        // Test -g option
-        let caps = ALIAS_SINGLE_RE.captures("alias -g ll='ls -la'").unwrap();
-        assert_eq!(&caps[1], "ll");
-        assert_eq!(&caps[2], "ls -la");
-
-        // Test -a option with double quotes
-        let caps = ALIAS_DOUBLE_RE
-            .captures(r#"alias -a gs="git status""#)
-            .unwrap();
-        assert_eq!(&caps[1], "gs");
-        assert_eq!(&caps[2], "git status");
-
-        // Test no-quote with option
-        let caps = ALIAS_NOQUOTE_RE.captures("alias -p ls=exa").unwrap();
-        assert_eq!(&caps[1], "ls");
-        assert_eq!(&caps[2], "exa");
-
-        // Test multiline start with option
-        let caps = ALIAS_MULTILINE_START_RE
-            .captures("alias -g complex='echo")
-            .unwrap();
-        assert_eq!(&caps[1], "complex");
+        for line in [
+            "alias -g ll='ls -la'",
+            r#"alias -a ll="ls -la""#,
+            "alias -p ll=exa",
+            "alias -g ll='echo",
+        ] {
+            let caps = ALIAS_START_RE.captures(line).unwrap();
+            assert_eq!(&caps[1], "ll", "{}", line);
+        }
     }
 
     #[test]
@@ -204,6 +253,32 @@ mod tests {
         assert_eq!(&caps[2], "nvim");
     }
 
+    #[test]
+    fn test_prompt_assign_re() {
+        for name in crate::model::PROMPT_VARIABLES {
+            let line = format!("{}='> '", name);
+            let caps = PROMPT_ASSIGN_RE.captures(&line).unwrap();
+            assert_eq!(&caps[1], *name);
+        }
+        assert!(!PROMPT_ASSIGN_RE.is_match("PS10=x"));
+        assert!(!PROMPT_ASSIGN_RE.is_match("MYPS1=x"));
+        assert!(!PROMPT_ASSIGN_RE.is_match("PS1 = x"));
+    }
+
+    #[test]
+    fn test_export_func_re() {
+        let caps = EXPORT_FUNC_RE.captures("export -f greet").unwrap();
+        assert_eq!(&caps[1], "greet");
+        let caps = EXPORT_FUNC_RE
+            .captures("export -f git-prompt my.func # share")
+            .unwrap();
+        assert_eq!(&caps[1], "git-prompt my.func");
+
+        assert!(!EXPORT_FUNC_RE.is_match("export -fn greet"));
+        assert!(!EXPORT_FUNC_RE.is_match("export -f greet; echo"));
+        assert!(!EXPORT_FUNC_RE.is_match("export FOO=bar"));
+    }
+
     #[test]
     fn test_export_multiline_start() {
         let caps = EXPORT_MULTILINE_START_RE
@@ -230,9 +305,28 @@ mod tests {
         assert_eq!(&caps[1], "hello");
     }
 
+    #[test]
+    fn test_heredoc_start_re() {
+        let caps = HEREDOC_START_RE.captures("cat <<EOF > file").unwrap();
+        assert_eq!(&caps[4], "EOF");
+
+        let caps = HEREDOC_START_RE.captures("cat <<-'END'").unwrap();
+        assert_eq!(&caps[1], "-");
+        assert_eq!(&caps[2], "END");
+
+        assert!(HEREDOC_START_RE.captures("cat <<< \"$var\"").is_none());
+        assert!(HEREDOC_START_RE.captures("echo $((1 << 2))").is_none());
+    }
+
     #[test]
     fn test_func_keyword_re() {
         let caps = FUNC_KEYWORD_RE.captures("function test {").unwrap();
         assert_eq!(&caps[1], "test");
+
+        let caps = FUNC_KEYWORD_RE.captures("function git-clean").unwrap();
+        assert_eq!(&caps[1], "git-clean");
+
+        assert!(FUNC_KEYWORD_RE.captures("function foo bar {").is_none());
+        assert!(FUNC_KEYWORD_RE.captures("functions_dir=/x").is_none());
     }
 }
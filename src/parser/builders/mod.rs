@@ -52,13 +52,19 @@
 //! This module also provides utility functions for quote-aware parsing:
 //!
 //! - [`count_braces_outside_quotes`] - Count `{` and `}` outside quoted strings
-//! - [`extract_comment`] - Extract inline comments respecting quotes
-//! - [`strip_quotes`] - Remove surrounding quotes from a value
+//! - [`split_lines`] - Split file content into lines for the parsers
+//!
+//! The quoting helpers of [`crate::utils::quoting`] ([`extract_comment`],
+//! [`strip_quotes`], [`scan_quotes`], ...) are re-exported here for the
+//! parsers.
 
 mod comment;
 mod quoted;
 
 // Re-export builders
+pub use crate::utils::quoting::{
+    extract_comment, scan_quotes, shell_word_end, strip_quotes, unquote_shell_word, QuoteState,
+};
 pub use comment::CommentBlockBuilder;
 pub use quoted::QuotedValueBuilder;
 
@@ -142,94 +148,39 @@ pub fn count_parens_outside_quotes(line: &str) -> (usize, usize) {
     (open_count, close_count)
 }
 
-/// Extract inline comment from a line, respecting quoted strings.
-///
-/// The comment character (typically `#`) is only recognized outside
-/// of single and double quotes.
-///
-/// # Arguments
-///
-/// - `line`: The line to analyze
-/// - `comment_char`: The comment character (usually `#`)
-///
-/// # Returns
+/// Split file content into the lines the parsers walk over.
 ///
-/// A tuple `(code, comment)` where:
-/// - `code`: The part before the comment (trimmed)
-/// - `comment`: The comment text without the `#`, or `None`
+/// Unlike `str::lines()`, blank lines at the end are kept, since they belong
+/// to the raw value of the last entry. A single final `\n` only terminates
+/// the last line, so `"a\n}"` and `"a\n}\n"` yield the same lines.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// let (code, comment) = extract_comment("alias ll='ls -la' # list files", '#');
-/// assert_eq!(code, "alias ll='ls -la'");
-/// assert_eq!(comment, Some("list files".to_string()));
-///
-/// // Comment inside quotes is not extracted
-/// let (code, comment) = extract_comment("alias x='echo # not a comment'", '#');
-/// assert_eq!(code, "alias x='echo # not a comment'");
-/// assert_eq!(comment, None);
+/// assert_eq!(split_lines("a\nb"), ["a", "b"]);
+/// assert_eq!(split_lines("a\nb\n"), ["a", "b"]);
+/// assert_eq!(split_lines("a\nb\n\n"), ["a", "b", ""]);
 /// ```
-pub fn extract_comment(line: &str, comment_char: char) -> (String, Option<String>) {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let chars: Vec<char> = line.chars().collect();
-
-    for (i, &c) in chars.iter().enumerate() {
-        match c {
-            '\'' if !in_double_quote => in_single_quote = !in_single_quote,
-            '"' if !in_single_quote => in_double_quote = !in_double_quote,
-            c if c == comment_char && !in_single_quote && !in_double_quote => {
-                let code: String = chars[..i].iter().collect();
-                let comment: String = chars[i + 1..].iter().collect();
-                return (
-                    code.trim_end().to_string(),
-                    Some(comment.trim().to_string()),
-                );
-            }
-            _ => {}
-        }
-    }
-
-    (line.to_string(), None)
-}
-
-/// Strip surrounding quotes from a value.
-///
-/// Removes matching single or double quotes from the beginning and end
-/// of the value. If the quotes don't match or aren't present, returns
-/// the original value (trimmed).
-///
-/// # Arguments
-///
-/// - `value`: The value to strip quotes from
-///
-/// # Returns
-///
-/// The value without surrounding quotes.
-///
-/// # Example
-///
-/// ```rust,ignore
-/// assert_eq!(strip_quotes("'hello'"), "hello");
-/// assert_eq!(strip_quotes("\"world\""), "world");
-/// assert_eq!(strip_quotes("no quotes"), "no quotes");
-/// ```
-pub fn strip_quotes(value: &str) -> String {
-    let trimmed = value.trim();
-    if (trimmed.starts_with('\'') && trimmed.ends_with('\''))
-        || (trimmed.starts_with('"') && trimmed.ends_with('"'))
-    {
-        trimmed[1..trimmed.len() - 1].to_string()
-    } else {
-        trimmed.to_string()
+pub fn split_lines(content: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    if lines.len() > 1 && lines.last() == Some(&"") {
+        lines.pop();
     }
+    lines
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_split_lines_final_newline() {
+        assert_eq!(split_lines("a\nb"), ["a", "b"]);
+        assert_eq!(split_lines("a\nb\n"), ["a", "b"]);
+        assert_eq!(split_lines("a\nb\n\n"), ["a", "b", ""]);
+        assert_eq!(split_lines(""), [""]);
+    }
+
     #[test]
     fn test_count_braces_simple() {
         let (open, close) = count_braces_outside_quotes("function test() {");
@@ -257,57 +208,4 @@ mod tests {
         assert_eq!(open, 1);
         assert_eq!(close, 1);
     }
-
-    #[test]
-    fn test_extract_comment_basic() {
-        let (code, comment) = extract_comment("alias ll='ls -la' # list files", '#');
-        assert_eq!(code, "alias ll='ls -la'");
-        assert_eq!(comment, Some("list files".to_string()));
-    }
-
-    #[test]
-    fn test_extract_comment_in_single_quotes() {
-        let (code, comment) = extract_comment("alias x='echo # not a comment'", '#');
-        assert_eq!(code, "alias x='echo # not a comment'");
-        assert_eq!(comment, None);
-    }
-
-    #[test]
-    fn test_extract_comment_in_double_quotes() {
-        let (code, comment) = extract_comment("echo \"# not a comment\"", '#');
-        assert_eq!(code, "echo \"# not a comment\"");
-        assert_eq!(comment, None);
-    }
-
-    #[test]
-    fn test_extract_comment_none() {
-        let (code, comment) = extract_comment("echo hello", '#');
-        assert_eq!(code, "echo hello");
-        assert_eq!(comment, None);
-    }
-
-    #[test]
-    fn test_strip_quotes_single() {
-        assert_eq!(strip_quotes("'hello'"), "hello");
-    }
-
-    #[test]
-    fn test_strip_quotes_double() {
-        assert_eq!(strip_quotes("\"hello\""), "hello");
-    }
-
-    #[test]
-    fn test_strip_quotes_none() {
-        assert_eq!(strip_quotes("hello"), "hello");
-    }
-
-    #[test]
-    fn test_strip_quotes_mismatched() {
-        assert_eq!(strip_quotes("'hello\""), "'hello\"");
-    }
-
-    #[test]
-    fn test_strip_quotes_with_whitespace() {
-        assert_eq!(strip_quotes("  'hello'  "), "hello");
-    }
 }
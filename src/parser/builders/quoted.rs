@@ -45,6 +45,7 @@
 //! single-quoted strings are treated as literal characters.
 
 use crate::model::{Entry, EntryType};
+use crate::utils::quoting::{scan_quotes, QuoteState};
 
 /// Builder for multi-line quoted values (alias, env var).
 ///
@@ -60,11 +61,15 @@ pub struct QuotedValueBuilder {
 }
 
 impl QuotedValueBuilder {
-    /// Count single quotes in a line that are outside double-quoted strings.
+    /// Count the single quotes in a line that open or close a
+    /// single-quoted string.
     ///
-    /// This correctly handles cases like:
+    /// Follows Bash quoting, so quotes that are literal don't count:
     /// - `alias x='hello'` → 2 quotes
-    /// - `alias x='say "hi"'` → 2 quotes (quotes inside are literal)
+    /// - `alias x='say "hi"'` → 2 quotes (double quotes inside are literal)
+    /// - `alias x="it's"` → 0 quotes (a single quote inside double quotes)
+    /// - `alias x='it'\''s'` → 4 quotes (the escaped one is literal)
+    /// - `alias x='C:\'` → 2 quotes (no escapes inside single quotes)
     /// - `alias x='it` → 1 quote (unclosed)
     ///
     /// # Arguments
@@ -73,36 +78,21 @@ impl QuotedValueBuilder {
     ///
     /// # Returns
     ///
-    /// Number of single quotes outside double-quoted regions.
+    /// Number of single-quote delimiters.
     pub fn count_single_quotes(line: &str) -> usize {
-        let mut count = 0;
-        let mut in_double = false;
-        let mut chars = line.chars().peekable();
+        Self::count_single_quotes_from(line, QuoteState::Unquoted)
+    }
 
-        while let Some(c) = chars.next() {
-            match c {
-                // Handle escape sequences
-                '\\' => {
-                    // Skip the next character (it's escaped)
-                    chars.next();
-                }
-                // Toggle double-quote state (but not inside single quotes)
-                '"' if !in_double => {
-                    // We're entering a double-quoted region
-                    in_double = true;
-                }
-                '"' if in_double => {
-                    // We're exiting a double-quoted region
-                    in_double = false;
-                }
-                // Count single quotes only outside double-quoted regions
-                '\'' if !in_double => {
-                    count += 1;
-                }
-                _ => {}
+    /// Like [`count_single_quotes`](Self::count_single_quotes), for a line
+    /// starting in `state` (e.g. inside a single-quoted string continued
+    /// from the line above)
+    pub fn count_single_quotes_from(line: &str, state: QuoteState) -> usize {
+        let mut count = 0;
+        scan_quotes(line, state, |_, c, before| {
+            if c == '\'' && matches!(before, QuoteState::Unquoted | QuoteState::Single) {
+                count += 1;
             }
-        }
-
+        });
         count
     }
 
@@ -136,15 +126,10 @@ impl QuotedValueBuilder {
 
     /// Check if the accumulated content has balanced quotes.
     ///
-    /// Returns `true` when the total number of single quotes
+    /// Returns `true` when the total number of single-quote delimiters
     /// (across all lines) is even, meaning the quoted value is complete.
     pub fn is_complete(&self) -> bool {
-        let total_quotes: usize = self
-            .lines
-            .iter()
-            .map(|l| Self::count_single_quotes(l))
-            .sum();
-        total_quotes.is_multiple_of(2)
+        Self::count_single_quotes(&self.lines.join("\n")).is_multiple_of(2)
     }
 
     /// Get the current line count.
@@ -199,6 +184,14 @@ impl QuotedValueBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_count_single_quotes_from_open_quote() {
+        assert_eq!(
+            QuotedValueBuilder::count_single_quotes_from("it's'", QuoteState::Single),
+            2
+        );
+    }
+
     #[test]
     fn test_count_single_quotes_basic() {
         assert_eq!(
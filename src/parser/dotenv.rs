@@ -0,0 +1,287 @@
+//! # `.env` Parser
+//!
+//! Parses plain `.env` files, which hold nothing but variables:
+//!
+//! | Line | Entry |
+//! |------|-------|
+//! | `KEY=value`, `export KEY="value"` | EnvVar (quotes and `export` optional) |
+//! | `# comment` | Comment, merged into a variable directly below it |
+//! | blank | blank Code entry |
+//! | anything else | Code, with a warning |
+//!
+//! A quoted value may span lines until its closing quote.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::model::{Entry, EntryType, ParseResult, ParseWarning, ShellType, WarningCode};
+use crate::parser::Parser;
+
+lazy_static! {
+    /// `KEY=value`, optionally prefixed by `export`
+    static ref ASSIGNMENT_RE: Regex =
+        Regex::new(r"^\s*(?:export\s+)?([A-Za-z_][A-Za-z0-9_.]*)\s*=\s*(.*)$").unwrap();
+}
+
+/// `.env` file parser.
+pub struct DotEnvParser {
+    merge_comments: bool,
+    attach_comments: bool,
+}
+
+impl DotEnvParser {
+    /// Create a new `.env` parser instance.
+    pub fn new() -> Self {
+        Self {
+            merge_comments: true,
+            attach_comments: false,
+        }
+    }
+
+    /// Keep every comment as its own Comment entry instead of merging it
+    /// into the variable below.
+    pub fn with_comment_merging(mut self, merge_comments: bool) -> Self {
+        self.merge_comments = merge_comments;
+        self
+    }
+
+    /// Also record the comment directly above each variable in its
+    /// `attached_comment`, whether or not it merged into the value.
+    pub fn with_attached_comments(mut self, attach_comments: bool) -> Self {
+        self.attach_comments = attach_comments;
+        self
+    }
+}
+
+impl Default for DotEnvParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The quote a value opens without closing on the same line, if any
+fn unclosed_quote(value: &str) -> Option<char> {
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    (!closes(&value[1..], quote)).then_some(quote)
+}
+
+/// Whether `text` contains an unescaped `quote`
+fn closes(text: &str, quote: char) -> bool {
+    let mut escaped = false;
+    for c in text.chars() {
+        match c {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            c if c == quote && !escaped => return true,
+            _ => escaped = false,
+        }
+    }
+    false
+}
+
+/// Name of a Code or Comment entry: `L3`, or `L3-L5` over several lines
+fn block_name(start: usize, lines: &[&str]) -> String {
+    match lines.len() {
+        0 | 1 => format!("L{}", start),
+        n => format!("L{}-L{}", start, start + n - 1),
+    }
+}
+
+/// Entry spanning `lines` (1-based `start`), joined with newlines
+fn entry(entry_type: EntryType, name: String, start: usize, lines: &[&str]) -> Entry {
+    let entry = Entry::new(entry_type, name, lines.join("\n")).with_line_number(start);
+    if lines.len() > 1 {
+        entry.with_end_line(start + lines.len() - 1)
+    } else {
+        entry
+    }
+}
+
+impl Parser for DotEnvParser {
+    fn parse(&self, content: &str) -> ParseResult {
+        let mut result = ParseResult::new();
+        let lines: Vec<&str> = content.lines().collect();
+        // Start of the comment lines right above the current line
+        let mut comments: Option<usize> = None;
+        let mut i = 0;
+
+        let flush_comments = |result: &mut ParseResult, comments: &mut Option<usize>, end| {
+            if let Some(start) = comments.take() {
+                let block = &lines[start..end];
+                let name = block_name(start + 1, block);
+                result.add_entry(entry(EntryType::Comment, name, start + 1, block));
+            }
+        };
+
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim();
+
+            if trimmed.starts_with('#') {
+                comments.get_or_insert(i);
+                i += 1;
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                flush_comments(&mut result, &mut comments, i);
+                let start = i;
+                while i < lines.len() && lines[i].trim().is_empty() {
+                    i += 1;
+                }
+                let block = &lines[start..i];
+                let name = block_name(start + 1, block);
+                result.add_entry(entry(EntryType::Code, name, start + 1, block));
+                continue;
+            }
+
+            let Some(caps) = ASSIGNMENT_RE.captures(line) else {
+                flush_comments(&mut result, &mut comments, i);
+                result.add_warning(ParseWarning::new(
+                    WarningCode::InvalidAssignment,
+                    i + 1,
+                    "Not a KEY=value assignment",
+                    line,
+                ));
+                let name = format!("L{}", i + 1);
+                result.add_entry(entry(EntryType::Code, name, i + 1, &lines[i..=i]));
+                i += 1;
+                continue;
+            };
+
+            let mut end = i;
+            if let Some(quote) = unclosed_quote(caps[2].trim_start()) {
+                match (i + 1..lines.len()).find(|&j| closes(lines[j], quote)) {
+                    Some(close) => end = close,
+                    None => result.add_warning(ParseWarning::new(
+                        WarningCode::UnclosedQuote,
+                        i + 1,
+                        format!("Unclosed {} quote in value of {}", quote, &caps[1]),
+                        line,
+                    )),
+                }
+            }
+
+            let start = match comments {
+                Some(start) if self.merge_comments => {
+                    comments = None;
+                    start
+                }
+                _ => {
+                    flush_comments(&mut result, &mut comments, i);
+                    i
+                }
+            };
+            result.add_entry(entry(
+                EntryType::EnvVar,
+                caps[1].to_string(),
+                start + 1,
+                &lines[start..=end],
+            ));
+            i = end + 1;
+        }
+        flush_comments(&mut result, &mut comments, lines.len());
+        if self.attach_comments {
+            result.record_attached_comments();
+        }
+
+        result
+    }
+
+    fn shell_type(&self) -> ShellType {
+        ShellType::DotEnv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignments_with_and_without_quotes() {
+        let result = DotEnvParser::new().parse(
+            "DATABASE_URL=postgres://localhost/app\nexport API_KEY=\"abc 123\"\nGREETING='hi # there'\nEMPTY=\n",
+        );
+
+        let vars: Vec<(&str, String)> = result
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.canonical_value()))
+            .collect();
+        assert_eq!(
+            vars,
+            [
+                ("DATABASE_URL", "postgres://localhost/app".to_string()),
+                ("API_KEY", "abc 123".to_string()),
+                ("GREETING", "hi # there".to_string()),
+                ("EMPTY", String::new()),
+            ]
+        );
+        assert!(result
+            .entries
+            .iter()
+            .all(|e| e.entry_type == EntryType::EnvVar));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let content = "# Database\nDB_HOST=localhost\n\n# Trailing notes\n";
+        let result = DotEnvParser::new().parse(content);
+
+        assert_eq!(result.entries.len(), 3);
+        let db = &result.entries[0];
+        assert_eq!(db.entry_type, EntryType::EnvVar);
+        assert_eq!(db.value, "# Database\nDB_HOST=localhost");
+        assert_eq!((db.line_number, db.end_line), (Some(1), Some(2)));
+        assert_eq!(db.canonical_value(), "localhost");
+        assert!(result.entries[1].is_blank());
+        assert_eq!(result.entries[2].entry_type, EntryType::Comment);
+
+        let unmerged = DotEnvParser::new()
+            .with_comment_merging(false)
+            .parse(content);
+        assert_eq!(unmerged.entries[0].entry_type, EntryType::Comment);
+        assert_eq!(unmerged.entries[1].value, "DB_HOST=localhost");
+    }
+
+    #[test]
+    fn test_attached_comments() {
+        let content = "# Database\nDB_HOST=localhost\nDB_PORT=5432\n";
+        for merge_comments in [true, false] {
+            let result = DotEnvParser::new()
+                .with_comment_merging(merge_comments)
+                .with_attached_comments(true)
+                .parse(content);
+            let vars: Vec<_> = result
+                .entries
+                .iter()
+                .filter(|e| e.entry_type == EntryType::EnvVar)
+                .map(|e| e.attached_comment.as_deref())
+                .collect();
+            assert_eq!(vars, vec![Some("# Database"), None], "{}", merge_comments);
+        }
+    }
+
+    #[test]
+    fn test_multi_line_quoted_value() {
+        let result = DotEnvParser::new()
+            .parse("KEY=\"-----BEGIN-----\nabc\\\"def\n-----END-----\"\nNEXT=1\n");
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(
+            (result.entries[0].line_number, result.entries[0].end_line),
+            (Some(1), Some(3))
+        );
+        assert_eq!(result.entries[1].name, "NEXT");
+    }
+
+    #[test]
+    fn test_other_lines_are_code_with_warning() {
+        let result = DotEnvParser::new().parse("echo hello\nKEY=1\n");
+
+        assert_eq!(result.entries[0].entry_type, EntryType::Code);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].line_number, 1);
+        assert_eq!(result.warnings[0].code, WarningCode::InvalidAssignment);
+    }
+}
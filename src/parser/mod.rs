@@ -7,11 +7,13 @@
 //! ```text
 //! parser/
 //! ├── mod.rs              - This file: Parser trait + factory function
+//! ├── dotenv.rs           - `.env` parser (KEY=value lines only)
 //! ├── bash/               - Bash shell parser
 //! │   ├── mod.rs          - BashParser struct + Parser impl
 //! │   ├── patterns.rs     - Regex patterns (ALIAS_*, FUNC_*, etc.)
 //! │   ├── control.rs      - Control structure detection (if/fi, etc.)
 //! │   └── parsers.rs      - Individual parse methods
+//! ├── regions.rs          - Preserved regions (wenv:ignore, conda init, ...)
 //! ├── pwsh/               - PowerShell parser (same structure)
 //! │   ├── mod.rs
 //! │   ├── patterns.rs
@@ -73,11 +75,19 @@
 
 mod bash;
 pub mod builders;
+mod dotenv;
 pub mod pending;
 mod pwsh;
+pub mod regions;
+mod sh;
 
+pub(crate) use bash::parsers::{setopt_options, trap_signals, zstyle_name};
 pub use bash::BashParser;
+pub use dotenv::DotEnvParser;
+pub(crate) use pwsh::parsers::alias_arguments as pwsh_alias_arguments;
+pub(crate) use pwsh::parsers::module_argument as pwsh_module_argument;
 pub use pwsh::PowerShellParser;
+pub use sh::ShParser;
 
 use crate::model::{Entry, EntryType, ParseResult, ShellType};
 pub use pending::BoundaryType;
@@ -205,9 +215,47 @@ pub trait Parser: Send + Sync {
 /// let result = parser.parse(content);
 /// ```
 pub fn get_parser(shell_type: ShellType) -> Box<dyn Parser> {
+    get_configured_parser(shell_type, true, false)
+}
+
+/// Like [`get_parser`], but every comment is kept as a standalone Comment
+/// entry instead of merging into the entry or code below it.
+///
+/// Gives a strictly positional, line-by-line view of the file for tooling.
+pub fn get_unmerged_parser(shell_type: ShellType) -> Box<dyn Parser> {
+    get_configured_parser(shell_type, false, false)
+}
+
+/// Parser for `shell_type` with the comment options set: `merge_comments`
+/// as in [`get_parser`] (off as in [`get_unmerged_parser`]), and
+/// `attach_comments` to record the comment above each entry in its
+/// `attached_comment` (`parser.attach_comments`).
+pub fn get_configured_parser(
+    shell_type: ShellType,
+    merge_comments: bool,
+    attach_comments: bool,
+) -> Box<dyn Parser> {
     match shell_type {
-        ShellType::Bash | ShellType::Zsh => Box::new(BashParser::new()),
-        ShellType::PowerShell => Box::new(PowerShellParser::new()),
+        ShellType::Bash | ShellType::Zsh => Box::new(
+            BashParser::new()
+                .with_comment_merging(merge_comments)
+                .with_attached_comments(attach_comments),
+        ),
+        ShellType::PosixSh => Box::new(
+            ShParser::new()
+                .with_comment_merging(merge_comments)
+                .with_attached_comments(attach_comments),
+        ),
+        ShellType::PowerShell => Box::new(
+            PowerShellParser::new()
+                .with_comment_merging(merge_comments)
+                .with_attached_comments(attach_comments),
+        ),
+        ShellType::DotEnv => Box::new(
+            DotEnvParser::new()
+                .with_comment_merging(merge_comments)
+                .with_attached_comments(attach_comments),
+        ),
     }
 }
 
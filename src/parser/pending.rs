@@ -19,6 +19,8 @@
 //! | `BraceCounting` | Track `{` and `}` | `function() { ... }` |
 //! | `QuoteCounting` | Track odd/even quotes | Multi-line aliases |
 //! | `KeywordTracking` | Track control keywords | `if`/`fi`, `while`/`done` |
+//! | `Heredoc` | Wait for terminator line | `cat <<EOF` ... `EOF` |
+//! | `Guarded` | Wait for the region's last line | `# >>> conda initialize >>>` ... |
 //! | `AdjacentMerging` | Merge consecutive lines | Comments, blank lines |
 
 use crate::model::EntryType;
@@ -57,6 +59,23 @@ pub enum BoundaryType {
         depth: usize,
     },
 
+    /// Accumulate here-document lines verbatim until the terminator line.
+    /// No other counting (braces, keywords) is done inside the body.
+    Heredoc {
+        /// The terminator word (e.g., `EOF`).
+        terminator: String,
+        /// `<<-` form: leading tabs are stripped before matching the terminator.
+        strip_tabs: bool,
+    },
+
+    /// Accumulate a preserved region verbatim up to its last line (see
+    /// [`crate::parser::regions`]). The block becomes a single Code entry
+    /// that formatting keeps in place.
+    Guarded {
+        /// 1-based number of the region's last line.
+        end_line: usize,
+    },
+
     /// Merge adjacent lines of the same type (comments, blank lines).
     /// Block is complete when a different line type is encountered.
     AdjacentMerging {
@@ -146,6 +165,13 @@ impl PendingBlock {
         block
     }
 
+    /// Create a pending block for a preserved region ending at `end_line`.
+    pub fn guarded(start_line: usize, first_line: &str, end_line: usize) -> Self {
+        let mut block = Self::new(start_line, first_line, BoundaryType::Guarded { end_line });
+        block.entry_hint = Some(EntryType::Code);
+        block
+    }
+
     /// Create a pending block for a multi-line alias.
     pub fn multiline_alias(
         name: String,
@@ -235,6 +261,9 @@ impl PendingBlock {
             BoundaryType::ParenthesisCounting { parenthesis_count } => *parenthesis_count == 0,
             BoundaryType::QuoteCounting { quote_count } => quote_count % 2 == 0,
             BoundaryType::KeywordTracking { depth } => *depth == 0,
+            // Heredoc and guarded blocks are completed externally when the
+            // terminator or closing marker is seen.
+            BoundaryType::Heredoc { .. } | BoundaryType::Guarded { .. } => false,
             // AdjacentMerging blocks are never "complete" by themselves;
             // they're completed externally when a non-matching line is seen.
             BoundaryType::AdjacentMerging { .. } => false,
@@ -331,7 +360,7 @@ impl PendingBlock {
         self.comment_count += 1;
     }
 
-    /// Check if this pending block represents a structured entry (Alias/EnvVar/Source/Function)
+    /// Check if this pending block represents a structured entry (Alias/EnvVar/Source/Function/...)
     /// that is absorbing trailing blank lines.
     pub fn is_structured_entry(&self) -> bool {
         matches!(
@@ -340,6 +369,11 @@ impl PendingBlock {
                 | Some(EntryType::EnvVar)
                 | Some(EntryType::Source)
                 | Some(EntryType::Function)
+                | Some(EntryType::Array)
+                | Some(EntryType::Unset)
+                | Some(EntryType::Trap)
+                | Some(EntryType::Setopt)
+                | Some(EntryType::Zstyle)
         )
     }
 }
@@ -9,9 +9,11 @@
 //! | Alias | `Set-Alias`, `New-Alias` | ❌ |
 //! | EnvVar | `$env:VAR = value` | ❌ |
 //! | Function | `function Name { ... }` | ✅ Brace counting |
-//! | Source | `. .\file.ps1` | ❌ |
+//! | Source | `. .\file.ps1`, `. "$HOME\x.ps1"`, `. (Join-Path ...)` | ❌ |
+//! | Source | `Import-Module Name`, `using module .\Mod.psm1` | ❌ |
 //! | Comment | `# text` | ✅ Adjacent merging |
 //! | Code | Control structures, other | ✅ Keyword tracking |
+//! | Code | `# >>> wenv:ignore >>>`, conda init, ... ([`regions`](crate::parser::regions)) | ✅ Verbatim to the region's end |
 //!
 //! ## Module Structure
 //!
@@ -29,14 +31,16 @@ pub mod control;
 pub mod parsers;
 pub mod patterns;
 
-use crate::model::{Entry, EntryType, ParseResult, ShellType};
-use crate::parser::builders::{count_braces_outside_quotes, CommentBlockBuilder};
+use crate::model::{Entry, EntryType, ParseResult, ShellType, WarningCode};
+use crate::parser::builders::{count_braces_outside_quotes, split_lines, CommentBlockBuilder};
 use crate::parser::pending::{BoundaryType, PendingBlock};
+use crate::parser::regions::find_region;
 use crate::parser::Parser;
 
 use control::{count_control_end, count_control_start};
 use parsers::{
     detect_function_start, is_heredoc_end, try_parse_alias, try_parse_env, try_parse_source,
+    try_parse_unset,
 };
 
 use crate::parser::ParseEvent;
@@ -44,12 +48,36 @@ use crate::parser::ParseEvent;
 /// PowerShell configuration file parser.
 ///
 /// Implements the [`Parser`] trait for parsing PowerShell profiles.
-pub struct PowerShellParser;
+pub struct PowerShellParser {
+    /// Merge a comment block into the code below it
+    merge_comments: bool,
+    /// Record the comment above each structured entry in `attached_comment`
+    attach_comments: bool,
+}
 
 impl PowerShellParser {
     /// Create a new PowerShell parser instance.
     pub fn new() -> Self {
-        Self
+        Self {
+            merge_comments: true,
+            attach_comments: false,
+        }
+    }
+
+    /// Enable or disable merging comments into the code below them.
+    ///
+    /// PowerShell comments never merge into structured entries; with merging
+    /// off they also stay separate from plain code and control blocks.
+    pub fn with_comment_merging(mut self, merge_comments: bool) -> Self {
+        self.merge_comments = merge_comments;
+        self
+    }
+
+    /// Also record the comment block directly above each structured entry
+    /// in its `attached_comment`.
+    pub fn with_attached_comments(mut self, attach_comments: bool) -> Self {
+        self.attach_comments = attach_comments;
+        self
     }
 }
 
@@ -75,23 +103,22 @@ impl Parser for PowerShellParser {
         let mut control_depth: usize = 0;
 
         // === Main parsing loop ===
-        // Use split('\n') instead of lines() to preserve trailing empty lines.
-        // lines() treats '\n' as a line terminator, so "a\nb\n" → ["a", "b"]
-        // But for raw_line format (where '\n' is a separator), we need "a\nb\n" → ["a", "b", ""]
-        // However, a file ending with '\n' is just proper file termination, not an extra line.
-        // So we strip the final empty element only if content ends with '\n'.
-        let lines_vec: Vec<&str> = content.split('\n').collect();
-        let lines_to_process: &[&str] = if lines_vec.last() == Some(&"") && content.ends_with('\n')
-        {
-            &lines_vec[..lines_vec.len() - 1]
-        } else {
-            &lines_vec[..]
-        };
+        // Trailing blank lines are kept; a final '\n' only terminates the last line
+        let lines_to_process = split_lines(content);
 
         for (line_num, line) in lines_to_process.iter().enumerate() {
             let line_number = line_num + 1;
             let trimmed = line.trim();
 
+            // A shebang is its own entry, never merged into the one below
+            if line_number == 1 && line.starts_with("#!") {
+                result.add_entry(
+                    Entry::new(EntryType::Comment, "L1".to_string(), line.to_string())
+                        .with_line_number(1),
+                );
+                continue;
+            }
+
             // ------------------------------------------------------------------
             // Handle active multi-line block (function, control, Here-String)
             // ------------------------------------------------------------------
@@ -157,11 +184,40 @@ impl Parser for PowerShellParser {
                             });
                         }
                     }
+                    BoundaryType::Guarded { end_line } if *end_line == line_number => {
+                        let entry = self.build_entry_from_pending(active_block.take().unwrap());
+                        result.add_entry(entry);
+                    }
                     _ => {}
                 }
                 continue;
             }
 
+            // ------------------------------------------------------------------
+            // Preserved region (`# >>> wenv:ignore >>>`, conda init, ...)
+            // ------------------------------------------------------------------
+            if let Some(region) = find_region(&lines_to_process, line_num) {
+                if let Some(e) = self.flush_pending_comment_code(&mut pending_entry) {
+                    result.add_entry(e);
+                }
+                if !region.closed {
+                    result.add_warning(crate::model::ParseWarning::new(
+                        WarningCode::UnclosedRegion,
+                        line_number,
+                        format!("Unclosed {} block at end of file", region.region.name),
+                        "",
+                    ));
+                }
+                let block = PendingBlock::guarded(line_number, line, region.end + 1);
+                if region.end == line_num {
+                    // Unclosed marker on the last line
+                    result.add_entry(self.build_entry_from_pending(block));
+                } else {
+                    active_block = Some(block);
+                }
+                continue;
+            }
+
             // ------------------------------------------------------------------
             // Check for control structure start/continuation
             // ------------------------------------------------------------------
@@ -173,10 +229,12 @@ impl Parser for PowerShellParser {
                 // Start control block - merge pending Comment/Code if present
                 if active_block.is_none() && prev_depth == 0 && control_depth > 0 {
                     if let Some(pending) = pending_entry.take() {
-                        if matches!(
-                            pending.entry_hint,
-                            Some(EntryType::Comment) | Some(EntryType::Code)
-                        ) {
+                        let mergeable = match pending.entry_hint {
+                            Some(EntryType::Comment) => self.merge_comments,
+                            Some(EntryType::Code) => true,
+                            _ => false,
+                        };
+                        if mergeable {
                             // Seed block with pending content
                             let mut lines = pending.lines;
                             lines.push(line.to_string());
@@ -328,6 +386,15 @@ impl Parser for PowerShellParser {
                 ParseEvent::None => {}
             }
 
+            // Try unset (`Remove-Item Env:VAR`, `Remove-Alias ll`)
+            if let ParseEvent::Complete(entry) = try_parse_unset(trimmed, line_number) {
+                if let Some(e) = self.flush_pending_comment_code(&mut pending_entry) {
+                    result.add_entry(e);
+                }
+                result.add_entry(entry);
+                continue;
+            }
+
             // Try function
             if let Some(func_name) = detect_function_start(trimmed) {
                 // Flush pending entry
@@ -355,7 +422,9 @@ impl Parser for PowerShellParser {
             // Fallback: capture as non-blank Code
             // ------------------------------------------------------------------
             match &mut pending_entry {
-                Some(pending) if pending.entry_hint == Some(EntryType::Comment) => {
+                Some(pending)
+                    if self.merge_comments && pending.entry_hint == Some(EntryType::Comment) =>
+                {
                     // Comment + non-blank Code → merge and upgrade to Code
                     pending.add_line(line, line_number);
                     pending.upgrade_to_code();
@@ -389,14 +458,27 @@ impl Parser for PowerShellParser {
 
         // Warn about unclosed active block
         if let Some(block) = active_block {
-            let msg = match block.entry_hint {
-                Some(EntryType::Function) => "Unclosed function definition at end of file",
-                Some(EntryType::EnvVar) => {
-                    "Unclosed environment variable Here-String at end of file"
-                }
-                _ => "Unclosed block at end of file",
+            let (code, msg) = match block.entry_hint {
+                Some(EntryType::Function) => (
+                    WarningCode::UnclosedFunction,
+                    "Unclosed function definition at end of file",
+                ),
+                Some(EntryType::EnvVar) => (
+                    WarningCode::UnclosedHeredoc,
+                    "Unclosed environment variable Here-String at end of file",
+                ),
+                _ => (WarningCode::UnclosedBlock, "Unclosed block at end of file"),
             };
-            result.add_warning(crate::model::ParseWarning::new(block.start_line, msg, ""));
+            result.add_warning(crate::model::ParseWarning::new(
+                code,
+                block.start_line,
+                msg,
+                "",
+            ));
+        }
+
+        if self.attach_comments {
+            result.record_attached_comments();
         }
 
         result
@@ -502,6 +584,73 @@ mod tests {
         assert_eq!(aliases[0].value, "Set-Alias ll Get-ChildItem");
     }
 
+    #[test]
+    fn test_eof_without_newline_matches_terminated_file() {
+        let parser = PowerShellParser::new();
+        for content in [
+            "function Greet {\n  Write-Host hi\n}",
+            "# Greets\nfunction Greet\n{\n  if ($x) {\n    Write-Host hi\n  }\n}",
+            "Set-Alias ll Get-ChildItem\nfunction Greet {\n  Write-Host hi\n} # done",
+            "$env:NOTES = @\"\nfirst\nsecond\n\"@",
+        ] {
+            let unterminated = parser.parse(content);
+            let terminated = parser.parse(&format!("{}\n", content));
+            assert_eq!(unterminated.entries.len(), terminated.entries.len());
+            for (a, b) in unterminated.entries.iter().zip(&terminated.entries) {
+                assert_eq!(a.entry_type, b.entry_type, "{:?}", content);
+                assert_eq!(a.value, b.value, "{:?}", content);
+                assert_eq!(
+                    (a.line_number, a.end_line),
+                    (b.line_number, b.end_line),
+                    "{:?}",
+                    content
+                );
+            }
+
+            let last = unterminated.entries.last().unwrap();
+            assert_eq!(
+                last.end_line,
+                Some(content.lines().count()),
+                "{:?}",
+                content
+            );
+            assert!(content.ends_with(&last.value), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn test_parse_dot_source_variants() {
+        let parser = PowerShellParser::new();
+        let content = ". (Join-Path $PSScriptRoot 'aliases.ps1')\n. \"$HOME\\profile-extra.ps1\"\n. $PSScriptRoot\\local.ps1\n";
+        let result = parser.parse(content);
+
+        let sources: Vec<(EntryType, &str, String)> = result
+            .entries
+            .iter()
+            .map(|e| (e.entry_type, e.name.as_str(), e.canonical_value()))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                (
+                    EntryType::Source,
+                    "(Join-Path $PSScriptRoot 'aliases.ps1')",
+                    "(Join-Path $PSScriptRoot 'aliases.ps1')".to_string()
+                ),
+                (
+                    EntryType::Source,
+                    "profile-extra",
+                    "$HOME\\profile-extra.ps1".to_string()
+                ),
+                (
+                    EntryType::Source,
+                    "local",
+                    "$PSScriptRoot\\local.ps1".to_string()
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_env() {
         let parser = PowerShellParser::new();
@@ -537,6 +686,33 @@ mod tests {
         assert_eq!(funcs[0].end_line, Some(3));
     }
 
+    #[test]
+    fn test_comment_merging_can_be_disabled() {
+        let content = "# note\nWrite-Host hi\n# loop\nforeach ($f in $files) {\n  $f\n}\n";
+        let types = |parser: PowerShellParser| {
+            parser
+                .parse(content)
+                .entries
+                .iter()
+                .map(|e| (e.entry_type, e.line_number))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            types(PowerShellParser::new()),
+            [(EntryType::Code, Some(1)), (EntryType::Code, Some(3))]
+        );
+        assert_eq!(
+            types(PowerShellParser::new().with_comment_merging(false)),
+            [
+                (EntryType::Comment, Some(1)),
+                (EntryType::Code, Some(2)),
+                (EntryType::Comment, Some(3)),
+                (EntryType::Code, Some(4)),
+            ]
+        );
+    }
+
     #[test]
     fn test_adjacent_comments_merged() {
         let parser = PowerShellParser::new();
@@ -742,6 +918,46 @@ Line with 'single quotes'
         assert!(envs[0].value.contains("$variable"));
     }
 
+    #[test]
+    fn test_guarded_block_is_one_code_entry() {
+        let parser = PowerShellParser::new();
+        let block = "# >>> wenv:ignore >>>\n(& conda.exe shell.powershell hook) | Out-String | Invoke-Expression\nfunction Half {\n# <<< wenv:ignore <<<";
+        let result = parser.parse(&format!("Set-Alias ll ls\n{}\n$env:A = \"1\"\n", block));
+
+        assert_eq!(result.entries.len(), 3);
+        assert!(result.entries[1].is_guarded());
+        assert_eq!(result.entries[1].value, block);
+        assert_eq!(result.entries[2].entry_type, EntryType::EnvVar);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_conda_region_is_one_code_entry() {
+        let parser = PowerShellParser::new();
+        let region = r#"#region conda initialize
+# !! Contents within this block are managed by 'conda init' !!
+If (Test-Path "C:\Users\me\miniconda3\Scripts\conda.exe") {
+    (& "C:\Users\me\miniconda3\Scripts\conda.exe" "shell.powershell" "hook") | Out-String | ?{$_} | Invoke-Expression
+}
+#endregion"#;
+        let result = parser.parse(&format!("Set-Alias ll ls\n\n{}\n", region));
+
+        let guarded: Vec<_> = result.entries.iter().filter(|e| e.is_guarded()).collect();
+        assert_eq!(guarded.len(), 1);
+        assert_eq!(guarded[0].value, region);
+        assert_eq!(guarded[0].line_number, Some(3));
+    }
+
+    #[test]
+    fn test_unclosed_function_warning_code() {
+        let parser = PowerShellParser::new();
+        let result = parser.parse("function Test {\n    Write-Host hi\n");
+
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].code, WarningCode::UnclosedFunction);
+        assert_eq!(result.warnings[0].line_number, 1);
+    }
+
     #[test]
     fn test_env_heredoc_unclosed_warning() {
         let parser = PowerShellParser::new();
@@ -753,6 +969,7 @@ D:\tools"#;
         assert!(result.warnings.iter().any(|w| w
             .message
             .contains("Unclosed environment variable Here-String")));
+        assert_eq!(result.warnings[0].code, WarningCode::UnclosedHeredoc);
     }
 
     #[test]
@@ -945,6 +1162,19 @@ C:\bin
         assert_eq!(alias.name, "ll");
     }
 
+    #[test]
+    fn test_comment_above_alias_is_attached() {
+        let result = PowerShellParser::new()
+            .with_attached_comments(true)
+            .parse("# Section header\nSet-Alias ll Get-ChildItem");
+
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(
+            result.entries[1].attached_comment.as_deref(),
+            Some("# Section header")
+        );
+    }
+
     #[test]
     fn test_comment_blank_alias_scenario() {
         let parser = PowerShellParser::new();
@@ -999,4 +1229,35 @@ C:\bin
         assert!(code_blocks[0].value.contains("# This is a conditional"));
         assert!(code_blocks[0].value.contains("if ($true)"));
     }
+
+    #[test]
+    fn test_tag_comment_attaches_tags() {
+        let parser = PowerShellParser::new();
+        let result = parser.parse("# wenv:tags=git,work\nSet-Alias gs git-status\n\n# wenv:tags=work\nfunction Greet {\n  Write-Host hi\n}\nSet-Alias ll Get-ChildItem\n");
+        let tags = |name: &str| {
+            result
+                .entries
+                .iter()
+                .find(|e| e.name == name)
+                .map(|e| e.tags.clone())
+                .unwrap()
+        };
+
+        assert_eq!(tags("gs"), vec!["git", "work"]);
+        assert_eq!(tags("Greet"), vec!["work"]);
+        assert_eq!(tags("ll"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_shebang_is_its_own_entry() {
+        let parser = PowerShellParser::new();
+        let result = parser.parse(
+            "#!/usr/bin/env pwsh
+Set-Alias ll Get-ChildItem
+",
+        );
+
+        assert!(result.entries[0].is_shebang());
+        assert_eq!(result.entries[1].value, "Set-Alias ll Get-ChildItem");
+    }
 }
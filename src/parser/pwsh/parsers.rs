@@ -8,6 +8,7 @@
 //! - `try_parse_alias(line, line_num) -> ParseEvent`
 //! - `try_parse_env(line, line_num) -> ParseEvent`
 //! - `try_parse_source(line, line_num) -> ParseEvent`
+//! - `try_parse_unset(line, line_num) -> ParseEvent`
 //!
 //! Returns:
 //! - `ParseEvent::Complete(entry)` for single-line entries
@@ -22,7 +23,8 @@
 //!
 //! - Alias: `Set-Alias`, `New-Alias`
 //! - EnvVar: `$env:NAME = value` (single-line or Here-String)
-//! - Source: `. .\file.ps1`
+//! - Source: `. .\file.ps1`, `Import-Module Name`, `using module .\Mod.psm1`
+//! - Unset: `Remove-Item Env:NAME`, `Remove-Item Alias:name`, `Remove-Alias name`
 //! - Function: `function Name { }`
 
 use super::patterns::*;
@@ -32,9 +34,10 @@ use crate::parser::{BoundaryType, ParseEvent};
 
 /// Try to parse a line as a PowerShell alias.
 ///
-/// Matches:
+/// Matches `Set-Alias` and `New-Alias` with positional or named parameters
+/// in any order (see [`alias_arguments`]):
 /// - `Set-Alias name value`
-/// - `New-Alias -Name name -Value value`
+/// - `New-Alias -Name name -Value value -Description 'text'`
 ///
 /// # Arguments
 ///
@@ -46,23 +49,100 @@ use crate::parser::{BoundaryType, ParseEvent};
 /// - `ParseEvent::Complete(entry)` if the line is an alias
 /// - `ParseEvent::None` otherwise
 pub fn try_parse_alias(line: &str, line_num: usize) -> ParseEvent {
-    // Try simple format first
-    if let Some(caps) = ALIAS_SIMPLE_RE.captures(line) {
-        return ParseEvent::Complete(
-            Entry::new(EntryType::Alias, caps[1].to_string(), line.to_string())
-                .with_line_number(line_num),
-        );
+    match alias_arguments(line) {
+        Some((name, _)) => ParseEvent::Complete(
+            Entry::new(EntryType::Alias, name, line.to_string()).with_line_number(line_num),
+        ),
+        None => ParseEvent::None,
     }
+}
 
-    // Try complex format with -Name and -Value
-    if let Some(caps) = ALIAS_RE.captures(line) {
-        let name = caps[2].to_string();
-        return ParseEvent::Complete(
-            Entry::new(EntryType::Alias, name, line.to_string()).with_line_number(line_num),
-        );
+/// Parameters of `Set-Alias`/`New-Alias` that take an argument, besides
+/// `-Name` and `-Value`. Other parameters (`-Force`, `-PassThru`, ...) are
+/// switches.
+const ALIAS_PARAMS_WITH_ARGUMENT: &[&str] = &["-description", "-option", "-scope"];
+
+/// Name and value of a `Set-Alias`/`New-Alias` command, with quotes removed.
+///
+/// `-Name` and `-Value` may be given in any order, positionally (name first)
+/// or as `-Name:ll`. Quoted arguments may contain spaces, and a trailing
+/// `# comment` is ignored. Returns `None` when the line isn't an alias
+/// command or lacks a name or value.
+///
+/// ```rust,ignore
+/// assert_eq!(
+///     alias_arguments("New-Alias gst Get-GitStatus -Description 'Git status'"),
+///     Some(("gst".into(), "Get-GitStatus".into()))
+/// );
+/// ```
+pub fn alias_arguments(line: &str) -> Option<(String, String)> {
+    let (code, _comment) = extract_comment(line, '#');
+    let args = ALIAS_RE.captures(code.trim())?.get(1)?.as_str().to_string();
+
+    let mut tokens = split_arguments(&args).into_iter();
+    let mut name = None;
+    let mut value = None;
+    let mut positional = Vec::new();
+    while let Some(token) = tokens.next() {
+        if !token.starts_with('-') {
+            positional.push(token);
+            continue;
+        }
+        let (param, inline) = match token.split_once(':') {
+            Some((param, arg)) => (param.to_lowercase(), Some(arg.to_string())),
+            None => (token.to_lowercase(), None),
+        };
+        let takes_argument = matches!(param.as_str(), "-name" | "-value")
+            || ALIAS_PARAMS_WITH_ARGUMENT.contains(&param.as_str());
+        let argument = match inline {
+            Some(arg) => Some(arg),
+            None if takes_argument => tokens.next(),
+            None => None,
+        };
+        match param.as_str() {
+            "-name" => name = argument,
+            "-value" => value = argument,
+            _ => {}
+        }
     }
 
-    ParseEvent::None
+    // Positional arguments fill -Name, then -Value
+    let mut positional = positional.into_iter();
+    let name = name.or_else(|| positional.next())?;
+    let value = value.or_else(|| positional.next())?;
+    let (name, value) = (strip_quotes(&name), strip_quotes(&value));
+    (!name.is_empty() && !value.is_empty()).then_some((name, value))
+}
+
+/// Split command arguments on whitespace, keeping quoted text together.
+/// Quotes are kept in the tokens.
+fn split_arguments(args: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in args.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, c) if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
 }
 
 /// Try to parse a line as a PowerShell environment variable.
@@ -110,7 +190,18 @@ pub fn try_parse_env(line: &str, line_num: usize) -> ParseEvent {
 
 /// Try to parse a line as a PowerShell source statement.
 ///
-/// Matches: `. .\file.ps1`
+/// Matches dot-sourcing of a literal, quoted, variable-based or computed
+/// path:
+/// - `. .\file.ps1`
+/// - `. "$HOME\profile-extra.ps1"`
+/// - `. $PSScriptRoot\local.ps1`
+/// - `. (Join-Path $PSScriptRoot 'aliases.ps1')`
+///
+/// Module imports (`Import-Module PSReadLine`, `using module ./MyMod.psm1`)
+/// are Source entries too, named after the module (see [`module_argument`]).
+///
+/// The name is the file name without extension, or the raw expression for
+/// computed paths and bare variables (see [`source_name`]).
 ///
 /// # Arguments
 ///
@@ -124,20 +215,137 @@ pub fn try_parse_env(line: &str, line_num: usize) -> ParseEvent {
 pub fn try_parse_source(line: &str, line_num: usize) -> ParseEvent {
     if let Some(caps) = SOURCE_RE.captures(line) {
         let (path_clean, _inline_comment) = extract_comment(&caps[1], '#');
-        let path = strip_quotes(&path_clean);
-        // Extract filename (without extension) as name for TUI identification
-        let name = std::path::Path::new(&path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or(&path)
-            .to_string();
+        let name = source_name(&path_clean);
         return ParseEvent::Complete(
             Entry::new(EntryType::Source, name, line.to_string()).with_line_number(line_num),
         );
     }
+    if let Some(module) = module_argument(line) {
+        return ParseEvent::Complete(
+            Entry::new(EntryType::Source, source_name(&module), line.to_string())
+                .with_line_number(line_num),
+        );
+    }
     ParseEvent::None
 }
 
+/// Try to parse a line as the removal of a definition.
+///
+/// Matches:
+/// - `Remove-Item Env:NAME` (also `Alias:` and `Function:`)
+/// - `Remove-Alias name`
+///
+/// # Arguments
+///
+/// - `line`: The trimmed line to parse
+/// - `line_num`: 1-based line number
+///
+/// # Returns
+///
+/// - `ParseEvent::Complete(entry)` if the line removes one definition
+/// - `ParseEvent::None` otherwise
+pub fn try_parse_unset(line: &str, line_num: usize) -> ParseEvent {
+    let Some(caps) = REMOVE_ITEM_RE
+        .captures(line)
+        .or_else(|| REMOVE_ALIAS_RE.captures(line))
+    else {
+        return ParseEvent::None;
+    };
+    ParseEvent::Complete(
+        Entry::new(EntryType::Unset, caps[1].to_string(), line.to_string())
+            .with_line_number(line_num),
+    )
+}
+
+/// Parameters of `Import-Module` that take an argument, besides `-Name`
+const MODULE_PARAMS_WITH_ARGUMENT: &[&str] = &[
+    "-argumentlist",
+    "-args",
+    "-function",
+    "-cmdlet",
+    "-variable",
+    "-alias",
+    "-prefix",
+    "-scope",
+    "-minimumversion",
+    "-maximumversion",
+    "-requiredversion",
+    "-version",
+    "-fullyqualifiedname",
+];
+
+/// Module imported by an `Import-Module` or `using module` line, taken from
+/// `-Name` or the first positional argument, without quotes.
+///
+/// ```rust,ignore
+/// assert_eq!(module_argument("Import-Module PSReadLine"), Some("PSReadLine".into()));
+/// assert_eq!(
+///     module_argument("Import-Module -DisableNameChecking -Name 'posh-git'"),
+///     Some("posh-git".into())
+/// );
+/// assert_eq!(module_argument("using module ./MyMod.psm1"), Some("./MyMod.psm1".into()));
+/// ```
+pub fn module_argument(line: &str) -> Option<String> {
+    let (code, _comment) = extract_comment(line, '#');
+    let args = MODULE_RE
+        .captures(code.trim())?
+        .get(1)?
+        .as_str()
+        .to_string();
+
+    let mut tokens = split_arguments(&args).into_iter();
+    let mut name = None;
+    let mut positional = None;
+    while let Some(token) = tokens.next() {
+        if !token.starts_with('-') {
+            positional = positional.or(Some(token));
+            continue;
+        }
+        let (param, inline) = match token.split_once(':') {
+            Some((param, arg)) => (param.to_lowercase(), Some(arg.to_string())),
+            None => (token.to_lowercase(), None),
+        };
+        let argument = match inline {
+            Some(arg) => Some(arg),
+            None if param == "-name" || MODULE_PARAMS_WITH_ARGUMENT.contains(&param.as_str()) => {
+                tokens.next()
+            }
+            None => None,
+        };
+        if param == "-name" {
+            name = argument;
+        }
+    }
+
+    let module = strip_quotes(&name.or(positional)?);
+    (!module.is_empty()).then_some(module)
+}
+
+/// Display name for a dot-sourced path expression.
+///
+/// Paths name the file without directory or extension, with `\` and `/`
+/// both treated as separators (`"$HOME\extra.ps1"` → `extra`). Computed
+/// paths such as `(Join-Path $PSScriptRoot 'a.ps1')` and bare variables such
+/// as `$PROFILE` keep the raw expression, since there's no file name to show.
+pub fn source_name(expr: &str) -> String {
+    let expr = expr.trim();
+    if expr.starts_with('(') || expr.starts_with('&') {
+        return expr.to_string();
+    }
+
+    let path = strip_quotes(expr);
+    let file = path.rsplit(['\\', '/']).next().unwrap_or(&path);
+    let stem = match file.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file,
+    };
+    if stem.is_empty() {
+        expr.to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
 /// Detect if a line starts a function definition.
 ///
 /// Matches: `function Name {` or `function Name($param) {`
@@ -198,6 +406,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_alias_named_parameters_in_any_order() {
+        let parse = |line: &str| match try_parse_alias(line, 1) {
+            ParseEvent::Complete(entry) => entry.name,
+            _ => panic!("Expected Complete for {:?}", line),
+        };
+        assert_eq!(parse("Set-Alias -Name ll -Value Get-ChildItem"), "ll");
+        assert_eq!(parse("Set-Alias -Value Get-ChildItem -Name ll"), "ll");
+        assert_eq!(parse("New-Alias gst Get-GitStatus -Description 'x'"), "gst");
+        assert_eq!(
+            parse("New-Alias -Scope Global -Force ll Get-ChildItem"),
+            "ll"
+        );
+        assert_eq!(parse("Set-Alias -Name:np -Value:notepad.exe"), "np");
+
+        assert!(matches!(
+            try_parse_alias("Set-Alias -Name ll", 1),
+            ParseEvent::None
+        ));
+    }
+
+    #[test]
+    fn test_alias_arguments() {
+        let args = |line: &str| alias_arguments(line).unwrap();
+        assert_eq!(
+            args("Set-Alias ll Get-ChildItem"),
+            ("ll".into(), "Get-ChildItem".into())
+        );
+        assert_eq!(
+            args("New-Alias gst Get-GitStatus -Description 'Show git status' # git"),
+            ("gst".into(), "Get-GitStatus".into())
+        );
+        assert_eq!(
+            args("Set-Alias -Description \"Open editor\" -Value 'C:\\Program Files\\ed.exe' -Name ed"),
+            ("ed".into(), "C:\\Program Files\\ed.exe".into())
+        );
+        assert_eq!(
+            args("New-Alias -Name ll Get-ChildItem -Option ReadOnly"),
+            ("ll".into(), "Get-ChildItem".into())
+        );
+        assert_eq!(alias_arguments("Set-Alias"), None);
+        assert_eq!(alias_arguments("Get-Alias ll"), None);
+    }
+
+    #[test]
+    fn test_try_parse_source_variants() {
+        let name = |line: &str| match try_parse_source(line, 1) {
+            ParseEvent::Complete(entry) => entry.name,
+            _ => panic!("Expected Complete for {:?}", line),
+        };
+        assert_eq!(name(r#". .\aliases.ps1"#), "aliases");
+        assert_eq!(name(r#". "$HOME\profile-extra.ps1""#), "profile-extra");
+        assert_eq!(name(r#". '$PSScriptRoot/other.ps1' # extras"#), "other");
+        assert_eq!(name(r#". $PSScriptRoot\local.ps1"#), "local");
+        assert_eq!(
+            name(r#". (Join-Path $PSScriptRoot 'aliases.ps1')"#),
+            "(Join-Path $PSScriptRoot 'aliases.ps1')"
+        );
+        assert_eq!(name(". $PROFILE"), "$PROFILE");
+    }
+
+    #[test]
+    fn test_try_parse_module_imports() {
+        let parse = |line: &str| match try_parse_source(line, 1) {
+            ParseEvent::Complete(entry) => (entry.entry_type, entry.name),
+            _ => panic!("Expected Complete for {:?}", line),
+        };
+        assert_eq!(
+            parse("Import-Module PSReadLine"),
+            (EntryType::Source, "PSReadLine".into())
+        );
+        assert_eq!(
+            parse("using module ./MyMod.psm1"),
+            (EntryType::Source, "MyMod".into())
+        );
+        assert_eq!(
+            parse("import-module -Name posh-git -ArgumentList $false # prompt"),
+            (EntryType::Source, "posh-git".into())
+        );
+        assert_eq!(
+            parse("Import-Module -Scope Global Terminal-Icons"),
+            (EntryType::Source, "Terminal-Icons".into())
+        );
+
+        assert!(matches!(
+            try_parse_source("Import-Module", 1),
+            ParseEvent::None
+        ));
+        assert!(matches!(
+            try_parse_source("using namespace System.IO", 1),
+            ParseEvent::None
+        ));
+    }
+
     #[test]
     fn test_try_parse_env() {
         match try_parse_env(r#"$env:EDITOR = "code""#, 10) {
@@ -227,7 +529,7 @@ mod tests {
         match try_parse_source(r#". .\aliases.ps1"#, 15) {
             ParseEvent::Complete(entry) => {
                 assert_eq!(entry.entry_type, EntryType::Source);
-                assert_eq!(entry.name, ".\\aliases");
+                assert_eq!(entry.name, "aliases");
             }
             _ => panic!("Expected Complete"),
         }
@@ -12,7 +12,8 @@
 //!
 //! - Aliases use `Set-Alias` or `New-Alias` cmdlets
 //! - Environment variables use `$env:NAME` syntax
-//! - Source uses dot-sourcing: `. .\file.ps1`
+//! - Source uses dot-sourcing: `. .\file.ps1`, or module imports
+//!   (`Import-Module`, `using module`)
 //! - Functions use `function Name { }` syntax
 
 use lazy_static::lazy_static;
@@ -23,28 +24,18 @@ lazy_static! {
     // Alias Patterns
     // =========================================================================
 
-    /// Matches complex alias format with optional `-Name` and `-Value` parameters.
+    /// Matches an alias command and captures its arguments, which
+    /// [`alias_arguments`](super::parsers::alias_arguments) reads in any order.
     ///
     /// Examples:
+    /// - `Set-Alias ll Get-ChildItem`
     /// - `Set-Alias -Name ll -Value Get-ChildItem`
-    /// - `New-Alias ll Get-ChildItem`
+    /// - `New-Alias gst Get-GitStatus -Description 'x'`
     ///
     /// Captures:
-    /// - Group 1: Optional `-Name ` prefix
-    /// - Group 2: alias name
-    /// - Group 3: Optional `-Value ` prefix
-    /// - Group 4: alias value
+    /// - Group 1: the arguments
     pub static ref ALIAS_RE: Regex = Regex::new(
-        r#"^(?:Set-Alias|New-Alias)\s+(-Name\s+)?(\w+)\s+(-Value\s+)?(.+)$"#
-    ).unwrap();
-
-    /// Matches simple alias format: `Set-Alias name value`
-    ///
-    /// Captures:
-    /// - Group 1: alias name
-    /// - Group 2: alias value (word characters and hyphens)
-    pub static ref ALIAS_SIMPLE_RE: Regex = Regex::new(
-        r#"^(?:Set-Alias|New-Alias)\s+(\w+)\s+(\w[\w-]*)$"#
+        r#"^(?i:Set-Alias|New-Alias)\s+(.+)$"#
     ).unwrap();
 
     // =========================================================================
@@ -68,6 +59,29 @@ lazy_static! {
         r#"^\$env:(\w+)\s*=\s*@"$"#
     ).unwrap();
 
+    // =========================================================================
+    // Unset Patterns
+    // =========================================================================
+
+    /// Matches the removal of one item from the `Env:`, `Alias:` or
+    /// `Function:` drive: `Remove-Item Env:VAR`,
+    /// `Remove-Item -Path Alias:ls -ErrorAction SilentlyContinue`
+    ///
+    /// Captures:
+    /// - Group 1: the name
+    pub static ref REMOVE_ITEM_RE: Regex = Regex::new(
+        r#"^(?i:Remove-Item)\s+(?:-(?i:Path|LiteralPath)\s+)?['"]?(?i:env|alias|function):\\?([\w.-]+)['"]?(?:\s+-\w+(?:\s+\w+)?)*\s*(?:#.*)?$"#
+    ).unwrap();
+
+    /// Matches the removal of one alias: `Remove-Alias ll` or
+    /// `Remove-Alias -Name ll -Force`
+    ///
+    /// Captures:
+    /// - Group 1: alias name
+    pub static ref REMOVE_ALIAS_RE: Regex = Regex::new(
+        r#"^(?i:Remove-Alias)\s+(?:-(?i:Name)\s+)?([\w.-]+)(?:\s+-\w+(?:\s+\w+)?)*\s*(?:#.*)?$"#
+    ).unwrap();
+
     // =========================================================================
     // Source Patterns
     // =========================================================================
@@ -80,6 +94,16 @@ lazy_static! {
         r#"^\.\s+(.+)$"#
     ).unwrap();
 
+    /// Matches a module import: `Import-Module PSReadLine` or
+    /// `using module ./MyMod.psm1` (keywords are case-insensitive)
+    ///
+    /// Captures:
+    /// - Group 1: the arguments, which
+    ///   [`module_argument`](super::parsers::module_argument) reads
+    pub static ref MODULE_RE: Regex = Regex::new(
+        r#"^(?i:Import-Module|using\s+module)\s+(.+)$"#
+    ).unwrap();
+
     // =========================================================================
     // Function Patterns
     // =========================================================================
@@ -98,19 +122,12 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_alias_simple_re() {
-        let caps = ALIAS_SIMPLE_RE
-            .captures("Set-Alias ll Get-ChildItem")
-            .unwrap();
-        assert_eq!(&caps[1], "ll");
-        assert_eq!(&caps[2], "Get-ChildItem");
-    }
-
-    #[test]
-    fn test_alias_re_with_params() {
-        let caps = ALIAS_RE.captures("Set-Alias -Name gs -Value git").unwrap();
-        assert_eq!(&caps[2], "gs");
-        assert_eq!(&caps[4], "git");
+    fn test_alias_re() {
+        let caps = ALIAS_RE.captures("Set-Alias ll Get-ChildItem").unwrap();
+        assert_eq!(&caps[1], "ll Get-ChildItem");
+        let caps = ALIAS_RE.captures("new-alias -Name gs -Value git").unwrap();
+        assert_eq!(&caps[1], "-Name gs -Value git");
+        assert!(!ALIAS_RE.is_match("Set-AliasX ll ls"));
     }
 
     #[test]
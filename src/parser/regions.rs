@@ -0,0 +1,193 @@
+//! # Preserved Regions
+//!
+//! Blocks of a configuration file that wenv keeps verbatim and in place.
+//! Each becomes a single Code entry that formatting never reorders
+//! internally or moves entries across (see [`Entry::is_guarded`]).
+//!
+//! ## Known Regions
+//!
+//! | Region | Start | End |
+//! |--------|-------|-----|
+//! | `wenv:ignore` | `# >>> wenv:ignore >>>` | `# <<< wenv:ignore <<<` |
+//! | `conda initialize` | `# >>> conda initialize >>>` | `# <<< conda initialize <<<` |
+//! | `conda initialize` (PowerShell) | `#region conda initialize` | `#endregion` |
+//! | `mamba initialize` | `# >>> mamba initialize >>>` | `# <<< mamba initialize <<<` |
+//! | `juliaup initialize` | `# >>> juliaup initialize >>>` | `# <<< juliaup initialize <<<` |
+//! | `nvm` | `export NVM_DIR=...` | Last adjacent line mentioning `NVM_DIR` |
+//!
+//! A marker region missing its end marker runs to the end of the file, so an
+//! edited block is never taken apart.
+//!
+//! [`Entry::is_guarded`]: crate::model::Entry::is_guarded
+
+/// Line opening a block wenv keeps verbatim and in place
+pub const GUARD_START: &str = "# >>> wenv:ignore >>>";
+
+/// Line closing a block opened by [`GUARD_START`]
+pub const GUARD_END: &str = "# <<< wenv:ignore <<<";
+
+/// How the first line of a region is recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionStart {
+    /// The marker line, ignoring surrounding whitespace and the spacing
+    /// between its words
+    Marker(&'static str),
+    /// A line starting with the text
+    Prefix(&'static str),
+}
+
+/// How the last line of a region is recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionEnd {
+    /// The marker line, matched like [`RegionStart::Marker`]
+    Marker(&'static str),
+    /// The last of the lines right after the start that contain the text.
+    /// The region needs at least one such line.
+    WhileContains(&'static str),
+}
+
+/// A kind of preserved region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub name: &'static str,
+    pub start: RegionStart,
+    pub end: RegionEnd,
+}
+
+/// Regions recognized by both parsers, the user's `wenv:ignore` first
+pub const KNOWN_REGIONS: &[Region] = &[
+    Region {
+        name: "wenv:ignore",
+        start: RegionStart::Marker(GUARD_START),
+        end: RegionEnd::Marker(GUARD_END),
+    },
+    Region {
+        name: "conda initialize",
+        start: RegionStart::Marker("# >>> conda initialize >>>"),
+        end: RegionEnd::Marker("# <<< conda initialize <<<"),
+    },
+    Region {
+        name: "conda initialize",
+        start: RegionStart::Marker("#region conda initialize"),
+        end: RegionEnd::Marker("#endregion"),
+    },
+    Region {
+        name: "mamba initialize",
+        start: RegionStart::Marker("# >>> mamba initialize >>>"),
+        end: RegionEnd::Marker("# <<< mamba initialize <<<"),
+    },
+    Region {
+        name: "juliaup initialize",
+        start: RegionStart::Marker("# >>> juliaup initialize >>>"),
+        end: RegionEnd::Marker("# <<< juliaup initialize <<<"),
+    },
+    Region {
+        name: "nvm",
+        start: RegionStart::Prefix("export NVM_DIR="),
+        end: RegionEnd::WhileContains("NVM_DIR"),
+    },
+];
+
+/// A region found in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionMatch {
+    pub region: &'static Region,
+    /// 0-based index of the region's last line
+    pub end: usize,
+    /// Whether the end marker was found; unclosed regions run to the last line
+    pub closed: bool,
+}
+
+impl RegionStart {
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            RegionStart::Marker(marker) => same_words(line, marker),
+            RegionStart::Prefix(prefix) => line.trim_start().starts_with(prefix),
+        }
+    }
+}
+
+/// The known region whose start line is `line`, if any
+pub fn region_starting_at(line: &str) -> Option<&'static Region> {
+    KNOWN_REGIONS
+        .iter()
+        .find(|region| region.start.matches(line))
+}
+
+/// The region starting at `lines[start]`, if any
+pub fn find_region(lines: &[&str], start: usize) -> Option<RegionMatch> {
+    let region = region_starting_at(lines.get(start)?)?;
+    let mut rest = lines.iter().enumerate().skip(start + 1);
+    let (end, closed) = match region.end {
+        RegionEnd::Marker(marker) => match rest.find(|(_, line)| same_words(line, marker)) {
+            Some((end, _)) => (end, true),
+            None => (lines.len() - 1, false),
+        },
+        RegionEnd::WhileContains(text) => {
+            let (end, _) = rest.take_while(|(_, line)| line.contains(text)).last()?;
+            (end, true)
+        }
+    };
+    Some(RegionMatch {
+        region,
+        end,
+        closed,
+    })
+}
+
+fn same_words(line: &str, marker: &str) -> bool {
+    line.split_whitespace().eq(marker.split_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONDA: &str = "# >>> conda initialize >>>
+# !! Contents within this block are managed by 'conda init' !!
+unset __conda_setup
+# <<< conda initialize <<<";
+
+    #[test]
+    fn test_marker_regions() {
+        let lines: Vec<&str> = CONDA.lines().collect();
+        let found = find_region(&lines, 0).unwrap();
+        assert_eq!(found.region.name, "conda initialize");
+        assert_eq!((found.end, found.closed), (lines.len() - 1, true));
+
+        let lines = [
+            "#region conda initialize",
+            "# !! Contents within this block are managed by 'conda init' !!",
+            "#endregion",
+        ];
+        assert_eq!(find_region(&lines, 0).unwrap().end, 2);
+
+        assert!(region_starting_at("  #  >>>  wenv:ignore >>>\r").is_some());
+        assert!(region_starting_at("# <<< wenv:ignore <<<").is_none());
+        assert!(region_starting_at("echo '# >>> wenv:ignore >>>'").is_none());
+    }
+
+    #[test]
+    fn test_unclosed_marker_region_runs_to_end() {
+        let lines = ["# >>> wenv:ignore >>>", "alias a=1", "alias b=2"];
+        let found = find_region(&lines, 0).unwrap();
+        assert_eq!((found.end, found.closed), (2, false));
+    }
+
+    #[test]
+    fn test_nvm_region() {
+        let lines = [
+            r#"export NVM_DIR="$HOME/.nvm""#,
+            r#"[ -s "$NVM_DIR/nvm.sh" ] && \. "$NVM_DIR/nvm.sh"  # This loads nvm"#,
+            r#"[ -s "$NVM_DIR/bash_completion" ] && \. "$NVM_DIR/bash_completion""#,
+            "",
+            r#"echo "$NVM_DIR""#,
+        ];
+        let found = find_region(&lines, 0).unwrap();
+        assert_eq!(found.region.name, "nvm");
+        assert_eq!(found.end, 2);
+
+        // A lone NVM_DIR export is an ordinary variable
+        assert!(find_region(&lines[..1], 0).is_none());
+    }
+}
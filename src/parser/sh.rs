@@ -0,0 +1,95 @@
+//! # POSIX sh Parser
+//!
+//! Parses `/etc/profile`, `~/.profile` and other POSIX `sh` files.
+//!
+//! Reuses the Bash state machine, but only recognizes the POSIX function form
+//! `name() { ... }`. Bash/Zsh-only forms (`function name {`, anonymous
+//! `() {`) are left as Code so they stand out instead of looking valid.
+
+use crate::model::{ParseResult, ShellType};
+use crate::parser::{BashParser, Parser};
+
+/// POSIX `sh` configuration file parser.
+pub struct ShParser {
+    inner: BashParser,
+}
+
+impl ShParser {
+    /// Create a new POSIX sh parser instance.
+    pub fn new() -> Self {
+        Self {
+            inner: BashParser::posix(),
+        }
+    }
+
+    /// See [`BashParser::with_comment_merging`].
+    pub fn with_comment_merging(mut self, merge_comments: bool) -> Self {
+        self.inner = self.inner.with_comment_merging(merge_comments);
+        self
+    }
+
+    /// See [`BashParser::with_attached_comments`].
+    pub fn with_attached_comments(mut self, attach_comments: bool) -> Self {
+        self.inner = self.inner.with_attached_comments(attach_comments);
+        self
+    }
+}
+
+impl Default for ShParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for ShParser {
+    fn parse(&self, content: &str) -> ParseResult {
+        self.inner.parse(content)
+    }
+
+    fn shell_type(&self) -> ShellType {
+        ShellType::PosixSh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::EntryType;
+
+    #[test]
+    fn test_posix_function_form() {
+        let result = ShParser::new().parse("greet() {\n  echo hi\n}\n");
+
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].entry_type, EntryType::Function);
+        assert_eq!(result.entries[0].name, "greet");
+    }
+
+    #[test]
+    fn test_function_keyword_is_not_a_function() {
+        let content = "function greet {\n  echo hi\n}\n";
+
+        let bash = BashParser::new().parse(content);
+        assert_eq!(bash.entries[0].entry_type, EntryType::Function);
+
+        let sh = ShParser::new().parse(content);
+        assert!(sh
+            .entries
+            .iter()
+            .all(|e| e.entry_type != EntryType::Function));
+    }
+
+    #[test]
+    fn test_function_keyword_with_parens_is_not_a_function() {
+        let sh = ShParser::new().parse("function greet() {\n  echo hi\n}\n");
+        assert!(sh
+            .entries
+            .iter()
+            .all(|e| e.entry_type != EntryType::Function));
+    }
+
+    #[test]
+    fn test_shell_type() {
+        assert_eq!(ShParser::new().shell_type(), ShellType::PosixSh);
+    }
+}
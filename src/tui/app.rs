@@ -2,17 +2,26 @@
 
 use std::io;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use crate::i18n::Messages;
-use crate::model::{Entry, EntryType, ShellType};
+use crate::model::{ColorsConfig, Entry, EntryType, ShellType};
+use crate::tui::search::{find_matches, SearchOptions};
+use crate::utils::clipboard::copy_to_clipboard;
+use crate::utils::colors::{entry_type_color, to_tui_color};
+use crate::utils::reload::{apply_command, reload_command};
+use crate::utils::validate::{check_alias_line, AliasIssue};
 
 /// Application mode
 #[derive(Debug, Clone, PartialEq)]
@@ -115,11 +124,30 @@ pub struct TuiApp {
     pub selected_index: usize,
     pub list_scroll_offset: usize,
     pub list_visible_height: usize, // Updated during draw
+    /// Screen row of the list's top border (updated during draw)
+    pub list_top: u16,
+    /// First list item drawn, header rows included (updated during draw)
+    pub list_render_offset: usize,
+    /// Time and entry of the last click, for double-click detection
+    last_click: Option<(Instant, usize)>,
 
     // File
     pub file_path: PathBuf,
     pub file_content: String,
     pub shell_type: ShellType,
+    /// Skip backups before writes (`--no-backup`)
+    pub no_backup: bool,
+    /// `--editor` override for external editing
+    pub editor: Option<String>,
+    /// Browse only: every mutating action is refused (`--view`)
+    pub view_only: bool,
+    /// Entry type colors from the `[colors]` config table
+    pub colors: ColorsConfig,
+    /// Attach a single comment to the entry below it (off with
+    /// `--no-merge-comments`)
+    pub merge_comments: bool,
+    /// Record the comment above each entry (`parser.attach_comments`)
+    pub attach_comments: bool,
 
     // UI state
     pub mode: AppMode,
@@ -179,6 +207,8 @@ pub struct TuiApp {
     pub search_active: bool,        // Search mode active
     pub search_matches: Vec<usize>, // Matched entry indices
     pub search_cursor: usize,       // Cursor position in search input
+    pub search_options: SearchOptions,
+    pub search_error: Option<String>, // Invalid regex message
 
     // Full redraw flag (set after external editor to clear artifacts)
     pub needs_full_redraw: bool,
@@ -192,9 +222,6 @@ impl TuiApp {
         messages: &'static Messages,
     ) -> Result<Self> {
         let file_content = crate::utils::path::read_file(&file_path)?;
-        let parser = crate::parser::get_parser(shell_type);
-        let parse_result = parser.parse(&file_content);
-        let entries = parse_result.entries;
 
         // Create temp file path in same directory
         let temp_file_path = {
@@ -205,14 +232,20 @@ impl TuiApp {
         // Initialize temp file with original content at startup
         std::fs::write(&temp_file_path, &file_content)?;
 
-        Ok(Self {
-            entries,
+        let mut app = Self {
+            entries: Vec::new(),
             selected_index: 0,
             list_scroll_offset: 0,
             list_visible_height: 20,
+            list_top: 0,
+            list_render_offset: 0,
+            last_click: None,
             file_path,
             file_content,
             shell_type,
+            no_backup: false,
+            editor: None,
+            view_only: false,
             mode: AppMode::Normal,
             message: None,
             should_quit: false,
@@ -241,8 +274,109 @@ impl TuiApp {
             search_active: false,
             search_matches: Vec::new(),
             search_cursor: 0,
+            search_options: SearchOptions::default(),
+            search_error: None,
             needs_full_redraw: false,
-        })
+            colors: ColorsConfig::default(),
+            merge_comments: true,
+            attach_comments: false,
+        };
+        app.entries = app.parser().parse(&app.file_content).entries;
+        Ok(app)
+    }
+
+    /// Disable backups before writes (overrides `backup.enabled`)
+    pub fn with_no_backup(mut self, no_backup: bool) -> Self {
+        self.no_backup = no_backup;
+        self
+    }
+
+    pub fn with_editor(mut self, editor: Option<String>) -> Self {
+        self.editor = editor;
+        self
+    }
+
+    /// Color entry types as set in the `[colors]` config table
+    pub fn with_colors(mut self, colors: ColorsConfig) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Keep every comment as its own entry with `false`
+    /// (`--no-merge-comments`)
+    pub fn with_merge_comments(mut self, merge_comments: bool) -> Self {
+        self.merge_comments = merge_comments;
+        self.entries = self.parser().parse(&self.file_content).entries;
+        self
+    }
+
+    /// Record the comment directly above each entry, shown in its details
+    /// (`parser.attach_comments`)
+    pub fn with_attached_comments(mut self, attach_comments: bool) -> Self {
+        self.attach_comments = attach_comments;
+        self.entries = self.parser().parse(&self.file_content).entries;
+        self
+    }
+
+    /// Parser for the file, with the comment options set
+    fn parser(&self) -> Box<dyn crate::parser::Parser> {
+        crate::parser::get_configured_parser(
+            self.shell_type,
+            self.merge_comments,
+            self.attach_comments,
+        )
+    }
+
+    /// Color the list shows entries of `entry_type` in
+    pub fn type_color(&self, entry_type: EntryType) -> ratatui::style::Color {
+        to_tui_color(entry_type_color(&self.colors, entry_type))
+    }
+
+    /// Start with the entry `name` (of `entry_type`, if given) selected and
+    /// open for editing, or in the normal view with a message when there is
+    /// no such entry
+    pub fn with_editing(mut self, entry_type: Option<EntryType>, name: &str) -> Self {
+        match find_entry_index(&self.entries, entry_type, name) {
+            Some(index) => {
+                self.selected_index = index;
+                self.adjust_scroll_for_selection();
+                self.start_editing();
+            }
+            None => {
+                let type_label = entry_type.map_or_else(|| "entry".to_string(), |t| t.to_string());
+                self.message = Some(
+                    self.messages
+                        .entry_not_found
+                        .replacen("{}", &type_label, 1)
+                        .replacen("{}", name, 1),
+                );
+            }
+        }
+        self
+    }
+
+    /// Browse without changing anything (`wenv tui --view`)
+    pub fn with_view_only(mut self, view_only: bool) -> Self {
+        self.view_only = view_only;
+        self
+    }
+
+    /// In view-only mode, explain that changes are disabled and return
+    /// `true`; mutating actions return early on it
+    fn refuse_in_view_only(&mut self) -> bool {
+        if self.view_only {
+            self.message = Some(self.messages.tui_msg_read_only.to_string());
+        }
+        self.view_only
+    }
+
+    /// Back up the file before writing, unless backups are disabled
+    fn create_backup(&self, config: &crate::model::Config) -> Result<()> {
+        if config.backup.enabled && !self.no_backup {
+            crate::backup::BackupManager::new(self.shell_type, config)
+                .create_backup(&self.file_path)?;
+        }
+        Ok(())
     }
 
     /// Run the TUI application
@@ -250,7 +384,7 @@ impl TuiApp {
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
@@ -259,7 +393,11 @@ impl TuiApp {
 
         // Restore terminal
         disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
         terminal.show_cursor()?;
 
         result
@@ -284,7 +422,7 @@ impl TuiApp {
                     }
                 }
                 Event::Mouse(mouse) => {
-                    self.handle_mouse(mouse.kind)?;
+                    self.handle_mouse(mouse)?;
                 }
                 _ => {}
             }
@@ -300,8 +438,11 @@ impl TuiApp {
     }
 
     /// Handle mouse input
-    fn handle_mouse(&mut self, kind: MouseEventKind) -> Result<()> {
-        match kind {
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.mode == AppMode::Normal => {
+                self.click_row(mouse.row);
+            }
             MouseEventKind::ScrollUp => {
                 match self.mode {
                     AppMode::Normal => {
@@ -358,11 +499,10 @@ impl TuiApp {
                             preview.scroll_offset = preview.scroll_offset.saturating_add(3);
                         }
                     }
-                    AppMode::SelectingType => {
-                        const NUM_TYPES: usize = 5;
-                        if self.type_selection_index < NUM_TYPES - 1 {
-                            self.type_selection_index += 1;
-                        }
+                    AppMode::SelectingType
+                        if self.type_selection_index + 1 < self.addable_types().len() =>
+                    {
+                        self.type_selection_index += 1;
                     }
                     _ => {}
                 }
@@ -373,11 +513,42 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Select the entry drawn at screen `row`; a second click on the same
+    /// entry shortly after opens its details
+    fn click_row(&mut self, row: u16) {
+        const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+        let Some(index) = crate::tui::ui::entry_index_at_row(
+            row,
+            self.list_top,
+            self.list_visible_height,
+            self.list_render_offset,
+            self.entries.len(),
+        ) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let double_click = self
+            .last_click
+            .is_some_and(|(at, last)| last == index && now.duration_since(at) <= DOUBLE_CLICK);
+
+        self.clear_selection();
+        self.selected_index = index;
+        self.adjust_scroll_for_selection();
+        if double_click {
+            self.last_click = None;
+            self.mode = AppMode::ShowingDetail;
+        } else {
+            self.last_click = Some((now, index));
+        }
+    }
+
     /// Handle keyboard input
     fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
         match self.mode {
             AppMode::Normal => self.handle_normal_mode(key)?,
-            AppMode::Searching => self.handle_searching_mode(key.code)?,
+            AppMode::Searching => self.handle_searching_mode(key)?,
             AppMode::ShowingDetail => self.handle_detail_mode(key.code)?,
             AppMode::ShowingHelp => self.handle_help_mode(key.code)?,
             AppMode::ConfirmDelete => self.handle_confirm_delete_mode(key.code)?,
@@ -408,6 +579,10 @@ impl TuiApp {
             KeyCode::Char('w') if !has_ctrl && !has_shift && !has_alt => {
                 self.save_to_original_file()?;
             }
+            // W: Save and copy the command reloading the file
+            KeyCode::Char('W') if !has_ctrl && !has_alt => {
+                self.save_and_copy_reload_command()?;
+            }
             // Ctrl+C or Alt+C: Copy selected entries
             KeyCode::Char('c') if has_ctrl => {
                 self.copy_selected()?;
@@ -457,6 +632,7 @@ impl TuiApp {
                     self.search_query.clear();
                     self.search_matches.clear();
                     self.search_cursor = 0;
+                    self.search_error = None;
                 } else if self.non_contiguous_mode || !self.selected_indices.is_empty() {
                     // Exit selection mode and clear all selections
                     self.clear_selection();
@@ -499,8 +675,12 @@ impl TuiApp {
                 self.mode = AppMode::ShowingDetail;
             }
             KeyCode::Char('d') | KeyCode::Delete => {
-                self.delete_confirm_scroll = 0;
-                self.mode = AppMode::ConfirmDelete;
+                self.start_deleting();
+            }
+            // Shift+D: Duplicate entry and edit the copy
+            KeyCode::Char('D') => {
+                self.clear_selection();
+                self.duplicate_entry()?;
             }
             KeyCode::Home => {
                 self.clear_selection();
@@ -571,6 +751,7 @@ impl TuiApp {
                 self.detail_scroll = 0;
                 self.start_editing();
             }
+            KeyCode::Char('c') => self.copy_apply_command(),
             KeyCode::Up | KeyCode::Char('k') => {
                 // Scroll up in detail view
                 self.detail_scroll = self.detail_scroll.saturating_sub(1);
@@ -759,7 +940,7 @@ impl TuiApp {
 
     /// Handle type selection mode for Add
     fn handle_selecting_type_mode(&mut self, key: KeyCode) -> Result<()> {
-        const NUM_TYPES: usize = 5; // Alias, Function, EnvVar, Source, Code/Comment
+        let num_types = self.addable_types().len();
 
         match key {
             KeyCode::Up | KeyCode::Char('k') => {
@@ -768,31 +949,16 @@ impl TuiApp {
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if self.type_selection_index < NUM_TYPES - 1 {
+                if self.type_selection_index + 1 < num_types {
                     self.type_selection_index += 1;
                 }
             }
             KeyCode::Enter => {
                 self.confirm_type_selection();
             }
-            KeyCode::Char('1') => {
-                self.type_selection_index = 0;
-                self.confirm_type_selection();
-            }
-            KeyCode::Char('2') => {
-                self.type_selection_index = 1;
-                self.confirm_type_selection();
-            }
-            KeyCode::Char('3') => {
-                self.type_selection_index = 2;
-                self.confirm_type_selection();
-            }
-            KeyCode::Char('4') => {
-                self.type_selection_index = 3;
-                self.confirm_type_selection();
-            }
-            KeyCode::Char('5') => {
-                self.type_selection_index = 4;
+            // [1]-[9] pick the type shown with that number
+            KeyCode::Char(c @ '1'..='9') if (c as usize - '1' as usize) < num_types => {
+                self.type_selection_index = c as usize - '1' as usize;
                 self.confirm_type_selection();
             }
             KeyCode::Esc | KeyCode::Char('q') => {
@@ -806,6 +972,31 @@ impl TuiApp {
         Ok(())
     }
 
+    /// The types offered when adding an entry, in menu order. Code stands
+    /// for Code/Comment: the parser decides from the content.
+    pub fn addable_types(&self) -> &'static [EntryType] {
+        const COMMON: &[EntryType] = &[
+            EntryType::Alias,
+            EntryType::Function,
+            EntryType::EnvVar,
+            EntryType::Source,
+            EntryType::Code,
+        ];
+        const ZSH: &[EntryType] = &[
+            EntryType::Alias,
+            EntryType::Function,
+            EntryType::EnvVar,
+            EntryType::Source,
+            EntryType::Setopt,
+            EntryType::Zstyle,
+            EntryType::Code,
+        ];
+        match self.shell_type {
+            ShellType::Zsh => ZSH,
+            _ => COMMON,
+        }
+    }
+
     /// Get entry template with initial cursor position
     /// Returns: (template_string, cursor_position, cursor_row, cursor_col)
     fn get_entry_template(&self, entry_type: &EntryType) -> (String, usize, usize, usize) {
@@ -815,8 +1006,13 @@ impl TuiApp {
                 EntryType::Function => ("function  {\n    \n}".to_string(), 9, 0, 9),
                 EntryType::EnvVar => ("$env: = ''".to_string(), 5, 0, 5),
                 EntryType::Source => (". ".to_string(), 2, 0, 2),
+                EntryType::Unset => ("Remove-Item Env:".to_string(), 16, 0, 16),
                 EntryType::Comment => ("# ".to_string(), 2, 0, 2),
-                EntryType::Code => (String::new(), 0, 0, 0),
+                EntryType::Code
+                | EntryType::Array
+                | EntryType::Trap
+                | EntryType::Setopt
+                | EntryType::Zstyle => (String::new(), 0, 0, 0),
             },
             _ => match entry_type {
                 // Bash/Zsh
@@ -824,6 +1020,11 @@ impl TuiApp {
                 EntryType::Function => ("() {\n    \n}".to_string(), 0, 0, 0),
                 EntryType::EnvVar => ("export =''".to_string(), 7, 0, 7),
                 EntryType::Source => ("source ".to_string(), 7, 0, 7),
+                EntryType::Array => ("=()".to_string(), 0, 0, 0),
+                EntryType::Unset => ("unset ".to_string(), 6, 0, 6),
+                EntryType::Trap => ("trap '' EXIT".to_string(), 6, 0, 6),
+                EntryType::Setopt => ("setopt ".to_string(), 7, 0, 7),
+                EntryType::Zstyle => ("zstyle ':completion:*' ".to_string(), 23, 0, 23),
                 EntryType::Comment => ("# ".to_string(), 2, 0, 2),
                 EntryType::Code => (String::new(), 0, 0, 0),
             },
@@ -832,15 +1033,11 @@ impl TuiApp {
 
     /// Confirm type selection and open edit window
     fn confirm_type_selection(&mut self) {
-        // Code/Comment merged: index 4 is now Code/Comment, parser decides final type
-        let entry_type = match self.type_selection_index {
-            0 => EntryType::Alias,
-            1 => EntryType::Function,
-            2 => EntryType::EnvVar,
-            3 => EntryType::Source,
-            4 => EntryType::Code, // Code/Comment - parser will decide based on content
-            _ => EntryType::Alias,
-        };
+        let entry_type = self
+            .addable_types()
+            .get(self.type_selection_index)
+            .copied()
+            .unwrap_or(EntryType::Alias);
 
         // Get template for the selected entry type
         let (value_buffer, cursor_position, cursor_row, cursor_col) =
@@ -1282,19 +1479,8 @@ impl TuiApp {
         let first = indices[0];
         let count = indices.len();
 
-        // Extract selected entries (cut)
-        let mut extracted: Vec<Entry> = indices
-            .iter()
-            .rev()
-            .map(|&i| self.entries.remove(i))
-            .collect();
-        extracted.reverse();
-
-        // Insert above (paste)
-        let insert_pos = first - 1;
-        for (offset, entry) in extracted.into_iter().enumerate() {
-            self.entries.insert(insert_pos + offset, entry);
-        }
+        // Cut and paste one position above
+        crate::utils::reorder::move_entries(&mut self.entries, &indices, first - 1);
 
         // Update selected indices (shift all indices up by 1)
         if !self.selected_indices.is_empty() {
@@ -1325,19 +1511,8 @@ impl TuiApp {
         let first = indices[0];
         let count = indices.len();
 
-        // Extract selected entries (cut)
-        let mut extracted: Vec<Entry> = indices
-            .iter()
-            .rev()
-            .map(|&i| self.entries.remove(i))
-            .collect();
-        extracted.reverse();
-
-        // Insert below (paste)
-        let insert_pos = first + 1;
-        for (offset, entry) in extracted.into_iter().enumerate() {
-            self.entries.insert(insert_pos + offset, entry);
-        }
+        // Cut and paste one position below
+        crate::utils::reorder::move_entries(&mut self.entries, &indices, first + 1);
 
         // Update selected indices (shift all indices down by 1)
         if !self.selected_indices.is_empty() {
@@ -1364,17 +1539,9 @@ impl TuiApp {
             return Ok(());
         }
 
-        // Get formatter for current shell type
-        let formatter = crate::formatter::get_formatter(self.shell_type);
-
         // Rebuild file content from current entries order
-        let mut new_lines: Vec<String> = Vec::new();
-        for entry in &self.entries {
-            let formatted = formatter.format_entry(entry);
-            new_lines.push(formatted);
-        }
-
-        let new_content = new_lines.join("\n") + "\n";
+        let formatter = crate::formatter::get_formatter(self.shell_type);
+        let new_content = crate::utils::reorder::render_entries(&self.entries, formatter.as_ref());
         self.write_temp_with_undo(&new_content)?;
         self.reload_from_temp()?;
 
@@ -1553,8 +1720,7 @@ impl TuiApp {
     /// Refresh entries from file
     pub fn refresh(&mut self) -> Result<()> {
         self.file_content = crate::utils::path::read_file(&self.file_path)?;
-        let parser = crate::parser::get_parser(self.shell_type);
-        let parse_result = parser.parse(&self.file_content);
+        let parse_result = self.parser().parse(&self.file_content);
         self.entries = parse_result.entries;
 
         // Try to keep selection on same entry by name
@@ -1568,6 +1734,9 @@ impl TuiApp {
 
     /// Format the configuration file (with preview)
     fn format_file(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         self.preview_format()?;
         Ok(())
     }
@@ -1613,7 +1782,7 @@ impl TuiApp {
         let mut summary = Vec::new();
 
         // 1. Check for duplicates
-        let check_result = check_all(&self.entries);
+        let check_result = check_all(&self.entries, self.shell_type);
         if !check_result.issues.is_empty() {
             summary.push(format!("⚠ Found {} issues:", check_result.issues.len()));
             for issue in check_result.issues.iter().take(10) {
@@ -1735,11 +1904,14 @@ impl TuiApp {
             }
 
             // Create backup before writing
-            let backup_manager = crate::backup::BackupManager::new(self.shell_type, &config);
-            backup_manager.create_backup(&self.file_path)?;
+            self.create_backup(&config)?;
 
             // Write formatted content
-            std::fs::write(&self.file_path, preview.formatted_content)?;
+            crate::utils::path::save_config_content(
+                &self.file_path,
+                &preview.formatted_content,
+                config.format.preserve_trailing_blanks,
+            )?;
 
             // Refresh entries
             self.refresh()?;
@@ -1763,11 +1935,14 @@ impl TuiApp {
             let config = crate::config::load_or_create_config()?;
 
             // Create backup before writing
-            let backup_manager = crate::backup::BackupManager::new(self.shell_type, &config);
-            backup_manager.create_backup(&self.file_path)?;
+            self.create_backup(&config)?;
 
             // Write formatted content
-            std::fs::write(&self.file_path, preview.formatted_content)?;
+            crate::utils::path::save_config_content(
+                &self.file_path,
+                &preview.formatted_content,
+                config.format.preserve_trailing_blanks,
+            )?;
 
             // Refresh entries
             self.refresh()?;
@@ -1780,14 +1955,29 @@ impl TuiApp {
 
     /// Start adding a new entry (show type selection menu)
     fn start_adding_entry(&mut self) {
+        if self.refuse_in_view_only() {
+            return;
+        }
         self.mode = AppMode::SelectingType;
         self.type_selection_index = 0;
         self.type_list_scroll_offset = 0;
         self.message = Some(self.messages.tui_msg_select_entry_type.to_string());
     }
 
+    /// Ask to confirm deleting the selected entry or selection
+    fn start_deleting(&mut self) {
+        if self.refuse_in_view_only() {
+            return;
+        }
+        self.delete_confirm_scroll = 0;
+        self.mode = AppMode::ConfirmDelete;
+    }
+
     /// Start editing the selected entry
     fn start_editing(&mut self) {
+        if self.refuse_in_view_only() {
+            return;
+        }
         if let Some(entry) = self.get_selected_entry() {
             let name = entry.name.clone();
             let entry_type = entry.entry_type;
@@ -1818,6 +2008,9 @@ impl TuiApp {
 
     /// Start moving the selected entry or selection
     fn start_moving(&mut self) {
+        if self.refuse_in_view_only() {
+            return;
+        }
         if !self.entries.is_empty() {
             // Don't clear selection - support multi-select moving
             // If no selection exists, auto-select current entry
@@ -1975,8 +2168,37 @@ impl TuiApp {
             return Ok(());
         }
 
-        // Auto-extract name from value_buffer for UI display
+        // Alias definitions get the checks of `wenv add`; an unquoted value
+        // is fixed up for the user to confirm with another Enter
         let mut state = state;
+        if state.entry_type == EntryType::Alias && self.shell_type != ShellType::PowerShell {
+            let issue = state
+                .value_buffer
+                .lines()
+                .flat_map(check_alias_line)
+                .find(AliasIssue::is_error);
+            if let Some(issue) = issue {
+                if let AliasIssue::UnquotedValue { suggestion } = &issue {
+                    state.value_buffer = state
+                        .value_buffer
+                        .lines()
+                        .map(|line| match check_alias_line(line).contains(&issue) {
+                            true => suggestion.as_str(),
+                            false => line,
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    state.cursor_position = state.value_buffer.len();
+                    state.cursor_row = state.value_buffer.lines().count().saturating_sub(1);
+                    state.cursor_col = state.value_buffer.lines().last().map_or(0, str::len);
+                }
+                self.message = Some(issue.message(self.messages));
+                self.edit_state = Some(state);
+                return Ok(());
+            }
+        }
+
+        // Auto-extract name from value_buffer for UI display
         state.name_buffer = extract_name_from_value(&state.entry_type, &state.value_buffer);
 
         // Read current content
@@ -2123,8 +2345,7 @@ impl TuiApp {
         }
 
         let content = std::fs::read_to_string(&self.temp_file_path)?;
-        let parser = crate::parser::get_parser(self.shell_type);
-        let parse_result = parser.parse(&content);
+        let parse_result = self.parser().parse(&content);
         self.entries = parse_result.entries;
 
         // Adjust selection to avoid out-of-bounds
@@ -2137,6 +2358,9 @@ impl TuiApp {
 
     /// Save entries to original file (with backup and validation)
     fn save_to_original_file(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         // Validate with shell first
         match self.validate_with_shell()? {
             Some(error_msg) => {
@@ -2157,12 +2381,15 @@ impl TuiApp {
 
         // Create backup
         let config = crate::config::load_or_create_config()?;
-        let backup_manager = crate::backup::BackupManager::new(self.shell_type, &config);
-        backup_manager.create_backup(&self.file_path)?;
+        self.create_backup(&config)?;
 
         // Generate content and write
         let content = self.generate_file_content();
-        std::fs::write(&self.file_path, &content)?;
+        crate::utils::path::save_config_content(
+            &self.file_path,
+            &content,
+            config.format.preserve_trailing_blanks,
+        )?;
 
         // Clean up temp file and reset dirty flag
         self.cleanup_temp_file();
@@ -2172,6 +2399,55 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Save, then show the command that reloads the file in the shell and
+    /// copy it to the clipboard. Nothing is copied when the save stops to
+    /// confirm validation errors.
+    fn save_and_copy_reload_command(&mut self) -> Result<()> {
+        self.save_to_original_file()?;
+        if self.dirty || self.mode == AppMode::ConfirmSaveWithErrors {
+            return Ok(());
+        }
+
+        let config = crate::config::load_or_create_config()?;
+        let path = if config.ui.collapse_home {
+            crate::utils::path::collapse_home(&self.file_path)
+        } else {
+            self.file_path.display().to_string()
+        };
+        let Some(command) = reload_command(self.shell_type, &path) else {
+            return Ok(());
+        };
+        let message = if copy_to_clipboard(&command) {
+            self.messages.tui_msg_saved_reload_copied
+        } else {
+            self.messages.tui_msg_saved_reload
+        };
+        self.message = Some(message.replace("{}", &command));
+        Ok(())
+    }
+
+    /// Copy the command applying the selected entry in the current shell
+    /// to the clipboard
+    fn copy_apply_command(&mut self) {
+        let Some(entry) = self.get_selected_entry() else {
+            return;
+        };
+        let message = match apply_command(entry, self.shell_type) {
+            None => self.messages.tui_msg_nothing_to_apply.to_string(),
+            Some(command) => {
+                let template = if copy_to_clipboard(&command) {
+                    self.messages.tui_msg_apply_command_copied
+                } else {
+                    self.messages.tui_msg_clipboard_unavailable
+                };
+                template
+                    .replacen("{}", &entry.entry_type.to_string(), 1)
+                    .replacen("{}", &entry.name, 1)
+            }
+        };
+        self.message = Some(message);
+    }
+
     /// Clean up temp file
     fn cleanup_temp_file(&self) {
         if self.temp_file_path.exists() {
@@ -2212,6 +2488,9 @@ impl TuiApp {
 
     /// Undo last change
     pub fn undo(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         if let Some(previous_content) = self.undo_stack.pop() {
             // Save current content to redo stack
             let current_content = self.read_current_content()?;
@@ -2231,6 +2510,9 @@ impl TuiApp {
 
     /// Redo last undone change
     pub fn redo(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         if let Some(next_content) = self.redo_stack.pop() {
             // Save current content to undo stack
             let current_content = self.read_current_content()?;
@@ -2462,10 +2744,16 @@ impl TuiApp {
 
     /// Open temp file in external editor
     fn open_temp_file_in_editor(&mut self) -> Result<()> {
-        use std::process::Command;
-
-        // Get editor from environment (fallback to vi)
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
+        let editor = match crate::utils::editor::resolve_editor(self.editor.as_deref()) {
+            Ok(editor) => editor,
+            Err(e) => {
+                self.message = Some(e.to_string());
+                return Ok(());
+            }
+        };
 
         // Get modification time before editing
         let metadata_before = std::fs::metadata(&self.temp_file_path).ok();
@@ -2473,18 +2761,30 @@ impl TuiApp {
 
         // Suspend TUI
         disable_raw_mode()?;
-        execute!(io::stdout(), LeaveAlternateScreen)?;
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
 
-        // Open editor (no line number targeting - open full file)
-        let status = Command::new(&editor).arg(&self.temp_file_path).status()?;
+        // Open editor at the selected entry (entries mirror the temp file)
+        let line = self
+            .entries
+            .get(self.selected_index)
+            .and_then(|e| e.line_number);
+        let status = editor.open_at(&self.temp_file_path, line);
 
         // Resume TUI
-        execute!(io::stdout(), EnterAlternateScreen)?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
         enable_raw_mode()?;
 
         // Set flag to force full redraw (clears artifacts after editor)
         self.needs_full_redraw = true;
 
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => {
+                self.message = Some(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+
         if status.success() {
             // Check if file was actually modified
             let metadata_after = std::fs::metadata(&self.temp_file_path).ok();
@@ -2509,6 +2809,9 @@ impl TuiApp {
     /// Uses pure text manipulation (add/remove # prefix per line)
     /// Supports multi-select
     fn toggle_comment(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         if self.entries.is_empty() {
             return Ok(());
         }
@@ -2611,6 +2914,9 @@ impl TuiApp {
 
     /// Paste content from internal clipboard buffer
     fn paste_entry(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
         if let Some(ref clipboard_content) = self.clipboard_buffer {
             if self.entries.is_empty() {
                 // Paste at end of file
@@ -2636,19 +2942,12 @@ impl TuiApp {
                     .or(current_entry.line_number)
                     .unwrap_or(1);
 
-                // Read current content
-                let content = self.read_current_content()?;
-                let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
                 // Insert clipboard content after current entry
-                let insert_idx = insert_line.min(lines.len()); // 0-indexed insert position
-
-                for (i, line) in clipboard_content.lines().enumerate() {
-                    lines.insert(insert_idx + i, line.to_string());
-                }
+                let content = self.read_current_content()?;
+                let block: Vec<&str> = clipboard_content.lines().collect();
+                let new_content = insert_lines_after(&content, insert_line, &block);
 
                 // Write to temp file and reload
-                let new_content = lines.join("\n") + "\n";
                 self.write_temp_with_undo(&new_content)?;
                 self.reload_from_temp()?;
 
@@ -2661,6 +2960,47 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Insert a copy of the selected entry's raw block right after it and
+    /// open the editor on the copy
+    fn duplicate_entry(&mut self) -> Result<()> {
+        if self.refuse_in_view_only() {
+            return Ok(());
+        }
+        let Some(entry) = self.get_selected_entry() else {
+            return Ok(());
+        };
+        let start = entry.line_number.unwrap_or(1);
+        let end = entry.end_line.unwrap_or(start);
+
+        let content = self.read_current_content()?;
+        let block: Vec<&str> = content
+            .lines()
+            .skip(start.saturating_sub(1))
+            .take(end + 1 - start)
+            .collect();
+        if block.is_empty() {
+            return Ok(());
+        }
+        let new_content = insert_lines_after(&content, end, &block);
+
+        self.write_temp_with_undo(&new_content)?;
+        self.reload_from_temp()?;
+
+        // Line numbers come from the reparse; the copy starts right after the original
+        let copy_line = end + 1;
+        if let Some(index) = self.entries.iter().position(|e| {
+            let first = e.line_number.unwrap_or(0);
+            first <= copy_line && copy_line <= e.end_line.unwrap_or(first)
+        }) {
+            self.selected_index = index;
+            self.adjust_scroll_for_selection();
+        }
+
+        self.start_editing();
+        self.message = Some(self.messages.tui_msg_entry_duplicated.to_string());
+        Ok(())
+    }
+
     /// Validate temp file using shell syntax check
     fn validate_with_shell(&self) -> Result<Option<String>> {
         let file_to_check = if self.temp_file_path.exists() {
@@ -2674,6 +3014,12 @@ impl TuiApp {
                 let path_str = file_to_check.to_string_lossy().to_string();
                 ("bash", vec!["-n".to_string(), path_str])
             }
+            ShellType::PosixSh => {
+                let path_str = file_to_check.to_string_lossy().to_string();
+                ("sh", vec!["-n".to_string(), path_str])
+            }
+            // `.env` files have no shell to check them with
+            ShellType::DotEnv => return Ok(None),
             ShellType::PowerShell => {
                 let path_str = file_to_check.display().to_string();
                 let script = format!(
@@ -2712,12 +3058,15 @@ impl TuiApp {
     fn force_save_to_original_file(&mut self) -> Result<()> {
         // Create backup
         let config = crate::config::load_or_create_config()?;
-        let backup_manager = crate::backup::BackupManager::new(self.shell_type, &config);
-        backup_manager.create_backup(&self.file_path)?;
+        self.create_backup(&config)?;
 
         // Generate content and write
         let content = self.generate_file_content();
-        std::fs::write(&self.file_path, &content)?;
+        crate::utils::path::save_config_content(
+            &self.file_path,
+            &content,
+            config.format.preserve_trailing_blanks,
+        )?;
 
         // Clean up temp file and reset dirty flag
         self.cleanup_temp_file();
@@ -2737,8 +3086,25 @@ impl TuiApp {
     }
 
     /// Handle searching mode keys
-    fn handle_searching_mode(&mut self, key: KeyCode) -> Result<()> {
-        match key {
+    fn handle_searching_mode(&mut self, key: KeyEvent) -> Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                // Ctrl+T: toggle case-insensitive matching
+                KeyCode::Char('t') => {
+                    self.search_options.case_insensitive = !self.search_options.case_insensitive;
+                    self.update_search_matches();
+                }
+                // Ctrl+R: toggle regex mode
+                KeyCode::Char('r') => {
+                    self.search_options.regex = !self.search_options.regex;
+                    self.update_search_matches();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match key.code {
             KeyCode::Esc => {
                 // Exit search mode, keep query
                 self.mode = AppMode::Normal;
@@ -2783,16 +3149,15 @@ impl TuiApp {
 
     /// Update search matches (search Name and Value)
     fn update_search_matches(&mut self) {
-        self.search_matches.clear();
-        if self.search_query.is_empty() {
-            return;
-        }
-        let query_lower = self.search_query.to_lowercase();
-        for (i, entry) in self.entries.iter().enumerate() {
-            if entry.name.to_lowercase().contains(&query_lower)
-                || entry.value.to_lowercase().contains(&query_lower)
-            {
-                self.search_matches.push(i);
+        match find_matches(&self.entries, &self.search_query, self.search_options) {
+            Ok(matches) => {
+                self.search_matches = matches;
+                self.search_error = None;
+            }
+            Err(error) => {
+                // Keep typing: the pattern may become valid with more input
+                self.search_matches.clear();
+                self.search_error = Some(error);
             }
         }
     }
@@ -2831,6 +3196,22 @@ impl TuiApp {
     }
 }
 
+/// Index of the first entry named `name`, of `entry_type` when given
+fn find_entry_index(entries: &[Entry], entry_type: Option<EntryType>, name: &str) -> Option<usize> {
+    entries
+        .iter()
+        .position(|e| e.name == name && entry_type.is_none_or(|t| e.entry_type == t))
+}
+
+/// Insert `block` after 1-based line `after_line` of `content` (clamped to
+/// the end of the file). The result always ends with a newline.
+fn insert_lines_after(content: &str, after_line: usize, block: &[&str]) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let index = after_line.min(lines.len());
+    lines.splice(index..index, block.iter().copied());
+    lines.join("\n") + "\n"
+}
+
 /// Find a valid character boundary at or before the given byte position
 /// This ensures safe string slicing by backing up to a valid boundary
 fn find_char_boundary(s: &str, pos: usize) -> usize {
@@ -2936,6 +3317,45 @@ fn extract_name_from_value(entry_type: &EntryType, value: &str) -> String {
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_else(|| "unknown".to_string())
         }
+        EntryType::Array => {
+            // Extract array name from "arr=(...)" or "declare -A arr=(...)"
+            match value.trim().split_once('=') {
+                Some((before, _)) => before
+                    .split_whitespace()
+                    .last()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                None => "unknown".to_string(),
+            }
+        }
+        EntryType::Unset => {
+            // Extract the name from "unalias ll", "unset -f name" or
+            // "Remove-Item Env:NAME"
+            value
+                .split_whitespace()
+                .skip(1)
+                .find(|word| !word.starts_with('-'))
+                .map(|word| {
+                    word.rsplit(':')
+                        .next()
+                        .unwrap_or(word)
+                        .trim_matches(['\'', '"', '\\'])
+                        .to_string()
+                })
+                .unwrap_or_else(|| "unknown".to_string())
+        }
+        EntryType::Trap => {
+            // The signals after the handler: "trap 'cleanup' INT TERM"
+            crate::parser::trap_signals(value).unwrap_or_else(|| "unknown".to_string())
+        }
+        EntryType::Setopt => {
+            // The options switched: "setopt autocd extendedglob"
+            crate::parser::setopt_options(value).unwrap_or_else(|| "unknown".to_string())
+        }
+        EntryType::Zstyle => {
+            // Pattern and style: "zstyle ':completion:*' menu select"
+            crate::parser::zstyle_name(value).unwrap_or_else(|| "unknown".to_string())
+        }
         EntryType::Code | EntryType::Comment => {
             // For Code/Comment, use a line-based identifier
             let line_count = value.split('\n').count();
@@ -2948,3 +3368,111 @@ fn extract_name_from_value(entry_type: &EntryType, value: &str) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_lines_after() {
+        let content = "alias a='1'\n# about b\nalias b='2'\nalias c='3'\n";
+
+        assert_eq!(
+            insert_lines_after(content, 3, &["# about b", "alias b='2'"]),
+            "alias a='1'\n# about b\nalias b='2'\n# about b\nalias b='2'\nalias c='3'\n"
+        );
+        assert_eq!(
+            insert_lines_after(content, 0, &["x"]),
+            "x\nalias a='1'\n# about b\nalias b='2'\nalias c='3'\n"
+        );
+        // Past the end appends
+        assert_eq!(
+            insert_lines_after("alias a='1'", 9, &["alias a='1'"]),
+            "alias a='1'\nalias a='1'\n"
+        );
+    }
+
+    #[test]
+    fn test_find_entry_index() {
+        let entries = [
+            Entry::new(EntryType::EnvVar, "ll".into(), "export ll=1".into()),
+            Entry::new(
+                EntryType::Alias,
+                "gs".into(),
+                "alias gs='git status'".into(),
+            ),
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into()),
+        ];
+
+        assert_eq!(find_entry_index(&entries, None, "ll"), Some(0));
+        assert_eq!(
+            find_entry_index(&entries, Some(EntryType::Alias), "ll"),
+            Some(2)
+        );
+        assert_eq!(
+            find_entry_index(&entries, Some(EntryType::Function), "ll"),
+            None
+        );
+        assert_eq!(find_entry_index(&entries, None, "missing"), None);
+    }
+
+    #[test]
+    fn test_with_merge_comments_off_keeps_comments_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".bashrc");
+        std::fs::write(&path, "# long listing\nalias ll='ls -la'\n").unwrap();
+        let app = TuiApp::new(path.clone(), ShellType::Bash, crate::i18n::messages()).unwrap();
+        assert_eq!(app.entries.len(), 1);
+
+        let mut app = app.with_merge_comments(false);
+        assert_eq!(app.entries.len(), 2);
+        assert_eq!(app.entries[0].entry_type, EntryType::Comment);
+        app.refresh().unwrap();
+        assert_eq!(app.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_with_attached_comments_records_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".bashrc");
+        std::fs::write(&path, "# long listing\nalias ll='ls -la'\n").unwrap();
+        let app = TuiApp::new(path.clone(), ShellType::Bash, crate::i18n::messages()).unwrap();
+        assert_eq!(app.entries[0].attached_comment, None);
+
+        let mut app = app.with_attached_comments(true);
+        assert_eq!(
+            app.entries[0].attached_comment.as_deref(),
+            Some("# long listing")
+        );
+        app.refresh().unwrap();
+        assert_eq!(
+            app.entries[0].attached_comment.as_deref(),
+            Some("# long listing")
+        );
+    }
+
+    #[test]
+    fn test_delete_is_a_no_op_in_view_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".bashrc");
+        let content = "alias ll='ls -la'\nalias gs='git status'\n";
+        std::fs::write(&path, content).unwrap();
+        let mut app = TuiApp::new(path.clone(), ShellType::Bash, crate::i18n::messages())
+            .unwrap()
+            .with_view_only(true);
+
+        for code in [KeyCode::Char('d'), KeyCode::Delete] {
+            app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+                .unwrap();
+            assert_eq!(app.mode, AppMode::Normal);
+        }
+        assert_eq!(app.message.as_deref(), Some(app.messages.tui_msg_read_only));
+        // Navigation still works
+        app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE))
+            .unwrap();
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.entries.len(), 2);
+        assert!(!app.dirty);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod app;
 pub mod event;
+pub mod search;
 pub mod ui;
 
 pub use app::TuiApp;
@@ -0,0 +1,170 @@
+//! Entry matching for the TUI search popup
+//!
+//! A query matches an entry when it occurs in the entry's name or value.
+//! Queries starting with `/` (or any query while regex mode is on) are
+//! compiled as regular expressions.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::model::Entry;
+
+/// Search toggles kept across searches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_insensitive: bool,
+    pub regex: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive: true,
+            regex: false,
+        }
+    }
+}
+
+/// A compiled search query
+#[derive(Debug)]
+pub enum Matcher {
+    Substring { needle: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Compile `query`. Returns the regex error message for invalid patterns.
+    pub fn new(query: &str, options: SearchOptions) -> Result<Self, String> {
+        let (pattern, is_regex) = match query.strip_prefix('/') {
+            Some(pattern) => (pattern, true),
+            None => (query, options.regex),
+        };
+
+        if !is_regex {
+            let needle = if options.case_insensitive {
+                pattern.to_lowercase()
+            } else {
+                pattern.to_string()
+            };
+            return Ok(Matcher::Substring {
+                needle,
+                ignore_case: options.case_insensitive,
+            });
+        }
+
+        RegexBuilder::new(pattern)
+            .case_insensitive(options.case_insensitive)
+            .build()
+            .map(Matcher::Regex)
+            .map_err(|e| {
+                e.to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string()
+            })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Substring {
+                needle,
+                ignore_case: true,
+            } => text.to_lowercase().contains(needle.as_str()),
+            Matcher::Substring { needle, .. } => text.contains(needle.as_str()),
+            Matcher::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+/// Indices of entries whose name or value matches `query`. An empty query
+/// (or a lone `/`) matches nothing.
+pub fn find_matches(
+    entries: &[Entry],
+    query: &str,
+    options: SearchOptions,
+) -> Result<Vec<usize>, String> {
+    if query.is_empty() || query == "/" {
+        return Ok(Vec::new());
+    }
+    let matcher = Matcher::new(query, options)?;
+    Ok(entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| matcher.is_match(&e.name) || matcher.is_match(&e.value))
+        .map(|(i, _)| i)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::EntryType;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry::new(EntryType::Alias, "ll".into(), "alias ll='ls -la'".into()),
+            Entry::new(
+                EntryType::EnvVar,
+                "EDITOR".into(),
+                "export EDITOR=vim".into(),
+            ),
+            Entry::new(
+                EntryType::Alias,
+                "gs".into(),
+                "alias gs='git status'".into(),
+            ),
+        ]
+    }
+
+    const MATCH_CASE: SearchOptions = SearchOptions {
+        case_insensitive: false,
+        regex: false,
+    };
+
+    #[test]
+    fn test_substring_case_handling() {
+        let entries = entries();
+        let options = SearchOptions::default();
+
+        assert_eq!(find_matches(&entries, "editor", options), Ok(vec![1]));
+        assert_eq!(find_matches(&entries, "editor", MATCH_CASE), Ok(vec![]));
+        assert_eq!(find_matches(&entries, "EDITOR", MATCH_CASE), Ok(vec![1]));
+        assert_eq!(find_matches(&entries, "", options), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_regex_by_prefix_or_mode() {
+        let entries = entries();
+        let options = SearchOptions::default();
+
+        assert_eq!(
+            find_matches(&entries, "/^(ll|gs)$", options),
+            Ok(vec![0, 2])
+        );
+        // Without regex mode the pattern is literal text
+        assert_eq!(find_matches(&entries, "^(ll|gs)$", options), Ok(vec![]));
+
+        let regex_mode = SearchOptions {
+            regex: true,
+            ..options
+        };
+        assert_eq!(find_matches(&entries, "GIT\\s", regex_mode), Ok(vec![2]));
+        let regex_match_case = SearchOptions {
+            case_insensitive: false,
+            regex: true,
+        };
+        assert_eq!(
+            find_matches(&entries, "GIT\\s", regex_match_case),
+            Ok(vec![])
+        );
+        assert_eq!(find_matches(&entries, "/", options), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_an_error() {
+        let error = find_matches(&entries(), "/ls(", SearchOptions::default()).unwrap_err();
+        assert!(!error.is_empty());
+        assert!(error.contains("unclosed"), "{}", error);
+    }
+}
@@ -9,7 +9,7 @@ use ratatui::{
 };
 
 use super::app::{AppMode, EditField, TuiApp};
-use crate::model::EntryType;
+use crate::model::{EntryType, ShellType};
 
 /// Header row count in the entry list (header + separator)
 const LIST_HEADER_OFFSET: usize = 2;
@@ -80,6 +80,7 @@ fn format_line_info(entry: &crate::model::Entry) -> String {
 fn draw_content(f: &mut Frame, app: &mut TuiApp, area: Rect) {
     // Update visible height for scroll calculations (used by keyboard navigation)
     app.list_visible_height = area.height as usize;
+    app.list_top = area.y;
 
     let msg = app.messages;
 
@@ -128,14 +129,7 @@ fn draw_content(f: &mut Frame, app: &mut TuiApp, area: Rect) {
         .iter()
         .enumerate()
         .map(|(i, entry)| {
-            let type_color = match entry.entry_type {
-                EntryType::Alias => Color::Green,
-                EntryType::Function => Color::LightBlue,
-                EntryType::EnvVar => Color::Yellow,
-                EntryType::Source => Color::Magenta,
-                EntryType::Code => Color::Cyan,
-                EntryType::Comment => Color::White,
-            };
+            let type_color = app.type_color(entry.entry_type);
 
             // Truncate long values
             let value = if entry.value.len() > 100 {
@@ -147,6 +141,11 @@ fn draw_content(f: &mut Frame, app: &mut TuiApp, area: Rect) {
 
             // Format line info
             let line_info = format_line_info(entry);
+            // Size badge for multi-line entries, so big blocks stand out
+            let badge = match entry.line_count() {
+                1 => String::new(),
+                count => format!("[{}] ", count),
+            };
 
             let line = Line::from(vec![
                 Span::styled(
@@ -164,6 +163,7 @@ fn draw_content(f: &mut Frame, app: &mut TuiApp, area: Rect) {
                     Style::default().fg(Color::Gray),
                 ),
                 Span::raw(" "),
+                Span::styled(badge, Style::default().fg(Color::DarkGray)),
                 Span::styled(value, Style::default().fg(Color::Gray)),
             ]);
 
@@ -249,12 +249,36 @@ fn draw_content(f: &mut Frame, app: &mut TuiApp, area: Rect) {
     // The select() will auto-scroll to keep selected item visible
 
     f.render_stateful_widget(list, area, &mut state);
+
+    // Remember where ratatui scrolled to, for mapping mouse clicks to rows
+    app.list_render_offset = state.offset();
+}
+
+/// Index of the entry drawn at screen `row` in the entry list, given the
+/// list's top row and height (borders included) and the first item drawn
+/// (counting the header and separator items). `None` for the border, the
+/// header rows and rows below the last entry.
+pub fn entry_index_at_row(
+    row: u16,
+    list_top: u16,
+    list_height: usize,
+    render_offset: usize,
+    entry_count: usize,
+) -> Option<usize> {
+    // Skip the top border; the bottom border is the last row
+    let inner_row = (row.checked_sub(list_top)? as usize).checked_sub(1)?;
+    if inner_row + 2 >= list_height {
+        return None;
+    }
+    let index = (render_offset + inner_row).checked_sub(LIST_HEADER_OFFSET)?;
+    (index < entry_count).then_some(index)
 }
 
 /// Draw the status bar
 fn draw_status_bar(f: &mut Frame, app: &TuiApp, area: Rect) {
     let msg = &app.messages;
     let help_text = match app.mode {
+        AppMode::Normal if app.view_only => msg.tui_status_view_only,
         AppMode::Normal => msg.tui_status_normal,
         AppMode::Searching => msg.tui_status_searching,
         AppMode::ShowingDetail => msg.tui_status_detail_extended,
@@ -268,8 +292,14 @@ fn draw_status_bar(f: &mut Frame, app: &TuiApp, area: Rect) {
         AppMode::Moving => msg.tui_status_moving,
     };
 
-    // Build status text with dirty indicator
-    let dirty_indicator = if app.dirty { "[*] " } else { "" };
+    // Build status text with dirty (or read-only) indicator
+    let dirty_indicator = if app.view_only {
+        format!("{} ", msg.tui_status_read_only)
+    } else if app.dirty {
+        "[*] ".to_string()
+    } else {
+        String::new()
+    };
 
     // Add search info if search is active
     let search_info = if app.search_active && app.mode == AppMode::Normal {
@@ -323,12 +353,28 @@ fn draw_search_popup(f: &mut Frame, app: &TuiApp) {
 
     let match_count = app.search_matches.len();
     let msg = &app.messages;
-    let match_info = if match_count > 0 {
-        msg.tui_search_matches
-            .replace("{}", &match_count.to_string())
+    let (match_info, match_color) = if let Some(error) = &app.search_error {
+        (msg.tui_search_invalid.replace("{}", error), Color::Red)
+    } else if match_count > 0 {
+        (
+            msg.tui_search_matches
+                .replace("{}", &match_count.to_string()),
+            Color::DarkGray,
+        )
+    } else {
+        (msg.tui_search_no_matches.to_string(), Color::DarkGray)
+    };
+
+    let options = app.search_options;
+    let mut mode_info = if options.case_insensitive {
+        msg.tui_search_ignore_case.to_string()
     } else {
-        msg.tui_search_no_matches.to_string()
+        msg.tui_search_match_case.to_string()
     };
+    if options.regex || app.search_query.starts_with('/') {
+        mode_info.push_str(" · ");
+        mode_info.push_str(msg.tui_search_regex);
+    }
 
     let lines = vec![
         Line::from(""),
@@ -343,11 +389,11 @@ fn draw_search_popup(f: &mut Frame, app: &TuiApp) {
             Span::styled(msg.tui_search_query, Style::default().fg(Color::Yellow)),
             Span::styled(&search_display, Style::default().fg(Color::White)),
         ]),
-        Line::from(""),
         Line::from(Span::styled(
-            &match_info,
+            format!("[{}]", mode_info),
             Style::default().fg(Color::DarkGray),
         )),
+        Line::from(Span::styled(&match_info, Style::default().fg(match_color))),
         Line::from(""),
         Line::from(Span::styled(
             msg.tui_search_hint,
@@ -420,22 +466,34 @@ fn draw_detail_popup(f: &mut Frame, app: &mut TuiApp) {
             Span::styled(msg.label_name, Style::default().fg(Color::Cyan)),
             Span::raw(&entry.name),
         ]),
-        Line::from(vec![Span::styled(
-            msg.label_value,
-            Style::default().fg(Color::Cyan),
-        )]),
     ];
 
+    // Comment recorded above the entry with `parser.attach_comments`
+    if let Some(comment) = &entry.attached_comment {
+        lines.push(Line::from(vec![Span::styled(
+            msg.header_comment,
+            Style::default().fg(Color::Cyan),
+        )]));
+        for comment_line in comment.lines() {
+            lines.push(Line::from(Span::styled(
+                comment_line.to_string(),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    lines.push(Line::from(vec![Span::styled(
+        msg.label_value,
+        Style::default().fg(Color::Cyan),
+    )]));
+
     // Use value for Comment/Code to show full content
     let display_value = match entry.entry_type {
         EntryType::Comment | EntryType::Code => &entry.value,
         _ => &entry.value,
     };
     for value_line in display_value.lines() {
-        lines.push(Line::from(Span::styled(
-            value_line.to_string(),
-            Style::default().fg(Color::Gray),
-        )));
+        lines.push(highlight_line(value_line, app.shell_type));
     }
 
     // Calculate visible area height (subtract 2 for borders)
@@ -492,6 +550,148 @@ fn draw_detail_popup(f: &mut Frame, app: &mut TuiApp) {
     f.render_widget(footer, footer_area);
 }
 
+/// Kind of a piece of shell code, for syntax highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Keyword,
+    String,
+    Comment,
+    Variable,
+    Plain,
+}
+
+const BASH_KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "in", "function", "return", "local", "export", "alias", "unalias", "unset", "source", "select",
+    "declare", "typeset", "readonly",
+];
+
+/// PowerShell keywords, compared case-insensitively
+const PWSH_KEYWORDS: &[&str] = &[
+    "if", "else", "elseif", "foreach", "for", "while", "do", "until", "switch", "function",
+    "filter", "param", "return", "begin", "process", "end", "try", "catch", "finally", "throw",
+    "trap", "break", "continue", "in",
+];
+
+/// Split one line of code into classified pieces that together are the
+/// whole line. A string left open at the end of the line is colored to the
+/// end of it; state is not carried to the next line.
+fn tokenize_line(line: &str, shell_type: ShellType) -> Vec<(TokenKind, &str)> {
+    let pwsh = shell_type == ShellType::PowerShell;
+    let escape = if pwsh { '`' } else { '\\' };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut tokens: Vec<(TokenKind, &str)> = Vec::new();
+    let mut plain_start = 0;
+    let mut chars = line.char_indices().peekable();
+    let mut previous: Option<char> = None;
+    while let Some((start, c)) = chars.next() {
+        let at_word_start = previous.is_none_or(|p| !is_word(p) && p != '$');
+        let end = match c {
+            '#' if previous.is_none_or(char::is_whitespace) => {
+                Some((TokenKind::Comment, line.len()))
+            }
+            c if c == escape => {
+                // An escaped character is plain text, even a quote or `$`
+                let next = line[start + 1..].chars().next().map_or(0, char::len_utf8);
+                Some((TokenKind::Plain, start + 1 + next))
+            }
+            '\'' => {
+                // Single quotes: no escapes, `''` in PowerShell
+                let close = line[start + 1..].find('\'').map(|i| start + 1 + i + 1);
+                Some((TokenKind::String, close.unwrap_or(line.len())))
+            }
+            '"' => {
+                let mut close = line.len();
+                let mut escaped = false;
+                for (i, ch) in line[start + 1..].char_indices() {
+                    if escaped {
+                        escaped = false;
+                    } else if ch == escape {
+                        escaped = true;
+                    } else if ch == '"' {
+                        close = start + 1 + i + 1;
+                        break;
+                    }
+                }
+                Some((TokenKind::String, close))
+            }
+            '$' => {
+                let rest = &line[start + 1..];
+                let length = if rest.starts_with('{') {
+                    rest.find('}').map_or(rest.len(), |i| i + 1)
+                } else if !pwsh && rest.starts_with(['?', '@', '#', '*', '!', '$']) {
+                    1
+                } else {
+                    rest.find(|ch: char| {
+                        !(ch.is_alphanumeric() || ch == '_' || (pwsh && ch == ':'))
+                    })
+                    .unwrap_or(rest.len())
+                };
+                (length > 0).then_some((TokenKind::Variable, start + 1 + length))
+            }
+            c if at_word_start && is_word(c) => {
+                let length = line[start..]
+                    .find(|ch: char| !is_word(ch))
+                    .unwrap_or(line.len() - start);
+                let word = &line[start..start + length];
+                let keyword = if pwsh {
+                    PWSH_KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+                } else {
+                    BASH_KEYWORDS.contains(&word)
+                };
+                let kind = if keyword {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Plain
+                };
+                Some((kind, start + length))
+            }
+            _ => None,
+        };
+
+        let Some((kind, end)) = end else {
+            previous = Some(c);
+            continue;
+        };
+        // Words that are not keywords join the plain text around them
+        if kind != TokenKind::Plain {
+            if plain_start < start {
+                tokens.push((TokenKind::Plain, &line[plain_start..start]));
+            }
+            tokens.push((kind, &line[start..end]));
+            plain_start = end;
+        }
+        previous = line[..end].chars().next_back();
+        while chars.peek().is_some_and(|&(i, _)| i < end) {
+            chars.next();
+        }
+    }
+    if plain_start < line.len() {
+        tokens.push((TokenKind::Plain, &line[plain_start..]));
+    }
+    tokens
+}
+
+/// One line of an entry's value with syntax highlighting
+fn highlight_line(line: &str, shell_type: ShellType) -> Line<'static> {
+    let spans: Vec<Span<'static>> = tokenize_line(line, shell_type)
+        .into_iter()
+        .map(|(kind, text)| {
+            let style = match kind {
+                TokenKind::Keyword => Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+                TokenKind::String => Style::default().fg(Color::Green),
+                TokenKind::Comment => Style::default().fg(Color::DarkGray),
+                TokenKind::Variable => Style::default().fg(Color::Yellow),
+                TokenKind::Plain => Style::default().fg(Color::Gray),
+            };
+            Span::styled(text.to_string(), style)
+        })
+        .collect();
+    Line::from(spans)
+}
+
 /// Draw help popup
 fn draw_help_popup(f: &mut Frame, app: &TuiApp) {
     let area = centered_rect(60, 60, f.size());
@@ -551,6 +751,10 @@ fn draw_help_popup(f: &mut Frame, app: &TuiApp) {
             Span::styled("s, Ctrl+S ", Style::default().fg(Color::Yellow)),
             Span::raw(msg.tui_help_save),
         ]),
+        Line::from(vec![
+            Span::styled("W         ", Style::default().fg(Color::Yellow)),
+            Span::raw(msg.tui_help_save_reload),
+        ]),
         Line::from(vec![
             Span::styled("Ctrl/Alt+C", Style::default().fg(Color::Yellow)),
             Span::raw(msg.tui_help_copy),
@@ -559,6 +763,10 @@ fn draw_help_popup(f: &mut Frame, app: &TuiApp) {
             Span::styled("Ctrl/Alt+V", Style::default().fg(Color::Yellow)),
             Span::raw(msg.tui_help_paste),
         ]),
+        Line::from(vec![
+            Span::styled("Shift+D   ", Style::default().fg(Color::Yellow)),
+            Span::raw(msg.tui_help_duplicate),
+        ]),
         Line::from(vec![
             Span::styled("Shift+↑/↓ ", Style::default().fg(Color::Yellow)),
             Span::raw(msg.tui_help_select_range),
@@ -946,13 +1154,23 @@ fn draw_type_selection_popup(f: &mut Frame, app: &mut TuiApp) {
     let area = centered_rect(40, 40, f.size());
 
     let msg = &app.messages;
-    let types = [
-        ("1", "Alias", msg.tui_type_alias_desc),
-        ("2", "Function", msg.tui_type_func_desc),
-        ("3", "EnvVar", msg.tui_type_env_desc),
-        ("4", "Source", msg.tui_type_source_desc),
-        ("5", "Code/Comment", msg.tui_type_code_desc),
-    ];
+    let types: Vec<(String, &str, &str)> = app
+        .addable_types()
+        .iter()
+        .enumerate()
+        .map(|(index, entry_type)| {
+            let (name, desc) = match entry_type {
+                EntryType::Alias => ("Alias", msg.tui_type_alias_desc),
+                EntryType::Function => ("Function", msg.tui_type_func_desc),
+                EntryType::EnvVar => ("EnvVar", msg.tui_type_env_desc),
+                EntryType::Source => ("Source", msg.tui_type_source_desc),
+                EntryType::Setopt => ("Setopt", msg.tui_type_setopt_desc),
+                EntryType::Zstyle => ("Zstyle", msg.tui_type_zstyle_desc),
+                _ => ("Code/Comment", msg.tui_type_code_desc),
+            };
+            ((index + 1).to_string(), name, desc)
+        })
+        .collect();
 
     // Calculate available height for type list
     // area.height includes borders (top=1, bottom=1)
@@ -992,7 +1210,7 @@ fn draw_type_selection_popup(f: &mut Frame, app: &mut TuiApp) {
     let visible_start = app.type_list_scroll_offset;
     let visible_end = (app.type_list_scroll_offset + available_height).min(total_items);
 
-    for (idx, &(key, name, desc)) in types
+    for (idx, (key, name, desc)) in types
         .iter()
         .enumerate()
         .skip(visible_start)
@@ -1316,3 +1534,79 @@ fn find_char_boundary(s: &str, pos: usize) -> usize {
     }
     p
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_index_at_row() {
+        // List drawn from row 3, 10 rows tall: border, header, separator,
+        // entries on rows 6..=11, bottom border on row 12
+        let at = |row, offset, count| entry_index_at_row(row, 3, 10, offset, count);
+
+        assert_eq!(at(3, 0, 20), None); // top border
+        assert_eq!(at(4, 0, 20), None); // header
+        assert_eq!(at(5, 0, 20), None); // separator
+        assert_eq!(at(6, 0, 20), Some(0));
+        assert_eq!(at(11, 0, 20), Some(5));
+        assert_eq!(at(12, 0, 20), None); // bottom border
+        assert_eq!(at(0, 0, 20), None); // title bar
+
+        // Scrolled so that entry 7 is the first row
+        assert_eq!(at(4, 9, 20), Some(7));
+        assert_eq!(at(11, 9, 20), Some(14));
+
+        // Below the last entry
+        assert_eq!(at(8, 0, 2), None);
+    }
+
+    #[test]
+    fn test_tokenize_line() {
+        let tokens = tokenize_line(
+            r#"if [ -n "$HOME" ]; then echo $USER 'a # b' \$x; fi # done"#,
+            ShellType::Bash,
+        );
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::Keyword, "if"),
+                (TokenKind::Plain, " [ -n "),
+                (TokenKind::String, "\"$HOME\""),
+                (TokenKind::Plain, " ]; "),
+                (TokenKind::Keyword, "then"),
+                (TokenKind::Plain, " echo "),
+                (TokenKind::Variable, "$USER"),
+                (TokenKind::Plain, " "),
+                (TokenKind::String, "'a # b'"),
+                (TokenKind::Plain, " \\$x; "),
+                (TokenKind::Keyword, "fi"),
+                (TokenKind::Plain, " "),
+                (TokenKind::Comment, "# done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_powershell_line() {
+        let tokens = tokenize_line(
+            "ForEach ($p in $env:PATH) { Write-Host \"`\"$p\" }",
+            ShellType::PowerShell,
+        );
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::Keyword, "ForEach"),
+                (TokenKind::Plain, " ("),
+                (TokenKind::Variable, "$p"),
+                (TokenKind::Plain, " "),
+                (TokenKind::Keyword, "in"),
+                (TokenKind::Plain, " "),
+                (TokenKind::Variable, "$env:PATH"),
+                (TokenKind::Plain, ") { Write-Host "),
+                (TokenKind::String, "\"`\"$p\""),
+                (TokenKind::Plain, " }"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Shareable "wenv bundle" documents
+//!
+//! A bundle is a TOML document listing entries by type, name and canonical
+//! value, independent of the syntax of the file they came from:
+//!
+//! ```toml
+//! format = "wenv-bundle"
+//! version = 1
+//! shell = "bash"
+//!
+//! [[entries]]
+//! type = "alias"
+//! name = "ll"
+//! value = "ls -la"
+//! ```
+//!
+//! Importing regenerates shell syntax with the target shell's formatter, so
+//! aliases, env vars and sources can move between Bash and PowerShell (see
+//! [`crate::utils::translate`]).
+//! Functions, arrays, raw code and PowerShell module imports are kept
+//! verbatim and only import into a shell with the same syntax.
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType, ShellType};
+use crate::parser::get_parser;
+use crate::utils::translate::{same_syntax, translate_references};
+
+/// Value of the `format` key that marks a bundle
+pub const BUNDLE_FORMAT: &str = "wenv-bundle";
+
+/// Bundle layout version written by this build
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A bundle document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub format: String,
+    pub version: u32,
+    /// Shell the entries were exported from (`bash`, `zsh`, `sh`, `pwsh`)
+    pub shell: String,
+    /// RFC 3339 export time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    /// File name the entries were exported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<BundleEntry>,
+}
+
+/// One entry of a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Entry type as shown by `wenv list` (`alias`, `env`, `func`, ...)
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub name: String,
+    /// Canonical value: no keyword, quotes or comments
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// An entry that could not be imported, with the reason
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub entry_type: String,
+    pub name: String,
+    pub reason: String,
+}
+
+impl Bundle {
+    /// Bundle `entries` exported from a `shell` file. Comments and blank
+    /// lines are left out.
+    pub fn from_entries(shell: ShellType, entries: &[Entry]) -> Self {
+        let entries = entries
+            .iter()
+            .filter(|e| e.entry_type != EntryType::Comment && !e.is_blank())
+            .map(|e| {
+                // Module imports are stored as code: regenerating them would
+                // dot-source the module name
+                let (entry_type, value) = if e.is_module_import() {
+                    let line = e.value.trim_end().lines().last().unwrap_or_default();
+                    (EntryType::Code, line.to_string())
+                } else {
+                    (e.entry_type, e.canonical_value())
+                };
+                BundleEntry {
+                    entry_type: entry_type.to_string(),
+                    name: e.name.clone(),
+                    value,
+                    tags: e.tags.clone(),
+                }
+            })
+            .collect();
+
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            shell: shell.name().to_string(),
+            created: None,
+            source: None,
+            entries,
+        }
+    }
+
+    pub fn with_created(mut self, created: impl Into<String>) -> Self {
+        self.created = Some(created.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Parse a bundle document, rejecting other TOML files and newer versions
+    pub fn parse(content: &str) -> Result<Self> {
+        let bundle: Bundle = toml::from_str(content).context("Invalid wenv bundle")?;
+        if bundle.format != BUNDLE_FORMAT {
+            anyhow::bail!("Not a wenv bundle (format = \"{}\")", bundle.format);
+        }
+        if bundle.version > BUNDLE_VERSION {
+            anyhow::bail!(
+                "Bundle version {} is newer than supported version {}",
+                bundle.version,
+                BUNDLE_VERSION
+            );
+        }
+        Ok(bundle)
+    }
+
+    /// Whether `content` looks like a bundle rather than a shell file
+    pub fn is_bundle(content: &str) -> bool {
+        content
+            .parse::<toml::Table>()
+            .is_ok_and(|table| table.get("format").and_then(|f| f.as_str()) == Some(BUNDLE_FORMAT))
+    }
+
+    /// Generate entries in `target` shell syntax. Entries that have no
+    /// equivalent in the target shell are returned as [`Skipped`].
+    pub fn to_entries(&self, target: ShellType) -> Result<(Vec<Entry>, Vec<Skipped>)> {
+        let source_shell = self
+            .shell
+            .parse::<ShellType>()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let formatter = get_formatter(target);
+        let parser = get_parser(target);
+
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+        for item in &self.entries {
+            let skip = |reason: &str| Skipped {
+                entry_type: item.entry_type.clone(),
+                name: item.name.clone(),
+                reason: reason.to_string(),
+            };
+
+            let Ok(entry_type) = item.entry_type.parse::<EntryType>() else {
+                skipped.push(skip("unknown entry type"));
+                continue;
+            };
+            let value = translate_references(&item.value, source_shell, target);
+            let mut syntax = match formatter.definition(entry_type, &item.name, &value) {
+                Some(syntax) => syntax,
+                None if same_syntax(source_shell, target) => item.value.clone(),
+                None => {
+                    skipped.push(skip(&format!("no {} equivalent", target)));
+                    continue;
+                }
+            };
+
+            if !item.tags.is_empty() {
+                syntax = format!("# wenv:tags={}\n{}", item.tags.join(","), syntax);
+            }
+
+            // Reparse so the entry gets the type and name the target shell
+            // sees (an alias with arguments is a function in PowerShell)
+            for mut entry in parser.parse(&syntax).entries {
+                entry.line_number = None;
+                entry.end_line = None;
+                entries.push(entry);
+            }
+        }
+
+        Ok((entries, skipped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle_of(shell: ShellType, content: &str) -> Bundle {
+        let entries = get_parser(shell).parse(content).entries;
+        Bundle::from_entries(shell, &entries)
+    }
+
+    fn values(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|e| e.value.as_str()).collect()
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let bundle = bundle_of(
+            ShellType::Bash,
+            "# wenv:tags=git\nalias gs='git status'\n\n# notes\n\nexport EDITOR=\"nvim\"\n",
+        )
+        .with_created("2026-01-01T00:00:00Z")
+        .with_source(".bashrc");
+
+        assert_eq!(bundle.entries.len(), 2);
+        assert_eq!(bundle.entries[0].value, "git status");
+        assert_eq!(bundle.entries[0].tags, ["git"]);
+
+        let content = bundle.to_toml().unwrap();
+        assert!(Bundle::is_bundle(&content));
+        assert_eq!(Bundle::parse(&content).unwrap(), bundle);
+
+        let (entries, _) = bundle.to_entries(ShellType::Bash).unwrap();
+        assert_eq!(entries[0].value, "# wenv:tags=git\nalias gs='git status'");
+        assert_eq!(entries[0].tags, ["git"]);
+    }
+
+    #[test]
+    fn test_same_shell_import() {
+        let bundle = bundle_of(
+            ShellType::Bash,
+            "alias ll=\"ls -la\"\nexport PATH=\"$HOME/bin:$PATH\"\ngreet() {\n  echo hi\n}\n",
+        );
+
+        let (entries, skipped) = bundle.to_entries(ShellType::Zsh).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(
+            values(&entries),
+            [
+                "alias ll='ls -la'",
+                "export PATH=$HOME/bin:$PATH",
+                "greet() {\n  echo hi\n}"
+            ]
+        );
+        assert_eq!(entries[2].entry_type, EntryType::Function);
+    }
+
+    #[test]
+    fn test_bash_to_powershell() {
+        let bundle = bundle_of(
+            ShellType::Bash,
+            "alias g=git\nalias ll='ls -la'\nexport EDITOR=vim\nsource ~/.aliases\ngreet() { echo hi; }\n",
+        );
+
+        let (entries, skipped) = bundle.to_entries(ShellType::PowerShell).unwrap();
+        assert_eq!(
+            values(&entries),
+            [
+                "Set-Alias -Name g -Value git",
+                "function ll { ls -la @args }",
+                "$env:EDITOR = \"vim\"",
+                ". \"~/.aliases\"",
+            ]
+        );
+        assert_eq!(entries[0].entry_type, EntryType::Alias);
+        assert_eq!(entries[1].entry_type, EntryType::Function);
+        assert_eq!(entries[2].entry_type, EntryType::EnvVar);
+        assert_eq!(entries[2].name, "EDITOR");
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].name, "greet");
+    }
+
+    #[test]
+    fn test_powershell_to_bash() {
+        let bundle = bundle_of(
+            ShellType::PowerShell,
+            "Set-Alias ll Get-ChildItem\n$env:EDITOR = 'code --wait'\n",
+        );
+
+        let (entries, skipped) = bundle.to_entries(ShellType::Bash).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(
+            values(&entries),
+            ["alias ll='Get-ChildItem'", "export EDITOR=\"code --wait\""]
+        );
+        assert_eq!(entries[1].entry_type, EntryType::EnvVar);
+    }
+
+    #[test]
+    fn test_module_imports_kept_verbatim() {
+        let bundle = bundle_of(
+            ShellType::PowerShell,
+            "# wenv:tags=prompt\nImport-Module -Name posh-git\n",
+        );
+        assert_eq!(bundle.entries[0].entry_type, "code");
+
+        let (entries, _) = bundle.to_entries(ShellType::PowerShell).unwrap();
+        let import = entries.last().unwrap();
+        assert_eq!(import.value, "Import-Module -Name posh-git");
+        assert_eq!(import.entry_type, EntryType::Source);
+        assert_eq!(import.name, "posh-git");
+        assert_eq!(import.tags, ["prompt"]);
+
+        let (entries, skipped) = bundle.to_entries(ShellType::Bash).unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(skipped[0].name, "posh-git");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_documents() {
+        assert!(!Bundle::is_bundle("alias ll='ls -la'\n"));
+        assert!(!Bundle::is_bundle("FOO = \"bar\"\n"));
+        assert!(Bundle::parse("format = \"other\"\nversion = 1\nshell = \"bash\"\n").is_err());
+        assert!(
+            Bundle::parse("format = \"wenv-bundle\"\nversion = 99\nshell = \"bash\"\n").is_err()
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Copying text to the system clipboard through the platform's own tool
+//!
+//! `clip` on Windows, `pbcopy` on macOS, and `wl-copy`, `xclip` or `xsel`
+//! elsewhere, whichever is installed first.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[cfg(windows)]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(not(any(windows, target_os = "macos")))]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Copy `text` to the clipboard. Returns whether a clipboard tool took it.
+pub fn copy_to_clipboard(text: &str) -> bool {
+    CLIPBOARD_COMMANDS.iter().any(|command| {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        child.wait().is_ok_and(|status| status.success()) && written
+    })
+}
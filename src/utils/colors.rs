@@ -0,0 +1,179 @@
+//! Entry type colors, customizable in the `[colors]` config table
+
+use colored::Color;
+
+use crate::model::{ColorsConfig, EntryType};
+
+/// Color of `entry_type` when `[colors]` doesn't set one
+pub fn default_color(entry_type: EntryType) -> Color {
+    match entry_type {
+        EntryType::Alias => Color::Green,
+        EntryType::Function => Color::BrightBlue,
+        EntryType::EnvVar => Color::Yellow,
+        EntryType::Source => Color::Magenta,
+        EntryType::Array => Color::BrightYellow,
+        EntryType::Unset => Color::Red,
+        EntryType::Trap => Color::BrightRed,
+        EntryType::Setopt => Color::BrightCyan,
+        EntryType::Zstyle => Color::BrightMagenta,
+        EntryType::Code => Color::Cyan,
+        EntryType::Comment => Color::White,
+    }
+}
+
+/// Color of `entry_type`: the one set in `[colors]` (under any name the
+/// type parses from, e.g. `func` or `function`), or the default when it is
+/// unset or not a color [`parse_color`] understands
+pub fn entry_type_color(colors: &ColorsConfig, entry_type: EntryType) -> Color {
+    colors
+        .types
+        .iter()
+        .find(|(key, _)| key.parse::<EntryType>().ok() == Some(entry_type))
+        .and_then(|(_, name)| parse_color(name))
+        .unwrap_or_else(|| default_color(entry_type))
+}
+
+/// A color name as `colored` spells it ("red", "bright blue", ...; `_`
+/// and `-` work as the space too) or a `#rrggbb` hex color
+pub fn parse_color(name: &str) -> Option<Color> {
+    let name = name.trim();
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::TrueColor {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+    name.replace(['_', '-'], " ").parse().ok()
+}
+
+/// The name [`parse_color`] reads back as `color`
+pub fn color_name(color: Color) -> String {
+    match color {
+        Color::Black => "black".into(),
+        Color::Red => "red".into(),
+        Color::Green => "green".into(),
+        Color::Yellow => "yellow".into(),
+        Color::Blue => "blue".into(),
+        Color::Magenta => "magenta".into(),
+        Color::Cyan => "cyan".into(),
+        Color::White => "white".into(),
+        Color::BrightBlack => "bright black".into(),
+        Color::BrightRed => "bright red".into(),
+        Color::BrightGreen => "bright green".into(),
+        Color::BrightYellow => "bright yellow".into(),
+        Color::BrightBlue => "bright blue".into(),
+        Color::BrightMagenta => "bright magenta".into(),
+        Color::BrightCyan => "bright cyan".into(),
+        Color::BrightWhite => "bright white".into(),
+        Color::TrueColor { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+/// The TUI's equivalent of `color`
+pub fn to_tui_color(color: Color) -> ratatui::style::Color {
+    use ratatui::style::Color as Tui;
+    match color {
+        Color::Black => Tui::Black,
+        Color::Red => Tui::Red,
+        Color::Green => Tui::Green,
+        Color::Yellow => Tui::Yellow,
+        Color::Blue => Tui::Blue,
+        Color::Magenta => Tui::Magenta,
+        Color::Cyan => Tui::Cyan,
+        Color::White => Tui::White,
+        Color::BrightBlack => Tui::DarkGray,
+        Color::BrightRed => Tui::LightRed,
+        Color::BrightGreen => Tui::LightGreen,
+        Color::BrightYellow => Tui::LightYellow,
+        Color::BrightBlue => Tui::LightBlue,
+        Color::BrightMagenta => Tui::LightMagenta,
+        Color::BrightCyan => Tui::LightCyan,
+        Color::BrightWhite => Tui::White,
+        Color::TrueColor { r, g, b } => Tui::Rgb(r, g, b),
+    }
+}
+
+/// `[colors]` settings that have no effect: keys that aren't an entry
+/// type, and colors [`parse_color`] doesn't understand
+pub fn invalid_settings(colors: &ColorsConfig) -> Vec<String> {
+    colors
+        .types
+        .iter()
+        .filter_map(|(key, name)| {
+            if key.parse::<EntryType>().is_err() {
+                Some(format!("unknown entry type '{}'", key))
+            } else if parse_color(name).is_none() {
+                Some(format!("{} = '{}' is not a color", key, name))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_color_overrides_default() {
+        let colors: ColorsConfig = toml::from_str("alias = \"bright blue\"\n").unwrap();
+
+        assert_eq!(
+            entry_type_color(&colors, EntryType::Alias),
+            Color::BrightBlue
+        );
+        assert_eq!(
+            entry_type_color(&colors, EntryType::EnvVar),
+            default_color(EntryType::EnvVar)
+        );
+    }
+
+    #[test]
+    fn test_unknown_color_falls_back_to_default() {
+        let colors: ColorsConfig = toml::from_str("func = \"plaid\"\n").unwrap();
+        assert_eq!(
+            entry_type_color(&colors, EntryType::Function),
+            default_color(EntryType::Function)
+        );
+        assert_eq!(
+            invalid_settings(&colors),
+            vec!["func = 'plaid' is not a color"]
+        );
+    }
+
+    #[test]
+    fn test_parse_color_names_and_hex() {
+        assert_eq!(parse_color("Bright_Red"), Some(Color::BrightRed));
+        assert_eq!(parse_color("bright-cyan"), Some(Color::BrightCyan));
+        assert_eq!(
+            parse_color("#0072b2"),
+            Some(Color::TrueColor {
+                r: 0x00,
+                g: 0x72,
+                b: 0xb2
+            })
+        );
+        assert_eq!(parse_color("#07b2"), None);
+        assert_eq!(parse_color("#ééé"), None);
+
+        for entry_type in EntryType::ALL {
+            let color = default_color(entry_type);
+            assert_eq!(parse_color(&color_name(color)), Some(color));
+        }
+    }
+
+    #[test]
+    fn test_invalid_settings_reports_unknown_types() {
+        let colors: ColorsConfig = toml::from_str("aliases = \"red\"\nenv = \"red\"\n").unwrap();
+        assert_eq!(
+            invalid_settings(&colors),
+            vec!["unknown entry type 'aliases'"]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Entry-level diff between two versions of a configuration file
+//!
+//! Structured entries are matched by type and name and compared by
+//! [`Entry::canonical_value`], so quoting or comment changes alone don't
+//! count as modifications. Code and Comment entries have line-based names,
+//! so they are matched by content instead and only ever show up as added
+//! or removed.
+
+use colored::Colorize;
+use std::collections::HashMap;
+
+use crate::model::{Entry, EntryType};
+
+/// One difference between an old and a new list of entries
+#[derive(Debug)]
+pub enum EntryChange<'a> {
+    Added(&'a Entry),
+    Removed(&'a Entry),
+    Modified { old: &'a Entry, new: &'a Entry },
+}
+
+impl EntryChange<'_> {
+    /// One-line summary, e.g. `~ alias    ll  ls -l → ls -la`
+    pub fn summary(&self) -> String {
+        match self {
+            EntryChange::Added(entry) => format!(
+                "{} {:<8} {}  {}",
+                "+".green(),
+                entry.entry_type.to_string(),
+                label(entry),
+                first_line(&entry.canonical_value())
+            ),
+            EntryChange::Removed(entry) => format!(
+                "{} {:<8} {}  {}",
+                "-".red(),
+                entry.entry_type.to_string(),
+                label(entry),
+                first_line(&entry.canonical_value()).dimmed()
+            ),
+            EntryChange::Modified { old, new } => format!(
+                "{} {:<8} {}  {} → {}",
+                "~".yellow(),
+                new.entry_type.to_string(),
+                new.name,
+                first_line(&old.canonical_value()).dimmed(),
+                first_line(&new.canonical_value())
+            ),
+        }
+    }
+}
+
+/// Counts of each kind of change
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+}
+
+impl DiffStats {
+    pub fn of(changes: &[EntryChange]) -> Self {
+        let mut stats = Self::default();
+        for change in changes {
+            match change {
+                EntryChange::Added(_) => stats.added += 1,
+                EntryChange::Removed(_) => stats.removed += 1,
+                EntryChange::Modified { .. } => stats.modified += 1,
+            }
+        }
+        stats
+    }
+}
+
+/// Diff two entry lists. Removals come first in old order, followed by
+/// additions and modifications in new order.
+pub fn diff_entries<'a>(old: &'a [Entry], new: &'a [Entry]) -> Vec<EntryChange<'a>> {
+    // Repeated keys pair up by occurrence
+    let mut old_by_key: HashMap<(EntryType, String), Vec<&Entry>> = HashMap::new();
+    for entry in old.iter().filter(|e| !e.is_blank()) {
+        old_by_key.entry(key(entry)).or_default().push(entry);
+    }
+    for matches in old_by_key.values_mut() {
+        matches.reverse();
+    }
+
+    let mut changes = Vec::new();
+    let mut matched_new: Vec<(Option<&Entry>, &Entry)> = Vec::new();
+    for entry in new.iter().filter(|e| !e.is_blank()) {
+        let old_entry = old_by_key.get_mut(&key(entry)).and_then(|m| m.pop());
+        matched_new.push((old_entry, entry));
+    }
+
+    let leftover: Vec<&Entry> = old_by_key.into_values().flatten().collect();
+    for entry in old.iter().filter(|e| !e.is_blank()) {
+        if leftover.iter().any(|l| std::ptr::eq(*l, entry)) {
+            changes.push(EntryChange::Removed(entry));
+        }
+    }
+
+    for (old_entry, new_entry) in matched_new {
+        match old_entry {
+            None => changes.push(EntryChange::Added(new_entry)),
+            Some(old_entry) if old_entry.canonical_value() != new_entry.canonical_value() => {
+                changes.push(EntryChange::Modified {
+                    old: old_entry,
+                    new: new_entry,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes
+}
+
+fn key(entry: &Entry) -> (EntryType, String) {
+    match entry.entry_type {
+        EntryType::Code | EntryType::Comment => (entry.entry_type, entry.value.trim().to_string()),
+        _ => (entry.entry_type, entry.name.clone()),
+    }
+}
+
+fn label(entry: &Entry) -> &str {
+    match entry.entry_type {
+        // Line-based names are meaningless across versions
+        EntryType::Code | EntryType::Comment => "",
+        _ => &entry.name,
+    }
+}
+
+fn first_line(value: &str) -> String {
+    let mut lines = value.lines();
+    let first = lines.next().unwrap_or("").trim().to_string();
+    if lines.next().is_some() {
+        format!("{} …", first)
+    } else {
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    fn parse(content: &str) -> Vec<Entry> {
+        crate::parser::get_parser(ShellType::Bash)
+            .parse(content)
+            .entries
+    }
+
+    fn describe(changes: &[EntryChange]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|c| match c {
+                EntryChange::Added(e) => format!("+{}", e.name),
+                EntryChange::Removed(e) => format!("-{}", e.name),
+                EntryChange::Modified { new, .. } => format!("~{}", new.name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let old = parse("alias ll='ls -l'\nalias gs='git status'\nexport EDITOR=vim\n");
+        let new = parse("alias ll=\"ls -la\"\nexport EDITOR='vim'\nexport PAGER=less\n");
+
+        let changes = diff_entries(&old, &new);
+        assert_eq!(describe(&changes), ["-gs", "~ll", "+PAGER"]);
+        assert_eq!(
+            DiffStats::of(&changes),
+            DiffStats {
+                added: 1,
+                removed: 1,
+                modified: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_moves_and_quoting_are_not_changes() {
+        let old = parse("# tools\nalias a='1'\n\nalias b=\"2\"\necho hi\n");
+        let new = parse("alias b='2'\n# tools\nalias a='1'\necho hi\n");
+        assert!(diff_entries(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_code_matched_by_content() {
+        let old = parse("echo one\n");
+        let new = parse("echo two\n");
+        let changes = diff_entries(&old, &new);
+        assert!(matches!(changes[0], EntryChange::Removed(e) if e.value == "echo one"));
+        assert!(matches!(changes[1], EntryChange::Added(e) if e.value == "echo two"));
+    }
+}
@@ -0,0 +1,229 @@
+//! External editor resolution
+//!
+//! The editor is taken from the first of: the `--editor` flag, `$VISUAL`,
+//! `$EDITOR`, and a per-OS default (`nano`, falling back to `vi`, or
+//! `notepad` on Windows). Values may carry arguments, e.g. `code --wait`.
+//!
+//! Known editors are opened at a given line; see [`LineStyle`].
+
+use anyhow::{Context as _, Result};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// Editors tried when neither the flag nor the environment names one
+#[cfg(windows)]
+const DEFAULT_EDITORS: &[&str] = &["notepad"];
+#[cfg(not(windows))]
+const DEFAULT_EDITORS: &[&str] = &["nano", "vi"];
+
+/// How an editor is told which line to open a file at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineStyle {
+    /// `+N file` (vi, vim, nano, emacs, ...)
+    Plus,
+    /// `--goto file:N` (VS Code and its forks)
+    Goto,
+    /// `file:N` (Sublime Text, Helix, Zed)
+    Suffix,
+    /// Unknown editor: open the file without positioning
+    Unsupported,
+}
+
+impl LineStyle {
+    /// Detect the style from the editor program, ignoring its directory and
+    /// a Windows `.exe` extension
+    pub fn of(program: &str) -> Self {
+        let name = Path::new(program)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match name.as_str() {
+            "vi" | "vim" | "nvim" | "gvim" | "view" | "nano" | "pico" | "emacs" | "emacsclient"
+            | "micro" | "kak" | "joe" | "ne" | "mg" => LineStyle::Plus,
+            "code" | "code-insiders" | "codium" | "vscodium" | "cursor" => LineStyle::Goto,
+            "subl" | "sublime_text" | "hx" | "helix" | "zed" => LineStyle::Suffix,
+            _ => LineStyle::Unsupported,
+        }
+    }
+}
+
+/// An editor command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Editor {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Editor {
+    /// Split a command such as `code --wait` into program and arguments.
+    /// Returns `None` for blank commands.
+    pub fn parse(command: &str) -> Option<Self> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words.next()?;
+        Some(Self {
+            program,
+            args: words.collect(),
+        })
+    }
+
+    /// Arguments that open `path`, at `line` (1-based) when the editor
+    /// supports it
+    pub fn file_args(&self, path: &Path, line: Option<usize>) -> Vec<OsString> {
+        let mut args: Vec<OsString> = self.args.iter().map(OsString::from).collect();
+        let with_line = |line: usize| {
+            let mut arg = path.as_os_str().to_owned();
+            arg.push(format!(":{}", line));
+            arg
+        };
+        match (line, LineStyle::of(&self.program)) {
+            (Some(line), LineStyle::Plus) => {
+                args.push(format!("+{}", line).into());
+                args.push(path.into());
+            }
+            (Some(line), LineStyle::Goto) => {
+                args.push("--goto".into());
+                args.push(with_line(line));
+            }
+            (Some(line), LineStyle::Suffix) => args.push(with_line(line)),
+            _ => args.push(path.into()),
+        }
+        args
+    }
+
+    /// Open `path` and wait for the editor to exit
+    pub fn open(&self, path: &Path) -> Result<ExitStatus> {
+        self.open_at(path, None)
+    }
+
+    /// Open `path` at `line` (when supported) and wait for the editor to exit
+    pub fn open_at(&self, path: &Path, line: Option<usize>) -> Result<ExitStatus> {
+        Command::new(&self.program)
+            .args(self.file_args(path, line))
+            .status()
+            .with_context(|| {
+                format!(
+                    "Failed to launch editor '{}'. Set $VISUAL or $EDITOR, or pass --editor <CMD>",
+                    self.program
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for Editor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve the editor to use, with `flag` being the `--editor` value
+pub fn resolve_editor(flag: Option<&str>) -> Result<Editor> {
+    let visual = std::env::var("VISUAL").ok();
+    let editor = std::env::var("EDITOR").ok();
+    let default = DEFAULT_EDITORS
+        .iter()
+        .copied()
+        .find(|name| is_on_path(name));
+
+    choose_editor(flag, visual.as_deref(), editor.as_deref(), default)
+        .and_then(Editor::parse)
+        .context("No editor found. Set $VISUAL or $EDITOR, or pass --editor <CMD>")
+}
+
+/// First non-blank of the flag, `$VISUAL`, `$EDITOR` and the default
+fn choose_editor<'a>(
+    flag: Option<&'a str>,
+    visual: Option<&'a str>,
+    editor: Option<&'a str>,
+    default: Option<&'a str>,
+) -> Option<&'a str> {
+    [flag, visual, editor, default]
+        .into_iter()
+        .flatten()
+        .find(|command| !command.trim().is_empty())
+}
+
+/// Whether an executable named `name` is found in `$PATH`
+fn is_on_path(name: &str) -> bool {
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(name);
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_precedence() {
+        let choose = choose_editor;
+
+        assert_eq!(
+            choose(Some("hx"), Some("code"), Some("vim"), Some("nano")),
+            Some("hx")
+        );
+        assert_eq!(
+            choose(None, Some("code"), Some("vim"), Some("nano")),
+            Some("code")
+        );
+        assert_eq!(choose(None, None, Some("vim"), Some("nano")), Some("vim"));
+        assert_eq!(choose(None, None, None, Some("nano")), Some("nano"));
+        assert_eq!(choose(None, None, None, None), None);
+
+        // Blank values are skipped
+        assert_eq!(choose(Some(" "), Some(""), Some("vim"), None), Some("vim"));
+    }
+
+    #[test]
+    fn test_editor_with_arguments() {
+        let editor = Editor::parse("code --wait").unwrap();
+        assert_eq!(editor.program, "code");
+        assert_eq!(editor.args, ["--wait"]);
+        assert_eq!(editor.to_string(), "code --wait");
+        assert!(Editor::parse("  ").is_none());
+    }
+
+    #[test]
+    fn test_line_arguments() {
+        let path = Path::new("/home/me/.bashrc");
+        let args = |command: &str, line| -> Vec<String> {
+            Editor::parse(command)
+                .unwrap()
+                .file_args(path, line)
+                .iter()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(args("vim", Some(12)), ["+12", "/home/me/.bashrc"]);
+        assert_eq!(args("/usr/bin/nano", Some(3)), ["+3", "/home/me/.bashrc"]);
+        assert_eq!(
+            args("emacsclient -t", Some(7)),
+            ["-t", "+7", "/home/me/.bashrc"]
+        );
+        assert_eq!(
+            args("code --wait", Some(12)),
+            ["--wait", "--goto", "/home/me/.bashrc:12"]
+        );
+        assert_eq!(args("hx", Some(5)), ["/home/me/.bashrc:5"]);
+
+        // Unknown editors and missing lines open the plain file
+        assert_eq!(args("ed", Some(12)), ["/home/me/.bashrc"]);
+        assert_eq!(args("vim", None), ["/home/me/.bashrc"]);
+    }
+
+    #[test]
+    fn test_line_style_ignores_directory_and_extension() {
+        assert_eq!(LineStyle::of("NVIM"), LineStyle::Plus);
+        assert_eq!(LineStyle::of("/opt/vscode/Code.exe"), LineStyle::Goto);
+        assert_eq!(LineStyle::of("notepad"), LineStyle::Unsupported);
+    }
+}
@@ -0,0 +1,162 @@
+//! Best-effort alias and variable expansion
+//!
+//! Substitutes references to aliases and environment variables defined in the
+//! same file, so alias chains like `alias gl='g log'` → `alias g='git'` can be
+//! inspected as `git log`. Names already being expanded are left as-is, which
+//! guards against cycles (and keeps self-references like `$PATH` intact).
+
+use crate::model::{Entry, EntryType};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+
+lazy_static! {
+    /// `$VAR`, `${VAR}` and PowerShell `$env:VAR` references
+    static ref VAR_REF_RE: Regex =
+        Regex::new(r"\$\{([A-Za-z_]\w*)\}|\$env:([A-Za-z_]\w*)|\$([A-Za-z_]\w*)").unwrap();
+
+    /// A word in command position: start of text or after `|`, `;`, `&`
+    static ref COMMAND_WORD_RE: Regex = Regex::new(r"(^|[|;&]\s*)([^\s|;&]+)").unwrap();
+}
+
+/// Known alias and environment variable definitions (last definition wins)
+struct Definitions {
+    aliases: HashMap<String, String>,
+    vars: HashMap<String, String>,
+}
+
+impl Definitions {
+    fn from_entries(entries: &[Entry]) -> Self {
+        let mut aliases = HashMap::new();
+        let mut vars = HashMap::new();
+        for entry in entries {
+            match entry.entry_type {
+                EntryType::Alias => {
+                    aliases.insert(entry.name.clone(), entry.canonical_value());
+                }
+                EntryType::EnvVar => {
+                    vars.insert(entry.name.clone(), entry.canonical_value());
+                }
+                _ => {}
+            }
+        }
+        Self { aliases, vars }
+    }
+
+    fn expand(
+        &self,
+        text: &str,
+        seen_aliases: &HashSet<&str>,
+        seen_vars: &HashSet<&str>,
+    ) -> String {
+        // Variables first, so alias names introduced by variables are not expanded
+        let text = VAR_REF_RE.replace_all(text, |caps: &Captures| {
+            let name = caps
+                .get(1)
+                .or(caps.get(2))
+                .or(caps.get(3))
+                .map_or("", |m| m.as_str());
+            match self.vars.get_key_value(name) {
+                Some((key, value)) if !seen_vars.contains(name) => {
+                    let mut seen = seen_vars.clone();
+                    seen.insert(key.as_str());
+                    self.expand(value, seen_aliases, &seen)
+                }
+                _ => caps[0].to_string(),
+            }
+        });
+
+        COMMAND_WORD_RE
+            .replace_all(&text, |caps: &Captures| {
+                let word = &caps[2];
+                match self.aliases.get_key_value(word) {
+                    Some((key, value)) if !seen_aliases.contains(word) => {
+                        let mut seen = seen_aliases.clone();
+                        seen.insert(key.as_str());
+                        format!("{}{}", &caps[1], self.expand(value, &seen, seen_vars))
+                    }
+                    _ => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Expand an entry's canonical value using aliases and variables from `entries`.
+///
+/// Only Alias and EnvVar entries are expanded; other types return their
+/// canonical value unchanged.
+pub fn expand_entry(entry: &Entry, entries: &[Entry]) -> String {
+    let value = entry.canonical_value();
+    let defs = Definitions::from_entries(entries);
+
+    let mut seen_aliases = HashSet::new();
+    let mut seen_vars = HashSet::new();
+    match entry.entry_type {
+        EntryType::Alias => {
+            seen_aliases.insert(entry.name.as_str());
+        }
+        EntryType::EnvVar => {
+            seen_vars.insert(entry.name.as_str());
+        }
+        _ => return value,
+    }
+
+    defs.expand(&value, &seen_aliases, &seen_vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn parse(content: &str) -> Vec<Entry> {
+        get_parser(ShellType::Bash).parse(content).entries
+    }
+
+    fn find<'a>(entries: &'a [Entry], name: &str) -> &'a Entry {
+        entries.iter().find(|e| e.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_two_level_alias_chain() {
+        let entries = parse("alias g='git'\nalias gl='g log'\nalias gll='gl --oneline | less'\n");
+
+        assert_eq!(expand_entry(find(&entries, "gl"), &entries), "git log");
+        assert_eq!(
+            expand_entry(find(&entries, "gll"), &entries),
+            "git log --oneline | less"
+        );
+    }
+
+    #[test]
+    fn test_variables_expanded() {
+        let entries =
+            parse("export BASE=/opt\nexport TOOLS=\"$BASE/tools\"\nalias t='cd ${TOOLS} && ls'\n");
+
+        assert_eq!(
+            expand_entry(find(&entries, "t"), &entries),
+            "cd /opt/tools && ls"
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_guarded() {
+        let entries = parse("alias a='b -x'\nalias b='a -y'\n");
+
+        // a -> b -x -> a -y -x: the second `a` is left unexpanded
+        assert_eq!(expand_entry(find(&entries, "a"), &entries), "a -y -x");
+    }
+
+    #[test]
+    fn test_self_reference_left_intact() {
+        let entries = parse("export PATH=\"$HOME/bin:$PATH\"\nalias ls='ls --color'\n");
+
+        assert_eq!(
+            expand_entry(find(&entries, "PATH"), &entries),
+            "$HOME/bin:$PATH"
+        );
+        assert_eq!(expand_entry(find(&entries, "ls"), &entries), "ls --color");
+    }
+}
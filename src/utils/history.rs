@@ -0,0 +1,92 @@
+//! Change history
+//!
+//! Every command that writes a configuration file appends one line to
+//! `history.log` in the wenv config directory (see [`Config::history_path`]):
+//!
+//! ```text
+//! 2026-03-05T14:07:09Z  remove func 'greet'  /home/me/.bashrc  backup 2026-03-05_140709_.bashrc
+//! ```
+//!
+//! Unlike backups, the log only records what was done; it can't be used to
+//! undo anything. The backup id refers to the backup taken before the change.
+//!
+//! [`Config::history_path`]: crate::model::Config::history_path
+
+use anyhow::Result;
+use std::io::Write;
+use std::path::Path;
+
+/// One line of the history log
+pub fn format_record(timestamp: &str, action: &str, file: &Path, backup: Option<&Path>) -> String {
+    let backup = match backup.and_then(|path| path.file_stem()) {
+        Some(id) => format!("backup {}", id.to_string_lossy()),
+        None => "no backup".to_string(),
+    };
+    format!("{}  {}  {}  {}", timestamp, action, file.display(), backup)
+}
+
+/// Append `record` as a line to the log at `path`, creating it if needed
+pub fn append(path: &Path, record: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", record)?;
+    Ok(())
+}
+
+/// Contents of the log at `path`; empty when nothing was recorded yet
+pub fn read(path: &Path) -> Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Delete the log at `path`. Returns whether there was one.
+pub fn clear(path: &Path) -> Result<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_record() {
+        let file = Path::new("/home/me/.bashrc");
+        let backup = Path::new("/backups/bash/2026-03-05_140709_.bashrc.bak");
+
+        assert_eq!(
+            format_record("2026-03-05T14:07:09Z", "remove func 'greet'", file, Some(backup)),
+            "2026-03-05T14:07:09Z  remove func 'greet'  /home/me/.bashrc  backup 2026-03-05_140709_.bashrc"
+        );
+        assert_eq!(
+            format_record("2026-03-05T14:07:09Z", "remove func 'greet'", file, None),
+            "2026-03-05T14:07:09Z  remove func 'greet'  /home/me/.bashrc  no backup"
+        );
+    }
+
+    #[test]
+    fn test_append_read_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wenv").join("history.log");
+
+        assert_eq!(read(&path).unwrap(), "");
+        append(&path, "first").unwrap();
+        append(&path, "second").unwrap();
+        assert_eq!(read(&path).unwrap(), "first\nsecond\n");
+
+        assert!(clear(&path).unwrap());
+        assert!(!clear(&path).unwrap());
+        assert_eq!(read(&path).unwrap(), "");
+    }
+}
@@ -1,8 +1,22 @@
 //! Utility modules
 
+pub mod bundle;
+pub mod clipboard;
+pub mod colors;
 pub mod dependency;
+pub mod diff;
+pub mod editor;
+pub mod expand;
+pub mod history;
 pub mod http;
 pub mod path;
 pub mod path_merge;
+pub mod quoting;
+pub mod reload;
+pub mod reorder;
 pub mod shell_detect;
+pub mod source_tree;
 pub mod strings;
+pub mod template;
+pub mod translate;
+pub mod validate;
@@ -1,18 +1,62 @@
 //! Path utilities
 
 use anyhow::Result;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Expand tilde (~) in path to home directory
 pub fn expand_tilde(path: &str) -> PathBuf {
     if let Some(stripped) = path.strip_prefix('~') {
         if let Some(home) = dirs::home_dir() {
-            return home.join(stripped.trim_start_matches('/'));
+            return home.join(stripped.trim_start_matches(['/', '\\']));
         }
     }
     PathBuf::from(path)
 }
 
+/// Show `path` with the user's home directory collapsed to `~`, the
+/// reverse of [`expand_tilde`]
+pub fn collapse_home(path: &Path) -> String {
+    let shown = path.display().to_string();
+    match dirs::home_dir() {
+        Some(home) => collapse_home_in(&shown, &home.display().to_string()),
+        None => shown,
+    }
+}
+
+/// `path` with a leading `home` replaced by `~`. Windows-style homes
+/// (`C:\Users\me`) match case-insensitively, like the file system.
+pub fn collapse_home_in(path: &str, home: &str) -> String {
+    let home = home.trim_end_matches(['/', '\\']);
+    let windows = home.contains('\\');
+    let prefix_matches = !home.is_empty()
+        && path.get(..home.len()).is_some_and(|prefix| {
+            if windows {
+                prefix.eq_ignore_ascii_case(home)
+            } else {
+                prefix == home
+            }
+        });
+    if !prefix_matches {
+        return path.to_string();
+    }
+    let rest = &path[home.len()..];
+    if rest.is_empty() || rest.starts_with(['/', '\\']) {
+        format!("~{}", rest)
+    } else {
+        path.to_string()
+    }
+}
+
+/// Short label for `path` in output about several files: its file name,
+/// or the whole path when it has none
+pub fn file_label(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
 /// Normalize a path (expand tilde, resolve relative paths)
 pub fn normalize_path(path: &str) -> PathBuf {
     let expanded = expand_tilde(path);
@@ -37,10 +81,29 @@ pub fn check_file_readable(path: &Path) -> Result<()> {
 }
 
 /// Read file content with proper error handling
+///
+/// Files that are not UTF-8 (e.g. a Latin-1 `.profile`) fail with an error
+/// naming the file and where the first invalid byte is.
 pub fn read_file(path: &Path) -> Result<String> {
     check_file_readable(path)?;
-    let content = std::fs::read_to_string(path)?;
-    Ok(content)
+    let bytes = std::fs::read(path)?;
+    String::from_utf8(bytes).map_err(|e| {
+        let bytes = e.as_bytes();
+        let offset = e.utf8_error().valid_up_to();
+        anyhow::anyhow!(
+            "{} is not valid UTF-8: byte 0x{:02X} at offset {} (line {}). \
+             Convert it first, e.g. `iconv -f latin1 -t utf-8`",
+            path.display(),
+            bytes[offset],
+            offset,
+            line_of_offset(bytes, offset)
+        )
+    })
+}
+
+/// The 1-based line `offset` lies on in `bytes`
+fn line_of_offset(bytes: &[u8], offset: usize) -> usize {
+    bytes[..offset].iter().filter(|&&b| b == b'\n').count() + 1
 }
 
 /// Write file content with proper error handling
@@ -52,6 +115,79 @@ pub fn write_file(path: &Path, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Overwrite a configuration file atomically, keeping its original permissions.
+///
+/// This is the save path for every rewrite of the user's config (TUI save,
+/// format, import). Content goes to a temp file in the same directory which
+/// is then renamed over the original, so an interrupted write never leaves a
+/// truncated config behind, and e.g. an executable `.bash_profile` stays
+/// executable. Symlinks are followed so dotfile-manager links survive.
+pub fn save_file(path: &Path, content: &str) -> Result<()> {
+    save_file_with(path, |w| w.write_all(content.as_bytes()))
+}
+
+/// Save rewritten config `content` with [`save_file`], ending it in exactly
+/// one newline (see [`with_single_trailing_newline`]) unless
+/// `preserve_trailing_blanks` (`format.preserve_trailing_blanks`).
+///
+/// [`with_single_trailing_newline`]: crate::utils::strings::with_single_trailing_newline
+pub fn save_config_content(
+    path: &Path,
+    content: &str,
+    preserve_trailing_blanks: bool,
+) -> Result<()> {
+    if preserve_trailing_blanks {
+        save_file(path, content)
+    } else {
+        save_file(
+            path,
+            &crate::utils::strings::with_single_trailing_newline(content),
+        )
+    }
+}
+
+/// Like [`save_file`], but the content is streamed into the temp file by
+/// `write` (e.g. [`Formatter::format_to_writer`](crate::formatter::Formatter::format_to_writer))
+pub fn save_file_with(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> Result<()> {
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let permissions = std::fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    std::fs::create_dir_all(&dir)?;
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = dir.join(format!(".{}.wenv-{}.tmp", file_name, std::process::id()));
+
+    let result = write_synced(&temp_path, write)
+        .and_then(|()| match permissions {
+            Some(permissions) => std::fs::set_permissions(&temp_path, permissions),
+            None => Ok(()),
+        })
+        .and_then(|()| std::fs::rename(&temp_path, &target));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    Ok(result?)
+}
+
+/// Write a new file with `write` and flush it to disk
+fn write_synced(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write(&mut file)?;
+    file.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,9 +198,175 @@ mod tests {
         assert!(!path.to_string_lossy().starts_with('~'));
     }
 
+    #[test]
+    fn test_collapse_home() {
+        // Unix
+        assert_eq!(
+            collapse_home_in("/home/me/.bashrc", "/home/me"),
+            "~/.bashrc"
+        );
+        assert_eq!(collapse_home_in("/home/me", "/home/me/"), "~");
+        assert_eq!(
+            collapse_home_in("/home/meg/.bashrc", "/home/me"),
+            "/home/meg/.bashrc"
+        );
+        assert_eq!(collapse_home_in("/etc/profile", "/home/me"), "/etc/profile");
+        assert_eq!(collapse_home_in("/Home/Me/x", "/home/me"), "/Home/Me/x");
+
+        // Windows, case-insensitive
+        assert_eq!(
+            collapse_home_in(
+                r"C:\Users\Me\Documents\PowerShell\profile.ps1",
+                r"C:\Users\me"
+            ),
+            r"~\Documents\PowerShell\profile.ps1"
+        );
+        assert_eq!(
+            collapse_home_in(r"D:\Users\me\x", r"C:\Users\me"),
+            r"D:\Users\me\x"
+        );
+
+        assert_eq!(collapse_home_in("/home/me/x", ""), "/home/me/x");
+    }
+
+    #[test]
+    fn test_collapse_and_expand_round_trip() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let path = home.join(".bashrc");
+        let collapsed = collapse_home(&path);
+        assert!(collapsed.starts_with('~'));
+        assert_eq!(expand_tilde(&collapsed), path);
+    }
+
     #[test]
     fn test_normalize_absolute_path() {
         let path = normalize_path("/etc/passwd");
         assert_eq!(path, PathBuf::from("/etc/passwd"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_file_preserves_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".bash_profile");
+        std::fs::write(&path, "echo old\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750)).unwrap();
+
+        save_file(&path, "echo new\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "echo new\n");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    fn test_save_file_with_streams_content() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".bashrc");
+        std::fs::write(&path, "old\n").unwrap();
+
+        save_file_with(&path, |w| {
+            for i in 0..3 {
+                writeln!(w, "export N{}={}", i, i)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "export N0=0\nexport N1=1\nexport N2=2\n"
+        );
+
+        // A failed write leaves the original untouched
+        let failed = save_file_with(&path, |w| {
+            w.write_all(b"partial")?;
+            Err(std::io::Error::other("boom"))
+        });
+        assert!(failed.is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "export N0=0\nexport N1=1\nexport N2=2\n"
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_save_file_replaces_content_without_leftovers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".bashrc");
+        std::fs::write(&path, "alias a='old'\nalias b='old'\n").unwrap();
+
+        save_file(&path, "alias a='new'\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "alias a='new'\n");
+        // Only the config itself remains; the temp file was renamed away
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![std::ffi::OsString::from(".bashrc")]);
+    }
+
+    #[test]
+    fn test_save_config_content_trailing_newline() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".bashrc");
+        let read = || std::fs::read_to_string(&path).unwrap();
+
+        save_config_content(&path, "alias a=1\n\n\n", false).unwrap();
+        assert_eq!(read(), "alias a=1\n");
+        save_config_content(&path, "alias a=1", false).unwrap();
+        assert_eq!(read(), "alias a=1\n");
+
+        save_config_content(&path, "alias a=1\n\n\n", true).unwrap();
+        assert_eq!(read(), "alias a=1\n\n\n");
+    }
+
+    #[test]
+    fn test_save_file_creates_missing_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested").join(".zshrc");
+
+        save_file(&path, "export A=1\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "export A=1\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_file_follows_symlink() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let real = dir.path().join("dotfiles_bashrc");
+        let link = dir.path().join(".bashrc");
+        std::fs::write(&real, "old\n").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        save_file(&link, "new\n").unwrap();
+
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "new\n");
+    }
+
+    #[test]
+    fn test_read_file_names_invalid_utf8_byte() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".profile");
+        // "café" in Latin-1 on the second line
+        std::fs::write(&path, b"export A=1\nexport B=caf\xe9\n").unwrap();
+
+        let error = read_file(&path).unwrap_err().to_string();
+        assert!(error.contains(".profile is not valid UTF-8"), "{}", error);
+        assert!(
+            error.contains("byte 0xE9 at offset 23 (line 2)"),
+            "{}",
+            error
+        );
+    }
 }
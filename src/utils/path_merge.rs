@@ -1,8 +1,30 @@
 //! PATH environment variable merging utilities
 
-use crate::model::Entry;
+use crate::model::{Entry, ShellType};
+use crate::utils::path::expand_tilde;
 use std::collections::HashSet;
 
+/// Get the PATH list separator used by a shell
+///
+/// Bash/Zsh use `:`, PowerShell uses `;` (Windows convention).
+pub fn path_separator(shell_type: ShellType) -> char {
+    match shell_type {
+        ShellType::Bash | ShellType::Zsh | ShellType::PosixSh | ShellType::DotEnv => ':',
+        ShellType::PowerShell => ';',
+    }
+}
+
+/// Split a PATH-like value into trimmed, non-empty segments
+///
+/// Variable references such as `$PATH` or `$env:PATH` are kept as-is.
+pub fn split_path_segments(value: &str, separator: char) -> Vec<&str> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 /// Result of merging multiple PATH definitions
 #[derive(Debug, Clone)]
 pub struct PathMergeResult {
@@ -59,12 +81,7 @@ pub fn merge_path_definitions(entries: &[&Entry]) -> Option<PathMergeResult> {
         }
 
         // Split value by ':'
-        for segment in entry.value.split(':') {
-            let trimmed = segment.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-
+        for trimmed in split_path_segments(&entry.value, ':') {
             // Check if this is a $PATH self-reference
             if trimmed == "$PATH" || trimmed == "${PATH}" {
                 has_path_ref = true;
@@ -91,11 +108,131 @@ pub fn merge_path_definitions(entries: &[&Entry]) -> Option<PathMergeResult> {
     })
 }
 
+/// Whether `segment` refers to a variable (`$HOME/bin`, `%APPDATA%`), so
+/// its directory cannot be known without running the shell
+pub fn has_variable_ref(segment: &str) -> bool {
+    segment.contains('$') || segment.contains('%') || segment.contains('`')
+}
+
+/// Whether `segment` is the previous value of PATH itself (`$PATH`,
+/// `${PATH}`, `$env:PATH`)
+fn is_path_self_reference(segment: &str) -> bool {
+    let name = segment.trim_start_matches('$');
+    let name = name
+        .strip_prefix('{')
+        .and_then(|n| n.strip_suffix('}'))
+        .unwrap_or(name);
+    let name = name
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("env:"))
+        .map_or(name, |_| &name[4..]);
+    segment.starts_with('$') && name.eq_ignore_ascii_case("PATH")
+}
+
+/// Result of [`normalize_path_values`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathNormalization {
+    /// Segments of the single definition, `$PATH` included where it belongs
+    pub segments: Vec<String>,
+    /// Segments dropped because they were already on the PATH
+    pub duplicates: Vec<String>,
+    /// Segments dropped because their directory does not exist
+    pub missing: Vec<String>,
+}
+
+/// Collapse the values of successive PATH definitions into the segments of
+/// one definition with the same effect.
+///
+/// Each definition is applied in turn, a self-reference standing for the
+/// PATH built so far, so `A:$PATH` followed by `B:$PATH` gives `B:A:$PATH`
+/// and a definition without a self-reference discards everything before
+/// it. Repeated segments are dropped after their first (winning) occurrence.
+/// Unless `keep_missing` is set, segments naming a directory that does not
+/// exist are dropped too; segments with variable references are always
+/// kept. With `sort`, the segments are sorted, `$PATH` last.
+pub fn normalize_path_values(
+    values: &[String],
+    separator: char,
+    keep_missing: bool,
+    sort: bool,
+) -> PathNormalization {
+    // None stands for the inherited PATH
+    let mut effective: Vec<Option<&str>> = vec![None];
+    for value in values {
+        effective = split_path_segments(value, separator)
+            .into_iter()
+            .flat_map(|segment| {
+                if is_path_self_reference(segment) {
+                    effective.clone()
+                } else {
+                    vec![Some(segment)]
+                }
+            })
+            .collect();
+    }
+
+    let self_reference = match separator {
+        ';' => "$env:PATH",
+        _ => "$PATH",
+    };
+    let mut result = PathNormalization {
+        segments: Vec::new(),
+        duplicates: Vec::new(),
+        missing: Vec::new(),
+    };
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut inherits = false;
+    for segment in effective {
+        let Some(segment) = segment else {
+            if !inherits {
+                inherits = true;
+                result.segments.push(self_reference.to_string());
+            }
+            continue;
+        };
+        if !seen.insert(segment) {
+            result.duplicates.push(segment.to_string());
+        } else if !keep_missing && !has_variable_ref(segment) && !expand_tilde(segment).is_dir() {
+            result.missing.push(segment.to_string());
+        } else {
+            result.segments.push(segment.to_string());
+        }
+    }
+
+    if sort {
+        result.segments.retain(|s| s != self_reference);
+        result.segments.sort();
+        if inherits {
+            result.segments.push(self_reference.to_string());
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::model::{Entry, EntryType};
 
+    #[test]
+    fn test_path_separator() {
+        assert_eq!(path_separator(ShellType::Bash), ':');
+        assert_eq!(path_separator(ShellType::Zsh), ':');
+        assert_eq!(path_separator(ShellType::PowerShell), ';');
+    }
+
+    #[test]
+    fn test_split_path_segments() {
+        assert_eq!(
+            split_path_segments("$PATH:/a::/b ", ':'),
+            vec!["$PATH", "/a", "/b"]
+        );
+        assert_eq!(
+            split_path_segments(r"C:\bin;$env:PATH", ';'),
+            vec![r"C:\bin", "$env:PATH"]
+        );
+    }
+
     #[test]
     fn test_no_merge_needed_single_path() {
         let entry = Entry::new(EntryType::EnvVar, "PATH".into(), "$HOME/bin:$PATH".into())
@@ -243,4 +380,58 @@ mod tests {
         // Only 1 EnvVar PATH, so no merge
         assert!(result.is_none());
     }
+
+    fn normalize(values: &[&str], keep_missing: bool, sort: bool) -> PathNormalization {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        normalize_path_values(&values, ':', keep_missing, sort)
+    }
+
+    #[test]
+    fn test_normalize_follows_lookup_order() {
+        let result = normalize(
+            &["$HOME/a:$PATH", "$HOME/b:$PATH", "$PATH:$HOME/c"],
+            true,
+            false,
+        );
+        assert_eq!(result.segments, ["$HOME/b", "$HOME/a", "$PATH", "$HOME/c"]);
+
+        // A definition without $PATH replaces everything before it
+        let result = normalize(&["$HOME/a:$PATH", "/usr/bin:/bin"], true, false);
+        assert_eq!(result.segments, ["/usr/bin", "/bin"]);
+    }
+
+    #[test]
+    fn test_normalize_drops_duplicates() {
+        let result = normalize(&["$HOME/a:$PATH", "$HOME/b:$HOME/a:${PATH}"], true, false);
+        assert_eq!(result.segments, ["$HOME/b", "$HOME/a", "$PATH"]);
+        assert_eq!(result.duplicates, ["$HOME/a"]);
+    }
+
+    #[test]
+    fn test_normalize_drops_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().display().to_string();
+        let missing = dir.path().join("missing").display().to_string();
+        let value = format!("{}:{}:$HOME/missing:$PATH", existing, missing);
+
+        let result = normalize(&[&value], false, false);
+        assert_eq!(
+            result.segments,
+            [existing.as_str(), "$HOME/missing", "$PATH"]
+        );
+        assert_eq!(result.missing, [missing]);
+
+        let kept = normalize(&[&value], true, false);
+        assert_eq!(kept.segments.len(), 4);
+        assert!(kept.missing.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_sorted() {
+        let result = normalize(&["$PATH:/z", "/b:/a:$PATH"], true, true);
+        assert_eq!(result.segments, ["/a", "/b", "/z", "$PATH"]);
+
+        let pwsh = normalize_path_values(&[r"C:\bin;$env:Path".to_string()], ';', true, false);
+        assert_eq!(pwsh.segments, [r"C:\bin", "$env:PATH"]);
+    }
 }
@@ -0,0 +1,270 @@
+//! Quote-aware helpers for lines of shell code
+//!
+//! Shared by the parsers and by [`crate::model::Entry`], which compares
+//! values with their quoting removed.
+
+/// Quoting in effect at a point of a line of shell code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteState {
+    Unquoted,
+    /// Inside `'...'`, where nothing is escaped
+    Single,
+    /// Inside `"..."`, where a backslash escapes `"`, `\\`, `$` and `` ` ``
+    Double,
+}
+
+/// Walk `text` from `state` following Bash quoting rules, calling `visit`
+/// with the byte offset of each character that is not escaped, the
+/// character, and the state in effect before it. Escaped characters and
+/// the backslashes escaping them are skipped. Returns the state at the end.
+pub fn scan_quotes(
+    text: &str,
+    mut state: QuoteState,
+    mut visit: impl FnMut(usize, char, QuoteState),
+) -> QuoteState {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let escapes = match state {
+            QuoteState::Unquoted => c == '\\',
+            QuoteState::Single => false,
+            QuoteState::Double => {
+                c == '\\' && chars.peek().is_some_and(|(_, n)| "\"\\$`".contains(*n))
+            }
+        };
+        if escapes {
+            chars.next();
+            continue;
+        }
+        visit(i, c, state);
+        state = match (state, c) {
+            (QuoteState::Unquoted, '\'') => QuoteState::Single,
+            (QuoteState::Unquoted, '"') => QuoteState::Double,
+            (QuoteState::Single, '\'') | (QuoteState::Double, '"') => QuoteState::Unquoted,
+            (state, _) => state,
+        };
+    }
+    state
+}
+
+/// Byte length of the shell word `text` starts with: up to the first
+/// unquoted whitespace, or all of `text`. `None` when a quote is left open.
+pub fn shell_word_end(text: &str) -> Option<usize> {
+    let mut end = None;
+    let state = scan_quotes(text, QuoteState::Unquoted, |i, c, state| {
+        if end.is_none() && state == QuoteState::Unquoted && c.is_whitespace() {
+            end = Some(i);
+        }
+    });
+    end.or((state == QuoteState::Unquoted).then_some(text.len()))
+}
+
+/// The text a shell word stands for, with its quotes and escapes removed:
+/// `'it'\''s'` is `it's`, `"say \"hi\""` is `say "hi"`. Nothing is expanded.
+pub fn unquote_shell_word(word: &str) -> String {
+    let mut text = String::new();
+    let mut last = 0;
+    scan_quotes(word, QuoteState::Unquoted, |i, c, state| {
+        // Escaped characters were skipped: keep them, minus the backslash
+        text.push_str(&unescape(&word[last..i]));
+        last = i + c.len_utf8();
+        let delimiter = match state {
+            QuoteState::Unquoted => c == '\'' || c == '"',
+            QuoteState::Single => c == '\'',
+            QuoteState::Double => c == '"',
+        };
+        if !delimiter {
+            text.push(c);
+        }
+    });
+    text.push_str(&unescape(&word[last..]));
+    text
+}
+
+/// Skipped text between visited characters: `\x` sequences, written as `x`
+fn unescape(skipped: &str) -> String {
+    skipped.chars().skip(1).step_by(2).collect()
+}
+
+/// Extract inline comment from a line, respecting quoted strings.
+///
+/// The comment character (typically `#`) is only recognized outside
+/// of single and double quotes.
+///
+/// # Arguments
+///
+/// - `line`: The line to analyze
+/// - `comment_char`: The comment character (usually `#`)
+///
+/// # Returns
+///
+/// A tuple `(code, comment)` where:
+/// - `code`: The part before the comment (trimmed)
+/// - `comment`: The comment text without the `#`, or `None`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (code, comment) = extract_comment("alias ll='ls -la' # list files", '#');
+/// assert_eq!(code, "alias ll='ls -la'");
+/// assert_eq!(comment, Some("list files".to_string()));
+///
+/// // Comment inside quotes is not extracted
+/// let (code, comment) = extract_comment("alias x='echo # not a comment'", '#');
+/// assert_eq!(code, "alias x='echo # not a comment'");
+/// assert_eq!(comment, None);
+/// ```
+pub fn extract_comment(line: &str, comment_char: char) -> (String, Option<String>) {
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let chars: Vec<char> = line.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '\'' if !in_double_quote => in_single_quote = !in_single_quote,
+            '"' if !in_single_quote => in_double_quote = !in_double_quote,
+            c if c == comment_char && !in_single_quote && !in_double_quote => {
+                let code: String = chars[..i].iter().collect();
+                let comment: String = chars[i + 1..].iter().collect();
+                return (
+                    code.trim_end().to_string(),
+                    Some(comment.trim().to_string()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    (line.to_string(), None)
+}
+
+/// Strip surrounding quotes from a value.
+///
+/// Removes matching single or double quotes from the beginning and end
+/// of the value. If the quotes don't match or aren't present, returns
+/// the original value (trimmed).
+///
+/// # Arguments
+///
+/// - `value`: The value to strip quotes from
+///
+/// # Returns
+///
+/// The value without surrounding quotes.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(strip_quotes("'hello'"), "hello");
+/// assert_eq!(strip_quotes("\"world\""), "world");
+/// assert_eq!(strip_quotes("no quotes"), "no quotes");
+/// ```
+pub fn strip_quotes(value: &str) -> String {
+    let trimmed = value.trim();
+    if (trimmed.starts_with('\'') && trimmed.ends_with('\''))
+        || (trimmed.starts_with('"') && trimmed.ends_with('"'))
+    {
+        trimmed[1..trimmed.len() - 1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_word_end() {
+        assert_eq!(shell_word_end("'ls -la' # list"), Some(8));
+        assert_eq!(shell_word_end(r#""echo \"hi there\"""#), Some(19));
+        assert_eq!(shell_word_end(r"'echo it'\''s fine'"), Some(19));
+        assert_eq!(shell_word_end("git"), Some(3));
+        // A backslash does not escape inside single quotes
+        assert_eq!(shell_word_end(r"'cd C:\'"), Some(8));
+        // Nor does it open a single quote inside double quotes
+        assert_eq!(shell_word_end(r#""echo \'hi" x"#), Some(11));
+        assert_eq!(shell_word_end("'unclosed"), None);
+        assert_eq!(shell_word_end("'line1\nline2'"), Some(13));
+    }
+
+    #[test]
+    fn test_unquote_shell_word() {
+        assert_eq!(
+            unquote_shell_word(r#""echo \"hi there\"""#),
+            r#"echo "hi there""#
+        );
+        assert_eq!(unquote_shell_word(r"'echo it'\''s fine'"), "echo it's fine");
+        assert_eq!(
+            unquote_shell_word(r#"'awk '"'"'{print $1}'"'"''"#),
+            "awk '{print $1}'"
+        );
+        // Only `"\$` and backtick are escapes inside double quotes
+        assert_eq!(unquote_shell_word(r#""a \$HOME \n""#), r"a $HOME \n");
+        assert_eq!(unquote_shell_word(r"'cd C:\'"), r"cd C:\");
+    }
+
+    #[test]
+    fn test_scan_quotes_carries_state() {
+        assert_eq!(
+            scan_quotes("'open", QuoteState::Unquoted, |_, _, _| {}),
+            QuoteState::Single
+        );
+        assert_eq!(
+            scan_quotes("close' \"x", QuoteState::Single, |_, _, _| {}),
+            QuoteState::Double
+        );
+    }
+
+    #[test]
+    fn test_extract_comment_basic() {
+        let (code, comment) = extract_comment("alias ll='ls -la' # list files", '#');
+        assert_eq!(code, "alias ll='ls -la'");
+        assert_eq!(comment, Some("list files".to_string()));
+    }
+
+    #[test]
+    fn test_extract_comment_in_single_quotes() {
+        let (code, comment) = extract_comment("alias x='echo # not a comment'", '#');
+        assert_eq!(code, "alias x='echo # not a comment'");
+        assert_eq!(comment, None);
+    }
+
+    #[test]
+    fn test_extract_comment_in_double_quotes() {
+        let (code, comment) = extract_comment("echo \"# not a comment\"", '#');
+        assert_eq!(code, "echo \"# not a comment\"");
+        assert_eq!(comment, None);
+    }
+
+    #[test]
+    fn test_extract_comment_none() {
+        let (code, comment) = extract_comment("echo hello", '#');
+        assert_eq!(code, "echo hello");
+        assert_eq!(comment, None);
+    }
+
+    #[test]
+    fn test_strip_quotes_single() {
+        assert_eq!(strip_quotes("'hello'"), "hello");
+    }
+
+    #[test]
+    fn test_strip_quotes_double() {
+        assert_eq!(strip_quotes("\"hello\""), "hello");
+    }
+
+    #[test]
+    fn test_strip_quotes_none() {
+        assert_eq!(strip_quotes("hello"), "hello");
+    }
+
+    #[test]
+    fn test_strip_quotes_mismatched() {
+        assert_eq!(strip_quotes("'hello\""), "'hello\"");
+    }
+
+    #[test]
+    fn test_strip_quotes_with_whitespace() {
+        assert_eq!(strip_quotes("  'hello'  "), "hello");
+    }
+}
@@ -0,0 +1,101 @@
+//! The command that applies a saved config file to the running shell
+
+use crate::model::{Entry, EntryType, ShellType};
+
+/// The command that reloads the file shown as `path` in the current shell:
+/// `source ~/.bashrc`, `. ~/.profile`, `. $HOME\profile.ps1`. `None` for
+/// `.env` files, which programs read on start.
+pub fn reload_command(shell_type: ShellType, path: &str) -> Option<String> {
+    match shell_type {
+        ShellType::Bash | ShellType::Zsh => Some(format!("source {}", path)),
+        ShellType::PosixSh | ShellType::PowerShell => Some(format!(". {}", path)),
+        ShellType::DotEnv => None,
+    }
+}
+
+/// The command that applies just `entry` in the current shell, for trying
+/// it without reloading the whole file: its definition as written. `.env`
+/// variables are exported so programs started from the shell see them.
+/// `None` for comments and blank lines.
+pub fn apply_command(entry: &Entry, shell_type: ShellType) -> Option<String> {
+    let definition = entry.definition();
+    if definition.is_empty() {
+        return None;
+    }
+    Some(match (shell_type, entry.entry_type) {
+        (ShellType::DotEnv, EntryType::EnvVar) if !definition.starts_with("export ") => {
+            format!("export {}", definition)
+        }
+        _ => definition.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_command_per_shell() {
+        assert_eq!(
+            reload_command(ShellType::Bash, "~/.bashrc").as_deref(),
+            Some("source ~/.bashrc")
+        );
+        assert_eq!(
+            reload_command(ShellType::Zsh, "~/.zshrc").as_deref(),
+            Some("source ~/.zshrc")
+        );
+        assert_eq!(
+            reload_command(ShellType::PosixSh, "~/.profile").as_deref(),
+            Some(". ~/.profile")
+        );
+        assert_eq!(
+            reload_command(ShellType::PowerShell, "~/profile.ps1").as_deref(),
+            Some(". ~/profile.ps1")
+        );
+        assert_eq!(reload_command(ShellType::DotEnv, ".env"), None);
+    }
+
+    #[test]
+    fn test_apply_command_per_entry_type() {
+        let entry = |entry_type, value: &str| Entry::new(entry_type, "x".into(), value.into());
+
+        // Merged comments and absorbed blank lines are left out
+        assert_eq!(
+            apply_command(
+                &entry(EntryType::Alias, "# list\nalias ll='ls -la'\n\n"),
+                ShellType::Bash
+            )
+            .as_deref(),
+            Some("alias ll='ls -la'")
+        );
+        // A function is copied whole
+        assert_eq!(
+            apply_command(
+                &entry(EntryType::Function, "greet() {\n  echo hi\n}\n"),
+                ShellType::Zsh
+            )
+            .as_deref(),
+            Some("greet() {\n  echo hi\n}")
+        );
+        assert_eq!(
+            apply_command(
+                &entry(EntryType::EnvVar, "$env:EDITOR = 'code'"),
+                ShellType::PowerShell
+            )
+            .as_deref(),
+            Some("$env:EDITOR = 'code'")
+        );
+        assert_eq!(
+            apply_command(&entry(EntryType::EnvVar, "PORT=8080"), ShellType::DotEnv).as_deref(),
+            Some("export PORT=8080")
+        );
+        assert_eq!(
+            apply_command(&entry(EntryType::Comment, "# notes"), ShellType::Bash),
+            None
+        );
+        assert_eq!(
+            apply_command(&entry(EntryType::Code, "\n"), ShellType::Bash),
+            None
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Entry reordering shared by the TUI move mode and `wenv move`
+//!
+//! Moves are a cut-and-paste of whole entries: raw values travel unchanged
+//! and the file is rebuilt from the new order.
+
+use std::ops::Range;
+
+use crate::formatter::Formatter;
+use crate::model::{Entry, EntryType};
+
+/// Index range of the entry at `index` plus a Comment entry attached
+/// directly above it (ending on the line before), which belongs to the entry
+/// when it moves.
+pub fn block_range(entries: &[Entry], index: usize) -> Range<usize> {
+    let attached = index > 0 && {
+        let prev = &entries[index - 1];
+        prev.entry_type == EntryType::Comment
+            && entries[index].entry_type != EntryType::Comment
+            && matches!(
+                (prev.end_line.or(prev.line_number), entries[index].line_number),
+                (Some(end), Some(start)) if end + 1 == start
+            )
+    };
+    if attached {
+        index - 1..index + 1
+    } else {
+        index..index + 1
+    }
+}
+
+/// Cut the entries at `indices` (sorted ascending) and paste them, in their
+/// original relative order, at position `to` of the remaining list.
+pub fn move_entries(entries: &mut Vec<Entry>, indices: &[usize], to: usize) {
+    let mut extracted: Vec<Entry> = indices.iter().rev().map(|&i| entries.remove(i)).collect();
+    extracted.reverse();
+
+    let to = to.min(entries.len());
+    entries.splice(to..to, extracted);
+}
+
+/// Rebuild file content from entries in their current order
+pub fn render_entries(entries: &[Entry], formatter: &dyn Formatter) -> String {
+    let lines: Vec<String> = entries.iter().map(|e| formatter.format_entry(e)).collect();
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+
+    fn names(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_move_entries() {
+        let mut entries: Vec<Entry> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|n| Entry::new(EntryType::Alias, n.to_string(), String::new()))
+            .collect();
+
+        move_entries(&mut entries, &[2, 3], 0);
+        assert_eq!(names(&entries), ["c", "d", "a", "b"]);
+
+        move_entries(&mut entries, &[0], 10);
+        assert_eq!(names(&entries), ["d", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_block_range_includes_attached_comment() {
+        let parser = crate::parser::get_parser(ShellType::PowerShell);
+        let entries = parser
+            .parse("# Listing\nSet-Alias ll Get-ChildItem\nSet-Alias gs git-status\n")
+            .entries;
+
+        assert_eq!(names(&entries), ["#L1", "ll", "gs"]);
+        assert_eq!(block_range(&entries, 1), 0..2);
+        assert_eq!(block_range(&entries, 2), 2..3);
+        assert_eq!(block_range(&entries, 0), 0..1);
+    }
+
+    #[test]
+    fn test_render_entries_preserves_raw_values() {
+        let parser = crate::parser::get_parser(ShellType::Bash);
+        let content = "alias b='x'  # keep\n\n# about a\nalias a=\"y\"\n";
+        let mut entries = parser.parse(content).entries;
+        let formatter = crate::formatter::get_formatter(ShellType::Bash);
+
+        assert_eq!(render_entries(&entries, formatter.as_ref()), content);
+
+        move_entries(&mut entries, &[1], 0);
+        assert_eq!(
+            render_entries(&entries, formatter.as_ref()),
+            "# about a\nalias a=\"y\"\nalias b='x'  # keep\n\n"
+        );
+    }
+}
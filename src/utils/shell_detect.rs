@@ -1,8 +1,34 @@
 //! Shell detection utilities
+//!
+//! The shell of a config file is decided by the first rule that applies:
+//!
+//! 1. `--force-shell` (see [`ShellSource::Forced`])
+//! 2. `--shell`
+//! 3. the shebang on its first line ([`detect_from_shebang`])
+//! 4. shell-specific commands in its content ([`detect_from_content`])
+//! 5. its name or extension ([`detect_from_file`])
+//! 6. the environment (`$SHELL`, `PSModulePath`)
+//! 7. bash
 
 use crate::model::ShellType;
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::path::Path;
 
+lazy_static! {
+    /// Lines only PowerShell profiles contain
+    static ref PWSH_CONTENT_RE: Regex = Regex::new(
+        r"(?mi)^\s*(?:\$env:\w+\s*=|(?:Set|New)-Alias\s|Import-Module\s)"
+    ).unwrap();
+
+    /// Lines only zsh config files contain
+    static ref ZSH_CONTENT_RE: Regex =
+        Regex::new(r"(?m)^\s*(?:setopt|unsetopt|autoload|zstyle|bindkey|compdef)\s").unwrap();
+
+    /// Lines only bash config files contain
+    static ref BASH_CONTENT_RE: Regex = Regex::new(r"(?m)^\s*(?:shopt|complete)\s").unwrap();
+}
+
 /// Detect shell type from file extension
 pub fn detect_from_file(path: &Path) -> Option<ShellType> {
     // First check filename for common patterns (for files without extensions)
@@ -26,6 +52,14 @@ pub fn detect_from_file(path: &Path) -> Option<ShellType> {
         if filename.contains("profile.ps1") || filename.contains("PowerShell") {
             return Some(ShellType::PowerShell);
         }
+        // POSIX sh patterns (/etc/profile, ~/.profile)
+        if filename == ".profile" || filename == "profile" {
+            return Some(ShellType::PosixSh);
+        }
+        // .env, .env.local, .env.production (but not direnv's .envrc)
+        if filename == ".env" || filename.starts_with(".env.") {
+            return Some(ShellType::DotEnv);
+        }
     }
 
     // Then check extension
@@ -34,6 +68,7 @@ pub fn detect_from_file(path: &Path) -> Option<ShellType> {
             "zsh" => return Some(ShellType::Zsh),
             "sh" | "bash" => return Some(ShellType::Bash),
             "ps1" | "psm1" => return Some(ShellType::PowerShell),
+            "env" => return Some(ShellType::DotEnv),
             _ => {}
         }
     }
@@ -41,20 +76,105 @@ pub fn detect_from_file(path: &Path) -> Option<ShellType> {
     None
 }
 
+/// Shell named by a `#!` line (`#!/bin/zsh`, `#!/usr/bin/env pwsh`)
+pub fn detect_from_shebang(content: &str) -> Option<ShellType> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-'))?;
+    }
+    match program {
+        "bash" => Some(ShellType::Bash),
+        "zsh" => Some(ShellType::Zsh),
+        "sh" | "dash" | "ash" => Some(ShellType::PosixSh),
+        "pwsh" | "powershell" => Some(ShellType::PowerShell),
+        _ => None,
+    }
+}
+
+/// Shell whose own commands the content uses (`$env:X = ...`, `setopt`,
+/// `shopt`, ...). Syntax shared between shells decides nothing.
+pub fn detect_from_content(content: &str) -> Option<ShellType> {
+    if PWSH_CONTENT_RE.is_match(content) {
+        Some(ShellType::PowerShell)
+    } else if ZSH_CONTENT_RE.is_match(content) {
+        Some(ShellType::Zsh)
+    } else if BASH_CONTENT_RE.is_match(content) {
+        Some(ShellType::Bash)
+    } else {
+        None
+    }
+}
+
+/// Where the active shell type came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellSource {
+    /// Given with `--force-shell`, which also applies to files read
+    /// alongside the config file (e.g. import sources)
+    Forced,
+    /// Given explicitly with `--shell`
+    Specified,
+    /// Named by the shebang on the first line
+    Shebang,
+    /// Inferred from shell-specific commands in the file
+    Content,
+    /// Inferred from the config file name or extension
+    FileName,
+    /// Detected from the environment (`$SHELL`, `PSModulePath`)
+    Environment,
+    /// Nothing matched, fell back to bash
+    Default,
+}
+
+impl ShellSource {
+    /// Short human-readable explanation
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ShellSource::Forced => "forced with --force-shell",
+            ShellSource::Specified => "specified with --shell",
+            ShellSource::Shebang => "detected from shebang",
+            ShellSource::Content => "detected from file content",
+            ShellSource::FileName => "detected from file name",
+            ShellSource::Environment => "detected from environment",
+            ShellSource::Default => "default (nothing detected)",
+        }
+    }
+}
+
 /// Get the appropriate shell type for the current context
 pub fn get_shell_type(specified: Option<ShellType>, file_path: Option<&Path>) -> ShellType {
-    // Priority: specified > file detection > environment detection > default
+    resolve_shell_type(specified, file_path).0
+}
+
+/// Like [`get_shell_type`], also reporting which rule decided (see the
+/// module docs for the order; `--force-shell` is handled by the caller)
+pub fn resolve_shell_type(
+    specified: Option<ShellType>,
+    file_path: Option<&Path>,
+) -> (ShellType, ShellSource) {
     if let Some(shell) = specified {
-        return shell;
+        return (shell, ShellSource::Specified);
     }
 
-    if let Some(path) = file_path {
-        if let Some(shell) = detect_from_file(path) {
-            return shell;
+    let content = file_path.and_then(|path| std::fs::read_to_string(path).ok());
+    if let Some(content) = content {
+        if let Some(shell) = detect_from_shebang(&content) {
+            return (shell, ShellSource::Shebang);
+        }
+        if let Some(shell) = detect_from_content(&content) {
+            return (shell, ShellSource::Content);
         }
     }
 
-    ShellType::detect().unwrap_or(ShellType::Bash)
+    if let Some(shell) = file_path.and_then(detect_from_file) {
+        return (shell, ShellSource::FileName);
+    }
+
+    match ShellType::detect() {
+        Some(shell) => (shell, ShellSource::Environment),
+        None => (ShellType::Bash, ShellSource::Default),
+    }
 }
 
 #[cfg(test)]
@@ -86,9 +206,110 @@ mod tests {
         assert_eq!(detect_from_file(&path), Some(ShellType::PowerShell));
     }
 
+    #[test]
+    fn test_detect_from_file_posix_sh() {
+        let path = PathBuf::from("/home/user/.profile");
+        assert_eq!(detect_from_file(&path), Some(ShellType::PosixSh));
+
+        let path2 = PathBuf::from("/etc/profile");
+        assert_eq!(detect_from_file(&path2), Some(ShellType::PosixSh));
+
+        let path3 = PathBuf::from("/home/user/.bash_profile");
+        assert_eq!(detect_from_file(&path3), Some(ShellType::Bash));
+    }
+
     #[test]
     fn test_get_shell_type_specified() {
         let result = get_shell_type(Some(ShellType::PowerShell), None);
         assert_eq!(result, ShellType::PowerShell);
     }
+
+    #[test]
+    fn test_resolve_shell_type_source() {
+        let (shell, source) = resolve_shell_type(Some(ShellType::Zsh), None);
+        assert_eq!((shell, source), (ShellType::Zsh, ShellSource::Specified));
+
+        let path = PathBuf::from("/home/user/.bashrc");
+        let (shell, source) = resolve_shell_type(None, Some(&path));
+        assert_eq!((shell, source), (ShellType::Bash, ShellSource::FileName));
+    }
+
+    #[test]
+    fn test_detect_from_file_dotenv() {
+        for name in [".env", ".env.local", "app.env"] {
+            let path = PathBuf::from("/srv/app").join(name);
+            assert_eq!(detect_from_file(&path), Some(ShellType::DotEnv), "{}", name);
+        }
+        assert_ne!(
+            detect_from_file(&PathBuf::from("/srv/app/.envrc")),
+            Some(ShellType::DotEnv)
+        );
+    }
+
+    #[test]
+    fn test_detect_from_shebang() {
+        let cases = [
+            ("#!/bin/bash\n", Some(ShellType::Bash)),
+            ("#!/usr/bin/env zsh\n", Some(ShellType::Zsh)),
+            (
+                "#!/usr/bin/env -S pwsh -NoProfile\n",
+                Some(ShellType::PowerShell),
+            ),
+            ("#!/bin/sh\n", Some(ShellType::PosixSh)),
+            ("#!/usr/bin/python3\n", None),
+            ("alias ll='ls'\n#!/bin/zsh\n", None),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(detect_from_shebang(content), expected, "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_detect_from_content() {
+        assert_eq!(
+            detect_from_content("$env:EDITOR = \"code\"\n"),
+            Some(ShellType::PowerShell)
+        );
+        assert_eq!(
+            detect_from_content("alias ll='ls'\nsetopt autocd\n"),
+            Some(ShellType::Zsh)
+        );
+        assert_eq!(
+            detect_from_content("shopt -s histappend\n"),
+            Some(ShellType::Bash)
+        );
+        // Shared syntax decides nothing
+        assert_eq!(detect_from_content("alias ll='ls'\nexport A=1\n"), None);
+    }
+
+    #[test]
+    fn test_resolve_shell_type_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let resolve = |name: &str, content: &str, specified| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            resolve_shell_type(specified, Some(&path))
+        };
+
+        // --shell beats the shebang
+        assert_eq!(
+            resolve(".zshrc", "#!/bin/zsh\n", Some(ShellType::Bash)),
+            (ShellType::Bash, ShellSource::Specified)
+        );
+        // The shebang beats the content
+        assert_eq!(
+            resolve("rc", "#!/bin/bash\nsetopt autocd\n", None),
+            (ShellType::Bash, ShellSource::Shebang)
+        );
+        // The content beats the file name
+        assert_eq!(
+            resolve(".bashrc", "setopt autocd\n", None),
+            (ShellType::Zsh, ShellSource::Content)
+        );
+        // The file name comes last
+        assert_eq!(
+            resolve(".bashrc", "alias ll='ls'\n", None),
+            (ShellType::Bash, ShellSource::FileName)
+        );
+    }
 }
@@ -0,0 +1,193 @@
+//! Files a config file sources, followed recursively (`wenv sources`)
+//!
+//! A `source` argument is resolved the way a user's dotfiles usually mean
+//! it: `~`, `$HOME` and `$PSScriptRoot` are expanded and relative paths are
+//! taken from the directory of the sourcing file. Arguments referring to
+//! any other variable cannot be resolved without running the shell.
+
+use std::path::{Path, PathBuf};
+
+use crate::model::{Entry, EntryType};
+use crate::utils::path::expand_tilde;
+
+/// What became of one `source` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceStatus {
+    /// The file exists and its own sources were followed
+    Found,
+    /// The file does not exist
+    Missing,
+    /// The file is already being sourced further up the chain
+    Cycle,
+    /// The argument depends on a variable other than `$HOME`
+    Unresolved,
+}
+
+/// A sourced file and the files it sources in turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceNode {
+    /// The argument as written, e.g. `~/.bash_aliases`
+    pub target: String,
+    /// Resolved path, unless [`SourceStatus::Unresolved`]
+    pub path: Option<PathBuf>,
+    /// Line of the `source` entry in the sourcing file
+    pub line: Option<usize>,
+    pub status: SourceStatus,
+    pub children: Vec<SourceNode>,
+}
+
+/// Resolve the `source` argument `target` found in the file `from`
+pub fn resolve_source(target: &str, from: &Path) -> Option<PathBuf> {
+    let dir = from.parent().unwrap_or(Path::new(""));
+    let home = dirs::home_dir().map(|h| h.display().to_string());
+
+    let mut expanded = target.to_string();
+    for (variable, value) in [
+        ("${HOME}", home.as_deref()),
+        ("$HOME", home.as_deref()),
+        ("$env:USERPROFILE", home.as_deref()),
+        ("$PSScriptRoot", dir.to_str()),
+    ] {
+        if let Some(value) = value {
+            expanded = expanded.replace(variable, value);
+        }
+    }
+    if expanded.contains('$') || expanded.contains('%') || expanded.contains('`') {
+        return None;
+    }
+
+    let path = expand_tilde(&expanded);
+    Some(if path.is_relative() {
+        dir.join(path)
+    } else {
+        path
+    })
+}
+
+/// The sources of the file `root`, followed recursively.
+///
+/// `parse` returns the entries of a file; files it cannot parse are shown
+/// without children. PowerShell module imports are not files and are left
+/// out.
+pub fn source_tree(
+    root: &Path,
+    entries: &[Entry],
+    parse: &mut impl FnMut(&Path) -> Option<Vec<Entry>>,
+) -> Vec<SourceNode> {
+    let mut chain = vec![identity(root)];
+    sources_of(root, entries, &mut chain, parse)
+}
+
+/// A path as compared for cycles: canonical when it exists
+fn identity(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn sources_of(
+    file: &Path,
+    entries: &[Entry],
+    chain: &mut Vec<PathBuf>,
+    parse: &mut impl FnMut(&Path) -> Option<Vec<Entry>>,
+) -> Vec<SourceNode> {
+    entries
+        .iter()
+        .filter(|e| e.entry_type == EntryType::Source && !e.is_module_import())
+        .map(|entry| {
+            let target = entry.canonical_value();
+            let path = resolve_source(&target, file);
+            let (status, children) = match &path {
+                None => (SourceStatus::Unresolved, Vec::new()),
+                Some(path) if !path.is_file() => (SourceStatus::Missing, Vec::new()),
+                Some(path) if chain.contains(&identity(path)) => (SourceStatus::Cycle, Vec::new()),
+                Some(path) => {
+                    let children = match parse(path) {
+                        Some(entries) => {
+                            chain.push(identity(path));
+                            let children = sources_of(path, &entries, chain, parse);
+                            chain.pop();
+                            children
+                        }
+                        None => Vec::new(),
+                    };
+                    (SourceStatus::Found, children)
+                }
+            };
+            SourceNode {
+                target,
+                path,
+                line: entry.line_number,
+                status,
+                children,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ShellType;
+    use crate::parser::get_parser;
+
+    fn parse(path: &Path) -> Option<Vec<Entry>> {
+        let content = std::fs::read_to_string(path).ok()?;
+        Some(get_parser(ShellType::Bash).parse(&content).entries)
+    }
+
+    #[test]
+    fn test_resolve_source() {
+        let from = Path::new("/home/me/.bashrc");
+        assert_eq!(
+            resolve_source("lib/aliases.sh", from),
+            Some(PathBuf::from("/home/me/lib/aliases.sh"))
+        );
+        assert_eq!(
+            resolve_source("/etc/bashrc", from),
+            Some(PathBuf::from("/etc/bashrc"))
+        );
+        assert_eq!(
+            resolve_source("$PSScriptRoot/extra.ps1", Path::new("/cfg/profile.ps1")),
+            Some(PathBuf::from("/cfg/extra.ps1"))
+        );
+        assert_eq!(resolve_source("$ZSH/oh-my-zsh.sh", from), None);
+    }
+
+    #[test]
+    fn test_nested_sources_with_missing_file_and_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join(".bashrc");
+        std::fs::write(
+            &root,
+            "source .bash_aliases\n. .bash_missing\nsource $ZSH/init.sh\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(".bash_aliases"),
+            "alias ll='ls -la'\nsource .bash_functions\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".bash_functions"), "source .bashrc\n").unwrap();
+
+        let entries = parse(&root).unwrap();
+        let tree = source_tree(&root, &entries, &mut parse);
+
+        let statuses: Vec<_> = tree.iter().map(|n| (n.target.as_str(), n.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                (".bash_aliases", SourceStatus::Found),
+                (".bash_missing", SourceStatus::Missing),
+                ("$ZSH/init.sh", SourceStatus::Unresolved),
+            ]
+        );
+        assert_eq!(tree[1].line, Some(2));
+
+        let functions = &tree[0].children[0];
+        assert_eq!(functions.target, ".bash_functions");
+        assert_eq!(functions.status, SourceStatus::Found);
+        let back = &functions.children[0];
+        assert_eq!(back.target, ".bashrc");
+        assert_eq!(back.status, SourceStatus::Cycle);
+        assert!(back.children.is_empty());
+    }
+}
@@ -40,10 +40,112 @@ pub fn split_lines_preserve_trailing(s: &str) -> Vec<&str> {
     result
 }
 
+/// Truncate a string to at most `max_chars` characters, ending with `…` if cut.
+///
+/// # Examples
+///
+/// ```
+/// use wenv::utils::strings::truncate_with_ellipsis;
+///
+/// assert_eq!(truncate_with_ellipsis("hello", 10), "hello");
+/// assert_eq!(truncate_with_ellipsis("hello world", 6), "hello…");
+/// ```
+pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let mut truncated: String = s.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// `content` ending in exactly one newline: trailing blank lines are
+/// dropped and a missing final newline is added. Empty content stays empty.
+///
+/// # Examples
+///
+/// ```
+/// use wenv::utils::strings::with_single_trailing_newline;
+///
+/// assert_eq!(with_single_trailing_newline("a\n\n\n"), "a\n");
+/// assert_eq!(with_single_trailing_newline("a"), "a\n");
+/// assert_eq!(with_single_trailing_newline("\n\n"), "");
+/// ```
+pub fn with_single_trailing_newline(content: &str) -> String {
+    let body = crate::formatter::trim_trailing_blank_lines(content);
+    if body.trim().is_empty() {
+        return String::new();
+    }
+    format!("{}\n", body)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+///
+/// # Examples
+///
+/// ```
+/// use wenv::utils::strings::edit_distance;
+///
+/// assert_eq!(edit_distance("EDITOR", "EDTIOR"), 2);
+/// assert_eq!(edit_distance("HOME", "HOME"), 0);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+/// `s` with control characters spelled out so they can't act on the
+/// terminal: ESC as `\e`, tab as `\t`, others as `\xNN`. Newlines are
+/// kept.
+pub fn escape_control_chars(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\n' => escaped.push(c),
+            '\x1b' => escaped.push_str("\\e"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("PATH", "PAHT"), 2);
+        assert_eq!(edit_distance("EDITOR", "EDITORS"), 1);
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("short", 5), "short");
+        assert_eq!(truncate_with_ellipsis("longer", 5), "long…");
+        assert_eq!(truncate_with_ellipsis("abc", 1), "…");
+        assert_eq!(truncate_with_ellipsis("abc", 0), "");
+        // Counts characters, not bytes
+        assert_eq!(truncate_with_ellipsis("日本語テキスト", 4), "日本語…");
+    }
+
     #[test]
     fn test_empty_string() {
         assert_eq!(split_lines_preserve_trailing(""), Vec::<&str>::new());
@@ -121,4 +223,14 @@ mod tests {
         // "\n\n\n" = three empty lines + terminator → ["", "", ""]
         assert_eq!(split_lines_preserve_trailing("\n\n\n"), vec!["", "", ""]);
     }
+
+    #[test]
+    fn test_escape_control_chars() {
+        assert_eq!(
+            escape_control_chars("\x1b[32m\\u\x1b[0m\t$\x07"),
+            "\\e[32m\\u\\e[0m\\t$\\x07"
+        );
+        assert_eq!(escape_control_chars("a\nb"), "a\nb");
+        assert_eq!(escape_control_chars("plain"), "plain");
+    }
 }
@@ -0,0 +1,195 @@
+//! Per-entry output templates (`wenv list --output-template`)
+//!
+//! A template is literal text with placeholders such as `{name}`. `{{` and
+//! `}}` stand for literal braces, and `\t`, `\n` and `\\` for a tab, a
+//! newline and a backslash, so tabs can be written inside shell quotes.
+
+use std::str::FromStr;
+
+use crate::model::Entry;
+
+/// A value a template can insert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    Type,
+    Name,
+    /// The canonical value, which may span several lines
+    Value,
+    /// First line of the entry, empty when unknown
+    Line,
+    /// Last line of the entry, the first line for single-line entries
+    EndLine,
+    /// Comment directly above the entry, empty unless `parser.attach_comments`
+    /// recorded one
+    Comment,
+}
+
+impl Placeholder {
+    const ALL: [(&'static str, Placeholder); 6] = [
+        ("type", Placeholder::Type),
+        ("name", Placeholder::Name),
+        ("value", Placeholder::Value),
+        ("line", Placeholder::Line),
+        ("end_line", Placeholder::EndLine),
+        ("comment", Placeholder::Comment),
+    ];
+
+    fn value_of(self, entry: &Entry) -> String {
+        let line = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+        match self {
+            Placeholder::Type => entry.entry_type.to_string(),
+            Placeholder::Name => entry.name.clone(),
+            Placeholder::Value => entry.canonical_value(),
+            Placeholder::Line => line(entry.line_number),
+            Placeholder::EndLine => line(entry.end_line.or(entry.line_number)),
+            Placeholder::Comment => entry.attached_comment.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field(Placeholder),
+}
+
+/// A parsed template, rendered once per entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(format!(
+                                    "unclosed placeholder '{{{}' (write {{{{ for a literal brace)",
+                                    name
+                                ))
+                            }
+                        }
+                    }
+                    let Some(&(_, placeholder)) =
+                        Placeholder::ALL.iter().find(|(known, _)| *known == name)
+                    else {
+                        let known: Vec<String> = Placeholder::ALL
+                            .iter()
+                            .map(|(known, _)| format!("{{{}}}", known))
+                            .collect();
+                        return Err(format!(
+                            "unknown placeholder '{{{}}}', expected one of {} (write {{{{ for a literal brace)",
+                            name,
+                            known.join(", ")
+                        ));
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(placeholder));
+                }
+                '}' => return Err("unmatched '}' (write }} for a literal brace)".to_string()),
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        text.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        text.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        text.push('\\');
+                    }
+                    _ => text.push('\\'),
+                },
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+}
+
+impl Template {
+    /// The template filled in from `entry`
+    pub fn render(&self, entry: &Entry) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Field(placeholder) => placeholder.value_of(entry),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::EntryType;
+
+    fn entry() -> Entry {
+        Entry::new(
+            EntryType::Alias,
+            "ll".to_string(),
+            "alias ll='ls -la'".to_string(),
+        )
+        .with_line_number(3)
+    }
+
+    #[test]
+    fn test_placeholders_are_substituted() {
+        let template: Template = r"{type}\t{name}\t{value} ({line}-{end_line})"
+            .parse()
+            .unwrap();
+        assert_eq!(template.render(&entry()), "alias\tll\tls -la (3-3)");
+    }
+
+    #[test]
+    fn test_literal_braces() {
+        let template: Template = "{{{name}}} {{}}".parse().unwrap();
+        assert_eq!(template.render(&entry()), "{ll} {}");
+    }
+
+    #[test]
+    fn test_comment_placeholder() {
+        let template: Template = "{name}: {comment}".parse().unwrap();
+        assert_eq!(template.render(&entry()), "ll: ");
+
+        let mut commented = entry();
+        commented.attached_comment = Some("# long listing".to_string());
+        assert_eq!(template.render(&commented), "ll: # long listing");
+    }
+
+    #[test]
+    fn test_unknown_placeholder_is_an_error() {
+        let err = "{name} {size}".parse::<Template>().unwrap_err();
+        assert!(err.contains("'{size}'"), "{}", err);
+        assert!("{name".parse::<Template>().is_err());
+        assert!("name}".parse::<Template>().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Translation of entries between Bash-family shells and PowerShell
+//!
+//! Only entries with a clean equivalent are translated: aliases, environment
+//! variables and sourced files are regenerated with the target formatter,
+//! and variable references in their values are rewritten (`$FOO` ↔
+//! `$env:FOO`). Functions, arrays, raw code and PowerShell module imports
+//! use shell-specific syntax and have no translation.
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::formatter::get_formatter;
+use crate::model::{Entry, EntryType, ShellType};
+use crate::parser::get_parser;
+
+lazy_static! {
+    /// `$NAME` or `${NAME}`, with an optional escaping backslash
+    static ref BASH_REF_RE: Regex =
+        Regex::new(r"(\\?)\$(?:\{([A-Za-z_]\w*)\}|([A-Za-z_]\w*))").unwrap();
+
+    /// `$env:NAME` or `${env:NAME}`, with an optional escaping backtick
+    static ref PWSH_REF_RE: Regex =
+        Regex::new(r"(?i)(`?)\$(?:\{env:([A-Za-z_]\w*)\}|env:([A-Za-z_]\w*))").unwrap();
+}
+
+/// Whether raw code from one shell runs unchanged in the other
+pub fn same_syntax(a: ShellType, b: ShellType) -> bool {
+    let family = |shell| match shell {
+        ShellType::Bash | ShellType::Zsh | ShellType::PosixSh => 0,
+        ShellType::PowerShell => 1,
+        ShellType::DotEnv => 2,
+    };
+    family(a) == family(b)
+}
+
+/// Translate `entry` from `from` shell syntax to `to` shell syntax.
+///
+/// Entries are returned unchanged between shells that share a syntax.
+/// Returns `None` when the entry has no equivalent in the target shell.
+/// The translated entry keeps the name, tags and type the target parser
+/// sees (a Bash alias with arguments becomes a PowerShell function).
+pub fn translate(entry: &Entry, from: ShellType, to: ShellType) -> Option<Entry> {
+    if same_syntax(from, to) {
+        return Some(entry.clone());
+    }
+    if !matches!(
+        entry.entry_type,
+        EntryType::Alias | EntryType::EnvVar | EntryType::Source
+    ) || entry.is_module_import()
+    {
+        return None;
+    }
+
+    let value = translate_references(&entry.canonical_value(), from, to);
+    let syntax = get_formatter(to).definition(entry.entry_type, &entry.name, &value)?;
+
+    let mut translated = get_parser(to)
+        .parse(&syntax)
+        .entries
+        .into_iter()
+        .find(|e| e.entry_type != EntryType::Comment)?;
+    translated.line_number = None;
+    translated.end_line = None;
+    translated.tags = entry.tags.clone();
+    Some(translated)
+}
+
+/// Rewrite environment variable references in `value` for the `to` shell.
+/// `$HOME` is left alone since PowerShell defines it too.
+pub fn translate_references(value: &str, from: ShellType, to: ShellType) -> String {
+    match (from == ShellType::PowerShell, to == ShellType::PowerShell) {
+        (false, true) => BASH_REF_RE
+            .replace_all(value, |caps: &Captures| {
+                if !caps[1].is_empty() {
+                    return caps[0].to_string();
+                }
+                match (caps.get(2), caps.get(3)) {
+                    (Some(name), _) if name.as_str() == "HOME" => "${HOME}".to_string(),
+                    (Some(name), _) => format!("${{env:{}}}", name.as_str()),
+                    (_, Some(name)) if name.as_str() == "HOME" => "$HOME".to_string(),
+                    (_, Some(name)) => format!("$env:{}", name.as_str()),
+                    _ => caps[0].to_string(),
+                }
+            })
+            .into_owned(),
+        (true, false) => PWSH_REF_RE
+            .replace_all(value, |caps: &Captures| {
+                if !caps[1].is_empty() {
+                    return caps[0].to_string();
+                }
+                match (caps.get(2), caps.get(3)) {
+                    (Some(name), _) => format!("${{{}}}", name.as_str()),
+                    (_, Some(name)) => format!("${}", name.as_str()),
+                    _ => caps[0].to_string(),
+                }
+            })
+            .into_owned(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(shell: ShellType, content: &str) -> Entry {
+        get_parser(shell).parse(content).entries.remove(0)
+    }
+
+    fn bash_to_pwsh(content: &str) -> Option<Entry> {
+        let entry = parse_one(ShellType::Bash, content);
+        translate(&entry, ShellType::Bash, ShellType::PowerShell)
+    }
+
+    #[test]
+    fn test_bash_aliases_to_powershell() {
+        let entry = bash_to_pwsh("alias g=git\n").unwrap();
+        assert_eq!(entry.entry_type, EntryType::Alias);
+        assert_eq!(entry.value, "Set-Alias -Name g -Value git");
+
+        let entry = bash_to_pwsh("alias ll='ls -la'\n").unwrap();
+        assert_eq!(entry.entry_type, EntryType::Function);
+        assert_eq!(entry.name, "ll");
+        assert_eq!(entry.value, "function ll { ls -la @args }");
+    }
+
+    #[test]
+    fn test_bash_env_and_source_to_powershell() {
+        let entry = bash_to_pwsh("export FOO=bar\n").unwrap();
+        assert_eq!(entry.entry_type, EntryType::EnvVar);
+        assert_eq!(entry.value, "$env:FOO = \"bar\"");
+
+        let entry = bash_to_pwsh("export PATH=\"$HOME/bin:${GOPATH}/bin:$PATH\"\n").unwrap();
+        assert_eq!(
+            entry.canonical_value(),
+            "$HOME/bin:${env:GOPATH}/bin:$env:PATH"
+        );
+
+        let entry = bash_to_pwsh("source ~/.aliases\n").unwrap();
+        assert_eq!(entry.entry_type, EntryType::Source);
+        assert_eq!(entry.value, ". \"~/.aliases\"");
+    }
+
+    #[test]
+    fn test_powershell_to_bash() {
+        let pwsh = |content: &str| {
+            let entry = parse_one(ShellType::PowerShell, content);
+            translate(&entry, ShellType::PowerShell, ShellType::Bash)
+        };
+
+        let entry = pwsh("Set-Alias ll Get-ChildItem\n").unwrap();
+        assert_eq!(entry.value, "alias ll='Get-ChildItem'");
+
+        let entry = pwsh("$env:FOO = \"bar\"\n").unwrap();
+        assert_eq!(entry.value, "export FOO=bar");
+
+        let entry = pwsh("$env:PATH = \"$env:HOME/bin;${env:PATH}\"\n").unwrap();
+        assert_eq!(entry.value, "export PATH=\"$HOME/bin;${PATH}\"");
+    }
+
+    #[test]
+    fn test_untranslatable_entries() {
+        assert!(bash_to_pwsh("greet() { echo hi; }\n").is_none());
+        assert!(bash_to_pwsh("files=(a b c)\n").is_none());
+        assert!(bash_to_pwsh("echo hello\n").is_none());
+
+        let module = parse_one(ShellType::PowerShell, "Import-Module posh-git\n");
+        assert!(translate(&module, ShellType::PowerShell, ShellType::Bash).is_none());
+
+        // Same syntax family: unchanged
+        let entry = parse_one(ShellType::Bash, "greet() { echo hi; }\n");
+        let same = translate(&entry, ShellType::Bash, ShellType::Zsh).unwrap();
+        assert_eq!(same.value, entry.value);
+    }
+
+    #[test]
+    fn test_dotenv_variables() {
+        let entry = parse_one(ShellType::Bash, "export EDITOR='code --wait'\n");
+        let dotenv = translate(&entry, ShellType::Bash, ShellType::DotEnv).unwrap();
+        assert_eq!(dotenv.value, "EDITOR='code --wait'");
+
+        let entry = parse_one(ShellType::DotEnv, "DB_HOST=localhost\n");
+        let bash = translate(&entry, ShellType::DotEnv, ShellType::Bash).unwrap();
+        assert_eq!(bash.value, "export DB_HOST=localhost");
+
+        // Only variables exist in a .env file
+        assert!(translate(
+            &parse_one(ShellType::Bash, "alias ll='ls -la'\n"),
+            ShellType::Bash,
+            ShellType::DotEnv
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_escaped_references_are_kept() {
+        assert_eq!(
+            translate_references("\\$FOO $BAR", ShellType::Bash, ShellType::PowerShell),
+            "\\$FOO $env:BAR"
+        );
+        assert_eq!(
+            translate_references("`$env:FOO $ENV:BAR", ShellType::PowerShell, ShellType::Bash),
+            "`$env:FOO $BAR"
+        );
+    }
+}
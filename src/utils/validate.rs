@@ -0,0 +1,129 @@
+//! Validation of definitions typed by the user (`wenv add`, TUI editing)
+
+use crate::formatter::get_formatter;
+use crate::i18n::Messages;
+use crate::model::{EntryType, ShellType};
+use crate::parser::builders::shell_word_end;
+
+/// Something wrong, or probably wrong, with an alias definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AliasIssue {
+    /// The name is empty or contains whitespace or `=`
+    InvalidName(String),
+    /// The alias (named here) expands to nothing. Allowed, but likely a
+    /// mistake.
+    EmptyValue(String),
+    /// The value has spaces but no quotes, so the shell would only take its
+    /// first word. `suggestion` is the input with the quotes added.
+    UnquotedValue { suggestion: String },
+}
+
+impl AliasIssue {
+    /// Whether the definition must not be written as it is
+    pub fn is_error(&self) -> bool {
+        !matches!(self, AliasIssue::EmptyValue(_))
+    }
+
+    /// The message for this issue in the user's language
+    pub fn message(&self, messages: &Messages) -> String {
+        match self {
+            AliasIssue::InvalidName(name) => messages.invalid_alias_name.replacen("{}", name, 1),
+            AliasIssue::EmptyValue(name) => {
+                messages.alias_value_empty_warning.replacen("{}", name, 1)
+            }
+            AliasIssue::UnquotedValue { suggestion } => {
+                messages.alias_value_unquoted.replacen("{}", suggestion, 1)
+            }
+        }
+    }
+}
+
+/// [`AliasIssue::InvalidName`] unless `name` can name an alias
+pub fn check_alias_name(name: &str) -> Option<AliasIssue> {
+    let invalid = name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '=');
+    invalid.then(|| AliasIssue::InvalidName(name.to_string()))
+}
+
+/// Issues with a Bash/Zsh `alias name=value` line as typed, e.g.
+/// `alias ll=ls -la`. Lines that are not an alias definition have none.
+pub fn check_alias_line(line: &str) -> Vec<AliasIssue> {
+    let Some(rest) = line.trim().strip_prefix("alias ") else {
+        return Vec::new();
+    };
+    let mut rest = rest.trim_start();
+    // Zsh options such as `-g`
+    while rest.starts_with('-') {
+        rest = rest
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, r)| r.trim_start());
+    }
+    let Some((name, value)) = rest.split_once('=') else {
+        return Vec::new();
+    };
+
+    let mut issues: Vec<AliasIssue> = check_alias_name(name).into_iter().collect();
+    let value = value.trim_end();
+    if matches!(value, "" | "''" | "\"\"") {
+        issues.push(AliasIssue::EmptyValue(name.to_string()));
+    } else if !value.contains(['\'', '"', '\\']) {
+        let first_word_end = shell_word_end(value).unwrap_or(value.len());
+        let rest = value[first_word_end..].trim_start();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            let suggestion = get_formatter(ShellType::Bash)
+                .definition(EntryType::Alias, name, value)
+                .unwrap_or_default();
+            issues.push(AliasIssue::UnquotedValue { suggestion });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_alias_name() {
+        assert_eq!(check_alias_name("ll"), None);
+        assert_eq!(check_alias_name("..."), None);
+        assert_eq!(
+            check_alias_name("my alias"),
+            Some(AliasIssue::InvalidName("my alias".to_string()))
+        );
+        assert!(check_alias_name("a=b").is_some());
+        assert!(check_alias_name("").is_some());
+    }
+
+    #[test]
+    fn test_check_alias_line_suggests_quotes() {
+        assert_eq!(
+            check_alias_line("alias ll=ls -la"),
+            [AliasIssue::UnquotedValue {
+                suggestion: "alias ll='ls -la'".to_string()
+            }]
+        );
+        // A trailing comment is not part of the value
+        assert!(check_alias_line("alias ll=ls # list").is_empty());
+        assert!(check_alias_line("alias ll='ls -la'").is_empty());
+        assert!(check_alias_line("alias -g G=grep").is_empty());
+    }
+
+    #[test]
+    fn test_check_alias_line_name_and_empty_value() {
+        let issues = check_alias_line("alias my ll=''");
+        assert_eq!(
+            issues,
+            [
+                AliasIssue::InvalidName("my ll".to_string()),
+                AliasIssue::EmptyValue("my ll".to_string()),
+            ]
+        );
+        assert!(issues[0].is_error());
+        assert!(!issues[1].is_error());
+        assert_eq!(
+            issues[0].message(crate::i18n::messages()),
+            "Invalid alias name 'my ll': names cannot contain spaces or '='"
+        );
+        assert!(check_alias_line("echo hi").is_empty());
+    }
+}
@@ -0,0 +1,1773 @@
+//! Integration tests for wenv subcommands
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// Build a `wenv` command isolated from the user's real config directory
+fn wenv(home: &Path) -> Command {
+    let mut cmd = cargo_bin_cmd!("wenv");
+    cmd.env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("NO_COLOR", "1");
+    cmd
+}
+
+#[test]
+fn test_info_shows_raw_value() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nexport EDITOR=nvim\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "info", "alias", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alias ll='ls -la'"))
+        .stdout(predicate::str::contains("Expanded:").not());
+}
+
+#[test]
+fn test_info_expand_alias_chain() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias g='git'\nalias gl='g log'\n").unwrap();
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            rc_file.to_str().unwrap(),
+            "info",
+            "gl",
+            "--expand",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Expanded: git log"));
+}
+
+#[test]
+fn test_info_missing_entry_fails() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "info", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Entry not found"));
+}
+
+#[test]
+fn test_import_dry_run_writes_nothing() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let src_file = dir.path().join("shared.sh");
+    fs::write(&rc_file, "alias ll='ls -l'\n").unwrap();
+    fs::write(&src_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            rc_file.to_str().unwrap(),
+            "--import",
+            src_file.to_str().unwrap(),
+            "--on-conflict",
+            "skip",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- skip"))
+        .stdout(predicate::str::contains("+ add"))
+        .stdout(predicate::str::contains("No changes written"));
+
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), "alias ll='ls -l'\n");
+    assert!(!dir.path().join(".config/wenv/backups").exists());
+}
+
+#[test]
+fn test_import_no_backup() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let src_file = dir.path().join("shared.sh");
+    let backups = dir.path().join(".config/wenv/backups");
+    fs::write(&rc_file, "alias ll='ls -l'\n").unwrap();
+    fs::write(&src_file, "alias gs='git status'\n").unwrap();
+
+    let import = |extra: &[&str]| {
+        wenv(dir.path())
+            .args(["--file", rc_file.to_str().unwrap()])
+            .args(["--import", src_file.to_str().unwrap(), "--yes"])
+            .args(extra)
+            .assert()
+            .success();
+    };
+
+    import(&["--no-backup"]);
+    assert!(!backups.exists());
+
+    import(&[]);
+    let bak_count = fs::read_dir(backups.join("sh")).unwrap().count();
+    assert_eq!(bak_count, 1);
+}
+
+#[test]
+fn test_backup_prune_keep() {
+    let dir = tempdir().unwrap();
+    let backups = dir.path().join(".config/wenv/backups/sh");
+    fs::create_dir_all(&backups).unwrap();
+    for day in 1..=4 {
+        fs::write(
+            backups.join(format!("2024-01-0{}_120000_.bashrc.bak", day)),
+            "",
+        )
+        .unwrap();
+    }
+
+    wenv(dir.path())
+        .args([
+            "--shell",
+            "bash",
+            "backup",
+            "prune",
+            "--keep",
+            "1",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-01-01_120000_.bashrc.bak"))
+        .stdout(predicate::str::contains("2024-01-04").not())
+        .stdout(predicate::str::contains("3 backup(s) would be removed"));
+    assert_eq!(fs::read_dir(&backups).unwrap().count(), 4);
+
+    wenv(dir.path())
+        .args(["--shell", "bash", "backup", "prune", "--keep", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 3 backup(s)"));
+    assert_eq!(fs::read_dir(&backups).unwrap().count(), 1);
+}
+
+#[test]
+fn test_backup_migrate_moves_per_shell_backups() {
+    let dir = tempdir().unwrap();
+    let backups = dir.path().join(".config/wenv/backups");
+    fs::create_dir_all(backups.join("zsh")).unwrap();
+    fs::write(backups.join("zsh/2024-01-01_120000_.zshrc.bak"), "").unwrap();
+
+    wenv(dir.path())
+        .args(["backup", "migrate", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 backup(s) would move"));
+    assert!(backups.join("zsh/2024-01-01_120000_.zshrc.bak").exists());
+
+    wenv(dir.path())
+        .args(["backup", "migrate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved 1 backup(s)"));
+    assert!(backups.join("sh/2024-01-01_120000_.zshrc.bak").exists());
+    assert!(!backups.join("zsh").exists());
+
+    wenv(dir.path())
+        .args(["backup", "migrate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No backups to migrate"));
+}
+
+#[test]
+fn test_backup_auto_without_watch_backs_up_once() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "backup", "auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Backed up to"));
+
+    let backups: Vec<_> = fs::read_dir(dir.path().join(".config/wenv/backups/sh"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(
+        fs::read_to_string(&backups[0]).unwrap(),
+        "alias ll='ls -la'\n"
+    );
+}
+
+#[test]
+fn test_quiet_backup_watch_prints_nothing() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    // Watching never ends on its own; stop it after a moment
+    wenv(dir.path())
+        .args(["--file", file, "--quiet", "backup", "auto", "--watch"])
+        .timeout(std::time::Duration::from_millis(500))
+        .assert()
+        .interrupted()
+        .stdout("");
+}
+
+#[test]
+fn test_shell_precedence() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let file = rc_file.to_str().unwrap();
+    let shell_line = |args: &[&str]| {
+        let output = wenv(dir.path())
+            .args(["--file", file])
+            .args(args)
+            .arg("doctor")
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|line| line.contains("Shell type"))
+            .unwrap()
+            .to_string()
+    };
+
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+    assert!(shell_line(&[]).contains("bash (detected from file name)"));
+
+    fs::write(&rc_file, "setopt autocd\n").unwrap();
+    assert!(shell_line(&[]).contains("zsh (detected from file content)"));
+
+    fs::write(&rc_file, "#!/usr/bin/env pwsh\nsetopt autocd\n").unwrap();
+    assert!(shell_line(&[]).contains("pwsh (detected from shebang)"));
+    assert!(shell_line(&["--shell", "sh"]).contains("sh (specified with --shell)"));
+    assert!(shell_line(&["--shell", "sh", "--force-shell", "zsh"])
+        .contains("zsh (forced with --force-shell)"));
+}
+
+#[test]
+fn test_backup_prune_requires_criteria() {
+    let dir = tempdir().unwrap();
+
+    wenv(dir.path())
+        .args(["backup", "prune"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_list_filters_by_type() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nexport EDITOR=nvim\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "alias"])
+        .args(["--no-truncate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ls -la"))
+        .stdout(predicate::str::contains("EDITOR").not());
+}
+
+#[test]
+fn test_info_value_only() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "# editor\nexport EDITOR=\"nvim\" # fast\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["info", "envvar", "EDITOR", "--value-only"])
+        .assert()
+        .success()
+        .stdout("nvim\n");
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["info", "envvar", "PAGER", "--value-only"])
+        .assert()
+        .failure()
+        .stdout("");
+}
+
+#[test]
+fn test_color_flag_controls_ansi_codes() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+    let ansi = predicate::str::contains("\x1b[");
+
+    for (extra, colored) in [
+        (&["--color", "always"][..], true),
+        (&["--color", "never"][..], false),
+        // Captured stdout is not a terminal
+        (&[][..], false),
+    ] {
+        let assert = wenv(dir.path())
+            .env_remove("NO_COLOR")
+            .args(["--file", rc_file.to_str().unwrap(), "list"])
+            .args(extra)
+            .assert()
+            .success();
+        if colored {
+            assert.stdout(ansi.clone());
+        } else {
+            assert.stdout(ansi.clone().not());
+        }
+    }
+}
+
+#[test]
+fn test_doctor_reports_checklist() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+
+    // Missing config file is reported, not prompted for
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "doctor"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("bash (detected from file name)"))
+        .stdout(predicate::str::contains("not found"));
+
+    fs::write(&rc_file, "").unwrap();
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "doctor"])
+        .env("PATH", dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✓ Config file writable: yes"))
+        .stdout(predicate::str::contains("✗ bash on PATH"))
+        .stdout(predicate::str::contains("⚠ pwsh on PATH"));
+}
+
+#[test]
+fn test_list_filters_by_tag() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "# wenv:tags=work,git\nalias gs='git status'\n\n\
+         # wenv:tags=home\nalias music='cmus'\n\
+         alias ll='ls -la'\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "--tag", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gs"))
+        .stdout(predicate::str::contains("music").not())
+        .stdout(predicate::str::contains("ll").not());
+}
+
+#[test]
+fn test_export_to_stdout() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nexport EDITOR=nvim\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "--export", "-"])
+        .args(["--type", "alias"])
+        .assert()
+        .success()
+        .stdout("alias ll='ls -la'\n");
+
+    assert!(!dir.path().join("-").exists());
+}
+
+#[test]
+fn test_move_entry_before_another() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias gp='$PAGER'\nalias ll='ls -la'\nexport PAGER=less\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["move", "env", "PAGER", "--before", "gp"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "export PAGER=less\nalias gp='$PAGER'\nalias ll='ls -la'\n"
+    );
+    let backups = dir.path().join(".config/wenv/backups/sh");
+    assert_eq!(fs::read_dir(backups).unwrap().count(), 1);
+
+    // Exactly one of --before/--after is required
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["move", "env", "PAGER"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_backup_diff_since() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let backups = dir.path().join(".config/wenv/backups/sh");
+    fs::create_dir_all(&backups).unwrap();
+    fs::write(
+        backups.join("2024-01-01_120000_.bashrc.bak"),
+        "alias ll='ls -l'\nalias gs='git status'\n",
+    )
+    .unwrap();
+    fs::write(
+        backups.join("2024-01-02_120000_.bashrc.bak"),
+        "alias ll='ls -la'\nalias gs='git status'\n",
+    )
+    .unwrap();
+    fs::write(&rc_file, "alias ll='ls -la'\nexport PAGER=less\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["backup", "diff", "--since", "2024-01-01"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("~ alias    ll  ls -l → ls -la"))
+        .stdout(predicate::str::contains("- alias    gs"))
+        .stdout(predicate::str::contains("+ env      PAGER  less"))
+        .stdout(predicate::str::contains("1 added, 1 removed, 1 modified"));
+
+    let output = wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["backup", "diff", "--since", "2024-01-01", "--chain"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let steps: Vec<&str> = output.split("\n\n").collect();
+    assert_eq!(steps.len(), 2);
+    assert!(steps[0].contains("0 added, 0 removed, 1 modified"));
+    assert!(steps[1].contains("1 added, 1 removed, 0 modified"));
+}
+
+#[test]
+fn test_arrays_listed_and_shown() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias ll='ls -la'\nplugins=(\n  git\n  docker\n)\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "array"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("plugins"))
+        .stdout(predicate::str::contains("ll").not());
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "info", "plugins"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("array plugins"))
+        .stdout(predicate::str::contains("  docker"));
+}
+
+#[test]
+fn test_list_limit_and_offset() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias a1='one'\nexport E1=x\nalias a2='two'\nalias a3='three'\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "alias"])
+        .args(["--offset", "1", "--limit", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a2"))
+        .stdout(predicate::str::contains("a1").not())
+        .stdout(predicate::str::contains("a3").not())
+        .stdout(predicate::str::contains("Showing 2-2 of 3"));
+
+    // A limit past the end is clamped
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "--offset", "2"])
+        .args(["--limit", "10"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a3"))
+        .stdout(predicate::str::contains("Showing 3-4 of 4"));
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "--offset", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Showing 0 of 4"));
+}
+
+#[test]
+fn test_bundle_export_import_across_shells() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let profile = dir.path().join("profile.ps1");
+    let bundle = dir.path().join("shared.toml");
+    fs::write(
+        &rc_file,
+        "alias ll='ls -la'\nexport EDITOR=vim\ngreet() {\n  echo hi\n}\n",
+    )
+    .unwrap();
+    fs::write(&profile, "Set-Alias g git\n").unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap()])
+        .args(["--export", bundle.to_str().unwrap(), "--format", "bundle"])
+        .assert()
+        .success();
+    let content = fs::read_to_string(&bundle).unwrap();
+    assert!(content.contains("format = \"wenv-bundle\""));
+    assert!(content.contains("shell = \"bash\""));
+
+    wenv(dir.path())
+        .args(["--file", profile.to_str().unwrap(), "--shell", "pwsh"])
+        .args(["--import", bundle.to_str().unwrap(), "--yes", "--no-backup"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped func 'greet'"));
+
+    assert_eq!(
+        fs::read_to_string(&profile).unwrap(),
+        "Set-Alias g git\nfunction ll { ls -la @args }\n$env:EDITOR = \"vim\"\n"
+    );
+}
+
+#[test]
+fn test_import_translates_shell_file() {
+    let dir = tempdir().unwrap();
+    let profile = dir.path().join("profile.ps1");
+    let aliases = dir.path().join("aliases.sh");
+    fs::write(&profile, "").unwrap();
+    fs::write(
+        &aliases,
+        "alias g=git\nexport GOBIN=\"$HOME/go/bin\"\nmkcd() { mkdir -p \"$1\" && cd \"$1\"; }\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", profile.to_str().unwrap(), "--shell", "pwsh"])
+        .args([
+            "--import",
+            aliases.to_str().unwrap(),
+            "--yes",
+            "--no-backup",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Translating bash syntax to pwsh"))
+        .stdout(predicate::str::contains("Skipped func 'mkcd'"));
+
+    assert_eq!(
+        fs::read_to_string(&profile).unwrap(),
+        "Set-Alias -Name g -Value git\n$env:GOBIN = \"$HOME/go/bin\"\n"
+    );
+}
+
+#[test]
+fn test_config_get_set_path() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let config_file = dir.path().join(".config").join("wenv").join("config.toml");
+    let config = |args: &[&str]| {
+        let mut cmd = wenv(dir.path());
+        cmd.args(["--file", rc_file.to_str().unwrap(), "config"])
+            .args(args);
+        cmd
+    };
+
+    config(&["path"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(config_file.to_str().unwrap()));
+    config(&["get", "backup.max_count"])
+        .assert()
+        .success()
+        .stdout("20\n");
+
+    config(&["set", "backup.max_count", "5"]).assert().success();
+    config(&["get", "backup.max_count"])
+        .assert()
+        .success()
+        .stdout("5\n");
+    assert!(fs::read_to_string(&config_file)
+        .unwrap()
+        .contains("max_count = 5"));
+
+    config(&["set", "backup.max_count", "lots"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a non-negative number"));
+    config(&["get", "ui.colour"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown config key"));
+}
+
+#[test]
+fn test_no_merge_comments_keeps_comments_separate() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "# list files\nalias ll='ls -la'\n").unwrap();
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            rc_file.to_str().unwrap(),
+            "info",
+            "ll",
+            "--value-only",
+        ])
+        .assert()
+        .success()
+        .stdout("ls -la\n");
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "comment"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("list files").not());
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "--no-merge-comments"])
+        .args(["list", "comment"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# list files"));
+}
+
+#[test]
+fn test_parse_cache_follows_file_changes() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let cache_dir = dir.path().join(".config").join("wenv").join("parse-cache");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+    let list = || {
+        let mut cmd = wenv(dir.path());
+        cmd.args(["--file", rc_file.to_str().unwrap(), "list"]);
+        cmd
+    };
+
+    list().assert().success();
+    assert!(!cache_dir.exists());
+
+    wenv(dir.path())
+        .args(["config", "set", "cache.enabled", "true"])
+        .assert()
+        .success();
+    list()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ll"));
+    assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+    fs::write(&rc_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+    list()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gs"));
+
+    wenv(dir.path()).arg("--clear-cache").assert().success();
+    assert!(!cache_dir.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_editor_flag_overrides_environment() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\n").unwrap();
+    let source = || {
+        let mut cmd = wenv(dir.path());
+        cmd.env("VISUAL", "false").env("EDITOR", "false").args([
+            "--file",
+            rc_file.to_str().unwrap(),
+            "--source",
+        ]);
+        cmd
+    };
+
+    // Both environment editors exit with an error
+    source().assert().failure();
+    source()
+        .args(["--editor", "true"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in true"));
+    source()
+        .args(["--editor", "wenv-missing-editor"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--editor <CMD>"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_opens_entry_line() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "export EDITOR=vim\n\nalias ll='ls -la'\n").unwrap();
+
+    // `true` ignores its arguments; only the announced location is checked
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "--editor", "true"])
+        .args(["edit", "alias", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".bashrc:3"));
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "--editor", "true"])
+        .args(["edit", "gs"])
+        .assert()
+        .failure();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_line_opens_spanning_entry() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "export EDITOR=vim
+greet() {
+  echo hi
+}
+",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "--editor", "true", "edit", "--line", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".bashrc:2"));
+    // Past the last entry the file is opened at the line itself
+    wenv(dir.path())
+        .args(["--file", file, "--editor", "true", "edit", "--line", "9"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".bashrc:9"));
+}
+
+#[test]
+fn test_list_and_info_show_exported_functions() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "greet() {\n  echo hi\n}\nbye() { echo bye; }\nexport -f greet\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "list", "func"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("greet (exported)"))
+        .stdout(predicate::str::contains("bye (exported)").not());
+    wenv(dir.path())
+        .args(["--file", file, "info", "func", "greet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("func greet (exported)"));
+}
+
+#[test]
+fn test_remove_dry_run_previews_lines() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let content = "alias ll='ls -la'\ngreet() {\n  echo hi\n}\nexport EDITOR=vim\n";
+    fs::write(&rc_file, content).unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "remove", "func", "greet", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would remove func 'greet' (lines 2-4):\n- 2 | greet() {\n- 3 |   echo hi\n- 4 | }\n",
+        ));
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), content);
+    assert!(!dir.path().join(".config/wenv/backups").exists());
+
+    wenv(dir.path())
+        .args(["--file", file, "remove", "greet"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "alias ll='ls -la'\nexport EDITOR=vim\n"
+    );
+}
+
+#[test]
+fn test_verbose_reports_backup_path() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    let output = wenv(dir.path())
+        .args(["--file", file, "-v", "remove", "gs"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let backups = dir.path().join(".config/wenv/backups/sh");
+    let backup = fs::read_dir(&backups).unwrap().next().unwrap().unwrap();
+    assert!(
+        stderr.contains(&format!("Backup created: {}", backup.path().display())),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("Parsed 2 entries"), "{}", stderr);
+    assert!(stderr.contains("Write took"), "{}", stderr);
+
+    // Quiet without the flag
+    wenv(dir.path())
+        .args(["--file", file, "remove", "ll"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_normalize_paths_merges_definitions() {
+    let dir = tempdir().unwrap();
+    let bin = dir.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    let bin = bin.to_str().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let content = format!(
+        "# tools\nexport PATH=\"{bin}:$PATH\"\nexport EDITOR=vim\nexport PATH=\"/no/such/dir:{bin}:$PATH\"\n"
+    );
+    fs::write(&rc_file, &content).unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "normalize-paths", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("- {} (duplicate)", bin)))
+        .stdout(predicate::str::contains("- /no/such/dir (missing)"));
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), content);
+
+    wenv(dir.path())
+        .args(["--file", file, "normalize-paths"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        format!("# tools\nexport PATH={bin}:$PATH\nexport EDITOR=vim\n")
+    );
+    assert!(dir.path().join(".config/wenv/backups").exists());
+
+    wenv(dir.path())
+        .args(["--file", file, "normalize-paths"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already normalized"));
+}
+
+#[test]
+fn test_sources_tree_marks_missing_and_cycles() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "source ~/.bash_aliases\n. ~/.bash_missing\n").unwrap();
+    fs::write(
+        dir.path().join(".bash_aliases"),
+        "alias ll='ls -la'\nsource ~/.bashrc\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "sources", "--tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "├── ~/.bash_aliases (line 1)\n│   └── ~/.bashrc (line 2) ↺ cycle\n└── ~/.bash_missing (line 2) missing\n",
+        ));
+
+    // Without --tree only the direct sources are listed
+    wenv(dir.path())
+        .args(["--file", file, "sources"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cycle").not());
+}
+
+#[test]
+fn test_files_marks_existing_candidates() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join(".bashrc"), "alias ll='ls -la'\n").unwrap();
+    fs::write(dir.path().join(".profile"), "").unwrap();
+
+    wenv(dir.path())
+        .args(["--shell", "bash", "files"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "✓ ~/.bashrc (current)\n✗ ~/.bash_profile\n✓ ~/.profile\n",
+        ));
+}
+
+#[test]
+fn test_list_type_setopt() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".zshrc");
+    fs::write(
+        &rc_file,
+        "setopt autocd\nzstyle ':completion:*' menu select\nalias ll='ls -la'\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "list", "setopt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("autocd"))
+        .stdout(predicate::str::contains("menu").not());
+    wenv(dir.path())
+        .args(["--file", file, "list", "zstyle"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(":completion:* menu"));
+}
+
+#[test]
+fn test_list_marks_prompt_variables_and_escapes_control_chars() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "export PS1='\x1b[32m\\u\x1b[0m$ '\nexport EDITOR=vim\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", rc_file.to_str().unwrap(), "list", "--no-truncate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PS1 (prompt)"))
+        .stdout(predicate::str::contains("\\e[32m\\u\\e[0m$"))
+        .stdout(predicate::str::contains("\x1b").not())
+        .stdout(predicate::str::contains("EDITOR (prompt)").not());
+}
+
+#[test]
+fn test_bare_prompt_assignment_is_a_prompt_variable() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let file = rc_file.to_str().unwrap();
+    let original = "PS1='\\u@\\h \\$ '\n";
+    fs::write(&rc_file, original).unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PS1 (prompt)"));
+    wenv(dir.path())
+        .args(["--file", file, "info", "PS1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(prompt)"));
+
+    wenv(dir.path())
+        .args(["config", "set", "format.quote_style", "double"])
+        .assert()
+        .success();
+    wenv(dir.path())
+        .args(["--file", file, "format"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), original);
+}
+
+#[test]
+fn test_attach_comments_config_reaches_info_and_list() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let file = rc_file.to_str().unwrap();
+    fs::write(&rc_file, "# long listing\nalias ll='ls -la'\n").unwrap();
+    wenv(dir.path())
+        .args(["config", "set", "cache.enabled", "true"])
+        .assert()
+        .success();
+
+    // Off by default; this run also fills the parse cache
+    wenv(dir.path())
+        .args(["--file", file, "info", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comment:").not());
+
+    wenv(dir.path())
+        .args(["config", "set", "parser.attach_comments", "true"])
+        .assert()
+        .success();
+    wenv(dir.path())
+        .args(["--file", file, "info", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comment:\n  # long listing"));
+    wenv(dir.path())
+        .args(["--file", file, "list", "--no-truncate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ls -la  # long listing"));
+    wenv(dir.path())
+        .args([
+            "--file",
+            file,
+            "list",
+            "--output-template",
+            "{name}: {comment}",
+        ])
+        .assert()
+        .success()
+        .stdout("ll: # long listing\n");
+}
+
+#[test]
+fn test_saved_file_ends_in_one_newline() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let file = rc_file.to_str().unwrap();
+
+    fs::write(&rc_file, "alias a=1\nalias b=2\n\n\n").unwrap();
+    wenv(dir.path())
+        .args(["--file", file, "remove", "a"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), "alias b=2\n");
+
+    wenv(dir.path())
+        .args(["config", "set", "format.preserve_trailing_blanks", "true"])
+        .assert()
+        .success();
+    fs::write(&rc_file, "alias a=1\nalias b=2\n\n\n").unwrap();
+    wenv(dir.path())
+        .args(["--file", file, "remove", "a"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), "alias b=2\n\n\n");
+}
+
+#[test]
+fn test_remove_appends_history() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No changes recorded yet."));
+
+    wenv(dir.path())
+        .args(["--file", file, "remove", "gs"])
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(dir.path().join(".config/wenv/history.log")).unwrap();
+    let backup_id = fs::read_dir(dir.path().join(".config/wenv/backups/sh"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+        .file_stem()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    assert_eq!(log.lines().count(), 1);
+    assert!(
+        log.contains(&format!(
+            "  remove alias 'gs'  {}  backup {}",
+            file, backup_id
+        )),
+        "{}",
+        log
+    );
+
+    wenv(dir.path())
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("remove alias 'gs'"));
+    wenv(dir.path())
+        .args(["history", "--clear"])
+        .assert()
+        .success();
+    assert!(!dir.path().join(".config/wenv/history.log").exists());
+}
+
+#[test]
+fn test_check_style_is_opt_in() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la' \nexport EDITOR=vim\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+
+    wenv(dir.path())
+        .args(["--file", file, "check", "--style"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("line 1: Trailing whitespace"));
+}
+
+#[test]
+fn test_check_missing_dirs_is_opt_in() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let existing = dir.path().join("bin");
+    fs::create_dir(&existing).unwrap();
+    fs::write(
+        &rc_file,
+        format!(
+            "export PATH=\"$PATH:{}:/no/such/dir\"\n",
+            existing.display()
+        ),
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+
+    wenv(dir.path())
+        .args(["--file", file, "check", "--missing-dirs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Directory '/no/such/dir' in PATH does not exist",
+        ))
+        .stdout(predicate::str::contains("missing-path-directory"))
+        .stdout(predicate::str::contains("/bin' in PATH").not());
+}
+
+#[test]
+fn test_check_ignores_configured_codes() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias ll='ls -la'\nalias ll='ls -l'\ngreet() {\n    echo hi\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[duplicate-definition]"))
+        .stdout(predicate::str::contains("[unclosed-function]"));
+
+    let config_dir = dir.path().join(".config").join("wenv");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[checker]\nignore_codes = [\"duplicate-definition\"]\n\n[parser]\nignore_warning_codes = [\"unclosed-function\"]\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+}
+
+#[test]
+fn test_import_append_flag() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let source_file = dir.path().join("extra.sh");
+    fs::write(&rc_file, "#!/bin/bash\nalias ll='ls -la'\n").unwrap();
+    fs::write(&source_file, "alias gs='git status'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+    let source = source_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "--import", source, "--append", "--prepend"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    wenv(dir.path())
+        .args(["--file", file, "--import", source, "--append", "--yes"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "#!/bin/bash\nalias ll='ls -la'\nalias gs='git status'\n"
+    );
+}
+
+#[test]
+fn test_import_prepend_keeps_shebang_first() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let source_file = dir.path().join("extra.sh");
+    fs::write(&rc_file, "#!/bin/bash\nalias ll='ls -la'\n").unwrap();
+    fs::write(&source_file, "alias gs='git status'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            file,
+            "--import",
+            source_file.to_str().unwrap(),
+            "--prepend",
+            "--yes",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "#!/bin/bash\nalias gs='git status'\nalias ll='ls -la'\n"
+    );
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            file,
+            "--import",
+            source_file.to_str().unwrap(),
+            "--prepend",
+            "--after",
+            "ll",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_list_output_template() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nexport EDITOR=vim\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args([
+            "--file",
+            file,
+            "list",
+            "alias",
+            "--output-template",
+            r"{type}\t{name}\t{value} {{{line}}}",
+        ])
+        .assert()
+        .success()
+        .stdout("alias\tll\tls -la {1}\n");
+
+    wenv(dir.path())
+        .args(["--file", file, "list", "--output-template", "{size}"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown placeholder '{size}'"));
+}
+
+#[test]
+fn test_lint_rules_follow_config() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias cd='z'\nsource ~/.bash_missing\nexport EDITOR=vim\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    // missing-source is an error by default
+    wenv(dir.path())
+        .args(["--file", file, "lint"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("line 1: alias 'cd' shadows"))
+        .stdout(predicate::str::contains("1  missing-source"))
+        .stderr(predicate::str::contains("1 error(s), 1 warning(s)"));
+
+    let config_dir = dir.path().join(".config").join("wenv");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[lint]\nmissing-source = \"warning\"\nshadowed-builtin = \"off\"\n",
+    )
+    .unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "lint"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shadows").not())
+        .stdout(predicate::str::contains("1 warning(s)"));
+}
+
+#[test]
+fn test_format_range_leaves_other_lines_alone() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let original = "alias zz='echo z'\nalias yy='echo y'\n\nalias b=two\nalias a=one\n\ngreet() {\necho hi\n}\n";
+    fs::write(&rc_file, original).unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "format", "--range", "4:8"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped func 'greet'"))
+        .stdout(predicate::str::contains("Formatted lines 4-6"));
+
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        original.replace("alias b=two\nalias a=one", "alias a=one\nalias b=two")
+    );
+}
+
+#[test]
+fn test_list_include_disabled() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "# Old listing\nalias gs='git status'\n\n# alias ll='ls -la'\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "list", "alias"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ll").not());
+
+    wenv(dir.path())
+        .args(["--file", file, "list", "alias", "--include-disabled"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ll (disabled)"))
+        .stdout(predicate::str::contains("ls -la"));
+}
+
+#[test]
+fn test_grep_matches_values() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias gs='git status'\nalias ll='ls -la'\ngco() {\n    git checkout \"$@\"\n}\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "grep", "git "])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alias gs (line 1): git status"))
+        .stdout(predicate::str::contains("func gco (line 3): git checkout"))
+        .stdout(predicate::str::contains("ll").not());
+
+    wenv(dir.path())
+        .args(["--file", file, "grep", "git ", "--name-only"])
+        .assert()
+        .success()
+        .stdout("gs\ngco\n");
+
+    wenv(dir.path())
+        .args(["--file", file, "grep", "git ("])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid pattern 'git ('"));
+}
+
+#[test]
+fn test_count_prints_only_the_number() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(
+        &rc_file,
+        "alias ll='ls -la'\nalias gs='git status'\n\nexport EDITOR=vim\n",
+    )
+    .unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "count", "alias"])
+        .assert()
+        .success()
+        .stdout("2\n");
+    wenv(dir.path())
+        .args(["--file", file, "count"])
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn test_several_files_for_list_and_check() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let aliases_file = dir.path().join(".bash_aliases");
+    fs::write(&rc_file, "export EDITOR=vim\nalias ll='ls -la'\n").unwrap();
+    fs::write(&aliases_file, "alias gs='git status'\nalias ll='ls -l'\n").unwrap();
+    let (rc, aliases) = (rc_file.to_str().unwrap(), aliases_file.to_str().unwrap());
+
+    wenv(dir.path())
+        .args(["-f", rc, "-f", aliases, "list", "alias"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"ll\s+\.bashrc\s+2").unwrap())
+        .stdout(predicate::str::is_match(r"gs\s+\.bash_aliases\s+1").unwrap())
+        .stdout(predicate::str::is_match(r"ll\s+\.bash_aliases\s+2").unwrap());
+
+    // Provenance is shown for the entry found, and only with several files
+    wenv(dir.path())
+        .args(["-f", aliases, "-f", rc, "info", "alias", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("File: "))
+        .stdout(predicate::str::contains(".bash_aliases"))
+        .stdout(predicate::str::contains("alias ll='ls -l'"));
+    wenv(dir.path())
+        .args(["-f", rc, "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FILE").not());
+
+    wenv(dir.path())
+        .args(["-f", rc, "-f", aliases, "check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "alias 'll' is defined in several files: .bashrc:2, .bash_aliases:2",
+        ));
+
+    wenv(dir.path())
+        .args(["-f", rc, "-f", aliases, "count"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only supported by list, info, check, lint",
+        ));
+}
+
+#[test]
+fn test_parallel_parsing_matches_sequential() {
+    let dir = tempdir().unwrap();
+    let mut args = Vec::new();
+    for index in 0..6 {
+        let file = dir.path().join(format!("rc{}", index));
+        let content: String = (0..50)
+            .map(|n| {
+                format!(
+                    "alias a{}_{}='echo {}'\nexport V{}_{}={}\n",
+                    index, n, n, index, n, n
+                )
+            })
+            .collect();
+        fs::write(&file, content + "greet() {\n  echo hi\n}\n").unwrap();
+        args.extend(["-f".to_string(), file.to_str().unwrap().to_string()]);
+    }
+    args.extend(["--shell".to_string(), "bash".to_string()]);
+
+    for command in [&["list"][..], &["check"]] {
+        let sequential = wenv(dir.path()).args(&args).args(command).output().unwrap();
+        let parallel = wenv(dir.path())
+            .args(&args)
+            .arg("--parallel")
+            .args(command)
+            .output()
+            .unwrap();
+        assert_eq!(sequential.status.code(), parallel.status.code());
+        assert_eq!(
+            String::from_utf8_lossy(&sequential.stdout),
+            String::from_utf8_lossy(&parallel.stdout)
+        );
+    }
+    // A missing file fails the same way
+    wenv(dir.path())
+        .args(&args)
+        .args(["-f", "nope", "--parallel", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nope"));
+}
+
+#[test]
+fn test_profile_prints_phase_timings() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias b=two\nalias a=one\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "--profile", "check"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Phase"))
+        .stderr(predicate::str::contains("Read"))
+        .stderr(predicate::str::contains("Parse"))
+        .stderr(predicate::str::contains("Check"))
+        .stderr(predicate::str::contains("Total"));
+
+    wenv(dir.path())
+        .args(["--file", file, "--profile", "--no-backup", "format"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Format"));
+
+    // Nothing extra without the flag
+    wenv(dir.path())
+        .args(["--file", file, "check"])
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn test_reload_hint_collapses_home() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "remove", "gs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("source ~/.bashrc"));
+
+    wenv(dir.path())
+        .args(["--file", file, "--expand-home", "remove", "ll"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("source {}", file)));
+}
+
+#[test]
+fn test_add_existing_alias_compares_values_without_quotes() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias ll=\"ls -la\"\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "add", "alias", "ll=ls -la"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "alias 'll' already exists with an equivalent value, skipping",
+        ));
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "alias ll=\"ls -la\"\n"
+    );
+
+    wenv(dir.path())
+        .args(["--file", file, "add", "--yes", "alias", "ll=ls -l"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "already exists with value: ls -la",
+        ));
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), "alias ll='ls -l'\n");
+}
+
+#[test]
+fn test_add_alias_validates_name_and_quotes() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "export EDITOR=vim\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "add", "alias", "ll=ls -la"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "export EDITOR=vim\nalias ll='ls -la'\n"
+    );
+
+    // Without a terminal to ask on, a forgotten quote is an error
+    wenv(dir.path())
+        .args(["--file", file, "add", "alias", "la=ls", "-A"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Did you mean: wenv add alias 'la=ls -A'",
+        ));
+    wenv(dir.path())
+        .args(["--file", file, "add", "--yes", "alias", "la=ls", "-A"])
+        .assert()
+        .success();
+
+    wenv(dir.path())
+        .args(["--file", file, "add", "alias", "my ll=ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid alias name 'my ll'"));
+
+    wenv(dir.path())
+        .args(["--file", file, "add", "alias", "nothing="])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("'nothing' has an empty value"));
+
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "export EDITOR=vim\nalias ll='ls -la'\nalias la='ls -A'\nalias nothing=''\n"
+    );
+}
+
+#[test]
+fn test_quiet_add_prints_nothing() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    wenv(dir.path())
+        .args(["--file", file, "--quiet", "add", "alias", "ll=ls -la"])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(fs::read_to_string(&rc_file).unwrap(), "alias ll='ls -la'\n");
+
+    // Nor when an equivalent entry already exists
+    wenv(dir.path())
+        .args(["--file", file, "--quiet", "add", "alias", "ll=ls -la"])
+        .assert()
+        .success()
+        .stdout("");
+
+    // Errors are still reported
+    wenv(dir.path())
+        .args(["--file", file, "-q", "add", "alias", "my ll=ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid alias name"));
+}
+
+#[test]
+fn test_quiet_format_prints_nothing() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    fs::write(&rc_file, "alias a=one\nalias b=two\n").unwrap();
+    let file = rc_file.to_str().unwrap();
+
+    for args in [&["format"][..], &["format", "--range", "5:6"]] {
+        wenv(dir.path())
+            .args(["--file", file, "--quiet"])
+            .args(args)
+            .assert()
+            .success()
+            .stdout("");
+    }
+}
+
+#[test]
+fn test_config_init_writes_commented_template() {
+    let dir = tempdir().unwrap();
+    let config_file = dir.path().join(".config").join("wenv").join("config.toml");
+
+    wenv(dir.path()).args(["config", "init"]).assert().success();
+    let content = fs::read_to_string(&config_file).unwrap();
+    assert!(content.contains("# Backups kept per shell"));
+    wenv(dir.path())
+        .args(["config", "get", "backup.max_count"])
+        .assert()
+        .success()
+        .stdout("20\n");
+
+    // An existing file is only replaced with --force
+    wenv(dir.path())
+        .args(["config", "init"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    wenv(dir.path())
+        .args(["config", "init", "--force"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_apply_previews_then_merges_snippet() {
+    let dir = tempdir().unwrap();
+    let rc_file = dir.path().join(".bashrc");
+    let snippet = dir.path().join("git.sh");
+    fs::write(&rc_file, "alias ll='ls -l'\nexport EDITOR=vim\n").unwrap();
+    fs::write(&snippet, "alias ll='ls -la'\nalias gs='git status'\n").unwrap();
+
+    let apply = |extra: &[&str]| {
+        wenv(dir.path())
+            .args(["--file", rc_file.to_str().unwrap(), "--on-conflict"])
+            .args(["overwrite", "apply", snippet.to_str().unwrap()])
+            .args(extra)
+            .assert()
+            .success()
+    };
+
+    apply(&["--dry-run"])
+        .stdout(predicate::str::contains("~ alias    ll"))
+        .stdout(predicate::str::contains("+ alias    gs"))
+        .stdout(predicate::str::contains("1 added, 1 modified"))
+        .stdout(predicate::str::contains("No changes written"));
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "alias ll='ls -l'\nexport EDITOR=vim\n"
+    );
+
+    apply(&["--yes"]).stdout(predicate::str::contains("Applied"));
+    assert_eq!(
+        fs::read_to_string(&rc_file).unwrap(),
+        "alias ll='ls -la'\nexport EDITOR=vim\nalias gs='git status'\n"
+    );
+}
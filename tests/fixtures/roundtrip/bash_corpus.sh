@@ -0,0 +1,60 @@
+# Round-trip corpus: each snippet must survive parse -> format -> parse.
+
+# Multi-line single-quoted alias
+alias gitlog='git log \
+  --oneline \
+  --graph'
+
+alias ll='ls -la' # inline comment
+alias -g G='| grep'
+alias q="echo 'quoted'"
+alias dq='it'\''s'
+
+export EDITOR=nvim
+export PAGER="less" # inline comment
+export PATH="$HOME/bin:$PATH"
+export MULTI='line one
+line two'
+export EMPTY=''
+export A=1 B=2
+
+greet() {
+    echo "Hello, $1!"
+}
+
+function keyword_fn {
+  local x=1
+  echo "$x"
+}
+
+one_liner() { echo one; }
+
+braces_in_strings() {
+    echo "{ not a brace"
+    echo '}'
+}
+
+with_heredoc() {
+    cat <<EOF2
+}
+EOF2
+}
+
+if [ -f ~/.local_rc ]; then
+    source ~/.local_rc
+fi
+
+for f in ~/.bashrc.d/*.sh; do
+  . "$f"
+done
+
+case "$TERM" in
+    xterm*) export COLORTERM=1 ;;
+esac
+
+cat > /tmp/x <<'END'
+alias not_an_alias='x'
+END
+
+source ~/.bash_aliases
+. "$HOME/.env"
@@ -0,0 +1,24 @@
+# Round-trip corpus: each snippet must survive parse -> format -> parse.
+
+Set-Alias ll Get-ChildItem
+Set-Alias la Get-ChildItem # inline comment
+Set-Alias -Name gs -Value git-status
+New-Alias -Name np -Value notepad -Scope Global
+
+$env:EDITOR = "code"
+$env:SINGLE = 'single'
+$env:PAGER = "less" # inline comment
+$env:PATH = @"
+C:\bin
+D:\tools
+"@
+
+function Get-Greeting {
+    param($Name)
+    Write-Host "Hello, $Name!"
+}
+
+function One-Liner { Write-Host "one" }
+
+. .\aliases.ps1
+. "$HOME\profile-extra.ps1"
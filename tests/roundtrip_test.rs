@@ -0,0 +1,124 @@
+//! Round-trip tests: parse -> format -> parse must preserve every entry
+//!
+//! For each corpus file, every structured entry is formatted on its own with
+//! `format_entry` and re-parsed, and the whole file is formatted with
+//! `format` and re-parsed. Type, name and canonical value must be unchanged.
+
+use wenv::formatter::get_formatter;
+use wenv::model::{Config, Entry, EntryType, ShellType};
+use wenv::parser::get_parser;
+
+/// Comparable identity of an entry
+fn key(entry: &Entry) -> (String, String, String) {
+    (
+        entry.entry_type.to_string(),
+        entry.name.clone(),
+        entry.canonical_value(),
+    )
+}
+
+fn is_structured(entry: &Entry) -> bool {
+    !matches!(entry.entry_type, EntryType::Code | EntryType::Comment)
+}
+
+fn structured_keys(entries: &[Entry]) -> Vec<(String, String, String)> {
+    let mut keys: Vec<_> = entries
+        .iter()
+        .filter(|e| is_structured(e))
+        .map(key)
+        .collect();
+    keys.sort();
+    keys
+}
+
+fn assert_entries_round_trip(shell_type: ShellType, content: &str) {
+    let parser = get_parser(shell_type);
+    let formatter = get_formatter(shell_type);
+    let entries = parser.parse(content).entries;
+
+    for entry in entries.iter().filter(|e| is_structured(e)) {
+        let formatted = formatter.format_entry(entry);
+        let reparsed = parser.parse(&formatted);
+        let structured: Vec<_> = reparsed
+            .entries
+            .iter()
+            .filter(|e| is_structured(e))
+            .collect();
+
+        assert_eq!(
+            structured.len(),
+            1,
+            "expected one entry after re-parsing {:?}, got {:?}",
+            formatted,
+            reparsed.entries
+        );
+        assert_eq!(
+            key(structured[0]),
+            key(entry),
+            "entry changed: {:?}",
+            formatted
+        );
+    }
+}
+
+fn assert_file_round_trip(shell_type: ShellType, content: &str, config: &Config) {
+    let parser = get_parser(shell_type);
+    let formatter = get_formatter(shell_type);
+    let entries = parser.parse(content).entries;
+
+    let formatted = formatter.format(&entries, config);
+    let reparsed = parser.parse(&formatted).entries;
+
+    assert_eq!(
+        structured_keys(&reparsed),
+        structured_keys(&entries),
+        "formatted file:\n{}",
+        formatted
+    );
+
+    // Formatting is idempotent
+    assert_eq!(formatter.format(&reparsed, config), formatted);
+}
+
+fn grouped_config() -> Config {
+    Config::default()
+}
+
+fn ungrouped_config() -> Config {
+    let mut config = Config::default();
+    config.format.group_by_type = false;
+    config
+}
+
+const BASH_CORPUS: &str = include_str!("fixtures/roundtrip/bash_corpus.sh");
+const PWSH_CORPUS: &str = include_str!("fixtures/roundtrip/pwsh_corpus.ps1");
+const BASH_SAMPLE: &str = include_str!("fixtures/sample_bashrc");
+const PWSH_SAMPLE: &str = include_str!("fixtures/sample_profile.ps1");
+
+#[test]
+fn test_bash_entries_round_trip() {
+    assert_entries_round_trip(ShellType::Bash, BASH_CORPUS);
+    assert_entries_round_trip(ShellType::Bash, BASH_SAMPLE);
+}
+
+#[test]
+fn test_pwsh_entries_round_trip() {
+    assert_entries_round_trip(ShellType::PowerShell, PWSH_CORPUS);
+    assert_entries_round_trip(ShellType::PowerShell, PWSH_SAMPLE);
+}
+
+#[test]
+fn test_bash_file_round_trip() {
+    for config in [grouped_config(), ungrouped_config()] {
+        assert_file_round_trip(ShellType::Bash, BASH_CORPUS, &config);
+        assert_file_round_trip(ShellType::Bash, BASH_SAMPLE, &config);
+    }
+}
+
+#[test]
+fn test_pwsh_file_round_trip() {
+    for config in [grouped_config(), ungrouped_config()] {
+        assert_file_round_trip(ShellType::PowerShell, PWSH_CORPUS, &config);
+        assert_file_round_trip(ShellType::PowerShell, PWSH_SAMPLE, &config);
+    }
+}